@@ -59,6 +59,13 @@ impl EpochState {
         }
     }
 
+    /// Replace the committee derived from the system state. Used when the system state comes from
+    /// a fork of existing state, whose validator keys are not available to sign checkpoints.
+    pub fn with_committee(mut self, committee: Committee) -> Self {
+        self.committee = committee;
+        self
+    }
+
     pub fn epoch(&self) -> EpochId {
         self.epoch_start_state.epoch()
     }
@@ -73,6 +80,17 @@ impl EpochState {
         round
     }
 
+    /// The consensus round that will be handed out next, without advancing the counter.
+    pub fn peek_next_consensus_round(&self) -> u64 {
+        self.next_consensus_round
+    }
+
+    /// Resume the consensus round counter from a previously saved value.
+    pub fn with_next_consensus_round(mut self, round: u64) -> Self {
+        self.next_consensus_round = round;
+        self
+    }
+
     pub fn committee(&self) -> &Committee {
         &self.committee
     }
@@ -113,6 +131,7 @@ impl EpochState {
             &receiving_object_refs,
             deny_config,
             &store,
+            &store,
         )?;
 
         let (input_objects, receiving_objects) = store.read_objects_for_synchronous_execution(
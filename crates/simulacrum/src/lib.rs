@@ -10,13 +10,15 @@
 //!
 //! [`Simulacrum`]: crate::Simulacrum
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::num::NonZeroUsize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
 use fastcrypto::traits::Signer;
 use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
 use sui_config::verifier_signing_config::VerifierSigningConfig;
 use sui_config::{genesis, transaction_deny_config::TransactionDenyConfig};
 use sui_protocol_config::ProtocolVersion;
@@ -27,9 +29,11 @@ use sui_swarm_config::network_config_builder::ConfigBuilder;
 use sui_types::base_types::{AuthorityName, ObjectID, VersionNumber};
 use sui_types::crypto::AuthoritySignature;
 use sui_types::digests::ConsensusCommitDigest;
-use sui_types::object::Object;
+use sui_types::gas::GasCostSummary;
+use sui_types::object::{Object, Owner};
 use sui_types::storage::{ObjectStore, ReadStore, RpcStateReader};
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemState;
+use sui_types::sui_system_state::SuiSystemState;
 use sui_types::transaction::EndOfEpochTransactionKind;
 use sui_types::{
     base_types::SuiAddress,
@@ -47,7 +51,10 @@ use self::epoch_state::EpochState;
 pub use self::store::in_mem_store::InMemoryStore;
 use self::store::in_mem_store::KeyStore;
 pub use self::store::SimulatorStore;
-use sui_types::messages_checkpoint::{CheckpointContents, CheckpointSequenceNumber};
+use self::store::StoreContents;
+use sui_types::messages_checkpoint::{
+    CheckpointContents, CheckpointSequenceNumber, CheckpointSummary,
+};
 use sui_types::mock_checkpoint_builder::{MockCheckpointBuilder, ValidatorKeypairProvider};
 use sui_types::{
     gas_coin::GasCoin,
@@ -70,7 +77,6 @@ pub mod store;
 pub struct Simulacrum<R = OsRng, Store: SimulatorStore = InMemoryStore> {
     rng: R,
     keystore: KeyStore,
-    #[allow(unused)]
     genesis: genesis::Genesis,
     store: Store,
     checkpoint_builder: MockCheckpointBuilder,
@@ -82,6 +88,34 @@ pub struct Simulacrum<R = OsRng, Store: SimulatorStore = InMemoryStore> {
     deny_config: TransactionDenyConfig,
     data_ingestion_path: Option<PathBuf>,
     verifier_signing_config: VerifierSigningConfig,
+
+    // When forked from existing state, the committee of local validators that replaces the
+    // committee found in the system state in every epoch.
+    fork_committee: Option<Committee>,
+}
+
+/// A snapshot of the full state of a [`Simulacrum`], taken with [`Simulacrum::save_state`] and
+/// turned back into a running `Simulacrum` with [`Simulacrum::restore`].
+#[derive(Serialize, Deserialize)]
+pub struct SimulacrumState {
+    store: StoreContents,
+    genesis: genesis::Genesis,
+    keystore: KeyStore,
+    next_consensus_round: u64,
+    fork_committee: Option<Committee>,
+}
+
+impl SimulacrumState {
+    pub fn write_to_file(&self, path: &Path) -> Result<()> {
+        let bytes = bcs::to_bytes(self)?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    pub fn read_from_file(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
 }
 
 impl Simulacrum {
@@ -135,6 +169,48 @@ where
         Self::new_with_network_config_in_mem(&config, rng)
     }
 
+    /// Create a new Simulacrum whose state is forked from `objects`, a live object set such as the
+    /// one contained in a formal state snapshot.
+    ///
+    /// See [`Simulacrum::fork_with_network_config_store`] for details on how the fork is set up.
+    ///
+    /// A DB checkpoint holds the same live object set in its perpetual tables, so forking from one
+    /// goes through this function too, with the objects of
+    /// `AuthorityPerpetualTables::open(&checkpoint_dir.join("store"), None).iter_live_object_set(false)`.
+    ///
+    /// ```ignore
+    /// use sui_snapshot::reader::StateSnapshotReaderV1;
+    ///
+    /// let mut reader = StateSnapshotReaderV1::new(epoch, &remote, &local, usize::MAX, concurrency, m).await?;
+    /// let (_abort_handle, abort_registration) = futures::future::AbortHandle::new_pair();
+    /// let objects = reader.read_live_objects(abort_registration).await?;
+    /// let simulacrum = Simulacrum::new_from_live_objects(
+    ///     OsRng,
+    ///     objects.into_iter().filter_map(|object| object.to_normal()),
+    /// );
+    /// ```
+    pub fn new_from_live_objects(mut rng: R, objects: impl IntoIterator<Item = Object>) -> Self {
+        let config = ConfigBuilder::new_with_temp_dir()
+            .rng(&mut rng)
+            .with_chain_start_timestamp_ms(1)
+            .deterministic_committee_size(NonZeroUsize::new(1).unwrap())
+            .build();
+        Self::fork_with_network_config_store(&config, rng, InMemoryStore::default(), objects)
+    }
+
+    /// Restore a Simulacrum, backed by an in-memory store, from a previously saved `state`.
+    ///
+    /// See [`Simulacrum::restore_with_store`] for details.
+    pub fn restore(state: SimulacrumState, rng: R) -> Self {
+        Self::restore_with_store(state, rng, InMemoryStore::default())
+    }
+
+    /// The latest version of every live object, e.g. to write them out as a formal snapshot or
+    /// fork another Simulacrum from them.
+    pub fn live_objects(&self) -> impl Iterator<Item = &Object> {
+        self.store.live_objects()
+    }
+
     fn new_with_network_config_in_mem(config: &NetworkConfig, rng: R) -> Self {
         let store = InMemoryStore::new(&config.genesis);
         Self::new_with_network_config_store(config, rng, store)
@@ -159,6 +235,161 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
             deny_config: TransactionDenyConfig::default(),
             verifier_signing_config: VerifierSigningConfig::default(),
             data_ingestion_path: None,
+            fork_committee: None,
+        }
+    }
+
+    /// Create a Simulacrum whose state is forked from `objects`, instead of starting from the
+    /// genesis in `config`. `store` is expected to be empty.
+    ///
+    /// The system state, clock, packages and every other object are taken from `objects`, so the
+    /// fork executes transactions against the same state, epoch and protocol version as the chain
+    /// it was taken from. The keys of that chain's validators are not available, so checkpoints
+    /// are signed by the validators in `config` instead, whose committee replaces the one in the
+    /// system state in every epoch. The fork starts with an empty checkpoint 0 at the forked
+    /// epoch.
+    ///
+    /// The genesis gas coins of the accounts in `config` are added to the forked state (unless an
+    /// object with the same ID is already part of it), so that transactions can be sent from the
+    /// fork.
+    pub fn fork_with_network_config_store(
+        config: &NetworkConfig,
+        rng: R,
+        mut store: S,
+        objects: impl IntoIterator<Item = Object>,
+    ) -> Self {
+        let keystore = KeyStore::from_network_config(config);
+        let genesis = &config.genesis;
+
+        let accounts: BTreeSet<SuiAddress> = keystore.accounts().map(|(a, _)| *a).collect();
+        let mut written: BTreeMap<ObjectID, Object> = genesis
+            .objects()
+            .iter()
+            .filter(|object| {
+                matches!(object.owner, Owner::AddressOwner(owner) if accounts.contains(&owner))
+            })
+            .map(|object| (object.id(), object.clone()))
+            .collect();
+        written.extend(objects.into_iter().map(|object| (object.id(), object)));
+        store.update_objects(written, vec![]);
+
+        let fork_committee = genesis.committee().unwrap();
+        let epoch_state = Self::epoch_state_for(store.get_system_state(), Some(&fork_committee));
+        store.insert_committee(epoch_state.committee().clone());
+
+        // An empty checkpoint to build the forked chain on
+        let contents =
+            CheckpointContents::new_with_causally_ordered_execution_data(std::iter::empty());
+        let summary = CheckpointSummary::new(
+            epoch_state.protocol_config(),
+            epoch_state.epoch(),
+            0,
+            0,
+            &contents,
+            None,
+            GasCostSummary::default(),
+            None,
+            store.get_clock().timestamp_ms(),
+            vec![],
+        );
+        let checkpoint = MockCheckpointBuilder::create_certified_checkpoint(
+            &CommitteeWithKeys::new(&keystore, epoch_state.committee()),
+            summary,
+        );
+        store.insert_checkpoint(checkpoint.clone());
+        store.insert_checkpoint_contents(contents);
+        let checkpoint_builder = MockCheckpointBuilder::new(checkpoint);
+
+        Self {
+            rng,
+            keystore,
+            genesis: genesis.clone(),
+            store,
+            checkpoint_builder,
+            epoch_state,
+            deny_config: TransactionDenyConfig::default(),
+            verifier_signing_config: VerifierSigningConfig::default(),
+            data_ingestion_path: None,
+            fork_committee: Some(fork_committee),
+        }
+    }
+
+    /// Restore a Simulacrum from a previously saved `state`, loading it into `store`, which is
+    /// expected to be empty.
+    ///
+    /// The restored Simulacrum continues from where the saved one left off: the next checkpoint,
+    /// epoch, clock and consensus round are the same as they would have been in the original.
+    /// Settings that are not part of the chain's state, such as the data ingestion path, are not
+    /// restored.
+    pub fn restore_with_store(state: SimulacrumState, rng: R, mut store: S) -> Self {
+        let SimulacrumState {
+            store: contents,
+            genesis,
+            keystore,
+            next_consensus_round,
+            fork_committee,
+        } = state;
+
+        store.load_contents(contents);
+        let checkpoint_builder = MockCheckpointBuilder::new(
+            store
+                .get_highest_checkpint()
+                .expect("saved state contains at least one checkpoint"),
+        );
+        let epoch_state = Self::epoch_state_for(store.get_system_state(), fork_committee.as_ref())
+            .with_next_consensus_round(next_consensus_round);
+
+        Self {
+            rng,
+            keystore,
+            genesis,
+            store,
+            checkpoint_builder,
+            epoch_state,
+            deny_config: TransactionDenyConfig::default(),
+            verifier_signing_config: VerifierSigningConfig::default(),
+            data_ingestion_path: None,
+            fork_committee,
+        }
+    }
+
+    /// Save the full state of this Simulacrum, so that it can later be restored with
+    /// [`Simulacrum::restore`].
+    ///
+    /// Transactions executed since the last checkpoint are not part of the saved state, so this
+    /// fails unless they have been included in a checkpoint with
+    /// [`Simulacrum::create_checkpoint`] first.
+    pub fn save_state(&self) -> Result<SimulacrumState> {
+        if self.checkpoint_builder.size() > 0 {
+            return Err(anyhow!(
+                "{} transactions have not been checkpointed yet, create a checkpoint before saving",
+                self.checkpoint_builder.size()
+            ));
+        }
+
+        Ok(SimulacrumState {
+            store: self.store.contents(),
+            genesis: self.genesis.clone(),
+            keystore: self.keystore.copy(),
+            next_consensus_round: self.epoch_state.peek_next_consensus_round(),
+            fork_committee: self.fork_committee.clone(),
+        })
+    }
+
+    /// Build the state of the epoch described by `system_state`, signing with the validators of
+    /// `fork_committee` instead of those in the system state if given.
+    fn epoch_state_for(
+        system_state: SuiSystemState,
+        fork_committee: Option<&Committee>,
+    ) -> EpochState {
+        let epoch_state = EpochState::new(system_state);
+        match fork_committee {
+            Some(committee) => {
+                let voting_rights = committee.voting_rights.iter().cloned().collect();
+                let committee = Committee::new(epoch_state.epoch(), voting_rights);
+                epoch_state.with_committee(committee)
+            }
+            None => epoch_state,
         }
     }
 
@@ -282,7 +513,8 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
         self.execute_transaction(tx.into())
             .expect("advancing the epoch cannot fail");
 
-        let new_epoch_state = EpochState::new(self.store.get_system_state());
+        let new_epoch_state =
+            Self::epoch_state_for(self.store.get_system_state(), self.fork_committee.as_ref());
         let end_of_epoch_data = EndOfEpochData {
             next_epoch_committee: new_epoch_state.committee().voting_rights.clone(),
             next_epoch_protocol_version,
@@ -584,7 +816,8 @@ impl Simulacrum {
         let object = self
             .store()
             .owned_objects(sender)
-            .find(|object| object.is_gas_coin())
+            .filter(|object| object.is_gas_coin())
+            .min_by_key(|object| object.id())
             .unwrap();
         let gas_coin = GasCoin::try_from(&object).unwrap();
         let transfer_amount = gas_coin.value() / 2;
@@ -615,6 +848,7 @@ mod tests {
     use rand::{rngs::StdRng, SeedableRng};
     use sui_types::{
         base_types::SuiAddress, effects::TransactionEffectsAPI, gas_coin::GasCoin,
+        sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait,
         transaction::TransactionDataAPI,
     };
 
@@ -686,6 +920,125 @@ mod tests {
         dbg!(chain.store().get_highest_checkpint());
     }
 
+    #[test]
+    fn fork_from_live_objects() {
+        let mut source = Simulacrum::new();
+        source.advance_epoch(/* create_random_state */ false);
+        source.advance_epoch(/* create_random_state */ false);
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let (tx, transfer_amount) = source.transfer_txn(recipient);
+        source.execute_transaction(tx).unwrap();
+        source.create_checkpoint();
+
+        let mut fork =
+            Simulacrum::new_from_live_objects(OsRng, source.store.live_objects().cloned());
+
+        // The fork continues from the source's state
+        assert_eq!(fork.epoch_start_state().epoch(), 2);
+        assert_eq!(
+            fork.store().get_clock().timestamp_ms(),
+            source.store().get_clock().timestamp_ms()
+        );
+        assert_eq!(
+            transfer_amount,
+            fork.store()
+                .owned_objects(recipient)
+                .next()
+                .and_then(|object| GasCoin::try_from(&object).ok())
+                .unwrap()
+                .value()
+        );
+
+        // ...but is signed by its own validators
+        let checkpoint = fork.store().get_highest_checkpint().unwrap();
+        assert_eq!(checkpoint.sequence_number, 0);
+        assert_eq!(checkpoint.epoch, 2);
+        assert_ne!(
+            fork.store().get_committee_by_epoch(2),
+            source.store().get_committee_by_epoch(2)
+        );
+
+        // Transactions can be sent from the fork's own accounts
+        let (tx, _) = fork.transfer_txn(recipient);
+        let effects = fork.execute_transaction(tx).unwrap().0;
+        assert!(effects.status().is_ok());
+        fork.create_checkpoint();
+        assert_eq!(fork.store().owned_objects(recipient).count(), 2);
+
+        fork.advance_epoch(/* create_random_state */ false);
+        let checkpoint = fork.store().get_highest_checkpint().unwrap();
+        assert_eq!(checkpoint.epoch, 2);
+        assert!(checkpoint.end_of_epoch_data.is_some());
+        assert_eq!(fork.epoch_start_state().epoch(), 3);
+        assert_eq!(
+            fork.store()
+                .get_committee_by_epoch(3)
+                .unwrap()
+                .voting_rights,
+            fork.store()
+                .get_committee_by_epoch(2)
+                .unwrap()
+                .voting_rights
+        );
+    }
+
+    #[test]
+    fn save_and_restore() {
+        let mut sim = Simulacrum::new();
+        let recipient = SuiAddress::random_for_testing_only();
+        let (tx, _) = sim.transfer_txn(recipient);
+        sim.execute_transaction(tx).unwrap();
+        sim.advance_epoch(/* create_random_state */ false);
+        sim.advance_clock(Duration::from_millis(1000));
+
+        // Pending transactions have to be checkpointed first
+        assert!(sim.save_state().is_err());
+        sim.create_checkpoint();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.bcs");
+        sim.save_state().unwrap().write_to_file(&path).unwrap();
+        let state = SimulacrumState::read_from_file(&path).unwrap();
+        let mut restored = Simulacrum::restore(state, OsRng);
+
+        assert_eq!(
+            restored.store().get_highest_checkpint().unwrap().digest(),
+            sim.store().get_highest_checkpint().unwrap().digest()
+        );
+        assert_eq!(restored.epoch_start_state().epoch(), 1);
+        assert_eq!(
+            restored.store().get_clock().timestamp_ms(),
+            sim.store().get_clock().timestamp_ms()
+        );
+        let owned_refs = |chain: &Simulacrum| {
+            let mut refs: Vec<_> = chain
+                .store()
+                .owned_objects(recipient)
+                .map(|object| object.compute_object_reference())
+                .collect();
+            refs.sort();
+            refs
+        };
+        assert_eq!(owned_refs(&restored), owned_refs(&sim));
+
+        // Both chains continue identically from the saved state
+        let mut payments = vec![];
+        for chain in [&mut sim, &mut restored] {
+            chain.advance_clock(Duration::from_millis(1000));
+            let (tx, _) = chain.transfer_txn(recipient);
+            payments.push(tx.data().transaction_data().gas_data().payment.clone());
+            chain.execute_transaction(tx).unwrap();
+            chain.create_checkpoint();
+        }
+        assert_eq!(payments[0], payments[1]);
+        assert_eq!(
+            restored.store().get_highest_checkpint().unwrap().digest(),
+            sim.store().get_highest_checkpint().unwrap().digest()
+        );
+        assert_eq!(owned_refs(&restored), owned_refs(&sim));
+    }
+
     #[test]
     fn transfer() {
         let mut sim = Simulacrum::new();
@@ -1,9 +1,11 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use fastcrypto::traits::EncodeDecodeBase64;
 use move_binary_format::CompiledModule;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::{BTreeMap, HashMap};
 use sui_config::genesis;
 use sui_types::storage::{get_module, load_package_object_from_object_store, PackageObject};
@@ -23,7 +25,7 @@ use sui_types::{
     transaction::VerifiedTransaction,
 };
 
-use super::SimulatorStore;
+use super::{SimulatorStore, StoreContents};
 
 #[derive(Debug, Default)]
 pub struct InMemoryStore {
@@ -40,7 +42,7 @@ pub struct InMemoryStore {
     events_tx_digest_index: HashMap<TransactionDigest, TransactionEventsDigest>,
 
     // Committee data
-    epoch_to_committee: Vec<Committee>,
+    epoch_to_committee: BTreeMap<EpochId, Committee>,
 
     // Object data
     live_objects: HashMap<ObjectID, SequenceNumber>,
@@ -84,7 +86,7 @@ impl InMemoryStore {
     }
 
     pub fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<&Committee> {
-        self.epoch_to_committee.get(epoch as usize)
+        self.epoch_to_committee.get(&epoch)
     }
     pub fn get_transaction(&self, digest: &TransactionDigest) -> Option<&VerifiedTransaction> {
         self.transactions.get(digest)
@@ -126,6 +128,13 @@ impl InMemoryStore {
             .expect("clock object should deserialize")
     }
 
+    /// The latest version of every live object, e.g. to fork another Simulacrum from this state.
+    pub fn live_objects(&self) -> impl Iterator<Item = &Object> {
+        self.live_objects
+            .iter()
+            .flat_map(|(id, version)| self.get_object_at_version(id, *version))
+    }
+
     pub fn owned_objects(&self, owner: SuiAddress) -> impl Iterator<Item = &Object> {
         self.live_objects
             .iter()
@@ -161,16 +170,21 @@ impl InMemoryStore {
     }
 
     pub fn insert_committee(&mut self, committee: Committee) {
-        let epoch = committee.epoch as usize;
+        let epoch = committee.epoch;
 
-        if self.epoch_to_committee.get(epoch).is_some() {
+        if self.epoch_to_committee.contains_key(&epoch) {
             return;
         }
 
-        if self.epoch_to_committee.len() == epoch {
-            self.epoch_to_committee.push(committee);
-        } else {
-            panic!("committee was inserted into EpochCommitteeMap out of order");
+        // The first committee may be for any epoch (a store forked from existing state does not
+        // start at epoch 0), but after that committees must be inserted in order.
+        match self.epoch_to_committee.last_key_value() {
+            Some((last, _)) if *last + 1 != epoch => {
+                panic!("committee was inserted into EpochCommitteeMap out of order");
+            }
+            _ => {
+                self.epoch_to_committee.insert(epoch, committee);
+            }
         }
     }
 
@@ -370,6 +384,78 @@ impl KeyStore {
     pub fn accounts(&self) -> impl Iterator<Item = (&SuiAddress, &AccountKeyPair)> {
         self.account_keys.iter()
     }
+
+    /// Copy every key pair held by this keystore into a new one.
+    pub fn copy(&self) -> Self {
+        use fastcrypto::traits::KeyPair;
+
+        Self {
+            validator_keys: self
+                .validator_keys
+                .iter()
+                .map(|(name, key)| (*name, key.copy()))
+                .collect(),
+            account_keys: self
+                .account_keys
+                .iter()
+                .map(|(address, key)| (*address, key.copy()))
+                .collect(),
+        }
+    }
+}
+
+/// Serialized form of a [`KeyStore`], holding every key pair encoded as base64.
+#[derive(Serialize, Deserialize)]
+struct EncodedKeyStore {
+    validator_keys: Vec<String>,
+    account_keys: Vec<String>,
+}
+
+impl Serialize for KeyStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        EncodedKeyStore {
+            validator_keys: self
+                .validator_keys
+                .values()
+                .map(|key| key.encode_base64())
+                .collect(),
+            account_keys: self
+                .account_keys
+                .values()
+                .map(|key| key.encode_base64())
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use fastcrypto::traits::KeyPair;
+
+        let encoded = EncodedKeyStore::deserialize(deserializer)?;
+        let validator_keys = encoded
+            .validator_keys
+            .iter()
+            .map(|key| {
+                let key = AuthorityKeyPair::decode_base64(key).map_err(de::Error::custom)?;
+                Ok((key.public().into(), key))
+            })
+            .collect::<Result<_, D::Error>>()?;
+        let account_keys = encoded
+            .account_keys
+            .iter()
+            .map(|key| {
+                let key = AccountKeyPair::decode_base64(key).map_err(de::Error::custom)?;
+                Ok((key.public().into(), key))
+            })
+            .collect::<Result<_, D::Error>>()?;
+
+        Ok(Self {
+            validator_keys,
+            account_keys,
+        })
+    }
 }
 
 impl SimulatorStore for InMemoryStore {
@@ -490,4 +576,39 @@ impl SimulatorStore for InMemoryStore {
     fn backing_store(&self) -> &dyn sui_types::storage::BackingStore {
         self
     }
+
+    fn contents(&self) -> StoreContents {
+        StoreContents {
+            checkpoints: self
+                .checkpoints
+                .values()
+                .map(|checkpoint| checkpoint.serializable_ref().clone())
+                .collect(),
+            checkpoint_contents: self.checkpoint_contents.values().cloned().collect(),
+            committees: self.epoch_to_committee.values().cloned().collect(),
+            transactions: self
+                .transactions
+                .values()
+                .map(|transaction| transaction.serializable_ref().clone())
+                .collect(),
+            effects: self.effects.values().cloned().collect(),
+            events: self
+                .events_tx_digest_index
+                .iter()
+                .filter_map(|(tx_digest, events_digest)| {
+                    Some((*tx_digest, self.events.get(events_digest)?.clone()))
+                })
+                .collect(),
+            objects: self
+                .objects
+                .values()
+                .flat_map(|versions| versions.values().cloned())
+                .collect(),
+            live_objects: self
+                .live_objects
+                .iter()
+                .map(|(id, version)| (*id, *version))
+                .collect(),
+        }
+    }
 }
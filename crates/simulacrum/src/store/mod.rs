@@ -1,7 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use sui_config::genesis;
 use sui_types::base_types::ObjectRef;
 use sui_types::error::UserInputError;
@@ -17,14 +18,31 @@ use sui_types::{
     error::SuiResult,
     messages_checkpoint::{
         CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
-        VerifiedCheckpoint,
+        TrustedCheckpoint, VerifiedCheckpoint,
     },
     object::Object,
     storage::{BackingStore, ChildObjectResolver, ParentSync},
-    transaction::{InputObjectKind, VerifiedTransaction},
+    transaction::{InputObjectKind, TrustedTransaction, VerifiedTransaction},
 };
 pub mod in_mem_store;
 
+/// The full contents of a [`SimulatorStore`], in a form that can be serialized and loaded into
+/// another store with [`SimulatorStore::load_contents`].
+#[derive(Default, Serialize, Deserialize)]
+pub struct StoreContents {
+    pub checkpoints: Vec<TrustedCheckpoint>,
+    pub checkpoint_contents: Vec<CheckpointContents>,
+    /// Committees, in epoch order.
+    pub committees: Vec<Committee>,
+    pub transactions: Vec<TrustedTransaction>,
+    pub effects: Vec<TransactionEffects>,
+    pub events: Vec<(TransactionDigest, TransactionEvents)>,
+    /// Every stored version of every object.
+    pub objects: Vec<Object>,
+    /// The live version of every object that currently exists.
+    pub live_objects: Vec<(ObjectID, SequenceNumber)>,
+}
+
 pub trait SimulatorStore:
     sui_types::storage::BackingPackageStore
     + sui_types::storage::ObjectStore
@@ -120,6 +138,63 @@ pub trait SimulatorStore:
 
     fn backing_store(&self) -> &dyn BackingStore;
 
+    /// Export everything held by this store.
+    fn contents(&self) -> StoreContents;
+
+    /// Load `contents`, exported from another store, into this (empty) store.
+    fn load_contents(&mut self, contents: StoreContents) {
+        let StoreContents {
+            checkpoints,
+            checkpoint_contents,
+            committees,
+            transactions,
+            effects,
+            events,
+            mut objects,
+            live_objects,
+        } = contents;
+
+        for committee in committees {
+            self.insert_committee(committee);
+        }
+        for checkpoint in checkpoints {
+            self.insert_checkpoint(checkpoint.into());
+        }
+        for contents in checkpoint_contents {
+            self.insert_checkpoint_contents(contents);
+        }
+        for transaction in transactions {
+            self.insert_transaction(transaction.into());
+        }
+        for effects in effects {
+            self.insert_transaction_effects(effects);
+        }
+        for (tx_digest, events) in events {
+            self.insert_events(&tx_digest, events);
+        }
+
+        // Write every version of each object in version order, which leaves the latest version
+        // live, and then remove the objects that are no longer live.
+        objects.sort_by_key(|object| object.version());
+        let mut ids = BTreeSet::new();
+        for object in objects {
+            ids.insert(object.id());
+            self.update_objects(BTreeMap::from([(object.id(), object)]), vec![]);
+        }
+
+        for (id, _) in &live_objects {
+            ids.remove(id);
+        }
+        let removed = ids
+            .into_iter()
+            .map(|id| {
+                let version = SimulatorStore::get_object(self, &id).unwrap().version();
+                (id, version, ObjectDigest::OBJECT_DIGEST_DELETED)
+            })
+            .collect();
+        self.update_objects(BTreeMap::new(), removed);
+    }
+
     // TODO: This function is now out-of-sync with read_objects_for_execution from transaction_input_loader.rs.
     // For instance, it does not support the use of deleted shared objects.
     // We will need to make SimulatorStore implement ExecutionCacheRead, and keep track of deleted shared objects
@@ -98,7 +98,7 @@ const MAGIC_BYTES: usize = 4;
 const CHECKPOINT_FILE_SUFFIX: &str = "chk";
 const SUMMARY_FILE_SUFFIX: &str = "sum";
 const EPOCH_DIR_PREFIX: &str = "epoch_";
-const MANIFEST_FILENAME: &str = "MANIFEST";
+pub const MANIFEST_FILENAME: &str = "MANIFEST";
 
 #[derive(
     Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, TryFromPrimitive, IntoPrimitive,
@@ -102,7 +102,16 @@ where
         trace!("Reading config from {}", path.display());
         let reader = fs::File::open(path)
             .with_context(|| format!("Unable to load config from {}", path.display()))?;
-        Ok(serde_yaml::from_reader(reader)?)
+        let config: Self = serde_yaml::from_reader(reader)?;
+        config
+            .validate()
+            .with_context(|| format!("Invalid config in {}", path.display()))?;
+        Ok(config)
+    }
+
+    /// Checks made when the config is loaded, beyond what deserializing it covers.
+    fn validate(&self) -> Result<(), anyhow::Error> {
+        Ok(())
     }
 
     fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), anyhow::Error> {
@@ -518,7 +518,12 @@ fn is_true(value: &bool) -> bool {
     *value
 }
 
-impl Config for NodeConfig {}
+impl Config for NodeConfig {
+    fn validate(&self) -> Result<()> {
+        self.transaction_deny_config.reload_rules()?;
+        Ok(())
+    }
+}
 
 impl NodeConfig {
     pub fn protocol_key_pair(&self) -> &AuthorityKeyPair {
@@ -2,10 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::HashSet;
-
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context};
+use arc_swap::ArcSwap;
+use move_core_types::language_storage::StructTag;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::parse_sui_struct_tag;
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
@@ -71,6 +80,20 @@ pub struct TransactionDenyConfig {
     /// A list of disabled OAuth providers for zkLogin
     #[serde(default)]
     zklogin_disabled_providers: HashSet<String>,
+
+    /// Ordered list of rules, applied after all the checks above. The first rule matching a
+    /// transaction decides whether it is allowed, and transactions matching no rule are allowed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    rules: Vec<TransactionRule>,
+
+    /// A YAML file with a list of rules to use instead of `rules`. The file is read again on
+    /// every call to `reload_rules`, so that rules can be changed without restarting the node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rules_file: Option<PathBuf>,
+
+    /// The rules currently in effect, initialized from `rules_file` or `rules` on first use.
+    #[serde(skip)]
+    active_rules: OnceCell<Arc<ArcSwap<Vec<TransactionRule>>>>,
     // TODO: We could consider add a deny list for types that we want to disable public transfer.
     // TODO: We could also consider disable more types of commands, such as transfer, split and etc.
 }
@@ -118,6 +141,200 @@ impl TransactionDenyConfig {
     pub fn zklogin_disabled_providers(&self) -> &HashSet<String> {
         &self.zklogin_disabled_providers
     }
+
+    /// The rules currently in effect.
+    pub fn rules(&self) -> Arc<Vec<TransactionRule>> {
+        self.active_rules().load_full()
+    }
+
+    /// Read the rules from `rules_file` again, or go back to `rules` if there is no rules file.
+    /// Returns the number of rules now in effect. The rules in effect are left unchanged if the
+    /// file can't be read.
+    ///
+    /// This is also how the rules file is first loaded, when the node config is loaded and when
+    /// the node starts, so that a broken rules file is reported there.
+    pub fn reload_rules(&self) -> anyhow::Result<usize> {
+        let rules = self.read_rules()?;
+        let count = rules.len();
+        self.set_rules(rules);
+        Ok(count)
+    }
+
+    /// Replace the rules in effect, until they are reloaded.
+    pub fn set_rules(&self, rules: Vec<TransactionRule>) {
+        let rules = Arc::new(rules);
+        self.active_rules
+            .get_or_init(|| Arc::new(ArcSwap::new(rules.clone())))
+            .store(rules);
+    }
+
+    fn read_rules(&self) -> anyhow::Result<Vec<TransactionRule>> {
+        match &self.rules_file {
+            Some(path) => read_rules_file(path),
+            None => Ok(self.rules.clone()),
+        }
+    }
+
+    fn active_rules(&self) -> &ArcSwap<Vec<TransactionRule>> {
+        self.active_rules.get_or_init(|| {
+            // Configs loaded from a file or used by a node have their rules file loaded already.
+            let rules = self
+                .read_rules()
+                .unwrap_or_else(|err| panic!("Invalid transaction rules: {err:?}"));
+            Arc::new(ArcSwap::from_pointee(rules))
+        })
+    }
+}
+
+fn read_rules_file(path: &Path) -> anyhow::Result<Vec<TransactionRule>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read transaction rules from {}", path.display()))?;
+    serde_yaml::from_str(&contents)
+        .with_context(|| format!("Failed to parse transaction rules in {}", path.display()))
+}
+
+/// A rule allowing or denying the transactions that match all of its conditions. Conditions that
+/// are not set match every transaction, and list conditions match if any of their entries does.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct TransactionRule {
+    /// Reported in the error of transactions denied by the rule.
+    pub name: String,
+
+    pub action: RuleAction,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub senders: Vec<SuiAddress>,
+
+    /// Move functions called by the transaction.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub move_call_targets: Vec<MoveCallTarget>,
+
+    /// Types of the objects the transaction takes as input, including gas coins.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_object_types: Vec<ObjectTypeFilter>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_gas_budget: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_gas_budget: Option<u64>,
+
+    /// Kinds of commands in the programmable transaction.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub command_kinds: Vec<CommandKind>,
+}
+
+impl TransactionRule {
+    pub fn new(name: impl Into<String>, action: RuleAction) -> Self {
+        Self {
+            name: name.into(),
+            action,
+            senders: vec![],
+            move_call_targets: vec![],
+            input_object_types: vec![],
+            min_gas_budget: None,
+            max_gas_budget: None,
+            command_kinds: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RuleAction {
+    Allow,
+    Deny,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommandKind {
+    MoveCall,
+    TransferObjects,
+    SplitCoins,
+    MergeCoins,
+    Publish,
+    MakeMoveVec,
+    Upgrade,
+}
+
+/// A Move function, or all functions of a module or package, written as
+/// `package[::module[::function]]`.
+#[derive(Clone, Debug, PartialEq, Eq, SerializeDisplay, DeserializeFromStr)]
+pub struct MoveCallTarget {
+    pub package: ObjectID,
+    pub module: Option<String>,
+    pub function: Option<String>,
+}
+
+impl MoveCallTarget {
+    pub fn matches(&self, package: &ObjectID, module: &str, function: &str) -> bool {
+        self.package == *package
+            && self.module.as_deref().map_or(true, |m| m == module)
+            && self.function.as_deref().map_or(true, |f| f == function)
+    }
+}
+
+impl FromStr for MoveCallTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split("::");
+        let package = ObjectID::from_str(parts.next().unwrap_or_default())?;
+        let module = parts.next().map(str::to_owned);
+        let function = parts.next().map(str::to_owned);
+        if parts.next().is_some() {
+            return Err(anyhow!("Invalid Move call target: {s}"));
+        }
+        Ok(Self {
+            package,
+            module,
+            function,
+        })
+    }
+}
+
+impl fmt::Display for MoveCallTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.package)?;
+        if let Some(module) = &self.module {
+            write!(f, "::{module}")?;
+        }
+        if let Some(function) = &self.function {
+            write!(f, "::{function}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A Move struct type, such as `0x2::coin::Coin<0x2::sui::SUI>`. Types written without type
+/// parameters match all instantiations of the type.
+#[derive(Clone, Debug, PartialEq, Eq, SerializeDisplay, DeserializeFromStr)]
+pub struct ObjectTypeFilter(pub StructTag);
+
+impl ObjectTypeFilter {
+    pub fn matches(&self, type_: &StructTag) -> bool {
+        let ObjectTypeFilter(filter) = self;
+        filter.address == type_.address
+            && filter.module == type_.module
+            && filter.name == type_.name
+            && (filter.type_params.is_empty() || filter.type_params == type_.type_params)
+    }
+}
+
+impl FromStr for ObjectTypeFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(parse_sui_struct_tag(s)?))
+    }
+}
+
+impl fmt::Display for ObjectTypeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_canonical_string(/* with_prefix */ true))
+    }
 }
 
 #[derive(Default)]
@@ -183,4 +400,14 @@ impl TransactionDenyConfigBuilder {
         self.config.zklogin_disabled_providers.insert(provider);
         self
     }
+
+    pub fn add_rule(mut self, rule: TransactionRule) -> Self {
+        self.config.rules.push(rule);
+        self
+    }
+
+    pub fn rules_file(mut self, path: PathBuf) -> Self {
+        self.config.rules_file = Some(path);
+        self
+    }
 }
@@ -876,6 +876,7 @@ impl AuthorityState {
             &receiving_objects_refs,
             &self.config.transaction_deny_config,
             self.get_backing_package_store().as_ref(),
+            self.get_object_store().as_ref(),
         )?;
 
         let (input_objects, receiving_objects) = self.input_loader.read_objects_for_signing(
@@ -1772,6 +1773,7 @@ impl AuthorityState {
             &receiving_object_refs,
             &self.config.transaction_deny_config,
             self.get_backing_package_store().as_ref(),
+            self.get_object_store().as_ref(),
         )?;
 
         let (input_objects, receiving_objects) = self.input_loader.read_objects_for_signing(
@@ -1958,6 +1960,7 @@ impl AuthorityState {
             &receiving_object_refs,
             &self.config.transaction_deny_config,
             self.get_backing_package_store().as_ref(),
+            self.get_object_store().as_ref(),
         )?;
 
         let (input_objects, receiving_objects) = self.input_loader.read_objects_for_signing(
@@ -2120,6 +2123,7 @@ impl AuthorityState {
             &receiving_object_refs,
             &self.config.transaction_deny_config,
             self.get_backing_package_store().as_ref(),
+            self.get_object_store().as_ref(),
         )?;
 
         let (mut input_objects, receiving_objects) = self.input_loader.read_objects_for_signing(
@@ -11,6 +11,7 @@ use sui_types::digests::TransactionEventsDigest;
 use sui_types::effects::TransactionEffects;
 use sui_types::storage::MarkerValue;
 use typed_store::metrics::SamplingInterval;
+use typed_store::migration::{Migration, MigrationRunner};
 use typed_store::rocks::util::{empty_compaction_filter, reference_count_merge_operator};
 use typed_store::rocks::{
     default_db_options, read_size_from_env, DBBatch, DBMap, DBMapTableConfigMap, DBOptions,
@@ -194,6 +195,21 @@ impl AuthorityPerpetualTables {
         )
     }
 
+    /// Schema migrations of the perpetual tables, in order of version. When adding one, bump the
+    /// `schema_version` of `AuthorityPerpetualTables` to its version.
+    pub fn migrations() -> Vec<Migration> {
+        vec![]
+    }
+
+    /// Brings the tables to the schema version this binary expects, applying pending migrations
+    /// on the open database and resuming an interrupted one. Newly created tables have nothing to
+    /// migrate, so they are recorded at the latest version. Tables written by a newer binary are
+    /// refused.
+    pub fn run_migrations(&self, is_empty: bool) -> Result<(), TypedStoreError> {
+        MigrationRunner::new(&self.objects.rocksdb, Self::migrations())?
+            .run_to_latest(Self::SCHEMA_VERSION, is_empty)
+    }
+
     // This is used by indexer to find the correct version of dynamic field child object.
     // We do not store the version of the child object, but because of lamport timestamp,
     // we know the child must have version number less then or eq to the parent.
@@ -375,19 +375,39 @@ impl ValidatorService {
         traffic_controller_metrics: TrafficControllerMetrics,
         policy_config: Option<PolicyConfig>,
         firewall_config: Option<RemoteFirewallConfig>,
+    ) -> Self {
+        let traffic_controller = policy_config.clone().map(|policy| {
+            Arc::new(TrafficController::init(
+                policy,
+                traffic_controller_metrics,
+                firewall_config,
+            ))
+        });
+        Self::new_with_traffic_controller(
+            state,
+            consensus_adapter,
+            validator_metrics,
+            traffic_controller,
+            policy_config.map(|policy| policy.client_id_source),
+        )
+    }
+
+    /// Like `new`, but shares an existing traffic controller rather than
+    /// spawning a new one, e.g. so that it can also be managed from the
+    /// admin interface.
+    pub fn new_with_traffic_controller(
+        state: Arc<AuthorityState>,
+        consensus_adapter: Arc<ConsensusAdapter>,
+        validator_metrics: Arc<ValidatorServiceMetrics>,
+        traffic_controller: Option<Arc<TrafficController>>,
+        client_id_source: Option<ClientIdSource>,
     ) -> Self {
         Self {
             state,
             consensus_adapter,
             metrics: validator_metrics,
-            traffic_controller: policy_config.clone().map(|policy| {
-                Arc::new(TrafficController::init(
-                    policy,
-                    traffic_controller_metrics,
-                    firewall_config,
-                ))
-            }),
-            client_id_source: policy_config.map(|policy| policy.client_id_source),
+            traffic_controller,
+            client_id_source,
         }
     }
 
@@ -1331,6 +1351,7 @@ impl ValidatorService {
     fn handle_traffic_resp<T>(
         &self,
         client: Option<IpAddr>,
+        request_type: &str,
         wrapped_response: WrappedServiceResponse<T>,
     ) -> Result<tonic::Response<T>, tonic::Status> {
         let (error, spam_weight, unwrapped_response) = match wrapped_response {
@@ -1352,6 +1373,7 @@ impl ValidatorService {
                     (error_weight, error_type)
                 }),
                 spam_weight,
+                request_type: Some(request_type.to_owned()),
                 timestamp: SystemTime::now(),
             })
         }
@@ -1402,9 +1424,11 @@ macro_rules! handle_with_decoration {
         // check if either IP is blocked, in which case return early
         $self.handle_traffic_req(client.clone()).await?;
 
-        // handle traffic tallying
+        // handle traffic tallying, using the handler name without
+        // its `_impl` suffix as the request type
         let wrapped_response = $self.$func_name($request).await;
-        $self.handle_traffic_resp(client, wrapped_response)
+        let request_type = stringify!($func_name).trim_end_matches("_impl");
+        $self.handle_traffic_resp(client, request_type, wrapped_response)
     }};
 }
 
@@ -0,0 +1,275 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// Value stored in the in-memory blocklists for each blocked client.
+#[derive(Clone, Debug)]
+pub struct BlockEntry {
+    pub expiry: SystemTime,
+    pub reason: String,
+    /// Whether the block was issued through the admin interface.
+    pub manual: bool,
+}
+
+/// A blocked client, as reported by the admin interface and as
+/// written to the persisted blocklist snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlocklistEntry {
+    pub ip: IpAddr,
+    /// Whether the block applies to the proxied client IP (e.g. taken from
+    /// a forwarding header) rather than the connection IP.
+    pub proxied: bool,
+    pub reason: String,
+    /// Unix timestamp in milliseconds at which the block expires.
+    pub expires_at_ms: u64,
+    /// Whether the block was issued through the admin interface.
+    #[serde(default)]
+    pub manual: bool,
+}
+
+impl BlocklistEntry {
+    pub fn new(ip: IpAddr, proxied: bool, entry: &BlockEntry) -> Self {
+        Self {
+            ip,
+            proxied,
+            reason: entry.reason.clone(),
+            expires_at_ms: to_unix_ms(entry.expiry),
+            manual: entry.manual,
+        }
+    }
+
+    pub fn block_entry(&self) -> BlockEntry {
+        BlockEntry {
+            expiry: from_unix_ms(self.expires_at_ms),
+            reason: self.reason.clone(),
+            manual: self.manual,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// Client blocked by the spam or error policy.
+    PolicyBlock,
+    /// Client blocked through the admin interface.
+    ManualBlock,
+    /// Client unblocked through the admin interface.
+    ManualUnblock,
+    /// Block restored from the persisted snapshot on startup.
+    Restore,
+}
+
+/// A single line of the blocklist audit log.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditRecord {
+    pub timestamp_ms: u64,
+    pub action: AuditAction,
+    #[serde(flatten)]
+    pub entry: BlocklistEntry,
+}
+
+/// Handles persistence of the blocklists across restarts, as well as
+/// the audit log of every change made to them. Both are optional, and
+/// all methods are no-ops for the parts that are not configured.
+pub struct BlocklistStore {
+    persistence_path: Option<PathBuf>,
+    audit_log_path: Option<PathBuf>,
+    /// Set whenever the blocklists change in a way that is not
+    /// immediately persisted, so that the next flush writes a snapshot.
+    dirty: AtomicBool,
+}
+
+impl BlocklistStore {
+    pub fn new(persistence_path: Option<PathBuf>, audit_log_path: Option<PathBuf>) -> Self {
+        Self {
+            persistence_path,
+            audit_log_path,
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Reads the persisted snapshot, skipping any blocks that expired while
+    /// the node was down. A missing or unreadable snapshot yields no entries.
+    pub fn load(&self) -> Vec<BlocklistEntry> {
+        let Some(path) = &self.persistence_path else {
+            return vec![];
+        };
+        if !path.exists() {
+            return vec![];
+        }
+        let entries: Vec<BlocklistEntry> = match fs::read(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| serde_json::from_slice(&bytes).map_err(anyhow::Error::from))
+        {
+            Ok(entries) => entries,
+            Err(err) => {
+                warn!(
+                    "Failed to load persisted blocklist from {:?}: {}",
+                    path, err
+                );
+                return vec![];
+            }
+        };
+        let now = to_unix_ms(SystemTime::now());
+        let entries: Vec<_> = entries
+            .into_iter()
+            .filter(|entry| entry.expires_at_ms > now)
+            .collect();
+        info!(
+            "Loaded {} active blocklist entries from {:?}",
+            entries.len(),
+            path
+        );
+        entries
+    }
+
+    pub fn mark_dirty(&self) {
+        if self.persistence_path.is_some() {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Writes a snapshot if the blocklists changed since the last write.
+    /// `snapshot` is only invoked when a write is actually needed.
+    pub fn persist_if_dirty(&self, snapshot: impl FnOnce() -> Vec<BlocklistEntry>) {
+        if self.dirty.swap(false, Ordering::Relaxed) {
+            self.persist(&snapshot());
+        }
+    }
+
+    /// Atomically replaces the persisted snapshot with `entries`.
+    pub fn persist(&self, entries: &[BlocklistEntry]) {
+        let Some(path) = &self.persistence_path else {
+            return;
+        };
+        self.dirty.store(false, Ordering::Relaxed);
+        let tmp_path = path.with_extension("tmp");
+        let result = serde_json::to_vec(entries)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| fs::write(&tmp_path, bytes).map_err(anyhow::Error::from))
+            .and_then(|()| fs::rename(&tmp_path, path).map_err(anyhow::Error::from));
+        if let Err(err) = result {
+            warn!("Failed to persist blocklist to {:?}: {}", path, err);
+            // Retry on the next flush
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn audit(&self, action: AuditAction, entry: BlocklistEntry) {
+        let Some(path) = &self.audit_log_path else {
+            return;
+        };
+        let record = AuditRecord {
+            timestamp_ms: to_unix_ms(SystemTime::now()),
+            action,
+            entry,
+        };
+        // The file is reopened on every write so that it can be rotated externally
+        let result = serde_json::to_string(&record)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| {
+                let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+                writeln!(file, "{}", line)?;
+                Ok(())
+            });
+        if let Err(err) = result {
+            warn!(
+                "Failed to write blocklist audit record {:?} to {:?}: {}",
+                record, path, err
+            );
+        }
+    }
+}
+
+pub fn to_unix_ms(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+pub fn from_unix_ms(ms: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn entry(last_octet: u8, proxied: bool, expiry: SystemTime) -> BlocklistEntry {
+        BlocklistEntry::new(
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, last_octet)),
+            proxied,
+            &BlockEntry {
+                expiry,
+                reason: "test".to_string(),
+                manual: false,
+            },
+        )
+    }
+
+    #[test]
+    fn test_persist_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blocklist.json");
+        let store = BlocklistStore::new(Some(path.clone()), None);
+        assert!(store.load().is_empty());
+
+        let now = SystemTime::now();
+        let active = entry(1, false, now + Duration::from_secs(60));
+        let active_proxied = entry(2, true, now + Duration::from_secs(60));
+        let expired = entry(3, false, now - Duration::from_secs(1));
+        store.persist(&[active.clone(), active_proxied.clone(), expired]);
+        assert!(!path.with_extension("tmp").exists());
+
+        // Expired entries are dropped on load
+        let reopened = BlocklistStore::new(Some(path), None);
+        assert_eq!(reopened.load(), vec![active, active_proxied]);
+    }
+
+    #[test]
+    fn test_persist_if_dirty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blocklist.json");
+        let store = BlocklistStore::new(Some(path.clone()), None);
+        let active = entry(1, false, SystemTime::now() + Duration::from_secs(60));
+
+        store.persist_if_dirty(|| panic!("snapshot taken while clean"));
+        assert!(!path.exists());
+
+        store.mark_dirty();
+        store.persist_if_dirty(|| vec![active.clone()]);
+        assert_eq!(store.load(), vec![active]);
+        store.persist_if_dirty(|| panic!("snapshot taken while clean"));
+    }
+
+    #[test]
+    fn test_audit_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let store = BlocklistStore::new(None, Some(path.clone()));
+        let blocked = entry(1, false, SystemTime::now() + Duration::from_secs(60));
+
+        store.audit(AuditAction::ManualBlock, blocked.clone());
+        store.audit(AuditAction::ManualUnblock, blocked.clone());
+
+        let records: Vec<AuditRecord> = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].action, AuditAction::ManualBlock);
+        assert_eq!(records[1].action, AuditAction::ManualUnblock);
+        assert!(records.iter().all(|record| record.entry == blocked));
+    }
+}
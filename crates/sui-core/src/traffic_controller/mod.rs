@@ -1,11 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod blocklist_store;
 pub mod metrics;
 pub mod nodefw_client;
 pub mod nodefw_test_server;
 pub mod policies;
 
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 use fs::File;
 use prometheus::IntGauge;
@@ -14,6 +16,7 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::ops::Add;
 use std::sync::Arc;
 
+use self::blocklist_store::{AuditAction, BlockEntry, BlocklistEntry, BlocklistStore};
 use self::metrics::TrafficControllerMetrics;
 use crate::traffic_controller::nodefw_client::{BlockAddress, BlockAddresses, NodeFWClient};
 use crate::traffic_controller::policies::{
@@ -31,12 +34,68 @@ use tracing::{debug, error, info, trace, warn};
 pub const METRICS_INTERVAL_SECS: u64 = 2;
 pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 300;
 
-type Blocklist = Arc<DashMap<IpAddr, SystemTime>>;
+type Blocklist = Arc<DashMap<IpAddr, BlockEntry>>;
 
 #[derive(Clone)]
 struct Blocklists {
     clients: Blocklist,
     proxied_clients: Blocklist,
+    store: Arc<BlocklistStore>,
+}
+
+impl Blocklists {
+    fn blocklist(&self, proxied: bool) -> &Blocklist {
+        if proxied {
+            &self.proxied_clients
+        } else {
+            &self.clients
+        }
+    }
+
+    /// Inserts or refreshes a block and records it in the audit log.
+    /// Returns true if the client was not already blocked.
+    fn insert(&self, ip: IpAddr, proxied: bool, entry: BlockEntry, action: AuditAction) -> bool {
+        let (newly_blocked, entry) = match self.blocklist(proxied).entry(ip) {
+            Entry::Occupied(mut existing) => {
+                // Policies may only extend a manual block, which keeps its reason
+                let entry = if action == AuditAction::PolicyBlock && existing.get().manual {
+                    BlockEntry {
+                        expiry: entry.expiry.max(existing.get().expiry),
+                        ..existing.get().clone()
+                    }
+                } else {
+                    entry
+                };
+                existing.insert(entry.clone());
+                (false, entry)
+            }
+            Entry::Vacant(vacant) => {
+                vacant.insert(entry.clone());
+                (true, entry)
+            }
+        };
+        let audit_entry = BlocklistEntry::new(ip, proxied, &entry);
+        if newly_blocked || action != AuditAction::PolicyBlock {
+            // Policies refresh the expiry of blocked clients that keep
+            // being tallied, which is not worth an audit record
+            self.store.audit(action, audit_entry);
+        }
+        newly_blocked
+    }
+
+    fn snapshot(&self) -> Vec<BlocklistEntry> {
+        let mut entries: Vec<_> = [false, true]
+            .into_iter()
+            .flat_map(|proxied| {
+                self.blocklist(proxied)
+                    .iter()
+                    .map(|item| BlocklistEntry::new(*item.key(), proxied, item.value()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        entries.sort_by_key(|entry| (entry.proxied, entry.ip));
+        entries
+    }
 }
 
 #[derive(Clone)]
@@ -125,7 +184,25 @@ impl TrafficController {
         let blocklists = Blocklists {
             clients: Arc::new(DashMap::new()),
             proxied_clients: Arc::new(DashMap::new()),
+            store: Arc::new(BlocklistStore::new(
+                policy_config.blocklist_persistence_path.clone(),
+                policy_config.blocklist_audit_log_path.clone(),
+            )),
         };
+        for restored in blocklists.store.load() {
+            blocklists.insert(
+                restored.ip,
+                restored.proxied,
+                restored.block_entry(),
+                AuditAction::Restore,
+            );
+        }
+        metrics
+            .connection_ip_blocklist_len
+            .set(blocklists.clients.len() as i64);
+        metrics
+            .proxy_ip_blocklist_len
+            .set(blocklists.proxied_clients.len() as i64);
         let tally_loop_blocklists = blocklists.clone();
         let clear_loop_blocklists = blocklists.clone();
         let tally_loop_metrics = metrics.clone();
@@ -239,6 +316,88 @@ impl TrafficController {
         self.dry_run_mode
     }
 
+    /// Returns all currently active blocks. Always empty when running
+    /// with an allowlist.
+    pub fn list_blocks(&self) -> Vec<BlocklistEntry> {
+        match &self.acl {
+            Acl::Allowlist(_) => vec![],
+            Acl::Blocklists(blocklists) => {
+                let now = SystemTime::now();
+                blocklists
+                    .snapshot()
+                    .into_iter()
+                    .filter(|entry| blocklist_store::from_unix_ms(entry.expires_at_ms) > now)
+                    .collect()
+            }
+        }
+    }
+
+    /// Manually blocks `ip` for `ttl`, replacing any existing block of
+    /// the same client. If `proxied` is set, the block applies to the
+    /// proxied client IP rather than the connection IP. The block is
+    /// persisted by the time this returns.
+    pub async fn block(
+        &self,
+        ip: IpAddr,
+        proxied: bool,
+        ttl: Duration,
+        reason: String,
+    ) -> anyhow::Result<BlocklistEntry> {
+        let Acl::Blocklists(blocklists) = &self.acl else {
+            anyhow::bail!("Cannot block clients when running with an allowlist");
+        };
+        let blocklists = blocklists.clone();
+        let metrics = self.metrics.clone();
+        // The audit log and snapshot are written off the async runtime
+        let blocked = tokio::task::spawn_blocking(move || {
+            let entry = BlockEntry {
+                expiry: SystemTime::now() + ttl,
+                reason,
+                manual: true,
+            };
+            let blocked = BlocklistEntry::new(ip, proxied, &entry);
+            info!("Manually blocking client: {:?}", blocked);
+            if blocklists.insert(ip, proxied, entry, AuditAction::ManualBlock) {
+                blocklist_len_gauge(&metrics, proxied).inc();
+            }
+            blocklists.store.persist(&blocklists.snapshot());
+            blocked
+        })
+        .await?;
+        Ok(blocked)
+    }
+
+    /// Removes `ip` from both the connection and proxied client blocklists,
+    /// returning the blocks that were lifted. The removal is persisted by
+    /// the time this returns.
+    pub async fn unblock(&self, ip: IpAddr) -> anyhow::Result<Vec<BlocklistEntry>> {
+        let Acl::Blocklists(blocklists) = &self.acl else {
+            anyhow::bail!("Cannot unblock clients when running with an allowlist");
+        };
+        let blocklists = blocklists.clone();
+        let metrics = self.metrics.clone();
+        let unblocked = tokio::task::spawn_blocking(move || {
+            let mut unblocked = vec![];
+            for proxied in [false, true] {
+                if let Some((ip, entry)) = blocklists.blocklist(proxied).remove(&ip) {
+                    blocklist_len_gauge(&metrics, proxied).dec();
+                    let entry = BlocklistEntry::new(ip, proxied, &entry);
+                    info!("Manually unblocking client: {:?}", entry);
+                    blocklists
+                        .store
+                        .audit(AuditAction::ManualUnblock, entry.clone());
+                    unblocked.push(entry);
+                }
+            }
+            if !unblocked.is_empty() {
+                blocklists.store.persist(&blocklists.snapshot());
+            }
+            unblocked
+        })
+        .await?;
+        Ok(unblocked)
+    }
+
     async fn check_and_clear_blocklist(
         &self,
         client: &Option<IpAddr>,
@@ -254,7 +413,7 @@ impl TrafficController {
         // due to aquiring the lock on get, then holding across the remove
         let (should_block, should_remove) = {
             match blocklist.get(client) {
-                Some(expiration) if now >= *expiration => (false, true),
+                Some(entry) if now >= entry.expiry => (false, true),
                 None => (false, false),
                 _ => (true, false),
             }
@@ -267,20 +426,30 @@ impl TrafficController {
     }
 }
 
+fn blocklist_len_gauge(metrics: &TrafficControllerMetrics, proxied: bool) -> &IntGauge {
+    if proxied {
+        &metrics.proxy_ip_blocklist_len
+    } else {
+        &metrics.connection_ip_blocklist_len
+    }
+}
+
 /// Although we clear IPs from the blocklist lazily when they are checked,
 /// it's possible that over time we may accumulate a large number of stale
 /// IPs in the blocklist for clients that are added, then once blocked,
 /// never checked again. This function runs periodically to clear out any
 /// such stale IPs. This also ensures that the blocklist length metric
-/// accurately reflects TTL.
+/// accurately reflects TTL. Blocks added by policies since the last
+/// iteration are persisted here as well, if persistence is enabled.
 async fn run_clear_blocklists_loop(blocklists: Blocklists, metrics: Arc<TrafficControllerMetrics>) {
     loop {
         tokio::time::sleep(Duration::from_secs(3)).await;
         let now = SystemTime::now();
-        blocklists.clients.retain(|_, expiration| now < *expiration);
+        blocklists.clients.retain(|_, entry| now < entry.expiry);
         blocklists
             .proxied_clients
-            .retain(|_, expiration| now < *expiration);
+            .retain(|_, entry| now < entry.expiry);
+        blocklists.store.persist_if_dirty(|| blocklists.snapshot());
         metrics
             .connection_ip_blocklist_len
             .set(blocklists.clients.len() as i64);
@@ -457,7 +626,8 @@ async fn handle_error_tally(
             .await;
         }
     }
-    handle_policy_response(resp, policy_config, blocklists, metrics).await;
+    let reason = format!("error policy: {}", error_type);
+    handle_policy_response(resp, policy_config, &reason, blocklists, metrics).await;
     Ok(())
 }
 
@@ -471,7 +641,13 @@ async fn handle_spam_tally(
     metrics: Arc<TrafficControllerMetrics>,
     mem_drainfile_present: bool,
 ) -> Result<(), reqwest::Error> {
-    if !(tally.spam_weight.is_sampled() && policy_config.spam_sample_rate.is_sampled()) {
+    // Weighted policies account for the cost of every request themselves, sampling
+    // them would only make their limits proportionally higher than configured
+    let sampled = match policy {
+        TrafficControlPolicy::TokenBucket(_) | TrafficControlPolicy::SlidingWindow(_) => true,
+        _ => policy_config.spam_sample_rate.is_sampled(),
+    };
+    if !(tally.spam_weight.is_sampled() && sampled) {
         return Ok(());
     }
     let resp = policy.handle_tally(tally.clone());
@@ -491,13 +667,14 @@ async fn handle_spam_tally(
             .await;
         }
     }
-    handle_policy_response(resp, policy_config, blocklists, metrics).await;
+    handle_policy_response(resp, policy_config, "spam policy", blocklists, metrics).await;
     Ok(())
 }
 
 async fn handle_policy_response(
     response: PolicyResponse,
     policy_config: &PolicyConfig,
+    reason: &str,
     blocklists: Arc<Blocklists>,
     metrics: Arc<TrafficControllerMetrics>,
 ) {
@@ -510,15 +687,19 @@ async fn handle_policy_response(
         proxy_blocklist_ttl_sec,
         ..
     } = policy_config;
+    let mut updated = false;
     if let Some(client) = block_client {
-        if blocklists
-            .clients
-            .insert(
-                client,
-                SystemTime::now() + Duration::from_secs(*connection_blocklist_ttl_sec),
-            )
-            .is_none()
-        {
+        updated = true;
+        if blocklists.insert(
+            client,
+            false,
+            BlockEntry {
+                expiry: SystemTime::now() + Duration::from_secs(*connection_blocklist_ttl_sec),
+                reason: reason.to_string(),
+                manual: false,
+            },
+            AuditAction::PolicyBlock,
+        ) {
             // Only increment the metric if the client was not already blocked
             debug!("Blocking client: {:?}", client);
             metrics.requests_blocked_at_protocol.inc();
@@ -526,20 +707,26 @@ async fn handle_policy_response(
         }
     }
     if let Some(client) = block_proxied_client {
-        if blocklists
-            .proxied_clients
-            .insert(
-                client,
-                SystemTime::now() + Duration::from_secs(*proxy_blocklist_ttl_sec),
-            )
-            .is_none()
-        {
+        updated = true;
+        if blocklists.insert(
+            client,
+            true,
+            BlockEntry {
+                expiry: SystemTime::now() + Duration::from_secs(*proxy_blocklist_ttl_sec),
+                reason: reason.to_string(),
+                manual: false,
+            },
+            AuditAction::PolicyBlock,
+        ) {
             // Only increment the metric if the client was not already blocked
             debug!("Blocking proxied client: {:?}", client);
             metrics.requests_blocked_at_protocol.inc();
             metrics.proxy_ip_blocklist_len.inc();
         }
     }
+    if updated {
+        blocklists.store.mark_dirty();
+    }
 }
 
 async fn delegate_policy_response(
@@ -656,6 +843,20 @@ impl TrafficSim {
         per_client_tps: usize,
         duration: Duration,
         report: bool,
+    ) -> TrafficSimMetrics {
+        Self::run_with_request_type(policy, num_clients, per_client_tps, duration, report, None)
+            .await
+    }
+
+    /// Same as `run`, but every request sent by the clients is tallied with
+    /// `request_type`, to exercise policies that weigh requests by type.
+    pub async fn run_with_request_type(
+        policy: PolicyConfig,
+        num_clients: u8,
+        per_client_tps: usize,
+        duration: Duration,
+        report: bool,
+        request_type: Option<&str>,
     ) -> TrafficSimMetrics {
         assert!(
             per_client_tps <= 10_000,
@@ -673,9 +874,11 @@ impl TrafficSim {
                 duration,
                 task_num,
                 per_client_tps,
+                request_type.map(str::to_owned),
             ))
         });
 
+        let report_request_type = request_type.map(str::to_owned);
         let status_task = if report {
             Some(tokio::spawn(async move {
                 println!(
@@ -683,6 +886,9 @@ impl TrafficSim {
                     duration.as_secs()
                 );
                 println!("Policy: {:#?}", policy);
+                if let Some(request_type) = &report_request_type {
+                    println!("Request type: {}", request_type);
+                }
                 println!("Num clients: {}", num_clients);
                 println!("TPS per client: {}", per_client_tps);
                 println!(
@@ -728,6 +934,7 @@ impl TrafficSim {
         duration: Duration,
         task_num: u8,
         per_client_tps: usize,
+        request_type: Option<String>,
     ) -> TrafficSimMetrics {
         // Do an initial sleep for a random amount of time to smooth
         // out the traffic. This shouldn't be strictly necessary and
@@ -754,14 +961,17 @@ impl TrafficSim {
                     total_time_blocked += time_blocked_start.elapsed();
                     currently_blocked = false;
                 }
-                controller.tally(TrafficTally::new(
-                    client,
-                    // TODO add proxy IP for testing
-                    None,
-                    // TODO add weight adjustments
-                    None,
-                    Weight::one(),
-                ));
+                controller.tally(TrafficTally {
+                    request_type: request_type.clone(),
+                    ..TrafficTally::new(
+                        client,
+                        // TODO add proxy IP for testing
+                        None,
+                        // TODO add weight adjustments
+                        None,
+                        Weight::one(),
+                    )
+                });
             } else {
                 if !currently_blocked {
                     time_blocked_start = Instant::now();
@@ -846,3 +1056,66 @@ pub fn parse_ip(ip: &str) -> Option<IpAddr> {
             })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_block_extends_manual_block() {
+        let blocklists = Blocklists {
+            clients: Arc::new(DashMap::new()),
+            proxied_clients: Arc::new(DashMap::new()),
+            store: Arc::new(BlocklistStore::new(None, None)),
+        };
+        let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let now = SystemTime::now();
+        let block = |ttl_secs, reason: &str, manual| BlockEntry {
+            expiry: now + Duration::from_secs(ttl_secs),
+            reason: reason.to_string(),
+            manual,
+        };
+        let current = || blocklists.clients.get(&client).unwrap().clone();
+
+        assert!(blocklists.insert(
+            client,
+            false,
+            block(60, "abuse", true),
+            AuditAction::ManualBlock
+        ));
+
+        // A shorter policy block leaves the manual block untouched
+        assert!(!blocklists.insert(
+            client,
+            false,
+            block(10, "spam policy", false),
+            AuditAction::PolicyBlock
+        ));
+        let entry = current();
+        assert_eq!(entry.expiry, now + Duration::from_secs(60));
+        assert_eq!(entry.reason, "abuse");
+        assert!(entry.manual);
+
+        // A longer one extends it, keeping the manual reason
+        blocklists.insert(
+            client,
+            false,
+            block(120, "spam policy", false),
+            AuditAction::PolicyBlock,
+        );
+        let entry = current();
+        assert_eq!(entry.expiry, now + Duration::from_secs(120));
+        assert_eq!(entry.reason, "abuse");
+        assert!(entry.manual);
+
+        // Manual blocks replace whatever block is in place
+        blocklists.insert(
+            client,
+            false,
+            block(30, "mistake", true),
+            AuditAction::ManualBlock,
+        );
+        assert_eq!(current().expiry, now + Duration::from_secs(30));
+        assert_eq!(current().reason, "mistake");
+    }
+}
@@ -13,10 +13,16 @@ use std::fmt::Debug;
 use std::hash::Hash;
 use std::time::Duration;
 use std::time::{Instant, SystemTime};
-use sui_types::traffic_control::{FreqThresholdConfig, PolicyConfig, PolicyType, Weight};
+use sui_types::traffic_control::{
+    FreqThresholdConfig, PolicyConfig, PolicyType, RequestWeights, SlidingWindowConfig,
+    TokenBucketConfig, Weight,
+};
 use tracing::{info, trace};
 
 const HIGHEST_RATES_CAPACITY: usize = 20;
+/// How often the weighted policies forget about clients that are back to
+/// their initial state, to bound memory usage.
+const CLIENT_STATE_PRUNE_INTERVAL: Duration = Duration::from_secs(10);
 
 /// The type of request client.
 #[derive(Hash, Eq, PartialEq, Debug)]
@@ -228,6 +234,9 @@ pub struct TrafficTally {
     pub through_fullnode: Option<IpAddr>,
     pub error_info: Option<(Weight, String)>,
     pub spam_weight: Weight,
+    /// The type of request being tallied, e.g. the JSON-RPC method name,
+    /// used to weigh requests by `RequestWeights`.
+    pub request_type: Option<String>,
     pub timestamp: SystemTime,
 }
 
@@ -243,9 +252,15 @@ impl TrafficTally {
             through_fullnode,
             error_info,
             spam_weight,
+            request_type: None,
             timestamp: SystemTime::now(),
         }
     }
+
+    pub fn with_request_type(mut self, request_type: impl Into<String>) -> Self {
+        self.request_type = Some(request_type.into());
+        self
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -265,6 +280,8 @@ pub trait Policy {
 // not object safe, so we can't use a trait object instead
 pub enum TrafficControlPolicy {
     FreqThreshold(FreqThresholdPolicy),
+    TokenBucket(TokenBucketPolicy),
+    SlidingWindow(SlidingWindowPolicy),
     NoOp(NoOpPolicy),
     // Test policies below this point
     TestNConnIP(TestNConnIPPolicy),
@@ -276,6 +293,8 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::FreqThreshold(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::TokenBucket(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::SlidingWindow(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestNConnIP(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.handle_tally(tally),
         }
@@ -285,6 +304,8 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.policy_config(),
             TrafficControlPolicy::FreqThreshold(policy) => policy.policy_config(),
+            TrafficControlPolicy::TokenBucket(policy) => policy.policy_config(),
+            TrafficControlPolicy::SlidingWindow(policy) => policy.policy_config(),
             TrafficControlPolicy::TestNConnIP(policy) => policy.policy_config(),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.policy_config(),
         }
@@ -304,6 +325,12 @@ impl TrafficControlPolicy {
             PolicyType::FreqThreshold(freq_threshold_config) => Self::FreqThreshold(
                 FreqThresholdPolicy::new(policy_config, freq_threshold_config),
             ),
+            PolicyType::TokenBucket(token_bucket_config) => {
+                Self::TokenBucket(TokenBucketPolicy::new(policy_config, token_bucket_config))
+            }
+            PolicyType::SlidingWindow(sliding_window_config) => Self::SlidingWindow(
+                SlidingWindowPolicy::new(policy_config, sliding_window_config),
+            ),
             PolicyType::TestNConnIP(n) => {
                 Self::TestNConnIP(TestNConnIPPolicy::new(policy_config, n).await)
             }
@@ -416,6 +443,254 @@ impl FreqThresholdPolicy {
     }
 }
 
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, now: Instant, capacity: f64, refill_rate: f64) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * refill_rate).min(capacity);
+        self.last_refill = now;
+    }
+}
+
+struct TokenBuckets {
+    capacity: f64,
+    refill_rate: f64,
+    buckets: HashMap<IpAddr, TokenBucket>,
+}
+
+impl TokenBuckets {
+    fn new(capacity: u64, refill_rate: f64) -> Self {
+        Self {
+            capacity: capacity as f64,
+            refill_rate,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Takes `weight` tokens from the bucket of `client`. Returns false if
+    /// there were not enough tokens left, in which case the bucket is emptied.
+    fn consume(&mut self, client: IpAddr, weight: u64, now: Instant) -> bool {
+        let bucket = self.buckets.entry(client).or_insert(TokenBucket {
+            tokens: self.capacity,
+            last_refill: now,
+        });
+        bucket.refill(now, self.capacity, self.refill_rate);
+        bucket.tokens -= weight as f64;
+        if bucket.tokens < 0.0 {
+            bucket.tokens = 0.0;
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Forgets clients whose bucket is full again, as they are
+    /// indistinguishable from new clients.
+    fn prune(&mut self, now: Instant) {
+        let (capacity, refill_rate) = (self.capacity, self.refill_rate);
+        self.buckets.retain(|_, bucket| {
+            bucket.refill(now, capacity, refill_rate);
+            bucket.tokens < capacity
+        });
+    }
+}
+
+pub struct TokenBucketPolicy {
+    config: PolicyConfig,
+    request_weights: RequestWeights,
+    clients: TokenBuckets,
+    proxied_clients: Option<TokenBuckets>,
+    last_prune: Instant,
+}
+
+impl TokenBucketPolicy {
+    pub fn new(
+        config: PolicyConfig,
+        TokenBucketConfig {
+            capacity,
+            refill_rate,
+            proxied_capacity,
+            proxied_refill_rate,
+            request_weights,
+        }: TokenBucketConfig,
+    ) -> Self {
+        let proxied_clients = match (proxied_capacity, proxied_refill_rate) {
+            (Some(capacity), Some(refill_rate)) => Some(TokenBuckets::new(capacity, refill_rate)),
+            _ => None,
+        };
+        Self {
+            config,
+            request_weights,
+            clients: TokenBuckets::new(capacity, refill_rate),
+            proxied_clients,
+            last_prune: Instant::now(),
+        }
+    }
+
+    pub fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let now = Instant::now();
+        if now.duration_since(self.last_prune) >= CLIENT_STATE_PRUNE_INTERVAL {
+            self.clients.prune(now);
+            if let Some(proxied_clients) = self.proxied_clients.as_mut() {
+                proxied_clients.prune(now);
+            }
+            self.last_prune = now;
+        }
+
+        let weight = self.request_weights.weight(tally.request_type.as_deref());
+        trace!(
+            "TokenBucketPolicy handling tally -- weight: {:?}, request_type: {:?}, client: {:?}",
+            weight,
+            tally.request_type,
+            tally.direct,
+        );
+        let block_client = tally
+            .direct
+            .filter(|client| !self.clients.consume(*client, weight, now));
+        let mut block_proxied_client = None;
+        if let (Some(client), Some(proxied_clients)) =
+            (tally.through_fullnode, self.proxied_clients.as_mut())
+        {
+            if !proxied_clients.consume(client, weight, now) {
+                block_proxied_client = Some(client);
+            }
+        }
+        PolicyResponse {
+            block_client,
+            block_proxied_client,
+        }
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
+#[derive(Default)]
+struct RequestLog {
+    requests: VecDeque<(Instant, u64)>,
+    total_weight: u64,
+}
+
+impl RequestLog {
+    fn expire(&mut self, now: Instant, window: Duration) {
+        while let Some(&(time, weight)) = self.requests.front() {
+            if now.saturating_duration_since(time) < window {
+                break;
+            }
+            self.requests.pop_front();
+            self.total_weight -= weight;
+        }
+    }
+}
+
+struct SlidingWindowLog {
+    window: Duration,
+    threshold: u64,
+    logs: HashMap<IpAddr, RequestLog>,
+}
+
+impl SlidingWindowLog {
+    fn new(window: Duration, threshold: u64) -> Self {
+        Self {
+            window,
+            threshold,
+            logs: HashMap::new(),
+        }
+    }
+
+    /// Logs a request of `weight` from `client`. Returns true if the total weight
+    /// of the requests from `client` within the window now exceeds the threshold.
+    fn record(&mut self, client: IpAddr, weight: u64, now: Instant) -> bool {
+        let log = self.logs.entry(client).or_default();
+        log.expire(now, self.window);
+        log.requests.push_back((now, weight));
+        log.total_weight += weight;
+        log.total_weight > self.threshold
+    }
+
+    /// Forgets clients without requests in the window.
+    fn prune(&mut self, now: Instant) {
+        let window = self.window;
+        self.logs.retain(|_, log| {
+            log.expire(now, window);
+            !log.requests.is_empty()
+        });
+    }
+}
+
+pub struct SlidingWindowPolicy {
+    config: PolicyConfig,
+    request_weights: RequestWeights,
+    clients: SlidingWindowLog,
+    proxied_clients: Option<SlidingWindowLog>,
+    last_prune: Instant,
+}
+
+impl SlidingWindowPolicy {
+    pub fn new(
+        config: PolicyConfig,
+        SlidingWindowConfig {
+            client_threshold,
+            proxied_client_threshold,
+            window_size_secs,
+            request_weights,
+        }: SlidingWindowConfig,
+    ) -> Self {
+        let window = Duration::from_secs(window_size_secs);
+        Self {
+            config,
+            request_weights,
+            clients: SlidingWindowLog::new(window, client_threshold),
+            proxied_clients: proxied_client_threshold
+                .map(|threshold| SlidingWindowLog::new(window, threshold)),
+            last_prune: Instant::now(),
+        }
+    }
+
+    pub fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let now = Instant::now();
+        if now.duration_since(self.last_prune) >= CLIENT_STATE_PRUNE_INTERVAL {
+            self.clients.prune(now);
+            if let Some(proxied_clients) = self.proxied_clients.as_mut() {
+                proxied_clients.prune(now);
+            }
+            self.last_prune = now;
+        }
+
+        let weight = self.request_weights.weight(tally.request_type.as_deref());
+        trace!(
+            "SlidingWindowPolicy handling tally -- weight: {:?}, request_type: {:?}, client: {:?}",
+            weight,
+            tally.request_type,
+            tally.direct,
+        );
+        let block_client = tally
+            .direct
+            .filter(|client| self.clients.record(*client, weight, now));
+        let mut block_proxied_client = None;
+        if let (Some(client), Some(proxied_clients)) =
+            (tally.through_fullnode, self.proxied_clients.as_mut())
+        {
+            if proxied_clients.record(client, weight, now) {
+                block_proxied_client = Some(client);
+            }
+        }
+        PolicyResponse {
+            block_client,
+            block_proxied_client,
+        }
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
 ////////////// *** Test policies below this point *** //////////////
 
 #[derive(Clone)]
@@ -543,6 +818,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))),
             error_info: None,
             spam_weight: Weight::one(),
+            request_type: None,
             timestamp: SystemTime::now(),
         };
         let bob = TrafficTally {
@@ -550,6 +826,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(4, 3, 2, 1))),
             error_info: None,
             spam_weight: Weight::one(),
+            request_type: None,
             timestamp: SystemTime::now(),
         };
         let charlie = TrafficTally {
@@ -557,6 +834,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8))),
             error_info: None,
             spam_weight: Weight::one(),
+            request_type: None,
             timestamp: SystemTime::now(),
         };
 
@@ -674,6 +952,96 @@ mod tests {
         assert_eq!(proxied_rate, 1);
     }
 
+    #[sim_test]
+    async fn test_token_bucket_policy() {
+        // Allow bursts of 10 cheap requests, refilled at 2 per second,
+        // where a dry run costs as much as 5 cheap requests.
+        let mut policy = TokenBucketPolicy::new(
+            PolicyConfig::default(),
+            TokenBucketConfig {
+                capacity: 10,
+                refill_rate: 2.0,
+                proxied_capacity: None,
+                proxied_refill_rate: None,
+                request_weights: RequestWeights {
+                    default: 1,
+                    weights: [("dry_run".to_string(), 5)].into_iter().collect(),
+                },
+            },
+        );
+        let client = Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5)));
+        let proxied_client = Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)));
+        let read = TrafficTally::new(client, proxied_client, None, Weight::one());
+        let dry_run = read.clone().with_request_type("dry_run");
+
+        for i in 0..10 {
+            let response = policy.handle_tally(read.clone());
+            assert_eq!(response.block_client, None, "Blocked at i = {}", i);
+            // proxied clients are not tracked without a proxied bucket
+            assert_eq!(response.block_proxied_client, None);
+        }
+        let response = policy.handle_tally(read.clone());
+        assert_eq!(response.block_client, client);
+
+        // the bucket was emptied by the block, and only 2 tokens
+        // have been refilled since, which is not enough for a dry run
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        let response = policy.handle_tally(dry_run.clone());
+        assert_eq!(response.block_client, client);
+
+        // 6 tokens are enough for a dry run and a cheap request, but no more
+        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+        assert_eq!(policy.handle_tally(dry_run.clone()).block_client, None);
+        assert_eq!(policy.handle_tally(read.clone()).block_client, None);
+        assert_eq!(policy.handle_tally(read.clone()).block_client, client);
+    }
+
+    #[sim_test]
+    async fn test_sliding_window_policy() {
+        // Allow a total weight of 10 per client and 4 per proxied client
+        // over 2 seconds, where an execution costs 4.
+        let mut policy = SlidingWindowPolicy::new(
+            PolicyConfig::default(),
+            SlidingWindowConfig {
+                client_threshold: 10,
+                proxied_client_threshold: Some(4),
+                window_size_secs: 2,
+                request_weights: RequestWeights {
+                    default: 1,
+                    weights: [("execute".to_string(), 4)].into_iter().collect(),
+                },
+            },
+        );
+        let alice = Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5)));
+        let bob_proxy = Some(IpAddr::V4(Ipv4Addr::new(4, 3, 2, 1)));
+        let alice_read = TrafficTally::new(alice, None, None, Weight::one());
+        let alice_execute = alice_read.clone().with_request_type("execute");
+        let bob_read = TrafficTally::new(None, bob_proxy, None, Weight::one());
+        let bob_execute = bob_read.clone().with_request_type("execute");
+
+        // alice reaches, but does not exceed, the threshold
+        for tally in [&alice_execute, &alice_execute, &alice_read, &alice_read] {
+            assert_eq!(policy.handle_tally(tally.clone()).block_client, None);
+        }
+        assert_eq!(policy.handle_tally(alice_read.clone()).block_client, alice);
+
+        let response = policy.handle_tally(bob_execute.clone());
+        assert_eq!(response.block_client, None);
+        assert_eq!(response.block_proxied_client, None);
+        let response = policy.handle_tally(bob_read.clone());
+        assert_eq!(response.block_client, None);
+        assert_eq!(response.block_proxied_client, bob_proxy);
+
+        // once the window has passed, earlier requests no longer count
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        assert_eq!(
+            policy.handle_tally(alice_execute.clone()).block_client,
+            None
+        );
+        assert_eq!(policy.handle_tally(bob_execute).block_proxied_client, None);
+        assert_eq!(policy.clients.logs[&alice.unwrap()].total_weight, 4);
+    }
+
     #[sim_test]
     async fn test_traffic_sketch_mem_estimate() {
         // Test for getting a rough estimate of memory usage for the traffic sketch
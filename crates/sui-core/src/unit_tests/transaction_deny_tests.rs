@@ -13,7 +13,9 @@ use move_core_types::ident_str;
 use std::path::PathBuf;
 use std::sync::Arc;
 use sui_config::certificate_deny_config::CertificateDenyConfigBuilder;
-use sui_config::transaction_deny_config::{TransactionDenyConfig, TransactionDenyConfigBuilder};
+use sui_config::transaction_deny_config::{
+    CommandKind, RuleAction, TransactionDenyConfig, TransactionDenyConfigBuilder, TransactionRule,
+};
 use sui_swarm_config::genesis_config::{AccountConfig, DEFAULT_GAS_AMOUNT};
 use sui_swarm_config::network_config::NetworkConfig;
 use sui_test_transaction_builder::TestTransactionBuilder;
@@ -30,6 +32,7 @@ use sui_types::utils::get_zklogin_user_address;
 use sui_types::utils::{
     make_zklogin_tx, to_sender_signed_transaction, to_sender_signed_transaction_with_multi_signers,
 };
+use sui_types::SUI_FRAMEWORK_PACKAGE_ID;
 
 const ACCOUNT_NUM: usize = 5;
 const GAS_OBJECT_COUNT: usize = 15;
@@ -167,6 +170,24 @@ fn assert_denied<T: std::fmt::Debug>(result: &SuiResult<T>) {
     ));
 }
 
+fn assert_denied_by_rule<T: std::fmt::Debug>(result: &SuiResult<T>, rule_name: &str) {
+    assert!(matches!(
+        result.as_ref().unwrap_err(),
+        SuiError::UserInputError {
+            error: UserInputError::TransactionDeniedByRule { rule }
+        } if rule == rule_name
+    ));
+}
+
+fn assert_not_denied_by_rule<T: std::fmt::Debug>(result: &SuiResult<T>) {
+    assert!(!matches!(
+        result,
+        Err(SuiError::UserInputError {
+            error: UserInputError::TransactionDeniedByRule { .. }
+        })
+    ));
+}
+
 #[tokio::test]
 async fn test_user_transaction_disabled() {
     let (network_config, state) = setup_test(
@@ -438,6 +459,216 @@ async fn test_package_denied() {
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+async fn test_transaction_rules() {
+    // We need to create the authority state once to get the account addresses.
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+
+    // Allow transactions from accounts[0], and deny everything else.
+    let mut allow_rule = TransactionRule::new("allow-first-account", RuleAction::Allow);
+    allow_rule.senders = vec![accounts[0].0];
+    let state = reload_state_with_new_deny_config(
+        &network_config,
+        state,
+        TransactionDenyConfigBuilder::new()
+            .add_rule(allow_rule)
+            .add_rule(TransactionRule::new("deny-all", RuleAction::Deny))
+            .build(),
+    )
+    .await;
+    transfer_with_account(&accounts[0], &accounts[0], &state)
+        .await
+        .unwrap();
+    assert_denied_by_rule(
+        &transfer_with_account(&accounts[1], &accounts[1], &state).await,
+        "deny-all",
+    );
+
+    // Rules can be replaced at runtime without restarting the authority.
+    state
+        .config
+        .transaction_deny_config
+        .set_rules(vec![TransactionRule::new("deny-all", RuleAction::Deny)]);
+    assert_denied_by_rule(
+        &transfer_with_account(&accounts[0], &accounts[0], &state).await,
+        "deny-all",
+    );
+    state.config.transaction_deny_config.set_rules(vec![]);
+    transfer_with_account(&accounts[1], &accounts[1], &state)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_transaction_rules_file() {
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("rules.yaml");
+    let mut deny_rule = TransactionRule::new("deny-first-account", RuleAction::Deny);
+    deny_rule.senders = vec![accounts[0].0];
+    std::fs::write(&path, serde_yaml::to_string(&vec![deny_rule]).unwrap()).unwrap();
+
+    // The rules file takes the place of the inline rules.
+    let config = TransactionDenyConfigBuilder::new()
+        .add_rule(TransactionRule::new("deny-all", RuleAction::Deny))
+        .rules_file(path.clone())
+        .build();
+    assert_eq!(config.reload_rules().unwrap(), 1);
+    let state = reload_state_with_new_deny_config(&network_config, state, config).await;
+    assert_denied_by_rule(
+        &transfer_with_account(&accounts[0], &accounts[0], &state).await,
+        "deny-first-account",
+    );
+    transfer_with_account(&accounts[1], &accounts[1], &state)
+        .await
+        .unwrap();
+
+    // Changes to the file take effect once the rules are reloaded.
+    let mut deny_rule = TransactionRule::new("deny-second-account", RuleAction::Deny);
+    deny_rule.senders = vec![accounts[1].0];
+    std::fs::write(
+        &path,
+        serde_yaml::to_string(&vec![deny_rule.clone(), deny_rule]).unwrap(),
+    )
+    .unwrap();
+    let deny_config = &state.config.transaction_deny_config;
+    assert_eq!(deny_config.reload_rules().unwrap(), 2);
+    transfer_with_account(&accounts[0], &accounts[0], &state)
+        .await
+        .unwrap();
+    assert_denied_by_rule(
+        &transfer_with_account(&accounts[1], &accounts[1], &state).await,
+        "deny-second-account",
+    );
+
+    // A broken file is reported, and leaves the rules in effect alone.
+    std::fs::write(&path, "- name: [").unwrap();
+    deny_config.reload_rules().unwrap_err();
+    assert_eq!(deny_config.rules().len(), 2);
+    assert_denied_by_rule(
+        &transfer_with_account(&accounts[1], &accounts[1], &state).await,
+        "deny-second-account",
+    );
+
+    // As is a missing one.
+    std::fs::remove_file(&path).unwrap();
+    deny_config.reload_rules().unwrap_err();
+    assert_eq!(deny_config.rules().len(), 2);
+}
+
+#[test]
+fn test_transaction_rules_invalid_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("rules.yaml");
+    std::fs::write(&path, "- name: deny-all\n  action: block\n").unwrap();
+
+    let config = TransactionDenyConfigBuilder::new()
+        .add_rule(TransactionRule::new("deny-all", RuleAction::Deny))
+        .rules_file(path)
+        .build();
+    let err = config.reload_rules().unwrap_err();
+    assert!(
+        format!("{err:?}").contains("Failed to parse transaction rules"),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_transaction_rule_matchers() {
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+    let deny_config = &state.config.transaction_deny_config;
+    let transfer = || transfer_with_account(&accounts[0], &accounts[0], &state);
+
+    // Transferring SUI takes a single TransferObjects command.
+    let mut rule = TransactionRule::new("deny-move-calls", RuleAction::Deny);
+    rule.command_kinds = vec![CommandKind::MoveCall, CommandKind::SplitCoins];
+    deny_config.set_rules(vec![rule]);
+    transfer().await.unwrap();
+    let mut rule = TransactionRule::new("deny-transfers", RuleAction::Deny);
+    rule.command_kinds = vec![CommandKind::TransferObjects];
+    deny_config.set_rules(vec![rule]);
+    assert_denied_by_rule(&transfer().await, "deny-transfers");
+
+    // Gas budget bounds are inclusive.
+    let gas_budget =
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER * state.reference_gas_price_for_testing().unwrap();
+    let mut rule = TransactionRule::new("deny-large-budgets", RuleAction::Deny);
+    rule.min_gas_budget = Some(gas_budget + 1);
+    deny_config.set_rules(vec![rule]);
+    transfer().await.unwrap();
+    let mut rule = TransactionRule::new("deny-small-budgets", RuleAction::Deny);
+    rule.max_gas_budget = Some(gas_budget);
+    deny_config.set_rules(vec![rule]);
+    assert_denied_by_rule(&transfer().await, "deny-small-budgets");
+    let mut rule = TransactionRule::new("deny-budget", RuleAction::Deny);
+    rule.min_gas_budget = Some(gas_budget);
+    rule.max_gas_budget = Some(gas_budget);
+    deny_config.set_rules(vec![rule]);
+    assert_denied_by_rule(&transfer().await, "deny-budget");
+
+    // The gas coin is one of the input objects. Types without type parameters match all of their
+    // instantiations.
+    let mut rule = TransactionRule::new("deny-coin-metadata", RuleAction::Deny);
+    rule.input_object_types = vec![
+        "0x2::coin::CoinMetadata".parse().unwrap(),
+        "0x2::coin::Coin<0x2::coin::Coin<0x2::sui::SUI>>"
+            .parse()
+            .unwrap(),
+    ];
+    deny_config.set_rules(vec![rule]);
+    transfer().await.unwrap();
+    for type_ in ["0x2::coin::Coin", "0x2::coin::Coin<0x2::sui::SUI>"] {
+        let mut rule = TransactionRule::new("deny-coins", RuleAction::Deny);
+        rule.input_object_types = vec![type_.parse().unwrap()];
+        deny_config.set_rules(vec![rule]);
+        assert_denied_by_rule(&transfer().await, "deny-coins");
+    }
+
+    // Move call targets match a package, a module or a single function.
+    let call = |gas_payment_index| {
+        handle_move_call_transaction(
+            &state,
+            SUI_FRAMEWORK_PACKAGE_ID,
+            "clock",
+            "timestamp_ms",
+            vec![],
+            &accounts[1],
+            gas_payment_index,
+        )
+    };
+    for (index, target) in ["0x2", "0x2::clock", "0x2::clock::timestamp_ms"]
+        .into_iter()
+        .enumerate()
+    {
+        let mut rule = TransactionRule::new("deny-clock", RuleAction::Deny);
+        rule.move_call_targets = vec![target.parse().unwrap()];
+        deny_config.set_rules(vec![rule]);
+        assert_denied_by_rule(&call(index).await, "deny-clock");
+    }
+    for (index, target) in ["0x3", "0x2::coin", "0x2::clock::create"]
+        .into_iter()
+        .enumerate()
+    {
+        let mut rule = TransactionRule::new("deny-other", RuleAction::Deny);
+        rule.move_call_targets = vec![target.parse().unwrap()];
+        deny_config.set_rules(vec![rule]);
+        assert_not_denied_by_rule(&call(index + 3).await);
+    }
+
+    // The first matching rule decides, and transactions that match no rule are allowed.
+    let mut allow_rule = TransactionRule::new("allow-transfers", RuleAction::Allow);
+    allow_rule.command_kinds = vec![CommandKind::TransferObjects];
+    let mut deny_rule = TransactionRule::new("deny-move-calls", RuleAction::Deny);
+    deny_rule.command_kinds = vec![CommandKind::MoveCall];
+    deny_config.set_rules(vec![allow_rule, deny_rule]);
+    transfer().await.unwrap();
+    assert_denied_by_rule(&call(6).await, "deny-move-calls");
+}
+
 #[tokio::test]
 async fn test_certificate_deny() {
     let (network_config, state) = setup_test(TransactionDenyConfig::default()).await;
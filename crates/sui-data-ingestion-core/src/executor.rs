@@ -6,8 +6,8 @@ use crate::progress_store::{
 };
 use crate::reader::CheckpointReader;
 use crate::worker_pool::WorkerPool;
-use crate::Worker;
 use crate::{DataIngestionMetrics, ReaderOptions};
+use crate::{TransactionFilter, Worker};
 use anyhow::Result;
 use futures::Future;
 use mysten_metrics::spawn_monitored_task;
@@ -24,7 +24,7 @@ pub const MAX_CHECKPOINTS_IN_PROGRESS: usize = 10000;
 
 pub struct IndexerExecutor<P> {
     pools: Vec<Pin<Box<dyn Future<Output = ()> + Send>>>,
-    pool_senders: Vec<mpsc::Sender<Arc<CheckpointData>>>,
+    pool_senders: Vec<(mpsc::Sender<Arc<CheckpointData>>, Option<TransactionFilter>)>,
     progress_store: ProgressStoreWrapper<P>,
     pool_progress_sender: mpsc::Sender<(String, CheckpointSequenceNumber)>,
     pool_progress_receiver: mpsc::Receiver<(String, CheckpointSequenceNumber)>,
@@ -46,15 +46,16 @@ impl<P: ProgressStore> IndexerExecutor<P> {
     }
 
     /// Registers new worker pool in executor
-    pub async fn register<W: Worker + 'static>(&mut self, pool: WorkerPool<W>) -> Result<()> {
+    pub async fn register<W: Worker + 'static>(&mut self, mut pool: WorkerPool<W>) -> Result<()> {
         let checkpoint_number = self.progress_store.load(pool.task_name.clone()).await?;
         let (sender, receiver) = mpsc::channel(MAX_CHECKPOINTS_IN_PROGRESS);
+        let filter = pool.filter.take();
         self.pools.push(Box::pin(pool.run(
             checkpoint_number,
             receiver,
             self.pool_progress_sender.clone(),
         )));
-        self.pool_senders.push(sender);
+        self.pool_senders.push((sender, filter));
         Ok(())
     }
 
@@ -100,8 +101,12 @@ impl<P: ProgressStore> IndexerExecutor<P> {
                             break;
                         }
                     }
-                    for sender in &self.pool_senders {
-                        sender.send(checkpoint.clone()).await?;
+                    for (sender, filter) in &self.pool_senders {
+                        let checkpoint = match filter {
+                            Some(filter) => Arc::new(filter.apply(&checkpoint)),
+                            None => checkpoint.clone(),
+                        };
+                        sender.send(checkpoint).await?;
                     }
                 }
             }
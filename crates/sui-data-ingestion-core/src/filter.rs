@@ -0,0 +1,91 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::language_storage::StructTag;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::full_checkpoint_content::{CheckpointData, CheckpointTransaction};
+use sui_types::transaction::TransactionDataAPI;
+
+/// Selects the transactions of a checkpoint that are handed to a worker pool.
+///
+/// Struct types given without type parameters match any instantiation of the type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionFilter {
+    /// Transactions sent by this address
+    Sender(SuiAddress),
+    /// Transactions calling a Move function in this package, and optionally in this module and
+    /// with this name
+    MoveCall {
+        package: ObjectID,
+        module: Option<String>,
+        function: Option<String>,
+    },
+    /// Transactions emitting an event of this type
+    EventType(StructTag),
+    /// Transactions reading or writing an object of this type
+    ObjectType(StructTag),
+    /// Transactions matching any of the filters
+    Any(Vec<TransactionFilter>),
+    /// Transactions matching all of the filters
+    All(Vec<TransactionFilter>),
+}
+
+impl TransactionFilter {
+    pub fn matches(&self, transaction: &CheckpointTransaction) -> bool {
+        match self {
+            Self::Sender(sender) => transaction.transaction.transaction_data().sender() == *sender,
+            Self::MoveCall {
+                package,
+                module,
+                function,
+            } => transaction
+                .transaction
+                .transaction_data()
+                .move_calls()
+                .into_iter()
+                .any(|(call_package, call_module, call_function)| {
+                    call_package == package
+                        && module.as_ref().map_or(true, |module| module == call_module)
+                        && function
+                            .as_ref()
+                            .map_or(true, |function| function == call_function)
+                }),
+            Self::EventType(type_) => transaction.events.as_ref().map_or(false, |events| {
+                events
+                    .data
+                    .iter()
+                    .any(|event| type_matches(type_, &event.type_))
+            }),
+            Self::ObjectType(type_) => transaction
+                .input_objects
+                .iter()
+                .chain(&transaction.output_objects)
+                .filter_map(|object| object.struct_tag())
+                .any(|object_type| type_matches(type_, &object_type)),
+            Self::Any(filters) => filters.iter().any(|filter| filter.matches(transaction)),
+            Self::All(filters) => filters.iter().all(|filter| filter.matches(transaction)),
+        }
+    }
+
+    /// Returns a copy of the checkpoint with only the matching transactions. The summary and
+    /// contents of the checkpoint are kept as is.
+    pub fn apply(&self, checkpoint: &CheckpointData) -> CheckpointData {
+        CheckpointData {
+            checkpoint_summary: checkpoint.checkpoint_summary.clone(),
+            checkpoint_contents: checkpoint.checkpoint_contents.clone(),
+            transactions: checkpoint
+                .transactions
+                .iter()
+                .filter(|transaction| self.matches(transaction))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+fn type_matches(filter: &StructTag, type_: &StructTag) -> bool {
+    filter.address == type_.address
+        && filter.module == type_.module
+        && filter.name == type_.name
+        && (filter.type_params.is_empty() || filter.type_params == type_.type_params)
+}
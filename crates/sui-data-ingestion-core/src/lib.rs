@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod executor;
+mod filter;
 mod metrics;
 mod progress_store;
 mod reader;
@@ -14,10 +15,15 @@ mod worker_pool;
 use anyhow::Result;
 use async_trait::async_trait;
 pub use executor::{setup_single_workflow, IndexerExecutor, MAX_CHECKPOINTS_IN_PROGRESS};
+pub use filter::TransactionFilter;
 pub use metrics::DataIngestionMetrics;
-pub use progress_store::{FileProgressStore, ProgressStore, ShimProgressStore};
+pub use progress_store::{
+    FileProgressStore, ProgressStore, ShimProgressStore, SqlProgressStore, SqlReducer, SqlStore,
+    SqliteProgressStore, SqliteStore, SqliteTransaction, TransactionalReducer,
+};
 pub use reader::ReaderOptions;
 use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 pub use util::create_remote_store_client;
 pub use worker_pool::WorkerPool;
 
@@ -35,6 +41,18 @@ pub trait Worker: Send + Sync {
 pub trait Reducer<R: Send + Sync>: Send + Sync {
     async fn commit(&self, batch: Vec<R>) -> Result<()>;
 
+    /// Commits a batch, after which the watermark of the task will be advanced to `watermark`.
+    /// Reducers that write to the same store as the progress store can override this to persist
+    /// the batch and the watermark atomically.
+    async fn commit_with_watermark(
+        &self,
+        batch: Vec<R>,
+        _task_name: &str,
+        _watermark: CheckpointSequenceNumber,
+    ) -> Result<()> {
+        self.commit(batch).await
+    }
+
     fn should_close_batch(&self, _batch: &[R], next_item: Option<&R>) -> bool {
         next_item.is_none()
     }
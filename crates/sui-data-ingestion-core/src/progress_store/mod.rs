@@ -6,7 +6,11 @@ use async_trait::async_trait;
 use std::collections::HashMap;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 mod file;
+mod sql;
+mod sqlite;
 pub use file::FileProgressStore;
+pub use sql::{SqlProgressStore, SqlReducer, SqlStore, TransactionalReducer};
+pub use sqlite::{SqliteProgressStore, SqliteStore, SqliteTransaction};
 
 pub type ExecutorProgress = HashMap<String, CheckpointSequenceNumber>;
 
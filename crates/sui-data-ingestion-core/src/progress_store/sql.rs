@@ -0,0 +1,128 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::progress_store::ProgressStore;
+use crate::Reducer;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+/// A SQL database that can record the watermark of a task in the same transaction as the task's
+/// own output.
+#[async_trait]
+pub trait SqlStore: Send + Sync + 'static {
+    /// An open transaction. Dropping it without passing it to [`SqlStore::commit`] rolls it back.
+    type Transaction: Send;
+
+    async fn begin(&self) -> Result<Self::Transaction>;
+
+    async fn commit(&self, transaction: Self::Transaction) -> Result<()>;
+
+    async fn load_watermark(&self, task_name: &str) -> Result<Option<CheckpointSequenceNumber>>;
+
+    /// Records the watermark of a task as part of `transaction`. The watermark never moves
+    /// backwards, so that a stale update can't undo progress committed alongside a task's output.
+    async fn save_watermark(
+        &self,
+        transaction: &mut Self::Transaction,
+        task_name: &str,
+        checkpoint_number: CheckpointSequenceNumber,
+    ) -> Result<()>;
+}
+
+/// Progress store keeping watermarks in a [`SqlStore`].
+pub struct SqlProgressStore<S> {
+    store: Arc<S>,
+}
+
+impl<S: SqlStore> SqlProgressStore<S> {
+    pub fn new(store: Arc<S>) -> Self {
+        Self { store }
+    }
+
+    pub fn store(&self) -> &Arc<S> {
+        &self.store
+    }
+}
+
+#[async_trait]
+impl<S: SqlStore> ProgressStore for SqlProgressStore<S> {
+    async fn load(&mut self, task_name: String) -> Result<CheckpointSequenceNumber> {
+        Ok(self
+            .store
+            .load_watermark(&task_name)
+            .await?
+            .unwrap_or_default())
+    }
+
+    async fn save(
+        &mut self,
+        task_name: String,
+        checkpoint_number: CheckpointSequenceNumber,
+    ) -> Result<()> {
+        let mut transaction = self.store.begin().await?;
+        self.store
+            .save_watermark(&mut transaction, &task_name, checkpoint_number)
+            .await?;
+        self.store.commit(transaction).await
+    }
+}
+
+/// Reducer writing its batches to a [`SqlStore`], see [`TransactionalReducer`].
+#[async_trait]
+pub trait SqlReducer<S: SqlStore, R: Send + Sync>: Send + Sync {
+    async fn commit(&self, transaction: &mut S::Transaction, batch: Vec<R>) -> Result<()>;
+
+    fn should_close_batch(&self, _batch: &[R], next_item: Option<&R>) -> bool {
+        next_item.is_none()
+    }
+}
+
+/// Commits every batch of a [`SqlReducer`] in the same transaction as the watermark of the task
+/// it belongs to. If the progress store of the executor is a [`SqlProgressStore`] over the same
+/// store, a crash can neither lose the watermark of committed output, which would cause
+/// checkpoints to be processed twice, nor persist a watermark ahead of the output, which would
+/// leave a gap.
+pub struct TransactionalReducer<S, D> {
+    store: Arc<S>,
+    reducer: D,
+}
+
+impl<S, D> TransactionalReducer<S, D> {
+    pub fn new(store: Arc<S>, reducer: D) -> Self {
+        Self { store, reducer }
+    }
+}
+
+#[async_trait]
+impl<S, D, R> Reducer<R> for TransactionalReducer<S, D>
+where
+    S: SqlStore,
+    D: SqlReducer<S, R>,
+    R: Send + Sync + 'static,
+{
+    async fn commit(&self, batch: Vec<R>) -> Result<()> {
+        let mut transaction = self.store.begin().await?;
+        self.reducer.commit(&mut transaction, batch).await?;
+        self.store.commit(transaction).await
+    }
+
+    async fn commit_with_watermark(
+        &self,
+        batch: Vec<R>,
+        task_name: &str,
+        watermark: CheckpointSequenceNumber,
+    ) -> Result<()> {
+        let mut transaction = self.store.begin().await?;
+        self.reducer.commit(&mut transaction, batch).await?;
+        self.store
+            .save_watermark(&mut transaction, task_name, watermark)
+            .await?;
+        self.store.commit(transaction).await
+    }
+
+    fn should_close_batch(&self, batch: &[R], next_item: Option<&R>) -> bool {
+        self.reducer.should_close_batch(batch, next_item)
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::progress_store::{SqlProgressStore, SqlStore};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::Arc;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio::runtime::Handle;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tokio::task::spawn_blocking;
+
+pub type SqliteProgressStore = SqlProgressStore<SqliteStore>;
+
+/// [`SqlStore`] over a single SQLite connection. Watermarks are kept in the
+/// `ingestion_progress` table, which is created if missing.
+pub struct SqliteStore {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(connection: Connection) -> Result<Self> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS ingestion_progress (
+                task_name TEXT PRIMARY KEY,
+                checkpoint_number INTEGER NOT NULL
+            )",
+        )?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+}
+
+/// An open SQLite transaction, holding the store's connection until it is committed or dropped.
+///
+/// rusqlite calls block, so all work on the connection is moved off the async runtime, onto its
+/// blocking thread pool.
+pub struct SqliteTransaction {
+    /// Only `None` while the connection is in use on a blocking thread, or if that work was
+    /// cancelled or panicked, in which case the transaction was rolled back.
+    open: Option<OpenTransaction>,
+}
+
+/// The store's connection with a transaction open on it, which is rolled back when this is dropped
+/// before being committed. Dropping it blocks on SQLite.
+///
+/// It moves along with the connection onto blocking threads, so that the rollback happens even if
+/// the future waiting for that thread is cancelled. The connection stays locked until the rollback
+/// has run, so the next transaction can't start before it.
+struct OpenTransaction(Option<OwnedMutexGuard<Connection>>);
+
+impl OpenTransaction {
+    fn connection(&self) -> &Connection {
+        self.0.as_ref().expect("transaction is still open")
+    }
+
+    fn commit(mut self) -> rusqlite::Result<()> {
+        self.connection().execute_batch("COMMIT")?;
+        self.0 = None;
+        Ok(())
+    }
+}
+
+impl Drop for OpenTransaction {
+    fn drop(&mut self) {
+        if let Some(connection) = self.0.take() {
+            let _ = connection.execute_batch("ROLLBACK");
+        }
+    }
+}
+
+impl SqliteTransaction {
+    /// Runs `f` on the connection the transaction is open on (e.g. to write the task's output),
+    /// on a blocking thread.
+    pub async fn run<R, F>(&mut self, f: F) -> Result<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&Connection) -> rusqlite::Result<R> + Send + 'static,
+    {
+        let open = self.take_open()?;
+        let (open, result) = spawn_blocking(move || {
+            let result = f(open.connection());
+            (open, result)
+        })
+        .await?;
+        self.open = Some(open);
+        Ok(result?)
+    }
+
+    fn take_open(&mut self) -> Result<OpenTransaction> {
+        self.open
+            .take()
+            .ok_or_else(|| anyhow!("SQLite transaction was poisoned"))
+    }
+}
+
+impl Drop for SqliteTransaction {
+    fn drop(&mut self) {
+        let Some(open) = self.open.take() else {
+            return;
+        };
+        match Handle::try_current() {
+            Ok(handle) => drop(handle.spawn_blocking(move || drop(open))),
+            Err(_) => drop(open),
+        }
+    }
+}
+
+#[async_trait]
+impl SqlStore for SqliteStore {
+    type Transaction = SqliteTransaction;
+
+    async fn begin(&self) -> Result<SqliteTransaction> {
+        let connection = self.connection.clone().lock_owned().await;
+        // `BEGIN IMMEDIATE` waits for the database lock if another process holds it.
+        let connection = spawn_blocking(move || {
+            connection.execute_batch("BEGIN IMMEDIATE")?;
+            Ok::<_, rusqlite::Error>(connection)
+        })
+        .await??;
+        Ok(SqliteTransaction {
+            open: Some(OpenTransaction(Some(connection))),
+        })
+    }
+
+    async fn commit(&self, mut transaction: SqliteTransaction) -> Result<()> {
+        let open = transaction.take_open()?;
+        // If the commit fails, the transaction is rolled back as `open` is dropped.
+        spawn_blocking(move || open.commit()).await??;
+        Ok(())
+    }
+
+    async fn load_watermark(&self, task_name: &str) -> Result<Option<CheckpointSequenceNumber>> {
+        let connection = self.connection.clone().lock_owned().await;
+        let task_name = task_name.to_owned();
+        let watermark = spawn_blocking(move || {
+            connection
+                .query_row(
+                    "SELECT checkpoint_number FROM ingestion_progress WHERE task_name = ?1",
+                    params![task_name],
+                    |row| row.get::<_, i64>(0),
+                )
+                .optional()
+        })
+        .await??;
+        Ok(watermark.map(|watermark| watermark as CheckpointSequenceNumber))
+    }
+
+    async fn save_watermark(
+        &self,
+        transaction: &mut SqliteTransaction,
+        task_name: &str,
+        checkpoint_number: CheckpointSequenceNumber,
+    ) -> Result<()> {
+        let task_name = task_name.to_owned();
+        transaction
+            .run(move |connection| {
+                connection.execute(
+                    "INSERT INTO ingestion_progress (task_name, checkpoint_number) VALUES (?1, ?2)
+                    ON CONFLICT(task_name) DO UPDATE
+                    SET checkpoint_number = MAX(checkpoint_number, excluded.checkpoint_number)",
+                    params![task_name, checkpoint_number as i64],
+                )
+            })
+            .await?;
+        Ok(())
+    }
+}
@@ -30,7 +30,13 @@ pub(crate) async fn reduce<W: Worker>(
         while let Some(message) = unprocessed.remove(&current_checkpoint_number) {
             if let Some(ref reducer) = reducer {
                 if reducer.should_close_batch(&batch, Some(&message)) {
-                    reducer.commit(std::mem::take(&mut batch)).await?;
+                    reducer
+                        .commit_with_watermark(
+                            std::mem::take(&mut batch),
+                            &task_name,
+                            current_checkpoint_number,
+                        )
+                        .await?;
                     batch = vec![message];
                     progress_update = Some(current_checkpoint_number);
                 } else {
@@ -42,7 +48,13 @@ pub(crate) async fn reduce<W: Worker>(
         match reducer {
             Some(ref reducer) => {
                 if reducer.should_close_batch(&batch, None) {
-                    reducer.commit(std::mem::take(&mut batch)).await?;
+                    reducer
+                        .commit_with_watermark(
+                            std::mem::take(&mut batch),
+                            &task_name,
+                            current_checkpoint_number,
+                        )
+                        .await?;
                     progress_update = Some(current_checkpoint_number);
                 }
             }
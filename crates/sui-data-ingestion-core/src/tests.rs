@@ -3,24 +3,36 @@
 
 use crate::progress_store::ExecutorProgress;
 use crate::{DataIngestionMetrics, FileProgressStore, IndexerExecutor, WorkerPool};
-use crate::{ReaderOptions, Worker};
+use crate::{ProgressStore, ReaderOptions, Reducer, Worker};
+use crate::{SqlProgressStore, SqlReducer, SqlStore, SqliteStore, SqliteTransaction};
+use crate::{TransactionFilter, TransactionalReducer};
 use anyhow::Result;
 use async_trait::async_trait;
+use move_core_types::language_storage::StructTag;
 use prometheus::Registry;
 use rand::prelude::StdRng;
 use rand::SeedableRng;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 use sui_protocol_config::ProtocolConfig;
 use sui_storage::blob::{Blob, BlobEncoding};
-use sui_types::crypto::KeypairTraits;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::crypto::{get_key_pair, AccountKeyPair, KeypairTraits};
+use sui_types::effects::{TestEffectsBuilder, TransactionEvents};
+use sui_types::event::Event;
 use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::full_checkpoint_content::CheckpointTransaction;
 use sui_types::gas::GasCostSummary;
+use sui_types::gas_coin::GasCoin;
 use sui_types::messages_checkpoint::{
     CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber, CheckpointSummary,
     SignedCheckpointSummary,
 };
-use sui_types::utils::make_committee_key;
+use sui_types::object::Object;
+use sui_types::transaction::TransactionData;
+use sui_types::utils::{make_committee_key, to_sender_signed_transaction};
+use sui_types::Identifier;
 use tempfile::NamedTempFile;
 use tokio::sync::oneshot;
 
@@ -34,8 +46,8 @@ async fn add_worker_pool<W: Worker + 'static>(
     Ok(())
 }
 
-async fn run(
-    indexer: IndexerExecutor<FileProgressStore>,
+async fn run<P: ProgressStore + 'static>(
+    indexer: IndexerExecutor<P>,
     path: Option<PathBuf>,
     duration: Option<Duration>,
 ) -> Result<ExecutorProgress> {
@@ -106,6 +118,264 @@ async fn basic_flow() {
     assert_eq!(result.unwrap().get("test"), Some(&20));
 }
 
+struct SequenceNumberWorker;
+
+#[async_trait]
+impl Worker for SequenceNumberWorker {
+    type Result = CheckpointSequenceNumber;
+    async fn process_checkpoint(&self, checkpoint: &CheckpointData) -> Result<Self::Result> {
+        Ok(checkpoint.checkpoint_summary.sequence_number)
+    }
+}
+
+/// Records processed checkpoints in the `processed` table, failing on `fail_at` if set.
+struct ProcessedReducer {
+    fail_at: Option<CheckpointSequenceNumber>,
+}
+
+#[async_trait]
+impl SqlReducer<SqliteStore, CheckpointSequenceNumber> for ProcessedReducer {
+    async fn commit(
+        &self,
+        transaction: &mut SqliteTransaction,
+        batch: Vec<CheckpointSequenceNumber>,
+    ) -> Result<()> {
+        for checkpoint_number in batch {
+            transaction
+                .run(move |connection| {
+                    connection.execute(
+                        "INSERT INTO processed (checkpoint_number) VALUES (?1)",
+                        [checkpoint_number as i64],
+                    )
+                })
+                .await?;
+            if self.fail_at == Some(checkpoint_number) {
+                anyhow::bail!("failed to process checkpoint {checkpoint_number}");
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn create_sqlite_store(path: &std::path::Path) -> Arc<SqliteStore> {
+    let store = SqliteStore::open(path).unwrap();
+    let mut transaction = store.begin().await.unwrap();
+    transaction
+        .run(|connection| {
+            connection
+                .execute_batch("CREATE TABLE IF NOT EXISTS processed (checkpoint_number INTEGER)")
+        })
+        .await
+        .unwrap();
+    store.commit(transaction).await.unwrap();
+    Arc::new(store)
+}
+
+async fn processed_checkpoints(store: &SqliteStore) -> Vec<i64> {
+    let mut transaction = store.begin().await.unwrap();
+    transaction
+        .run(|connection| {
+            connection
+                .prepare("SELECT checkpoint_number FROM processed ORDER BY checkpoint_number")?
+                .query_map([], |row| row.get(0))?
+                .collect()
+        })
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn sqlite_transactional_watermark() {
+    let db_dir = temp_dir();
+    let store = create_sqlite_store(&db_dir.join("ingestion.db")).await;
+    let mut executor = IndexerExecutor::new(
+        SqlProgressStore::new(store.clone()),
+        1,
+        DataIngestionMetrics::new(&Registry::new()),
+    );
+    let reducer = TransactionalReducer::new(store.clone(), ProcessedReducer { fail_at: None });
+    let worker_pool = WorkerPool::new_with_reducer(
+        SequenceNumberWorker,
+        "test".to_string(),
+        5,
+        Box::new(reducer),
+    );
+    executor.register(worker_pool).await.unwrap();
+
+    let path = temp_dir();
+    for checkpoint_number in 0..20 {
+        let bytes = mock_checkpoint_data_bytes(checkpoint_number);
+        std::fs::write(path.join(format!("{}.chk", checkpoint_number)), bytes).unwrap();
+    }
+    let result = run(executor, Some(path), Some(Duration::from_secs(1))).await;
+    assert_eq!(result.unwrap().get("test"), Some(&20));
+
+    let store = create_sqlite_store(&db_dir.join("ingestion.db")).await;
+    assert_eq!(store.load_watermark("test").await.unwrap(), Some(20));
+    assert_eq!(
+        processed_checkpoints(&store).await,
+        (0..20).collect::<Vec<i64>>()
+    );
+}
+
+#[tokio::test]
+async fn sqlite_failed_commit_keeps_watermark() {
+    let store = create_sqlite_store(&temp_dir().join("ingestion.db")).await;
+    let mut progress_store = SqlProgressStore::new(store.clone());
+    progress_store.save("test".to_string(), 2).await.unwrap();
+
+    let reducer = TransactionalReducer::new(store.clone(), ProcessedReducer { fail_at: Some(3) });
+    assert!(reducer
+        .commit_with_watermark(vec![2, 3], "test", 4)
+        .await
+        .is_err());
+    assert_eq!(progress_store.load("test".to_string()).await.unwrap(), 2);
+    assert!(processed_checkpoints(&store).await.is_empty());
+
+    // Stale saves don't move the watermark backwards
+    reducer
+        .commit_with_watermark(vec![2], "test", 3)
+        .await
+        .unwrap();
+    progress_store.save("test".to_string(), 1).await.unwrap();
+    assert_eq!(progress_store.load("test".to_string()).await.unwrap(), 3);
+    assert_eq!(processed_checkpoints(&store).await, vec![2i64]);
+}
+
+#[tokio::test]
+async fn sqlite_cancelled_run_rolls_back() {
+    let store = create_sqlite_store(&temp_dir().join("ingestion.db")).await;
+    let mut transaction = store.begin().await.unwrap();
+    store
+        .save_watermark(&mut transaction, "test", 5)
+        .await
+        .unwrap();
+
+    // Drop the future while the closure is still running on its blocking thread
+    let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+    let run = transaction.run(move |connection| {
+        let _ = started_tx.send(());
+        std::thread::sleep(Duration::from_millis(100));
+        connection.execute("INSERT INTO processed (checkpoint_number) VALUES (1)", [])
+    });
+    tokio::select! {
+        _ = run => panic!("run should still be in progress"),
+        _ = started_rx => {}
+    }
+    assert!(transaction.run(|_| Ok(())).await.is_err());
+    drop(transaction);
+
+    // Neither write is visible, and the connection is free for new transactions
+    assert_eq!(store.load_watermark("test").await.unwrap(), None);
+    assert!(processed_checkpoints(&store).await.is_empty());
+}
+
+#[test]
+fn transaction_filters() {
+    let package = ObjectID::random();
+    let event_type = StructTag {
+        address: package.into(),
+        module: Identifier::new("module").unwrap(),
+        name: Identifier::new("Event").unwrap(),
+        type_params: vec![],
+    };
+    let (sender, first) = mock_checkpoint_transaction(package, event_type.clone());
+    let (_, second) = mock_checkpoint_transaction(ObjectID::random(), event_type.clone());
+    let checkpoint = CheckpointData {
+        transactions: vec![first, second],
+        ..mock_checkpoint_data(0)
+    };
+    let matching = |filter: TransactionFilter| filter.apply(&checkpoint).transactions.len();
+
+    assert_eq!(matching(TransactionFilter::Sender(sender)), 1);
+    assert_eq!(matching(TransactionFilter::Sender(SuiAddress::ZERO)), 0);
+    assert_eq!(
+        matching(TransactionFilter::MoveCall {
+            package,
+            module: Some("module".to_string()),
+            function: None,
+        }),
+        1
+    );
+    assert_eq!(
+        matching(TransactionFilter::MoveCall {
+            package,
+            module: Some("module".to_string()),
+            function: Some("other".to_string()),
+        }),
+        0
+    );
+    assert_eq!(
+        matching(TransactionFilter::EventType(event_type.clone())),
+        2
+    );
+    // Coin types without type parameters match coins of any type
+    let mut coin_type = GasCoin::type_();
+    assert_eq!(
+        matching(TransactionFilter::ObjectType(coin_type.clone())),
+        2
+    );
+    coin_type.type_params.clear();
+    assert_eq!(
+        matching(TransactionFilter::ObjectType(coin_type.clone())),
+        2
+    );
+    coin_type.name = Identifier::new("CoinMetadata").unwrap();
+    assert_eq!(matching(TransactionFilter::ObjectType(coin_type)), 0);
+    assert_eq!(
+        matching(TransactionFilter::All(vec![
+            TransactionFilter::EventType(event_type.clone()),
+            TransactionFilter::Sender(sender),
+        ])),
+        1
+    );
+    assert_eq!(
+        matching(TransactionFilter::Any(vec![
+            TransactionFilter::Sender(SuiAddress::ZERO),
+            TransactionFilter::EventType(event_type),
+        ])),
+        2
+    );
+}
+
+/// A transaction calling `package::module::function` and emitting an event of type `event_type`.
+fn mock_checkpoint_transaction(
+    package: ObjectID,
+    event_type: StructTag,
+) -> (SuiAddress, CheckpointTransaction) {
+    let (sender, key): (_, AccountKeyPair) = get_key_pair();
+    let gas = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let data = TransactionData::new_move_call(
+        sender,
+        package,
+        Identifier::new("module").unwrap(),
+        Identifier::new("function").unwrap(),
+        vec![],
+        gas.compute_object_reference(),
+        vec![],
+        1_000_000,
+        1,
+    )
+    .unwrap();
+    let transaction = to_sender_signed_transaction(data, &key);
+    let effects = TestEffectsBuilder::new(transaction.data()).build();
+    let event = Event {
+        package_id: package,
+        transaction_module: Identifier::new("module").unwrap(),
+        sender,
+        type_: event_type,
+        contents: vec![],
+    };
+    let checkpoint_transaction = CheckpointTransaction {
+        transaction,
+        effects,
+        events: Some(TransactionEvents { data: vec![event] }),
+        input_objects: vec![gas.clone()],
+        output_objects: vec![gas],
+    };
+    (sender, checkpoint_transaction)
+}
+
 fn temp_dir() -> std::path::PathBuf {
     tempfile::tempdir()
         .expect("Failed to open temporary directory")
@@ -134,6 +404,12 @@ const RNG_SEED: [u8; 32] = [
 ];
 
 fn mock_checkpoint_data_bytes(seq_number: CheckpointSequenceNumber) -> Vec<u8> {
+    Blob::encode(&mock_checkpoint_data(seq_number), BlobEncoding::Bcs)
+        .unwrap()
+        .to_bytes()
+}
+
+fn mock_checkpoint_data(seq_number: CheckpointSequenceNumber) -> CheckpointData {
     let mut rng = StdRng::from_seed(RNG_SEED);
     let (keys, committee) = make_committee_key(&mut rng);
     let contents = CheckpointContents::new_with_digests_only_for_tests(vec![]);
@@ -158,13 +434,10 @@ fn mock_checkpoint_data_bytes(seq_number: CheckpointSequenceNumber) -> Vec<u8> {
         })
         .collect();
 
-    let checkpoint_data = CheckpointData {
+    CheckpointData {
         checkpoint_summary: CertifiedCheckpointSummary::new(summary, sign_infos, &committee)
             .unwrap(),
         checkpoint_contents: contents,
         transactions: vec![],
-    };
-    Blob::encode(&checkpoint_data, BlobEncoding::Bcs)
-        .unwrap()
-        .to_bytes()
+    }
 }
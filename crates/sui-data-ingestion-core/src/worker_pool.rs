@@ -3,7 +3,7 @@
 
 use crate::executor::MAX_CHECKPOINTS_IN_PROGRESS;
 use crate::reducer::reduce;
-use crate::{Reducer, Worker};
+use crate::{Reducer, TransactionFilter, Worker};
 use mysten_metrics::spawn_monitored_task;
 use std::collections::{BTreeSet, VecDeque};
 use std::sync::Arc;
@@ -19,6 +19,7 @@ pub struct WorkerPool<W: Worker> {
     concurrency: usize,
     worker: Arc<W>,
     reducer: Option<Box<dyn Reducer<W::Result>>>,
+    pub(crate) filter: Option<TransactionFilter>,
 }
 
 impl<W: Worker + 'static> WorkerPool<W> {
@@ -28,6 +29,7 @@ impl<W: Worker + 'static> WorkerPool<W> {
             concurrency,
             worker: Arc::new(worker),
             reducer: None,
+            filter: None,
         }
     }
     pub fn new_with_reducer(
@@ -41,9 +43,17 @@ impl<W: Worker + 'static> WorkerPool<W> {
             concurrency,
             worker: Arc::new(worker),
             reducer: Some(reducer),
+            filter: None,
         }
     }
 
+    /// Only hand the workers the transactions matching `filter`. Workers still receive every
+    /// checkpoint, with the other transactions removed.
+    pub fn with_filter(mut self, filter: TransactionFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     pub async fn run(
         mut self,
         watermark: CheckpointSequenceNumber,
@@ -11,6 +11,7 @@ use jsonrpsee::{
     rpc_params,
 };
 use std::fs::File;
+use std::net::IpAddr;
 use std::num::NonZeroUsize;
 use std::time::Duration;
 use sui_core::authority_client::make_network_authority_clients_with_network_config;
@@ -30,7 +31,8 @@ use sui_types::{
     quorum_driver_types::ExecuteTransactionRequestType,
     signature::GenericSignature,
     traffic_control::{
-        FreqThresholdConfig, PolicyConfig, PolicyType, RemoteFirewallConfig, Weight,
+        FreqThresholdConfig, PolicyConfig, PolicyType, RemoteFirewallConfig, RequestWeights,
+        SlidingWindowConfig, TokenBucketConfig, Weight,
     },
 };
 use test_cluster::{TestCluster, TestClusterBuilder};
@@ -740,6 +742,110 @@ async fn test_traffic_sketch_allowlist_mode() {
     assert!(metrics.num_requests < expected_requests + 200);
 }
 
+#[sim_test]
+async fn test_traffic_sim_token_bucket_with_weights() {
+    let token_bucket_config = TokenBucketConfig {
+        capacity: 2_000,
+        refill_rate: 1_000.0,
+        proxied_capacity: None,
+        proxied_refill_rate: None,
+        request_weights: RequestWeights {
+            default: 1,
+            weights: [("dry_run".to_string(), 10)].into_iter().collect(),
+        },
+    };
+    let policy = PolicyConfig {
+        connection_blocklist_ttl_sec: 1,
+        spam_policy_type: PolicyType::TokenBucket(token_bucket_config),
+        // the default spam sample rate does not apply to weighted policies
+        dry_run: false,
+        ..Default::default()
+    };
+
+    // cheap requests stay well within the refill rate
+    let metrics = TrafficSim::run_with_request_type(
+        policy.clone(),
+        1,   // num_clients
+        500, // per_client_tps
+        Duration::from_secs(10),
+        true, // report
+        Some("object_info"),
+    )
+    .await;
+    assert_eq!(metrics.num_blocked, 0);
+    assert_eq!(metrics.num_blocklist_adds, 0);
+
+    // the same rate of dry runs costs 5 times the refill rate
+    let metrics = TrafficSim::run_with_request_type(
+        policy,
+        1,   // num_clients
+        500, // per_client_tps
+        Duration::from_secs(10),
+        true, // report
+        Some("dry_run"),
+    )
+    .await;
+    let expected_requests = 500 * 10;
+    assert!(metrics.num_requests > expected_requests - 100);
+    // the bucket is drained within the first second, after which the client
+    // is blocked for most of the time, as each 1 second block only refills
+    // enough tokens for a fraction of a second of traffic
+    assert!(metrics.abs_time_to_first_block.unwrap() < Duration::from_secs(1));
+    assert!(metrics.num_blocked > expected_requests / 2);
+    assert!(metrics.num_blocklist_adds >= 5);
+}
+
+#[sim_test]
+async fn test_traffic_sim_sliding_window_with_weights() {
+    let sliding_window_config = SlidingWindowConfig {
+        client_threshold: 1_000,
+        proxied_client_threshold: None,
+        window_size_secs: 2,
+        request_weights: RequestWeights {
+            default: 1,
+            weights: [("transaction".to_string(), 4)].into_iter().collect(),
+        },
+    };
+    let policy = PolicyConfig {
+        connection_blocklist_ttl_sec: 1,
+        spam_policy_type: PolicyType::SlidingWindow(sliding_window_config),
+        // the default spam sample rate does not apply to weighted policies
+        dry_run: false,
+        ..Default::default()
+    };
+
+    // 400 requests per second amount to a weight of 800 per window
+    let metrics = TrafficSim::run_with_request_type(
+        policy.clone(),
+        2,   // num_clients
+        400, // per_client_tps
+        Duration::from_secs(10),
+        true, // report
+        Some("object_info"),
+    )
+    .await;
+    assert_eq!(metrics.num_blocked, 0);
+    assert_eq!(metrics.num_blocklist_adds, 0);
+
+    // while the same number of transactions amounts to a weight of 3200
+    let metrics = TrafficSim::run_with_request_type(
+        policy,
+        2,   // num_clients
+        400, // per_client_tps
+        Duration::from_secs(10),
+        true, // report
+        Some("transaction"),
+    )
+    .await;
+    let expected_requests = 400 * 2 * 10;
+    assert!(metrics.num_requests > expected_requests - 200);
+    assert!(metrics.abs_time_to_first_block.unwrap() < Duration::from_secs(1));
+    assert!(metrics.num_blocked > expected_requests / 4);
+    // the window still exceeds the threshold when a block expires,
+    // so each client is blocked again right away
+    assert!(metrics.num_blocklist_adds >= 2 * 5);
+}
+
 async fn assert_traffic_control_ok(mut test_cluster: TestCluster) -> Result<(), anyhow::Error> {
     let context = &mut test_cluster.wallet;
     let jsonrpc_client = &test_cluster.fullnode_handle.rpc_client;
@@ -855,3 +961,57 @@ async fn assert_validator_traffic_control_dry_run(
     }
     Ok(())
 }
+
+#[tokio::test]
+async fn test_traffic_control_blocklist_persistence() {
+    let dir = tempfile::tempdir().unwrap();
+    let policy_config = PolicyConfig {
+        connection_blocklist_ttl_sec: 60,
+        dry_run: false,
+        blocklist_persistence_path: Some(dir.path().join("blocklist.json")),
+        blocklist_audit_log_path: Some(dir.path().join("blocklist_audit.log")),
+        ..Default::default()
+    };
+    let client: IpAddr = "10.0.0.1".parse().unwrap();
+    let proxied_client: IpAddr = "10.0.0.2".parse().unwrap();
+
+    let controller = TrafficController::init_for_test(policy_config.clone(), None);
+    controller
+        .block(client, false, Duration::from_secs(60), "test".to_string())
+        .await
+        .unwrap();
+    controller
+        .block(
+            proxied_client,
+            true,
+            Duration::from_secs(60),
+            "test".to_string(),
+        )
+        .await
+        .unwrap();
+    assert!(!controller.check(&Some(client), &None).await);
+    assert!(!controller.check(&None, &Some(proxied_client)).await);
+
+    // Blocks are restored by a new controller using the same persistence path,
+    // as would happen on restart
+    let restarted = TrafficController::init_for_test(policy_config.clone(), None);
+    let blocks = restarted.list_blocks();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks, controller.list_blocks());
+    assert!(!restarted.check(&Some(client), &None).await);
+
+    let unblocked = restarted.unblock(client).await.unwrap();
+    assert_eq!(unblocked.len(), 1);
+    assert!(restarted.check(&Some(client), &None).await);
+    assert!(restarted.unblock(client).await.unwrap().is_empty());
+
+    // Unblocks are persisted as well
+    let restarted = TrafficController::init_for_test(policy_config.clone(), None);
+    assert!(restarted.check(&Some(client), &None).await);
+    assert!(!restarted.check(&None, &Some(proxied_client)).await);
+
+    // 2 manual blocks, 2 restores, 1 unblock, 1 restore
+    let audit_log =
+        std::fs::read_to_string(policy_config.blocklist_audit_log_path.unwrap()).unwrap();
+    assert_eq!(audit_log.lines().count(), 6);
+}
@@ -0,0 +1,219 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::CacheConfig;
+use crate::metrics::AppMetrics;
+use moka::notification::RemovalCause;
+use moka::sync::Cache;
+use serde_json::Value;
+
+/// JSON-RPC read methods whose results never change once they are final, along with a check that
+/// a result is final.
+const CACHEABLE_METHODS: &[(&str, fn(&Value) -> bool)] = &[
+    ("sui_getTransactionBlock", is_checkpointed_transaction),
+    ("sui_multiGetTransactionBlocks", |result| {
+        result
+            .as_array()
+            .is_some_and(|txs| txs.iter().all(is_checkpointed_transaction))
+    }),
+    ("sui_getCheckpoint", |result| result.is_object()),
+    ("sui_tryGetPastObject", is_found_past_object),
+    ("sui_tryMultiGetPastObjects", |result| {
+        result
+            .as_array()
+            .is_some_and(|objects| objects.iter().all(is_found_past_object))
+    }),
+];
+
+/// Transactions can be returned before they are included in a checkpoint, in which case their
+/// checkpoint and timestamp are still missing.
+fn is_checkpointed_transaction(result: &Value) -> bool {
+    !result["checkpoint"].is_null()
+}
+
+/// Looking up a version that is higher than the latest version of an object can succeed later.
+fn is_found_past_object(result: &Value) -> bool {
+    result["status"] == "VersionFound"
+}
+
+/// A cacheable JSON-RPC request.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    method: &'static str,
+    params: String,
+}
+
+impl CacheKey {
+    /// The cache key of a JSON-RPC request, if the request is for one of the cacheable methods.
+    /// Batch requests are not cached.
+    pub fn from_request(request: &Value) -> Option<Self> {
+        let method = request.get("method")?.as_str()?;
+        let (method, _) = CACHEABLE_METHODS
+            .iter()
+            .find(|(cacheable, _)| *cacheable == method)?;
+        let params = request.get("params").unwrap_or(&Value::Null).to_string();
+        Some(Self { method, params })
+    }
+
+    pub fn method(&self) -> &'static str {
+        self.method
+    }
+}
+
+/// Bounded cache of the results of immutable read requests. Entries are weighted by the size of
+/// their result, and evicted when the cache is full or when they have not been read for a while.
+pub struct ResponseCache {
+    results: Cache<CacheKey, String>,
+    metrics: AppMetrics,
+}
+
+impl ResponseCache {
+    pub fn new(config: &CacheConfig, metrics: AppMetrics) -> Self {
+        let evictions = metrics.cache_evictions_total.clone();
+        let mut builder = Cache::builder()
+            .max_capacity(config.max_size_bytes)
+            .weigher(|key: &CacheKey, result: &String| {
+                (key.params.len() + result.len())
+                    .try_into()
+                    .unwrap_or(u32::MAX)
+            })
+            .eviction_listener(move |_, _, cause| {
+                if matches!(cause, RemovalCause::Size | RemovalCause::Expired) {
+                    evictions.inc();
+                }
+            });
+        if let Some(time_to_idle) = config.time_to_idle_seconds {
+            builder = builder.time_to_idle(time_to_idle);
+        }
+        Self {
+            results: builder.build(),
+            metrics,
+        }
+    }
+
+    /// Look up the response to a request, answering with the request's `id`.
+    pub fn get(&self, key: &CacheKey, id: &Value) -> Option<String> {
+        let Some(result) = self.results.get(key) else {
+            self.metrics
+                .cache_misses_total
+                .with_label_values(&[key.method])
+                .inc();
+            return None;
+        };
+        self.metrics
+            .cache_hits_total
+            .with_label_values(&[key.method])
+            .inc();
+        Some(format!(
+            r#"{{"jsonrpc":"2.0","result":{result},"id":{id}}}"#
+        ))
+    }
+
+    /// Cache the result of a successful response, if the result is final.
+    pub fn insert(&self, key: CacheKey, response: &[u8]) {
+        let Ok(mut response) = serde_json::from_slice::<Value>(response) else {
+            return;
+        };
+        let Some(result) = response.get_mut("result").map(Value::take) else {
+            return;
+        };
+        let is_final = CACHEABLE_METHODS
+            .iter()
+            .find(|(method, _)| *method == key.method)
+            .is_some_and(|(_, is_final)| is_final(&result));
+        if !is_final {
+            return;
+        }
+        self.results.insert(key, result.to_string());
+        self.metrics
+            .cache_size_bytes
+            .set(self.results.weighted_size() as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::Registry;
+    use serde_json::json;
+
+    fn cache() -> ResponseCache {
+        let config = CacheConfig {
+            max_size_bytes: 1024,
+            time_to_idle_seconds: None,
+        };
+        ResponseCache::new(&config, AppMetrics::new(&Registry::new()))
+    }
+
+    fn request(method: &str, params: Value) -> Value {
+        json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params})
+    }
+
+    fn response(result: Value) -> Vec<u8> {
+        serde_json::to_vec(&json!({"jsonrpc": "2.0", "id": 1, "result": result})).unwrap()
+    }
+
+    #[test]
+    fn only_immutable_methods_are_cacheable() {
+        assert!(CacheKey::from_request(&request("sui_getCheckpoint", json!(["10"]))).is_some());
+        assert!(CacheKey::from_request(&request(
+            "sui_getLatestCheckpointSequenceNumber",
+            json!([])
+        ))
+        .is_none());
+        assert!(
+            CacheKey::from_request(&json!([request("sui_getCheckpoint", json!(["10"]))])).is_none()
+        );
+    }
+
+    #[test]
+    fn cached_responses_use_request_id() {
+        let cache = cache();
+        let key = CacheKey::from_request(&request("sui_getCheckpoint", json!(["10"]))).unwrap();
+        assert_eq!(cache.get(&key, &json!(1)), None);
+
+        cache.insert(key.clone(), &response(json!({"sequenceNumber": "10"})));
+        cache.results.run_pending_tasks();
+        let cached: Value = serde_json::from_str(&cache.get(&key, &json!("abc")).unwrap()).unwrap();
+        assert_eq!(
+            cached,
+            json!({"jsonrpc": "2.0", "id": "abc", "result": {"sequenceNumber": "10"}})
+        );
+
+        // Other params are cached separately.
+        let other = CacheKey::from_request(&request("sui_getCheckpoint", json!(["11"]))).unwrap();
+        assert_eq!(cache.get(&other, &json!(1)), None);
+    }
+
+    #[test]
+    fn results_that_can_change_are_not_cached() {
+        let cache = cache();
+        let tx =
+            CacheKey::from_request(&request("sui_getTransactionBlock", json!(["digest"]))).unwrap();
+        cache.insert(tx.clone(), &response(json!({"digest": "digest"})));
+        let object =
+            CacheKey::from_request(&request("sui_tryGetPastObject", json!(["0x5", 10]))).unwrap();
+        cache.insert(
+            object.clone(),
+            &response(json!({"status": "VersionTooHigh"})),
+        );
+        let checkpoint =
+            CacheKey::from_request(&request("sui_getCheckpoint", json!(["10"]))).unwrap();
+        cache.insert(
+            checkpoint.clone(),
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"not found"}}"#,
+        );
+        cache.results.run_pending_tasks();
+
+        assert_eq!(cache.get(&tx, &json!(1)), None);
+        assert_eq!(cache.get(&object, &json!(1)), None);
+        assert_eq!(cache.get(&checkpoint, &json!(1)), None);
+
+        cache.insert(
+            tx.clone(),
+            &response(json!({"digest": "digest", "checkpoint": "10"})),
+        );
+        cache.results.run_pending_tasks();
+        assert!(cache.get(&tx, &json!(1)).is_some());
+    }
+}
@@ -1,11 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_with::formats::PreferMany;
 use serde_with::serde_as;
-use serde_with::DurationSeconds;
+use serde_with::{DurationSeconds, OneOrMany};
 use std::{net::SocketAddr, time::Duration};
 use tracing::error;
 use url::Url;
@@ -16,8 +17,22 @@ use url::Url;
 pub struct ProxyConfig {
     pub listen_address: SocketAddr,
     pub metrics_address: SocketAddr,
-    pub execution_peer: PeerConfig,
-    pub read_peer: PeerConfig,
+    /// Peers that transactions are executed on. A single peer can also be given as `execution-peer`.
+    #[serde_as(as = "OneOrMany<_, PreferMany>")]
+    #[serde(alias = "execution-peer")]
+    pub execution_peers: Vec<PeerConfig>,
+    /// Peers that all other requests are sent to. A single peer can also be given as `read-peer`.
+    #[serde_as(as = "OneOrMany<_, PreferMany>")]
+    #[serde(alias = "read-peer")]
+    pub read_peers: Vec<PeerConfig>,
+    /// How requests are balanced across the healthy peers of a pool.
+    #[serde(default)]
+    pub routing: RoutingStrategy,
+    #[serde(default)]
+    pub health_check: HealthCheckConfig,
+    /// Cache for the results of read requests that never change. Disabled if not set.
+    #[serde(default)]
+    pub cache: Option<CacheConfig>,
     /// Maximum number of idle connections to keep in the connection pool.
     /// When set, this limits the number of connections that remain open but unused,
     /// helping to conserve system resources.
@@ -45,6 +60,69 @@ pub struct PeerConfig {
     pub address: Url,
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoutingStrategy {
+    /// Send each request to the peer with the fewest requests in flight.
+    #[default]
+    LeastOutstandingRequests,
+    /// Send each request to the peer with the highest checkpoint, breaking ties by the number
+    /// of requests in flight.
+    LowestCheckpointLag,
+}
+
+/// Background health probing of peers. Every interval, each peer's `/health` endpoint is
+/// queried, along with its latest checkpoint if routing or ejection depends on it.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HealthCheckConfig {
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_health_check_interval")]
+    pub interval_seconds: Duration,
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_health_check_timeout")]
+    pub timeout_seconds: Duration,
+    /// Number of consecutive failed checks or requests after which a peer is ejected.
+    #[serde(default = "default_unhealthy_threshold")]
+    pub unhealthy_threshold: usize,
+    /// Number of consecutive successful checks after which an ejected peer is readmitted.
+    #[serde(default = "default_healthy_threshold")]
+    pub healthy_threshold: usize,
+    /// When set, peers whose latest checkpoint is more than this many checkpoints behind the most
+    /// up-to-date peer of their pool fail their health checks.
+    #[serde(default)]
+    pub max_checkpoint_lag: Option<u64>,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            interval_seconds: default_health_check_interval(),
+            timeout_seconds: default_health_check_timeout(),
+            unhealthy_threshold: default_unhealthy_threshold(),
+            healthy_threshold: default_healthy_threshold(),
+            max_checkpoint_lag: None,
+        }
+    }
+}
+
+fn default_health_check_interval() -> Duration {
+    Duration::from_secs(5)
+}
+
+fn default_health_check_timeout() -> Duration {
+    Duration::from_secs(2)
+}
+
+fn default_unhealthy_threshold() -> usize {
+    3
+}
+
+fn default_healthy_threshold() -> usize {
+    2
+}
+
 /// Load and validate configuration
 pub async fn load<P: AsRef<std::path::Path>>(path: P) -> Result<(ProxyConfig, Client)> {
     let path = path.as_ref();
@@ -61,12 +139,49 @@ pub async fn load<P: AsRef<std::path::Path>>(path: P) -> Result<(ProxyConfig, Cl
         .build()
         .expect("Failed to build HTTP/2 client");
 
-    validate_peer_url(&client, &config.read_peer).await?;
-    validate_peer_url(&client, &config.execution_peer).await?;
+    if config.health_check.interval_seconds.is_zero() {
+        bail!("Health check interval must be non-zero");
+    }
+    validate_peers("read", &config.read_peers)?;
+    validate_peers("execution", &config.execution_peers)?;
+    for peer in config.read_peers.iter().chain(&config.execution_peers) {
+        validate_peer_url(&client, peer).await?;
+    }
 
     Ok((config, client))
 }
 
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CacheConfig {
+    /// Maximum total size of the cached results. When the cache is full, the entries that are
+    /// least likely to be read again are evicted.
+    #[serde(default = "default_cache_max_size_bytes")]
+    pub max_size_bytes: u64,
+    /// Entries that have not been read for this long are evicted.
+    #[serde_as(as = "Option<DurationSeconds>")]
+    #[serde(default)]
+    pub time_to_idle_seconds: Option<Duration>,
+}
+
+fn default_cache_max_size_bytes() -> u64 {
+    256 * 1024 * 1024
+}
+
+/// Validate that a pool has peers, and that their health check URLs can be constructed.
+fn validate_peers(role: &str, peers: &[PeerConfig]) -> Result<()> {
+    if peers.is_empty() {
+        bail!("No {role} peers configured");
+    }
+    for peer in peers {
+        peer.address
+            .join("/health")
+            .with_context(|| format!("Invalid {role} peer address {}", peer.address))?;
+    }
+    Ok(())
+}
+
 /// Validate that the given PeerConfig URL has a valid host
 async fn validate_peer_url(client: &Client, peer: &PeerConfig) -> Result<()> {
     let health_url = peer
@@ -1,53 +1,43 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::config::PeerConfig;
+use crate::cache::{CacheKey, ResponseCache};
 use crate::metrics::AppMetrics;
+use crate::peers::{PeerPool, PeerRole};
 use axum::{
     body::Body,
     extract::{Request, State},
     http::request::Parts,
-    http::StatusCode,
+    http::{header, StatusCode},
     response::Response,
 };
 use bytes::Bytes;
+use std::sync::Arc;
 use std::time::Instant;
 use tracing::{debug, info, warn};
 
-#[derive(Debug)]
-enum PeerRole {
-    Read,
-    Execution,
-}
-
-impl PeerRole {
-    fn as_str(&self) -> &'static str {
-        match self {
-            PeerRole::Read => "read",
-            PeerRole::Execution => "execution",
-        }
-    }
-}
-
 #[derive(Clone)]
 pub struct AppState {
     client: reqwest::Client,
-    read_peer: PeerConfig,
-    execution_peer: PeerConfig,
+    read_peers: Arc<PeerPool>,
+    execution_peers: Arc<PeerPool>,
+    cache: Option<Arc<ResponseCache>>,
     metrics: AppMetrics,
 }
 
 impl AppState {
     pub fn new(
         client: reqwest::Client,
-        read_peer: PeerConfig,
-        execution_peer: PeerConfig,
+        read_peers: Arc<PeerPool>,
+        execution_peers: Arc<PeerPool>,
+        cache: Option<Arc<ResponseCache>>,
         metrics: AppMetrics,
     ) -> Self {
         Self {
             client,
-            read_peer,
-            execution_peer,
+            read_peers,
+            execution_peers,
+            cache,
             metrics,
         }
     }
@@ -76,22 +66,35 @@ pub async fn proxy_handler(
     {
         Some("sui_executeTransactionBlock") => {
             info!("Using execution peer");
-            proxy_request(state, parts, body_bytes, PeerRole::Execution).await
+            proxy_request(state, parts, body_bytes, PeerRole::Execution, None).await
         }
         _ => {
             let json_body = match serde_json::from_slice::<serde_json::Value>(&body_bytes) {
                 Ok(json_body) => json_body,
                 Err(_) => {
                     debug!("Failed to parse request body as JSON");
-                    return proxy_request(state, parts, body_bytes, PeerRole::Read).await;
+                    return proxy_request(state, parts, body_bytes, PeerRole::Read, None).await;
                 }
             };
             if let Some("sui_executeTransactionBlock") =
                 json_body.get("method").and_then(|m| m.as_str())
             {
-                proxy_request(state, parts, body_bytes, PeerRole::Execution).await
+                proxy_request(state, parts, body_bytes, PeerRole::Execution, None).await
             } else {
-                proxy_request(state, parts, body_bytes, PeerRole::Read).await
+                let cache_key = state
+                    .cache
+                    .as_ref()
+                    .and_then(|_| CacheKey::from_request(&json_body));
+                if let Some((cache, key)) = state.cache.as_ref().zip(cache_key.as_ref()) {
+                    if let Some(cached) = cache.get(key, &json_body["id"]) {
+                        debug!("Serving {} from cache", key.method());
+                        return Ok(Response::builder()
+                            .header(header::CONTENT_TYPE, "application/json")
+                            .body(Body::from(cached))
+                            .unwrap());
+                    }
+                }
+                proxy_request(state, parts, body_bytes, PeerRole::Read, cache_key).await
             }
         }
     }
@@ -102,6 +105,7 @@ async fn proxy_request(
     parts: Parts,
     body_bytes: Bytes,
     peer_type: PeerRole,
+    cache_key: Option<CacheKey>,
 ) -> Result<Response, (StatusCode, String)> {
     debug!(
         "Proxying request: method={:?}, uri={:?}, headers={:?}, body_len={}, peer_type={:?}",
@@ -123,62 +127,95 @@ async fn proxy_request(
         .with_label_values(&[peer_type_str])
         .observe(body_bytes.len() as f64);
 
-    let peer_config = match peer_type {
-        PeerRole::Read => &state.read_peer,
-        PeerRole::Execution => &state.execution_peer,
+    let pool = match peer_type {
+        PeerRole::Read => &state.read_peers,
+        PeerRole::Execution => &state.execution_peers,
     };
 
-    let mut target_url = peer_config.address.clone();
-    target_url.set_path(parts.uri.path());
-    if let Some(query) = parts.uri.query() {
-        target_url.set_query(Some(query));
-    }
-
     // remove host header to avoid interfering with reqwest auto-host header
     let mut headers = parts.headers.clone();
     headers.remove("host");
-    let request_builder = state
-        .client
-        .request(parts.method.clone(), target_url)
-        .headers(headers)
-        .body(body_bytes);
-    debug!("Request builder: {:?}", request_builder);
-
-    let upstream_start = Instant::now();
-    let response = match request_builder.send().await {
-        Ok(response) => {
-            let status = response.status().as_u16().to_string();
-            metrics
-                .upstream_response_latency
-                .with_label_values(&[peer_type_str, &status])
-                .observe(upstream_start.elapsed().as_secs_f64());
-            metrics
-                .requests_total
-                .with_label_values(&[peer_type_str, &status])
-                .inc();
-            debug!("Response: {:?}", response);
-            response
-        }
-        Err(e) => {
-            warn!("Failed to send request: {}", e);
-            metrics
-                .upstream_response_latency
-                .with_label_values(&[peer_type_str, "error"])
-                .observe(upstream_start.elapsed().as_secs_f64());
+
+    // Requests that could not reach a peer are retried on the other peers of the pool.
+    let mut tried = vec![];
+    let (_guard, response) = loop {
+        let Some(guard) = pool.select(&tried) else {
             metrics
                 .requests_total
                 .with_label_values(&[peer_type_str, "error"])
                 .inc();
-            if e.is_timeout() {
+            return Err((
+                StatusCode::BAD_GATEWAY,
+                format!("No {peer_type_str} peer could be reached"),
+            ));
+        };
+        let peer = guard.peer().clone();
+        let peer_str = peer.address().as_str();
+
+        let mut target_url = peer.address().clone();
+        target_url.set_path(parts.uri.path());
+        if let Some(query) = parts.uri.query() {
+            target_url.set_query(Some(query));
+        }
+
+        let request_builder = state
+            .client
+            .request(parts.method.clone(), target_url)
+            .headers(headers.clone())
+            .body(body_bytes.clone());
+        debug!("Request builder: {:?}", request_builder);
+
+        let upstream_start = Instant::now();
+        match request_builder.send().await {
+            Ok(response) => {
+                let status = response.status().as_u16().to_string();
+                metrics
+                    .upstream_response_latency
+                    .with_label_values(&[peer_type_str, &status])
+                    .observe(upstream_start.elapsed().as_secs_f64());
+                metrics
+                    .requests_total
+                    .with_label_values(&[peer_type_str, &status])
+                    .inc();
+                metrics
+                    .peer_requests_total
+                    .with_label_values(&[peer_type_str, peer_str, &status])
+                    .inc();
+                debug!("Response: {:?}", response);
+                pool.record_success(&peer);
+                break (guard, response);
+            }
+            Err(e) => {
+                warn!("Failed to send request to {}: {}", peer_str, e);
+                metrics
+                    .upstream_response_latency
+                    .with_label_values(&[peer_type_str, "error"])
+                    .observe(upstream_start.elapsed().as_secs_f64());
                 metrics
-                    .timeouts_total
-                    .with_label_values(&[peer_type_str])
+                    .peer_requests_total
+                    .with_label_values(&[peer_type_str, peer_str, "error"])
                     .inc();
+                if e.is_timeout() {
+                    metrics
+                        .timeouts_total
+                        .with_label_values(&[peer_type_str])
+                        .inc();
+                }
+                pool.record_failure(&peer);
+                if e.is_connect() {
+                    tried.push(peer);
+                    continue;
+                }
+                metrics
+                    .requests_total
+                    .with_label_values(&[peer_type_str, "error"])
+                    .inc();
+                return Err((StatusCode::BAD_GATEWAY, format!("Request failed: {}", e)));
             }
-            return Err((StatusCode::BAD_GATEWAY, format!("Request failed: {}", e)));
         }
     };
 
+    let response_status = response.status();
     let response_headers = response.headers().clone();
     let response_bytes = match response.bytes().await {
         Ok(bytes) => bytes,
@@ -199,6 +236,12 @@ async fn proxy_request(
         .with_label_values(&[peer_type_str])
         .observe(response_bytes.len() as f64);
 
+    if let Some((cache, key)) = state.cache.as_ref().zip(cache_key) {
+        if response_status.is_success() {
+            cache.insert(key, &response_bytes);
+        }
+    }
+
     let mut resp = Response::new(response_bytes.into());
     for (name, value) in response_headers {
         if let Some(name) = name {
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod cache;
 pub mod config;
 pub mod handlers;
 pub mod metrics;
+pub mod peers;
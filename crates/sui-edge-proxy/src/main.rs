@@ -5,9 +5,12 @@ use axum::{routing::any, Router};
 use clap::Parser;
 use mysten_metrics::start_prometheus_server;
 use reqwest::Client;
+use std::sync::Arc;
+use sui_edge_proxy::cache::ResponseCache;
 use sui_edge_proxy::config::{load, ProxyConfig};
 use sui_edge_proxy::handlers::{proxy_handler, AppState};
 use sui_edge_proxy::metrics::AppMetrics;
+use sui_edge_proxy::peers::{PeerPool, PeerRole};
 use tracing::info;
 
 #[derive(Parser, Debug)]
@@ -42,20 +45,43 @@ async fn main() {
 
     let app_metrics = AppMetrics::new(&prometheus_registry);
 
-    let app_state = AppState::new(
-        client,
-        config.read_peer.clone(),
-        config.execution_peer.clone(),
-        app_metrics,
-    );
+    let read_peers = Arc::new(PeerPool::new(
+        PeerRole::Read,
+        config.read_peers.clone(),
+        config.routing,
+        config.health_check.clone(),
+        app_metrics.clone(),
+    ));
+    let execution_peers = Arc::new(PeerPool::new(
+        PeerRole::Execution,
+        config.execution_peers.clone(),
+        config.routing,
+        config.health_check.clone(),
+        app_metrics.clone(),
+    ));
+    let health_checks = vec![
+        read_peers.spawn_health_checks(client.clone()),
+        execution_peers.spawn_health_checks(client.clone()),
+    ];
+
+    let cache = config
+        .cache
+        .as_ref()
+        .map(|cache| Arc::new(ResponseCache::new(cache, app_metrics.clone())));
+
+    let app_state = AppState::new(client, read_peers, execution_peers, cache, app_metrics);
 
     let app = Router::new()
         .fallback(any(proxy_handler))
         .with_state(app_state);
 
     info!("Starting server on {}", config.listen_address);
-    axum_server::Server::bind(config.listen_address)
-        .serve(app.into_make_service())
-        .await
-        .unwrap();
+    let server = axum_server::Server::bind(config.listen_address).serve(app.into_make_service());
+    // Health checks run for as long as the proxy does, routing would silently degrade without them.
+    tokio::select! {
+        result = server => result.unwrap(),
+        (result, _, _) = futures::future::select_all(health_checks) => {
+            panic!("Health check task exited unexpectedly: {result:?}");
+        }
+    }
 }
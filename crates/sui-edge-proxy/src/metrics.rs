@@ -3,7 +3,9 @@
 
 use prometheus::{
     register_gauge_vec_with_registry, register_histogram_vec_with_registry,
-    register_int_counter_vec_with_registry, GaugeVec, HistogramVec, IntCounterVec, Registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, GaugeVec, HistogramVec,
+    IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Registry,
 };
 
 #[derive(Clone)]
@@ -16,6 +18,16 @@ pub struct AppMetrics {
     pub request_size_bytes: HistogramVec,
     pub timeouts_total: IntCounterVec,
     pub error_counts: IntCounterVec,
+    pub peer_up: GaugeVec,
+    pub peer_requests_total: IntCounterVec,
+    pub peer_outstanding_requests: IntGaugeVec,
+    pub peer_checkpoint_lag: IntGaugeVec,
+    pub peer_health_check_failures: IntCounterVec,
+    pub peer_ejections_total: IntCounterVec,
+    pub cache_hits_total: IntCounterVec,
+    pub cache_misses_total: IntCounterVec,
+    pub cache_evictions_total: IntCounter,
+    pub cache_size_bytes: IntGauge,
 }
 
 impl AppMetrics {
@@ -77,6 +89,74 @@ impl AppMetrics {
                 registry
             )
             .unwrap(),
+            peer_up: register_gauge_vec_with_registry!(
+                "edge_proxy_peer_up",
+                "Indicates if a peer is admitted to its pool (1) or ejected (0)",
+                &["peer_type", "peer"],
+                registry
+            )
+            .unwrap(),
+            peer_requests_total: register_int_counter_vec_with_registry!(
+                "edge_proxy_peer_requests_total",
+                "Total number of requests sent to a peer",
+                &["peer_type", "peer", "status"],
+                registry
+            )
+            .unwrap(),
+            peer_outstanding_requests: register_int_gauge_vec_with_registry!(
+                "edge_proxy_peer_outstanding_requests",
+                "Number of requests in flight to a peer",
+                &["peer_type", "peer"],
+                registry
+            )
+            .unwrap(),
+            peer_checkpoint_lag: register_int_gauge_vec_with_registry!(
+                "edge_proxy_peer_checkpoint_lag",
+                "Number of checkpoints a peer is behind the most up-to-date peer of its pool",
+                &["peer_type", "peer"],
+                registry
+            )
+            .unwrap(),
+            peer_health_check_failures: register_int_counter_vec_with_registry!(
+                "edge_proxy_peer_health_check_failures",
+                "Total number of failed health checks of a peer",
+                &["peer_type", "peer"],
+                registry
+            )
+            .unwrap(),
+            peer_ejections_total: register_int_counter_vec_with_registry!(
+                "edge_proxy_peer_ejections_total",
+                "Total number of times a peer was ejected from its pool",
+                &["peer_type", "peer"],
+                registry
+            )
+            .unwrap(),
+            cache_hits_total: register_int_counter_vec_with_registry!(
+                "edge_proxy_cache_hits_total",
+                "Total number of requests answered from the response cache",
+                &["method"],
+                registry
+            )
+            .unwrap(),
+            cache_misses_total: register_int_counter_vec_with_registry!(
+                "edge_proxy_cache_misses_total",
+                "Total number of cacheable requests that were not in the response cache",
+                &["method"],
+                registry
+            )
+            .unwrap(),
+            cache_evictions_total: register_int_counter_with_registry!(
+                "edge_proxy_cache_evictions_total",
+                "Total number of entries evicted from the response cache",
+                registry
+            )
+            .unwrap(),
+            cache_size_bytes: register_int_gauge_with_registry!(
+                "edge_proxy_cache_size_bytes",
+                "Total size of the results in the response cache",
+                registry
+            )
+            .unwrap(),
         }
     }
 }
@@ -0,0 +1,461 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::{HealthCheckConfig, PeerConfig, RoutingStrategy};
+use crate::metrics::AppMetrics;
+use anyhow::{anyhow, bail, Context, Result};
+use prometheus::IntGauge;
+use reqwest::Client;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+use url::Url;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PeerRole {
+    Read,
+    Execution,
+}
+
+impl PeerRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PeerRole::Read => "read",
+            PeerRole::Execution => "execution",
+        }
+    }
+}
+
+/// A peer of a pool, along with what is known about its health and load.
+pub struct Peer {
+    address: Url,
+    healthy: AtomicBool,
+    outstanding: AtomicUsize,
+    /// Latest checkpoint reported by the peer, or `u64::MAX` if it is not known.
+    checkpoint: AtomicU64,
+    streak: Mutex<HealthStreak>,
+    outstanding_gauge: IntGauge,
+}
+
+/// Consecutive health check results of a peer. Only one of the counts is non-zero at a time.
+#[derive(Default)]
+struct HealthStreak {
+    failures: usize,
+    successes: usize,
+}
+
+impl Peer {
+    fn new(config: PeerConfig, outstanding_gauge: IntGauge) -> Self {
+        Self {
+            address: config.address,
+            healthy: AtomicBool::new(true),
+            outstanding: AtomicUsize::new(0),
+            checkpoint: AtomicU64::new(u64::MAX),
+            streak: Mutex::new(HealthStreak::default()),
+            outstanding_gauge,
+        }
+    }
+
+    pub fn address(&self) -> &Url {
+        &self.address
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    pub fn outstanding_requests(&self) -> usize {
+        self.outstanding.load(Ordering::Relaxed)
+    }
+
+    pub fn checkpoint(&self) -> Option<u64> {
+        match self.checkpoint.load(Ordering::Relaxed) {
+            u64::MAX => None,
+            checkpoint => Some(checkpoint),
+        }
+    }
+}
+
+/// A request in flight to a peer. The peer's outstanding request count is decremented when the
+/// guard is dropped.
+pub struct PeerGuard {
+    peer: Arc<Peer>,
+}
+
+impl PeerGuard {
+    fn new(peer: Arc<Peer>) -> Self {
+        peer.outstanding.fetch_add(1, Ordering::Relaxed);
+        peer.outstanding_gauge.inc();
+        Self { peer }
+    }
+
+    pub fn peer(&self) -> &Arc<Peer> {
+        &self.peer
+    }
+}
+
+impl Drop for PeerGuard {
+    fn drop(&mut self) {
+        self.peer.outstanding.fetch_sub(1, Ordering::Relaxed);
+        self.peer.outstanding_gauge.dec();
+    }
+}
+
+/// The peers serving one role. Peers are ejected from the pool after consecutive failures, and
+/// readmitted after consecutive successful health checks.
+pub struct PeerPool {
+    role: PeerRole,
+    peers: Vec<Arc<Peer>>,
+    routing: RoutingStrategy,
+    health_check: HealthCheckConfig,
+    /// Rotates the order in which peers are considered, so that ties are spread across peers.
+    next: AtomicUsize,
+    metrics: AppMetrics,
+}
+
+impl PeerPool {
+    pub fn new(
+        role: PeerRole,
+        peers: Vec<PeerConfig>,
+        routing: RoutingStrategy,
+        health_check: HealthCheckConfig,
+        metrics: AppMetrics,
+    ) -> Self {
+        let peers = peers
+            .into_iter()
+            .map(|config| {
+                let labels = [role.as_str(), config.address.as_str()];
+                metrics.peer_up.with_label_values(&labels).set(1.0);
+                let outstanding_gauge =
+                    metrics.peer_outstanding_requests.with_label_values(&labels);
+                Arc::new(Peer::new(config, outstanding_gauge))
+            })
+            .collect();
+        metrics
+            .backend_up
+            .with_label_values(&[role.as_str()])
+            .set(1.0);
+        Self {
+            role,
+            peers,
+            routing,
+            health_check,
+            next: AtomicUsize::new(0),
+            metrics,
+        }
+    }
+
+    pub fn role(&self) -> PeerRole {
+        self.role
+    }
+
+    pub fn peers(&self) -> &[Arc<Peer>] {
+        &self.peers
+    }
+
+    /// Pick the peer to send the next request to, skipping the `excluded` peers. Healthy peers
+    /// are preferred. If none of them is healthy, all peers are considered, so that requests keep
+    /// flowing while the pool recovers.
+    pub fn select(&self, excluded: &[Arc<Peer>]) -> Option<PeerGuard> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let candidates: Vec<_> = (0..self.peers.len())
+            .map(|i| &self.peers[(start + i) % self.peers.len()])
+            .filter(|peer| !excluded.iter().any(|excluded| Arc::ptr_eq(peer, excluded)))
+            .collect();
+
+        let healthy: Vec<_> = candidates
+            .iter()
+            .copied()
+            .filter(|peer| peer.is_healthy())
+            .collect();
+        let candidates = if healthy.is_empty() {
+            candidates
+        } else {
+            healthy
+        };
+
+        let peer = match self.routing {
+            RoutingStrategy::LeastOutstandingRequests => candidates
+                .into_iter()
+                .min_by_key(|peer| peer.outstanding_requests()),
+            RoutingStrategy::LowestCheckpointLag => candidates.into_iter().min_by_key(|peer| {
+                (
+                    std::cmp::Reverse(peer.checkpoint().unwrap_or(0)),
+                    peer.outstanding_requests(),
+                )
+            }),
+        };
+        peer.map(|peer| PeerGuard::new(peer.clone()))
+    }
+
+    /// Count a failed request to a peer towards its ejection.
+    pub fn record_failure(&self, peer: &Peer) {
+        self.update_health(peer, false);
+    }
+
+    /// Reset the failure streak of a peer that served a request, so that only consecutive
+    /// failures eject it. Ejected peers are still only readmitted by health checks.
+    pub fn record_success(&self, peer: &Peer) {
+        peer.streak.lock().unwrap().failures = 0;
+    }
+
+    /// Probe the health of the pool's peers in the background, until the returned task is aborted.
+    pub fn spawn_health_checks(self: &Arc<Self>, client: Client) -> JoinHandle<()> {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(pool.health_check.interval_seconds);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                pool.check_health(&client).await;
+            }
+        })
+    }
+
+    /// Probe every peer of the pool once, and eject or readmit them based on the results.
+    pub async fn check_health(&self, client: &Client) {
+        let fetch_checkpoint = self.routing == RoutingStrategy::LowestCheckpointLag
+            || self.health_check.max_checkpoint_lag.is_some();
+        let results = futures::future::join_all(
+            self.peers
+                .iter()
+                .map(|peer| self.probe(client, peer, fetch_checkpoint)),
+        )
+        .await;
+
+        let latest = results
+            .iter()
+            .filter_map(|result| result.as_ref().ok().copied().flatten())
+            .max();
+
+        for (peer, result) in self.peers.iter().zip(results) {
+            let labels = [self.role.as_str(), peer.address.as_str()];
+            let checkpoint = match result {
+                Ok(checkpoint) => checkpoint,
+                Err(e) => {
+                    debug!(
+                        "Health check failed for {} peer {}: {e:#}",
+                        self.role.as_str(),
+                        peer.address
+                    );
+                    self.metrics
+                        .peer_health_check_failures
+                        .with_label_values(&labels)
+                        .inc();
+                    self.update_health(peer, false);
+                    continue;
+                }
+            };
+
+            let lag = checkpoint
+                .zip(latest)
+                .map(|(checkpoint, latest)| latest.saturating_sub(checkpoint));
+            if let Some(checkpoint) = checkpoint {
+                peer.checkpoint.store(checkpoint, Ordering::Relaxed);
+            }
+            if let Some(lag) = lag {
+                self.metrics
+                    .peer_checkpoint_lag
+                    .with_label_values(&labels)
+                    .set(lag as i64);
+            }
+
+            let lagging = lag
+                .zip(self.health_check.max_checkpoint_lag)
+                .is_some_and(|(lag, max_lag)| lag > max_lag);
+            if lagging {
+                debug!(
+                    "{} peer {} is {} checkpoints behind",
+                    self.role.as_str(),
+                    peer.address,
+                    lag.unwrap_or_default()
+                );
+                self.metrics
+                    .peer_health_check_failures
+                    .with_label_values(&labels)
+                    .inc();
+            }
+            self.update_health(peer, !lagging);
+        }
+    }
+
+    /// Check that the peer reports itself healthy, and return its latest checkpoint if
+    /// `fetch_checkpoint` is set.
+    async fn probe(
+        &self,
+        client: &Client,
+        peer: &Peer,
+        fetch_checkpoint: bool,
+    ) -> Result<Option<u64>> {
+        let timeout = self.health_check.timeout_seconds;
+        let response = client
+            .get(peer.address.join("/health")?)
+            .timeout(timeout)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            bail!("health check returned status {}", response.status());
+        }
+        if !fetch_checkpoint {
+            return Ok(None);
+        }
+
+        let response: serde_json::Value = client
+            .post(peer.address.clone())
+            .timeout(timeout)
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "sui_getLatestCheckpointSequenceNumber",
+                "params": [],
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let checkpoint = match &response["result"] {
+            serde_json::Value::String(checkpoint) => checkpoint
+                .parse()
+                .context("invalid checkpoint sequence number")?,
+            serde_json::Value::Number(checkpoint) => checkpoint
+                .as_u64()
+                .ok_or_else(|| anyhow!("invalid checkpoint sequence number"))?,
+            _ => bail!("unexpected response to checkpoint query: {response}"),
+        };
+        Ok(Some(checkpoint))
+    }
+
+    fn update_health(&self, peer: &Peer, success: bool) {
+        let labels = [self.role.as_str(), peer.address.as_str()];
+        let mut streak = peer.streak.lock().unwrap();
+        if success {
+            streak.failures = 0;
+            streak.successes += 1;
+            if !peer.is_healthy() && streak.successes >= self.health_check.healthy_threshold {
+                info!("Readmitting {} peer {}", self.role.as_str(), peer.address);
+                peer.healthy.store(true, Ordering::Relaxed);
+                self.metrics.peer_up.with_label_values(&labels).set(1.0);
+            }
+        } else {
+            streak.successes = 0;
+            streak.failures += 1;
+            if peer.is_healthy() && streak.failures >= self.health_check.unhealthy_threshold {
+                warn!(
+                    "Ejecting {} peer {} after {} consecutive failures",
+                    self.role.as_str(),
+                    peer.address,
+                    streak.failures
+                );
+                peer.healthy.store(false, Ordering::Relaxed);
+                self.metrics.peer_up.with_label_values(&labels).set(0.0);
+                self.metrics
+                    .peer_ejections_total
+                    .with_label_values(&labels)
+                    .inc();
+            }
+        }
+        drop(streak);
+
+        let any_healthy = self.peers.iter().any(|peer| peer.is_healthy());
+        self.metrics
+            .backend_up
+            .with_label_values(&[self.role.as_str()])
+            .set(if any_healthy { 1.0 } else { 0.0 });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::Registry;
+
+    fn pool(routing: RoutingStrategy, peers: usize) -> PeerPool {
+        let peers = (0..peers)
+            .map(|i| PeerConfig {
+                address: format!("http://peer-{i}:9000").parse().unwrap(),
+            })
+            .collect();
+        let health_check = HealthCheckConfig {
+            unhealthy_threshold: 2,
+            healthy_threshold: 2,
+            ..Default::default()
+        };
+        PeerPool::new(
+            PeerRole::Read,
+            peers,
+            routing,
+            health_check,
+            AppMetrics::new(&Registry::new()),
+        )
+    }
+
+    #[test]
+    fn least_outstanding_requests() {
+        let pool = pool(RoutingStrategy::LeastOutstandingRequests, 2);
+        let first = pool.select(&[]).unwrap();
+        let second = pool.select(&[]).unwrap();
+        assert!(!Arc::ptr_eq(first.peer(), second.peer()));
+
+        // Once a request completes, its peer is the least loaded again.
+        let first_peer = first.peer().clone();
+        drop(first);
+        assert_eq!(first_peer.outstanding_requests(), 0);
+        let third = pool.select(&[]).unwrap();
+        assert!(Arc::ptr_eq(third.peer(), &first_peer));
+
+        // Excluded peers are skipped, even if they are the least loaded.
+        drop(second);
+        let fourth = pool.select(&[first_peer.clone()]).unwrap();
+        assert!(!Arc::ptr_eq(fourth.peer(), &first_peer));
+        assert!(pool.select(&[first_peer, fourth.peer().clone()]).is_none());
+    }
+
+    #[test]
+    fn lowest_checkpoint_lag() {
+        let pool = pool(RoutingStrategy::LowestCheckpointLag, 3);
+        pool.peers[0].checkpoint.store(10, Ordering::Relaxed);
+        pool.peers[1].checkpoint.store(12, Ordering::Relaxed);
+        pool.peers[2].checkpoint.store(12, Ordering::Relaxed);
+
+        let first = pool.select(&[]).unwrap();
+        let second = pool.select(&[]).unwrap();
+        assert_eq!(first.peer().checkpoint(), Some(12));
+        assert_eq!(second.peer().checkpoint(), Some(12));
+        assert!(!Arc::ptr_eq(first.peer(), second.peer()));
+    }
+
+    #[test]
+    fn ejection_and_readmission() {
+        let pool = pool(RoutingStrategy::LeastOutstandingRequests, 2);
+        let peer = pool.peers[0].clone();
+
+        pool.record_failure(&peer);
+        assert!(peer.is_healthy());
+        // A successful request in between resets the failure streak.
+        pool.record_success(&peer);
+        pool.record_failure(&peer);
+        assert!(peer.is_healthy());
+        pool.record_failure(&peer);
+        assert!(!peer.is_healthy());
+
+        // Ejected peers are not selected while another peer is healthy.
+        for _ in 0..4 {
+            let guard = pool.select(&[]).unwrap();
+            assert!(!Arc::ptr_eq(guard.peer(), &peer));
+        }
+
+        // If no peer is healthy, requests still go to one of them.
+        pool.record_failure(&pool.peers[1]);
+        pool.record_failure(&pool.peers[1]);
+        assert!(pool.select(&[]).is_some());
+
+        pool.update_health(&peer, true);
+        assert!(!peer.is_healthy());
+        pool.update_health(&peer, true);
+        assert!(peer.is_healthy());
+        let guard = pool.select(&[]).unwrap();
+        assert!(Arc::ptr_eq(guard.peer(), &peer));
+    }
+}
@@ -40,6 +40,12 @@ pub enum FaucetError {
     #[error("Coin amounts sent are incorrect:`{0}`")]
     CoinAmountTransferredIncorrect(String),
 
+    #[error("Too many requests for {key}, retry after {retry_after_secs} seconds")]
+    TooManyRequests { key: String, retry_after_secs: u64 },
+
+    #[error("Cannot determine the client IP of the request: {0}")]
+    UnknownClientIp(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -48,4 +54,14 @@ impl FaucetError {
     pub(crate) fn internal(e: impl ToString) -> Self {
         FaucetError::Internal(e.to_string())
     }
+
+    /// Seconds after which a throttled request can be retried.
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            FaucetError::TooManyRequests {
+                retry_after_secs, ..
+            } => Some(*retry_after_secs),
+            _ => None,
+        }
+    }
 }
@@ -6,11 +6,14 @@ use serde::{Deserialize, Serialize};
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use uuid::Uuid;
 
+mod quota;
 mod simple_faucet;
 mod write_ahead_log;
+pub use self::quota::{QuotaConfig, QuotaKey, TokenBucket};
 pub use self::simple_faucet::SimpleFaucet;
 use clap::Parser;
-use std::{net::Ipv4Addr, path::PathBuf, sync::Arc};
+use std::{net::Ipv4Addr, path::PathBuf, sync::Arc, time::Duration};
+use sui_types::traffic_control::ClientIdSource;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FaucetReceipt {
@@ -125,6 +128,48 @@ pub struct FaucetConfig {
 
     #[clap(long, action = clap::ArgAction::Set, default_value_t = false)]
     pub batch_enabled: bool,
+
+    /// Maximum number of requests a recipient address can make in a burst. Unlimited if unset.
+    #[clap(long)]
+    pub max_requests_per_address: Option<u64>,
+
+    /// Seconds after which a recipient address can make one more request.
+    #[clap(long, default_value_t = 3600)]
+    pub address_quota_refill_secs: u64,
+
+    /// Maximum number of requests a client IP can make in a burst. Unlimited if unset.
+    #[clap(long)]
+    pub max_requests_per_ip: Option<u64>,
+
+    /// Seconds after which a client IP can make one more request.
+    #[clap(long, default_value_t = 3600)]
+    pub ip_quota_refill_secs: u64,
+
+    /// Take the client IP for quotas from the `x-forwarded-for` header, this many hops from the
+    /// end of the list, rather than from the connection. Set when the faucet is behind a proxy.
+    #[clap(long)]
+    pub forwarded_for_hops: Option<usize>,
+}
+
+impl FaucetConfig {
+    pub fn address_quota(&self) -> Option<QuotaConfig> {
+        self.max_requests_per_address.map(|capacity| QuotaConfig {
+            capacity,
+            refill_interval: Duration::from_secs(self.address_quota_refill_secs),
+        })
+    }
+
+    pub fn ip_quota(&self) -> Option<QuotaConfig> {
+        self.max_requests_per_ip.map(|capacity| QuotaConfig {
+            capacity,
+            refill_interval: Duration::from_secs(self.ip_quota_refill_secs),
+        })
+    }
+
+    pub fn client_id_source(&self) -> ClientIdSource {
+        self.forwarded_for_hops
+            .map_or(ClientIdSource::SocketAddr, ClientIdSource::XForwardedFor)
+    }
 }
 
 impl Default for FaucetConfig {
@@ -143,6 +188,11 @@ impl Default for FaucetConfig {
             batch_request_size: 500,
             ttl_expiration: 300,
             batch_enabled: false,
+            max_requests_per_address: None,
+            address_quota_refill_secs: 3600,
+            max_requests_per_ip: None,
+            ip_quota_refill_secs: 3600,
+            forwarded_for_hops: None,
         }
     }
 }
@@ -0,0 +1,117 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::IpAddr;
+use std::time::Duration;
+use sui_types::base_types::SuiAddress;
+
+/// Limits how often a single recipient or client can request coins: up to `capacity` requests in a
+/// burst, after which one more request is allowed every `refill_interval`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuotaConfig {
+    pub capacity: u64,
+    pub refill_interval: Duration,
+}
+
+/// What a quota applies to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuotaKey {
+    Address(SuiAddress),
+    Ip(IpAddr),
+}
+
+impl fmt::Display for QuotaKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaKey::Address(address) => write!(f, "address {address}"),
+            QuotaKey::Ip(ip) => write!(f, "IP {ip}"),
+        }
+    }
+}
+
+/// Remaining requests in a quota, as persisted in the write-ahead log.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBucket {
+    pub tokens: u64,
+    /// Time the bucket was last refilled at, in milliseconds since the Unix epoch
+    pub refilled_at_ms: u64,
+}
+
+impl TokenBucket {
+    pub fn full(config: &QuotaConfig, now_ms: u64) -> Self {
+        Self {
+            tokens: config.capacity,
+            refilled_at_ms: now_ms,
+        }
+    }
+
+    /// Refill the bucket for the time elapsed since it was last refilled, and take a token from it.
+    /// If the bucket is empty, returns how long to wait until a token becomes available.
+    pub fn try_take(&mut self, config: &QuotaConfig, now_ms: u64) -> Result<(), Duration> {
+        let interval_ms = (config.refill_interval.as_millis() as u64).max(1);
+        let elapsed_ms = now_ms.saturating_sub(self.refilled_at_ms);
+        let refilled = elapsed_ms / interval_ms;
+        if self.tokens.saturating_add(refilled) >= config.capacity {
+            self.tokens = config.capacity;
+            self.refilled_at_ms = now_ms;
+        } else {
+            self.tokens += refilled;
+            self.refilled_at_ms += refilled * interval_ms;
+        }
+
+        if self.tokens == 0 {
+            let waited_ms = now_ms.saturating_sub(self.refilled_at_ms);
+            return Err(Duration::from_millis(interval_ms - waited_ms));
+        }
+        self.tokens -= 1;
+        Ok(())
+    }
+
+    /// Give back a token taken for a request that could not be served.
+    pub fn refund(&mut self, config: &QuotaConfig) {
+        self.tokens = (self.tokens + 1).min(config.capacity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket_refills_over_time() {
+        let config = QuotaConfig {
+            capacity: 2,
+            refill_interval: Duration::from_secs(10),
+        };
+        let mut bucket = TokenBucket::full(&config, 0);
+
+        assert_eq!(bucket.try_take(&config, 0), Ok(()));
+        assert_eq!(bucket.try_take(&config, 1_000), Ok(()));
+        assert_eq!(
+            bucket.try_take(&config, 4_000),
+            Err(Duration::from_millis(6_000))
+        );
+
+        // One token was refilled after the interval, and the partial interval carries over
+        assert_eq!(bucket.try_take(&config, 12_000), Ok(()));
+        assert_eq!(
+            bucket.try_take(&config, 15_000),
+            Err(Duration::from_millis(5_000))
+        );
+
+        // The bucket never holds more than its capacity
+        assert_eq!(bucket.try_take(&config, 1_000_000), Ok(()));
+        assert_eq!(bucket.try_take(&config, 1_000_000), Ok(()));
+        assert!(bucket.try_take(&config, 1_000_000).is_err());
+
+        // Refunds allow another request, up to the capacity
+        bucket.refund(&config);
+        assert_eq!(bucket.try_take(&config, 1_000_000), Ok(()));
+        bucket.refund(&config);
+        bucket.refund(&config);
+        bucket.refund(&config);
+        assert_eq!(bucket.tokens, config.capacity);
+    }
+}
@@ -12,8 +12,10 @@ use std::collections::HashMap;
 #[cfg(test)]
 use std::collections::HashSet;
 use std::fmt;
+use std::net::IpAddr;
 use std::path::Path;
 use std::sync::{Arc, Weak};
+use std::time::{SystemTime, UNIX_EPOCH};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use tap::tap::TapFallible;
 use tokio::sync::oneshot;
@@ -41,6 +43,7 @@ use tokio::time::{timeout, Duration};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use super::quota::{QuotaConfig, QuotaKey};
 use super::write_ahead_log::WriteAheadLog;
 use crate::{
     BatchFaucetReceipt, BatchSendStatus, BatchSendStatusType, CoinInfo, Faucet, FaucetConfig,
@@ -61,6 +64,8 @@ pub struct SimpleFaucet {
     task_id_cache: Mutex<TtlCache<Uuid, BatchSendStatus>>,
     ttl_expiration: u64,
     coin_amount: u64,
+    address_quota: Option<QuotaConfig>,
+    ip_quota: Option<QuotaConfig>,
     /// Shuts down the batch transfer task. Used only in testing.
     #[allow(unused)]
     batch_transfer_shutdown: parking_lot::Mutex<Option<oneshot::Sender<()>>>,
@@ -191,6 +196,8 @@ impl SimpleFaucet {
             task_id_cache: TtlCache::new(config.max_request_per_second as usize * 60 * 10).into(),
             ttl_expiration: config.ttl_expiration,
             coin_amount: config.amount,
+            address_quota: config.address_quota(),
+            ip_quota: config.ip_quota(),
             batch_transfer_shutdown: parking_lot::Mutex::new(Some(batch_transfer_shutdown)),
         };
 
@@ -375,6 +382,66 @@ impl SimpleFaucet {
         }))
     }
 
+    /// Quotas that apply to requests for `recipient` from the client's `ip`.
+    fn quotas(&self, recipient: SuiAddress, ip: Option<IpAddr>) -> Vec<(QuotaKey, QuotaConfig)> {
+        self.address_quota
+            .map(|quota| (QuotaKey::Address(recipient), quota))
+            .into_iter()
+            .chain(
+                ip.zip(self.ip_quota)
+                    .map(|(ip, quota)| (QuotaKey::Ip(ip), quota)),
+            )
+            .collect()
+    }
+
+    /// Take a request from the quotas of `recipient` and of the client's `ip`, if they are
+    /// configured. Fails with [`FaucetError::TooManyRequests`] if either quota is exhausted.
+    pub async fn take_quotas(
+        &self,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+    ) -> Result<(), FaucetError> {
+        let quotas = self.quotas(recipient, ip);
+        if quotas.is_empty() {
+            return Ok(());
+        }
+
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(FaucetError::internal)?
+            .as_millis() as u64;
+        let exhausted = self
+            .wal
+            .lock()
+            .await
+            .take_quotas(&quotas, now_ms)
+            .map_err(FaucetError::internal)?;
+        match exhausted {
+            None => Ok(()),
+            Some((key, retry_after)) => {
+                info!(%key, ?retry_after, "Request exceeds quota");
+                self.metrics.total_requests_throttled.inc();
+                Err(FaucetError::TooManyRequests {
+                    key: key.to_string(),
+                    retry_after_secs: retry_after.as_secs_f64().ceil() as u64,
+                })
+            }
+        }
+    }
+
+    /// Give back the request taken by [`Self::take_quotas`] for a request that failed, so that
+    /// failures do not count against the quotas.
+    pub async fn refund_quotas(&self, recipient: SuiAddress, ip: Option<IpAddr>) {
+        let quotas = self.quotas(recipient, ip);
+        if quotas.is_empty() {
+            return;
+        }
+
+        if let Err(err) = self.wal.lock().await.refund_quotas(&quotas) {
+            error!(?recipient, ?ip, "Failed to refund quotas: {:?}", err);
+        }
+    }
+
     /// Clear the WAL list in the faucet
     pub async fn retry_wal_coins(&self) -> Result<(), FaucetError> {
         let mut wal = self.wal.lock().await;
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::path::Path;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use sui_types::base_types::SuiAddress;
@@ -14,6 +15,8 @@ use tracing::info;
 use typed_store::DBMapUtils;
 use uuid::Uuid;
 
+use super::quota::{QuotaConfig, QuotaKey, TokenBucket};
+
 /// Persistent log of transactions paying out sui from the faucet, keyed by the coin serving the
 /// request.  Transactions are expected to be written to the log before they are sent to full-node,
 /// and removed after receiving a response back, before the coin becomes available for subsequent
@@ -21,9 +24,13 @@ use uuid::Uuid;
 ///
 /// This allows the faucet to go down and back up, and not forget which requests were in-flight that
 /// it needs to confirm succeeded or failed.
+///
+/// The request quotas of recipients and clients are kept alongside the log, so that they also
+/// survive restarts.
 #[derive(DBMapUtils, Clone)]
 pub struct WriteAheadLog {
     pub log: DBMap<ObjectID, Entry>,
+    pub quotas: DBMap<QuotaKey, TokenBucket>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -108,6 +115,51 @@ impl WriteAheadLog {
         Ok(())
     }
 
+    /// Take one request from each of the given quotas. If any of them is exhausted, no request is
+    /// taken from any of them, and the quota that takes the longest to allow a request again is
+    /// returned along with how long that takes.
+    pub(crate) fn take_quotas(
+        &mut self,
+        quotas: &[(QuotaKey, QuotaConfig)],
+        now_ms: u64,
+    ) -> Result<Option<(QuotaKey, Duration)>, TypedStoreError> {
+        let mut buckets = Vec::with_capacity(quotas.len());
+        let mut retry_after: Option<(QuotaKey, Duration)> = None;
+        for (key, config) in quotas {
+            let mut bucket = self
+                .quotas
+                .get(key)?
+                .unwrap_or_else(|| TokenBucket::full(config, now_ms));
+            if let Err(wait) = bucket.try_take(config, now_ms) {
+                if retry_after.map_or(true, |(_, longest)| wait > longest) {
+                    retry_after = Some((*key, wait));
+                }
+            }
+            buckets.push((*key, bucket));
+        }
+
+        if retry_after.is_none() {
+            self.quotas.multi_insert(buckets)?;
+        }
+        Ok(retry_after)
+    }
+
+    /// Give back one request to each of the given quotas, after a request that took from them
+    /// failed.
+    pub(crate) fn refund_quotas(
+        &mut self,
+        quotas: &[(QuotaKey, QuotaConfig)],
+    ) -> Result<(), TypedStoreError> {
+        let mut buckets = Vec::with_capacity(quotas.len());
+        for (key, config) in quotas {
+            if let Some(mut bucket) = self.quotas.get(key)? {
+                bucket.refund(config);
+                buckets.push((*key, bucket));
+            }
+        }
+        self.quotas.multi_insert(buckets)
+    }
+
     pub(crate) fn set_in_flight(
         &mut self,
         coin: ObjectID,
@@ -252,6 +304,43 @@ mod tests {
         wal.reserve(uuid, coin.0, recv1, tx1).unwrap();
     }
 
+    #[tokio::test]
+    async fn take_quotas_persists() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut wal = WriteAheadLog::open(&tmp.path().join("wal"));
+
+        let config = QuotaConfig {
+            capacity: 1,
+            refill_interval: Duration::from_secs(60),
+        };
+        let address = QuotaKey::Address(SuiAddress::random_for_testing_only());
+        let ip = QuotaKey::Ip("127.0.0.1".parse().unwrap());
+
+        assert_eq!(wal.take_quotas(&[(address, config)], 0), Ok(None));
+
+        // The address is exhausted, so the IP quota is left untouched
+        assert_eq!(
+            wal.take_quotas(&[(address, config), (ip, config)], 1_000),
+            Ok(Some((address, Duration::from_secs(59)))),
+        );
+        assert_eq!(wal.take_quotas(&[(ip, config)], 1_000), Ok(None));
+
+        // Quotas survive reopening the log
+        drop(wal);
+        let mut wal = WriteAheadLog::open(&tmp.path().join("wal"));
+        assert_eq!(
+            wal.take_quotas(&[(ip, config)], 2_000),
+            Ok(Some((ip, Duration::from_secs(59)))),
+        );
+        assert_eq!(wal.take_quotas(&[(address, config)], 60_000), Ok(None));
+
+        // Refunded quotas allow another request straight away
+        wal.refund_quotas(&[(address, config), (ip, config)])
+            .unwrap();
+        assert_eq!(wal.take_quotas(&[(address, config)], 60_000), Ok(None));
+        assert_eq!(wal.take_quotas(&[(ip, config)], 2_000), Ok(None));
+    }
+
     fn random_request(coin: ObjectRef) -> (SuiAddress, TransactionData) {
         let gas_price = 1;
         let send = SuiAddress::random_for_testing_only();
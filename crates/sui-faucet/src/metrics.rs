@@ -5,8 +5,9 @@
 use mysten_network::metrics::MetricsCallbackProvider;
 use prometheus::{
     register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
-    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, HistogramVec,
-    IntCounterVec, IntGauge, IntGaugeVec, Registry,
+    register_int_counter_with_registry, register_int_gauge_vec_with_registry,
+    register_int_gauge_with_registry, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Registry,
 };
 use std::time::Duration;
 use tonic::Code;
@@ -33,6 +34,7 @@ pub struct FaucetMetrics {
     pub(crate) total_available_coins: IntGauge,
     pub(crate) total_discarded_coins: IntGauge,
     pub(crate) total_coin_requests_succeeded: IntGauge,
+    pub(crate) total_requests_throttled: IntCounter,
 }
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
@@ -129,6 +131,12 @@ impl FaucetMetrics {
                 registry,
             )
             .unwrap(),
+            total_requests_throttled: register_int_counter_with_registry!(
+                "total_requests_throttled",
+                "Total number of requests rejected because the recipient or client exceeded its quota",
+                registry,
+            )
+            .unwrap(),
         }
     }
 }
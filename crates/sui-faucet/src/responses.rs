@@ -10,11 +10,15 @@ use uuid::Uuid;
 pub struct FaucetResponse {
     pub transferred_gas_objects: Vec<CoinInfo>,
     pub error: Option<String>,
+    /// Set if the request was throttled, to the number of seconds after which it can be retried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
 }
 
 impl From<FaucetError> for FaucetResponse {
     fn from(e: FaucetError) -> Self {
         Self {
+            retry_after_secs: e.retry_after_secs(),
             error: Some(e.to_string()),
             transferred_gas_objects: vec![],
         }
@@ -26,6 +30,7 @@ impl From<FaucetReceipt> for FaucetResponse {
         Self {
             transferred_gas_objects: v.sent,
             error: None,
+            retry_after_secs: None,
         }
     }
 }
@@ -36,11 +41,15 @@ pub struct BatchFaucetResponse {
     // This string is the Uuid for the req
     pub task: Option<String>,
     pub error: Option<String>,
+    /// Set if the request was throttled, to the number of seconds after which it can be retried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
 }
 
 impl From<FaucetError> for BatchFaucetResponse {
     fn from(e: FaucetError) -> Self {
         Self {
+            retry_after_secs: e.retry_after_secs(),
             error: Some(e.to_string()),
             task: None,
         }
@@ -52,6 +61,7 @@ impl From<BatchFaucetReceipt> for BatchFaucetResponse {
         Self {
             task: Some(v.task),
             error: None,
+            retry_after_secs: None,
         }
     }
 }
@@ -61,6 +71,7 @@ impl From<Uuid> for BatchFaucetResponse {
         Self {
             task: Some(v.to_string()),
             error: None,
+            retry_after_secs: None,
         }
     }
 }
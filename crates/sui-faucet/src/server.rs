@@ -8,8 +8,8 @@ use crate::{
 
 use axum::{
     error_handling::HandleErrorLayer,
-    extract::Path,
-    http::StatusCode,
+    extract::{ConnectInfo, Path},
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
     BoxError, Extension, Json, Router,
@@ -26,9 +26,11 @@ use std::{
 };
 use sui_config::SUI_CLIENT_CONFIG;
 use sui_sdk::wallet_context::WalletContext;
+use sui_types::base_types::SuiAddress;
+use sui_types::traffic_control::ClientIdSource;
 use tower::{limit::RateLimitLayer, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use crate::faucet::Faucet;
@@ -47,13 +49,50 @@ pub async fn start_faucet(
     let FaucetConfig {
         port,
         host_ip,
+        wal_retry_interval,
+        ..
+    } = app_state.config;
+
+    let app = app(app_state.clone(), concurrency_limit, prometheus_registry);
+
+    spawn_monitored_task!(async move {
+        info!("Starting task to clear WAL.");
+        loop {
+            // Every config.wal_retry_interval (Default: 300 seconds) we try to clear the wal coins
+            tokio::time::sleep(Duration::from_secs(wal_retry_interval)).await;
+            app_state.faucet.retry_wal_coins().await.unwrap();
+        }
+    });
+
+    let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
+    info!("listening on {}", addr);
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
+    Ok(())
+}
+
+fn app(
+    app_state: Arc<AppState>,
+    concurrency_limit: usize,
+    prometheus_registry: &Registry,
+) -> Router {
+    // TODO: restrict access if needed
+    let cors = CorsLayer::new()
+        .allow_methods(vec![Method::GET, Method::POST])
+        .allow_headers(Any)
+        .allow_origin(Any);
+
+    let FaucetConfig {
         request_buffer_size,
         max_request_per_second,
-        wal_retry_interval,
         ..
     } = app_state.config;
 
-    let app = Router::new()
+    Router::new()
         .route("/", get(health))
         .route("/gas", post(request_gas))
         .route("/v1/gas", post(batch_request_gas))
@@ -70,24 +109,9 @@ pub async fn start_faucet(
                     Duration::from_secs(1),
                 ))
                 .concurrency_limit(concurrency_limit)
-                .layer(Extension(app_state.clone()))
+                .layer(Extension(app_state))
                 .into_inner(),
-        );
-
-    spawn_monitored_task!(async move {
-        info!("Starting task to clear WAL.");
-        loop {
-            // Every config.wal_retry_interval (Default: 300 seconds) we try to clear the wal coins
-            tokio::time::sleep(Duration::from_secs(wal_retry_interval)).await;
-            app_state.faucet.retry_wal_coins().await.unwrap();
-        }
-    });
-
-    let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
-    info!("listening on {}", addr);
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await?;
-    Ok(())
+        )
 }
 
 /// basic handler that responds with a static string
@@ -98,6 +122,8 @@ async fn health() -> &'static str {
 /// handler for batch_request_gas requests
 async fn batch_request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     let id = Uuid::new_v4();
@@ -113,16 +139,31 @@ async fn batch_request_gas(
         );
     };
 
+    let ip = match take_quotas(&state, request.recipient, client, &headers).await {
+        Ok(ip) => ip,
+        Err(err) => {
+            warn!(uuid = ?id, "Rejected gas request: {:?}", err);
+            return (
+                quota_error_status(&err),
+                Json(BatchFaucetResponse::from(err)),
+            );
+        }
+    };
+
     if state.config.batch_enabled {
         let result = spawn_monitored_task!(async move {
-            state
+            let result = state
                 .faucet
                 .batch_send(
                     id,
                     request.recipient,
                     &vec![state.config.amount; state.config.num_coins],
                 )
-                .await
+                .await;
+            if result.is_err() {
+                state.faucet.refund_quotas(request.recipient, ip).await;
+            }
+            result
         })
         .await
         .unwrap();
@@ -144,14 +185,18 @@ async fn batch_request_gas(
         // TODO (jian): remove this feature gate when batch has proven to be baked long enough
         info!(uuid = ?id, "Falling back to v1 implementation");
         let result = spawn_monitored_task!(async move {
-            state
+            let result = state
                 .faucet
                 .send(
                     id,
                     request.recipient,
                     &vec![state.config.amount; state.config.num_coins],
                 )
-                .await
+                .await;
+            if result.is_err() {
+                state.faucet.refund_quotas(request.recipient, ip).await;
+            }
+            result
         })
         .await
         .unwrap();
@@ -203,6 +248,8 @@ async fn request_status(
 /// handler for all the request_gas requests
 async fn request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     // ID for traceability
@@ -210,17 +257,28 @@ async fn request_gas(
     info!(uuid = ?id, "Got new gas request.");
     let result = match payload {
         FaucetRequest::FixedAmountRequest(requests) => {
+            let ip = match take_quotas(&state, requests.recipient, client, &headers).await {
+                Ok(ip) => ip,
+                Err(err) => {
+                    warn!(uuid = ?id, "Rejected gas request: {:?}", err);
+                    return (quota_error_status(&err), Json(FaucetResponse::from(err)));
+                }
+            };
             // We spawn a tokio task for this such that connection drop will not interrupt
             // it and impact the recycling of coins
             spawn_monitored_task!(async move {
-                state
+                let result = state
                     .faucet
                     .send(
                         id,
                         requests.recipient,
                         &vec![state.config.amount; state.config.num_coins],
                     )
-                    .await
+                    .await;
+                if result.is_err() {
+                    state.faucet.refund_quotas(requests.recipient, ip).await;
+                }
+                result
             })
             .await
             .unwrap()
@@ -262,6 +320,78 @@ pub fn create_wallet_context(
     )
 }
 
+/// Take the quotas of a request from `recipient`, returning the client IP they were taken for so
+/// that they can be refunded.
+async fn take_quotas(
+    state: &AppState,
+    recipient: SuiAddress,
+    client: SocketAddr,
+    headers: &HeaderMap,
+) -> Result<Option<IpAddr>, FaucetError> {
+    let ip = quota_ip(&state.config, client, headers)?;
+    state.faucet.take_quotas(recipient, ip).await?;
+    Ok(ip)
+}
+
+/// IP of the client a request is from, to apply quotas to. Requests whose client IP cannot be
+/// determined are rejected if there is a per-IP quota to enforce, rather than letting them bypass
+/// it.
+fn quota_ip(
+    config: &FaucetConfig,
+    client: SocketAddr,
+    headers: &HeaderMap,
+) -> Result<Option<IpAddr>, FaucetError> {
+    match client_ip(&config.client_id_source(), client, headers) {
+        Ok(ip) => Ok(Some(ip)),
+        Err(err) if config.ip_quota().is_some() => Err(FaucetError::UnknownClientIp(err)),
+        Err(err) => {
+            warn!("Cannot determine client IP: {}", err);
+            Ok(None)
+        }
+    }
+}
+
+/// IP of the client a request is from. When the faucet is behind a proxy, this is taken from the
+/// `x-forwarded-for` header the same way the traffic controller does.
+fn client_ip(
+    source: &ClientIdSource,
+    client: SocketAddr,
+    headers: &HeaderMap,
+) -> Result<IpAddr, String> {
+    let num_hops = match source {
+        ClientIdSource::SocketAddr => return Ok(client.ip()),
+        ClientIdSource::XForwardedFor(num_hops) => *num_hops,
+    };
+    if num_hops == 0 {
+        error!("x-forwarded-for client IP source must be configured with at least one hop");
+        return Err("faucet is misconfigured".to_string());
+    }
+
+    let header = headers
+        .get("x-forwarded-for")
+        .ok_or_else(|| "x-forwarded-for header not present on request".to_string())?
+        .to_str()
+        .map_err(|_| "invalid UTF-8 in x-forwarded-for header".to_string())?;
+    let hops: Vec<_> = header.split(',').map(str::trim).collect();
+    let hop = hops
+        .len()
+        .checked_sub(num_hops)
+        .map(|i| hops[i])
+        .ok_or_else(|| {
+            format!("x-forwarded-for header {header:?} has fewer than {num_hops} hops")
+        })?;
+    hop.parse()
+        .map_err(|err| format!("failed to parse x-forwarded-for client IP {hop:?}: {err}"))
+}
+
+fn quota_error_status(error: &FaucetError) -> StatusCode {
+    match error {
+        FaucetError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+        FaucetError::UnknownClientIp(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 async fn handle_error(error: BoxError) -> impl IntoResponse {
     if error.is::<tower::load_shed::error::Overloaded>() {
         return (
@@ -275,3 +405,85 @@ async fn handle_error(error: BoxError) -> impl IntoResponse {
         Cow::from(format!("Unhandled internal error: {}", error)),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleFaucet;
+    use axum::body::Body;
+    use http::Request;
+    use test_cluster::TestClusterBuilder;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_ip_quota_behind_proxy() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let config = FaucetConfig {
+            max_requests_per_ip: Some(1),
+            forwarded_for_hops: Some(1),
+            ..Default::default()
+        };
+        let faucet = SimpleFaucet::new(
+            test_cluster.wallet,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config.clone(),
+        )
+        .await
+        .unwrap();
+        let app = app(
+            Arc::new(AppState::new(faucet, config)),
+            10,
+            &Registry::new(),
+        );
+
+        // All requests come from the proxy.
+        let proxy: SocketAddr = "10.0.0.1:8000".parse().unwrap();
+        let request = |path: &str, forwarded_for: Option<&str>| {
+            let body = serde_json::to_vec(&FaucetRequest::new_fixed_amount_request(
+                SuiAddress::random_for_testing_only(),
+            ))
+            .unwrap();
+            let mut request = Request::post(path).header("content-type", "application/json");
+            if let Some(forwarded_for) = forwarded_for {
+                request = request.header("x-forwarded-for", forwarded_for);
+            }
+            let mut request = request.body(Body::from(body)).unwrap();
+            request.extensions_mut().insert(ConnectInfo(proxy));
+            request
+        };
+        let status = |request: Request<Body>| {
+            let app = app.clone();
+            async move { app.oneshot(request).await.unwrap().status() }
+        };
+
+        // Requests that did not go through the proxy cannot be attributed to a client.
+        for path in ["/gas", "/v1/gas"] {
+            assert_eq!(status(request(path, None)).await, StatusCode::BAD_REQUEST);
+            assert_eq!(
+                status(request(path, Some("not an ip"))).await,
+                StatusCode::BAD_REQUEST
+            );
+        }
+
+        // The quota applies to the forwarded client IP, rather than to the proxy.
+        assert_eq!(
+            status(request("/gas", Some("1.2.3.4"))).await,
+            StatusCode::CREATED
+        );
+        assert_eq!(
+            status(request("/gas", Some("1.2.3.4"))).await,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(
+            status(request("/gas", Some("5.6.7.8, 1.2.3.4"))).await,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(
+            status(request("/gas", Some("1.2.3.4, 5.6.7.8"))).await,
+            StatusCode::CREATED
+        );
+    }
+}
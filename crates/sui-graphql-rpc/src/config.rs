@@ -81,6 +81,7 @@ pub struct ServiceConfig {
     pub background_tasks: BackgroundTasksConfig,
     pub zklogin: ZkLoginConfig,
     pub move_registry: MoveRegistryConfig,
+    pub persisted_queries: PersistedQueriesConfig,
 }
 
 #[DefaultConfig]
@@ -126,6 +127,8 @@ pub struct Limits {
     pub max_transaction_ids: u32,
     /// Maximum number of candidates to scan when gathering a page of results.
     pub max_scan_limit: u32,
+    /// Maximum number of subscriptions that can be active at once, across all clients.
+    pub max_subscriptions: u32,
 }
 
 #[DefaultConfig]
@@ -146,6 +149,18 @@ pub struct MoveRegistryConfig {
     pub(crate) registry_id: ObjectID,
 }
 
+/// Queries that are registered with the service ahead of time. Clients can refer to a persisted
+/// query by the hex-encoded SHA-256 hash of its text, using the `persistedQuery` request extension,
+/// instead of sending the query in full.
+#[DefaultConfig]
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct PersistedQueriesConfig {
+    /// The text of each persisted query.
+    pub queries: Vec<String>,
+    /// Only accept persisted queries (sent by hash or in full), rejecting any other query.
+    pub strict: bool,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub(crate) enum ResolutionType {
     Internal,
@@ -206,6 +221,7 @@ pub struct Experiments {
 #[DefaultConfig]
 #[derive(Clone, Debug)]
 pub struct InternalFeatureConfig {
+    pub(crate) persisted_queries: bool,
     pub(crate) query_limits_checker: bool,
     pub(crate) directive_checker: bool,
     pub(crate) feature_gate: bool,
@@ -349,6 +365,11 @@ impl ServiceConfig {
     async fn max_scan_limit(&self) -> u32 {
         self.limits.max_scan_limit
     }
+
+    /// Maximum number of subscriptions that can be active at once, across all clients.
+    async fn max_subscriptions(&self) -> u32 {
+        self.limits.max_subscriptions
+    }
 }
 
 impl TxExecFullNodeConfig {
@@ -513,6 +534,7 @@ impl Default for Limits {
             // for the `TransactionBlockFilter`.
             max_transaction_ids: 1000,
             max_scan_limit: 100_000_000,
+            max_subscriptions: 1000,
             // This value is set to be the size of the max transaction bytes allowed + base64
             // overhead (roughly 1/3 of the original string). This is rounded up.
             //
@@ -525,6 +547,7 @@ impl Default for Limits {
 impl Default for InternalFeatureConfig {
     fn default() -> Self {
         Self {
+            persisted_queries: true,
             query_limits_checker: true,
             directive_checker: true,
             feature_gate: true,
@@ -597,6 +620,7 @@ mod tests {
                 max-move-value-depth = 256
                 max-transaction-ids = 11
                 max-scan-limit = 50
+                max-subscriptions = 10
             "#,
         )
         .unwrap();
@@ -619,6 +643,7 @@ mod tests {
                 max_move_value_depth: 256,
                 max_transaction_ids: 11,
                 max_scan_limit: 50,
+                max_subscriptions: 10,
             },
             ..Default::default()
         };
@@ -663,6 +688,30 @@ mod tests {
         assert_eq!(actual, expect)
     }
 
+    #[test]
+    fn test_read_persisted_queries_in_service_config() {
+        let actual = ServiceConfig::read(
+            r#" [persisted-queries]
+                queries = ["{ chainIdentifier }", "{ epoch { epochId } }"]
+                strict = true
+            "#,
+        )
+        .unwrap();
+
+        let expect = ServiceConfig {
+            persisted_queries: PersistedQueriesConfig {
+                queries: vec![
+                    "{ chainIdentifier }".to_string(),
+                    "{ epoch { epochId } }".to_string(),
+                ],
+                strict: true,
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(actual, expect)
+    }
+
     #[test]
     fn test_read_everything_in_service_config() {
         let actual = ServiceConfig::read(
@@ -685,6 +734,7 @@ mod tests {
                 max-move-value-depth = 256
                 max-transaction-ids = 42
                 max-scan-limit = 420
+                max-subscriptions = 42
 
                 [experiments]
                 test-flag = true
@@ -710,6 +760,7 @@ mod tests {
                 max_move_value_depth: 256,
                 max_transaction_ids: 42,
                 max_scan_limit: 420,
+                max_subscriptions: 42,
             },
             disabled_features: BTreeSet::from([FunctionalGroup::Analytics]),
             experiments: Experiments { test_flag: true },
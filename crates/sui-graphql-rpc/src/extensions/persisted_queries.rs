@@ -0,0 +1,230 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest},
+    Request, ServerResult, Value,
+};
+use async_trait::async_trait;
+use fastcrypto::hash::{HashFunction, Sha256};
+
+use crate::config::PersistedQueriesConfig;
+use crate::error::{code, graphql_error};
+
+/// Name of the request extension that clients use to refer to a persisted query, following
+/// Apollo's protocol: `{"persistedQuery": {"version": 1, "sha256Hash": "<hex>"}}`.
+const PERSISTED_QUERY_EXTENSION: &str = "persistedQuery";
+
+/// The only version of the persisted query protocol that is supported.
+const PERSISTED_QUERY_VERSION: i32 = 1;
+
+/// Extension factory to resolve persisted queries from the hash of their text. In strict mode,
+/// only persisted queries are accepted, whether they are sent by hash or in full.
+pub(crate) struct PersistedQueries {
+    /// Persisted queries, keyed by the hex-encoded SHA-256 hash of their text.
+    queries: Arc<HashMap<String, String>>,
+    strict: bool,
+}
+
+struct PersistedQueriesExt {
+    queries: Arc<HashMap<String, String>>,
+    strict: bool,
+}
+
+impl PersistedQueries {
+    pub(crate) fn new(config: &PersistedQueriesConfig) -> Self {
+        let queries = config
+            .queries
+            .iter()
+            .map(|query| (query_hash(query), query.clone()))
+            .collect();
+
+        Self {
+            queries: Arc::new(queries),
+            strict: config.strict,
+        }
+    }
+}
+
+impl ExtensionFactory for PersistedQueries {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(PersistedQueriesExt {
+            queries: self.queries.clone(),
+            strict: self.strict,
+        })
+    }
+}
+
+#[async_trait]
+impl Extension for PersistedQueriesExt {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        mut request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        if let Some(extension) = request.extensions.get(PERSISTED_QUERY_EXTENSION) {
+            let hash = requested_hash(extension)?;
+            let Some(query) = self.queries.get(&hash) else {
+                return Err(graphql_error(
+                    code::PERSISTED_QUERY_NOT_FOUND,
+                    "PersistedQueryNotFound",
+                ));
+            };
+
+            if !request.query.is_empty() && request.query != *query {
+                return Err(graphql_error(
+                    code::BAD_USER_INPUT,
+                    format!("Query does not match persisted query hash {hash}"),
+                ));
+            }
+
+            request.query = query.clone();
+        } else if self.strict && !self.queries.contains_key(&query_hash(&request.query)) {
+            return Err(graphql_error(
+                code::PERSISTED_QUERY_NOT_SUPPORTED,
+                "Only persisted queries are accepted by this service",
+            ));
+        }
+
+        next.run(ctx, request).await
+    }
+}
+
+/// Hex-encoded SHA-256 hash of a query's text, which clients use to refer to it.
+fn query_hash(query: &str) -> String {
+    hex::encode(Sha256::digest(query.as_bytes()).digest)
+}
+
+/// Extract the hash of the query that a client is referring to from the value of its
+/// `persistedQuery` request extension.
+fn requested_hash(extension: &Value) -> ServerResult<String> {
+    let Value::Object(fields) = extension else {
+        return Err(graphql_error(
+            code::BAD_USER_INPUT,
+            "Expected 'persistedQuery' extension to be an object",
+        ));
+    };
+
+    match fields.get("version") {
+        Some(Value::Number(version))
+            if version.as_i64() == Some(PERSISTED_QUERY_VERSION.into()) => {}
+        _ => {
+            return Err(graphql_error(
+                code::BAD_USER_INPUT,
+                format!("Unsupported persisted query version, expected {PERSISTED_QUERY_VERSION}"),
+            ))
+        }
+    }
+
+    let Some(Value::String(hash)) = fields.get("sha256Hash") else {
+        return Err(graphql_error(
+            code::BAD_USER_INPUT,
+            "Expected 'persistedQuery' extension to contain a 'sha256Hash'",
+        ));
+    };
+
+    Ok(hash.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{EmptySubscription, Schema};
+    use serde_json::json;
+
+    use crate::{config::ServiceConfig, mutation::Mutation, types::query::Query};
+
+    use super::*;
+
+    const QUERY: &str = "{ serviceConfig { maxQueryDepth } }";
+
+    fn schema(strict: bool) -> Schema<Query, Mutation, EmptySubscription> {
+        let config = PersistedQueriesConfig {
+            queries: vec![QUERY.to_string()],
+            strict,
+        };
+
+        Schema::build(Query, Mutation, EmptySubscription)
+            .data(ServiceConfig::default())
+            .extension(PersistedQueries::new(&config))
+            .finish()
+    }
+
+    fn persisted(query: &str, hash: &str) -> Request {
+        let mut request = Request::new(query);
+        request.extensions.insert(
+            PERSISTED_QUERY_EXTENSION.to_string(),
+            Value::from_json(json!({"version": 1, "sha256Hash": hash})).unwrap(),
+        );
+        request
+    }
+
+    async fn errors(
+        schema: &Schema<Query, Mutation, EmptySubscription>,
+        req: Request,
+    ) -> Vec<String> {
+        let response = schema.execute(req).await;
+        response.errors.into_iter().map(|e| e.message).collect()
+    }
+
+    #[tokio::test]
+    async fn test_persisted_query_by_hash() {
+        let schema = schema(false);
+        let response = schema.execute(persisted("", &query_hash(QUERY))).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({"serviceConfig": {"maxQueryDepth": ServiceConfig::default().limits.max_query_depth}})
+        );
+
+        // The query can also be sent along with its hash, as long as they match.
+        assert!(errors(&schema, persisted(QUERY, &query_hash(QUERY)))
+            .await
+            .is_empty());
+        assert_eq!(
+            errors(
+                &schema,
+                persisted("{ chainIdentifier }", &query_hash(QUERY))
+            )
+            .await,
+            vec![format!(
+                "Query does not match persisted query hash {}",
+                query_hash(QUERY)
+            )],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_persisted_query() {
+        let schema = schema(false);
+        let hash = query_hash("{ chainIdentifier }");
+        assert_eq!(
+            errors(&schema, persisted("", &hash)).await,
+            vec!["PersistedQueryNotFound"],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_strict_mode() {
+        let schema = schema(true);
+
+        // Persisted queries are accepted by hash or in full.
+        assert!(errors(&schema, persisted("", &query_hash(QUERY)))
+            .await
+            .is_empty());
+        assert!(errors(&schema, Request::new(QUERY)).await.is_empty());
+
+        // Other queries are rejected.
+        assert_eq!(
+            errors(&schema, Request::new("{ chainIdentifier }")).await,
+            vec!["Only persisted queries are accepted by this service"],
+        );
+
+        // ...but are accepted when not in strict mode.
+        let schema = self::schema(false);
+        assert!(errors(&schema, Request::new(QUERY)).await.is_empty());
+    }
+}
@@ -14,6 +14,8 @@ use crate::data::{DataLoader, Db};
 use crate::extensions::directive_checker::DirectiveChecker;
 use crate::metrics::Metrics;
 use crate::mutation::Mutation;
+use crate::subscription::{Subscription, SubscriptionPermits};
+use crate::types::chain_identifier::ChainIdentifier;
 use crate::types::datatype::IMoveDatatype;
 use crate::types::move_object::IMoveObject;
 use crate::types::object::IObject;
@@ -25,6 +27,7 @@ use crate::{
     extensions::{
         feature_gate::FeatureGate,
         logger::Logger,
+        persisted_queries::PersistedQueries,
         query_limits_checker::{PayloadSize, QueryLimitsChecker, ShowUsage},
         timeout::Timeout,
     },
@@ -33,10 +36,11 @@ use crate::{
 };
 use async_graphql::extensions::ApolloTracing;
 use async_graphql::extensions::Tracing;
-use async_graphql::EmptySubscription;
-use async_graphql::{extensions::ExtensionFactory, Schema, SchemaBuilder};
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql::http::ALL_WEBSOCKET_PROTOCOLS;
+use async_graphql::{extensions::ExtensionFactory, Data, Executor, Schema, SchemaBuilder};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
 use axum::body::Body;
+use axum::extract::ws::WebSocketUpgrade;
 use axum::extract::FromRef;
 use axum::extract::{ConnectInfo, Query as AxumQuery, State};
 use axum::http::{HeaderMap, StatusCode};
@@ -48,6 +52,7 @@ use axum::Router;
 use axum_extra::headers::ContentLength;
 use axum_extra::TypedHeader;
 use chrono::Utc;
+use futures::stream::BoxStream;
 use http::{HeaderValue, Method, Request};
 use mysten_metrics::spawn_monitored_task;
 use mysten_network::callback::{CallbackLayer, MakeCallbackHandler, ResponseHandler};
@@ -61,7 +66,7 @@ use sui_indexer::db::check_db_migration_consistency;
 use sui_package_resolver::{PackageStoreWithLruCache, Resolver};
 use sui_sdk::SuiClientBuilder;
 use tokio::join;
-use tokio::sync::OnceCell;
+use tokio::sync::{watch, OnceCell};
 use tokio_util::sync::CancellationToken;
 use tower::{Layer, Service};
 use tower_http::cors::{AllowOrigin, CorsLayer};
@@ -145,7 +150,7 @@ impl Server {
 
 pub(crate) struct ServerBuilder {
     state: AppState,
-    schema: SchemaBuilder<Query, Mutation, EmptySubscription>,
+    schema: SchemaBuilder<Query, Mutation, Subscription>,
     router: Option<Router>,
     db_reader: Option<Db>,
     resolver: Option<PackageResolver>,
@@ -219,7 +224,7 @@ impl ServerBuilder {
     }
 
     #[cfg(test)]
-    fn build_schema(self) -> Schema<Query, Mutation, EmptySubscription> {
+    fn build_schema(self) -> Schema<Query, Mutation, Subscription> {
         self.schema.finish()
     }
 
@@ -229,7 +234,7 @@ impl ServerBuilder {
         self,
     ) -> (
         String,
-        Schema<Query, Mutation, EmptySubscription>,
+        Schema<Query, Mutation, Subscription>,
         Db,
         PackageResolver,
         Router,
@@ -256,6 +261,8 @@ impl ServerBuilder {
             let router: Router = Router::new()
                 .route("/", post(graphql_handler))
                 .route("/graphql", post(graphql_handler))
+                .route("/subscriptions", get(subscription_handler))
+                .route("/graphql/subscriptions", get(subscription_handler))
                 .route("/health", get(health_check))
                 .route("/graphql/health", get(health_check))
                 .with_state(self.state.clone())
@@ -345,6 +352,9 @@ impl ServerBuilder {
             .layer(axum::extract::Extension(schema))
             .layer(axum::extract::Extension(watermark_task.lock()))
             .layer(axum::extract::Extension(watermark_task.chain_id_lock()))
+            .layer(axum::extract::Extension(
+                watermark_task.checkpoint_receiver(),
+            ))
             .layer(Self::cors()?);
 
         Ok(Server {
@@ -469,7 +479,16 @@ impl ServerBuilder {
             .context_data(metrics.clone())
             .context_data(config.clone())
             .context_data(move_registry_config.clone())
-            .context_data(MoveRegistryDataLoader::new(move_registry_config));
+            .context_data(MoveRegistryDataLoader::new(move_registry_config))
+            .context_data(SubscriptionPermits::new(
+                config.service.limits.max_subscriptions,
+            ));
+
+        // Persisted queries are resolved before any other extension sees the request, so that they
+        // are checked in the same way as queries that are sent in full.
+        if config.internal_features.persisted_queries {
+            builder = builder.extension(PersistedQueries::new(&config.service.persisted_queries));
+        }
 
         if config.internal_features.feature_gate {
             builder = builder.extension(FeatureGate);
@@ -506,8 +525,8 @@ impl ServerBuilder {
     }
 }
 
-fn schema_builder() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
-    async_graphql::Schema::build(Query, Mutation, EmptySubscription)
+fn schema_builder() -> SchemaBuilder<Query, Mutation, Subscription> {
+    async_graphql::Schema::build(Query, Mutation, Subscription)
         .register_output_type::<IMoveObject>()
         .register_output_type::<IObject>()
         .register_output_type::<IOwner>()
@@ -555,6 +574,78 @@ async fn graphql_handler(
     (extensions, result.into())
 }
 
+/// Entry point for subscriptions, which are served over a WebSocket (using either the
+/// `graphql-ws` or the `graphql-transport-ws` protocol). Requests made over the WebSocket are
+/// stamped with the same data as requests made to `graphql_handler`.
+async fn subscription_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Extension(schema): Extension<SuiGraphQLSchema>,
+    Extension(checkpoint_receiver): Extension<watch::Receiver<Option<Watermark>>>,
+    Extension(chain_identifier_lock): Extension<ChainIdentifierLock>,
+    headers: HeaderMap,
+    protocol: GraphQLProtocol,
+    upgrade: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let executor = SubscriptionExecutor {
+        schema,
+        addr,
+        show_usage: headers.contains_key(ShowUsage::name()),
+        chain_identifier: chain_identifier_lock.read().await,
+        checkpoint_receiver,
+    };
+
+    upgrade
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| GraphQLWebSocket::new(stream, executor, protocol).serve())
+}
+
+/// Executes requests received over a subscription's WebSocket.
+#[derive(Clone)]
+struct SubscriptionExecutor {
+    schema: SuiGraphQLSchema,
+    addr: SocketAddr,
+    show_usage: bool,
+    chain_identifier: ChainIdentifier,
+    checkpoint_receiver: watch::Receiver<Option<Watermark>>,
+}
+
+impl SubscriptionExecutor {
+    /// Stamp the request with a unique ID, and the latest watermark, along with the details of the
+    /// connection it was received on. There is no `Content-Length` for each request, so its payload
+    /// size is estimated from its query and variables.
+    fn prepare(&self, mut req: async_graphql::Request) -> async_graphql::Request {
+        let variables_size = serde_json::to_string(&req.variables).map_or(0, |v| v.len());
+        let payload_size = (req.query.len() + variables_size) as u64;
+
+        req.data.insert(PayloadSize(payload_size));
+        req.data.insert(Uuid::new_v4());
+        if self.show_usage {
+            req.data.insert(ShowUsage)
+        }
+
+        req.data.insert(self.addr);
+        let watermark = *self.checkpoint_receiver.borrow();
+        req.data.insert(watermark.unwrap_or_default());
+        req.data.insert(self.chain_identifier);
+        req.data.insert(self.checkpoint_receiver.clone());
+        req
+    }
+}
+
+impl Executor for SubscriptionExecutor {
+    async fn execute(&self, request: async_graphql::Request) -> async_graphql::Response {
+        self.schema.execute(self.prepare(request)).await
+    }
+
+    fn execute_stream(
+        &self,
+        request: async_graphql::Request,
+        session_data: Option<Arc<Data>>,
+    ) -> BoxStream<'static, async_graphql::Response> {
+        Executor::execute_stream(&self.schema, self.prepare(request), session_data)
+    }
+}
+
 #[derive(Clone)]
 struct MetricsMakeCallbackHandler {
     metrics: Metrics,
@@ -18,7 +18,9 @@ async fn graphiql(
     } else {
         "/graphql".to_string()
     };
-    let gq = async_graphql::http::GraphiQLSource::build().endpoint(&endpoint);
+    let gq = async_graphql::http::GraphiQLSource::build()
+        .endpoint(&endpoint)
+        .subscription_endpoint("/graphql/subscriptions");
     if let axum::Extension(Some(title)) = ide_title {
         axum::response::Html(gq.title(&title).finish())
     } else {
@@ -32,6 +32,8 @@ pub(crate) struct WatermarkTask {
     cancel: CancellationToken,
     sender: watch::Sender<u64>,
     receiver: watch::Receiver<u64>,
+    checkpoint_sender: watch::Sender<Option<Watermark>>,
+    checkpoint_receiver: watch::Receiver<Option<Watermark>>,
 }
 
 #[derive(Clone, Default)]
@@ -63,6 +65,7 @@ impl WatermarkTask {
         cancel: CancellationToken,
     ) -> Self {
         let (sender, receiver) = watch::channel(0);
+        let (checkpoint_sender, checkpoint_receiver) = watch::channel(None);
 
         Self {
             watermark: Default::default(),
@@ -73,6 +76,8 @@ impl WatermarkTask {
             cancel,
             sender,
             receiver,
+            checkpoint_sender,
+            checkpoint_receiver,
         }
     }
 
@@ -113,6 +118,16 @@ impl WatermarkTask {
                     if epoch > prev_epoch {
                         self.sender.send(epoch).unwrap();
                     }
+
+                    // When new checkpoints have been indexed, notify subscribers
+                    self.checkpoint_sender.send_if_modified(|prev| {
+                        if prev.is_some_and(|prev| prev.hi_cp >= hi_cp) {
+                            return false;
+                        }
+
+                        *prev = Some(Watermark { hi_cp, hi_cp_timestamp_ms, epoch, lo_cp, lo_tx });
+                        true
+                    });
                 }
             }
         }
@@ -131,6 +146,12 @@ impl WatermarkTask {
         self.receiver.clone()
     }
 
+    /// Receiver for subscribing to new checkpoints. Holds the latest watermark once it is known,
+    /// and is only updated when the watermark's upper bound advances.
+    pub(crate) fn checkpoint_receiver(&self) -> watch::Receiver<Option<Watermark>> {
+        self.checkpoint_receiver.clone()
+    }
+
     // Fetch the chain identifier (once) from the database and cache it.
     async fn get_and_cache_chain_identifier(&self, interval: &mut Interval) {
         loop {
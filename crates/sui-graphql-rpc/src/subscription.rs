@@ -0,0 +1,237 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::future::Future;
+use std::sync::Arc;
+
+use async_graphql::connection::CursorType;
+use async_graphql::*;
+use futures::stream::{self, Stream, StreamExt};
+use tokio::sync::{watch, Semaphore};
+
+use crate::config::ServiceConfig;
+use crate::error::Error;
+use crate::server::watermark_task::Watermark;
+use crate::types::checkpoint::{self, Checkpoint, CheckpointCursor};
+use crate::types::cursor::Page;
+use crate::types::event::{self, Event, EventFilter};
+use crate::types::transaction_block::{self, TransactionBlock, TransactionBlockFilter};
+use crate::types::uint53::UInt53;
+
+pub(crate) struct Subscription;
+
+/// Limits the number of subscriptions that are active at once, across all clients.
+pub(crate) struct SubscriptionPermits(Arc<Semaphore>);
+
+/// Subscriptions stream data from checkpoints as they are indexed.
+///
+/// Each subscription is driven by the watermark: when the watermark's upper bound advances, the
+/// checkpoints between the previous and the new upper bound are queried for results, which are
+/// streamed in order, a page at a time. Results are viewed at the checkpoint they were queried at.
+///
+/// The number of subscriptions that can be active at once is limited by
+/// `serviceConfig.maxSubscriptions`.
+#[Subscription]
+impl Subscription {
+    /// Checkpoints, as they are indexed.
+    async fn checkpoints<'ctx>(
+        &self,
+        ctx: &'ctx Context<'ctx>,
+    ) -> Result<impl Stream<Item = Result<Checkpoint>> + 'ctx> {
+        stream_new_checkpoints(ctx, move |after_cp, hi_cp, after| async move {
+            let after = after.unwrap_or_else(|| {
+                checkpoint::Cursor::new(CheckpointCursor {
+                    checkpoint_viewed_at: hi_cp,
+                    sequence_number: after_cp,
+                })
+            });
+
+            let page = page_after(ctx, Some(after))?;
+            let conn = Checkpoint::paginate(ctx.data_unchecked(), page, None, hi_cp)
+                .await
+                .extend()?;
+
+            let next = match conn.edges.last() {
+                Some(last) if conn.has_next_page => {
+                    Some(checkpoint::Cursor::decode_cursor(&last.cursor)?)
+                }
+                _ => None,
+            };
+
+            Ok((conn.edges.into_iter().map(|edge| edge.node).collect(), next))
+        })
+    }
+
+    /// Transaction blocks that match the optional `filter`, as the checkpoints they are in are
+    /// indexed.
+    async fn transactions<'ctx>(
+        &self,
+        ctx: &'ctx Context<'ctx>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<impl Stream<Item = Result<TransactionBlock>> + 'ctx> {
+        let limits = &ctx.data_unchecked::<ServiceConfig>().limits;
+        let filter = filter.unwrap_or_default();
+
+        // Filters that need to scan through transactions are scanned through as far as the limits
+        // allow, and then resumed until the checkpoints have been scanned completely.
+        let scan_limit = filter
+            .requires_scan_limit()
+            .then_some(limits.max_scan_limit as u64);
+
+        stream_new_checkpoints(ctx, move |after_cp, hi_cp, after| {
+            let filter = filter.clone().intersect(TransactionBlockFilter {
+                after_checkpoint: Some(UInt53::from(after_cp)),
+                ..Default::default()
+            });
+
+            async move {
+                let Some(filter) = filter else {
+                    return Ok((vec![], None));
+                };
+
+                let page = page_after(ctx, after)?;
+                let conn = TransactionBlock::paginate(ctx, page, filter, hi_cp, scan_limit)
+                    .await
+                    .extend()?;
+
+                let end_cursor = conn
+                    .end_cursor
+                    .clone()
+                    .or_else(|| conn.edges.last().map(|edge| edge.cursor.clone()));
+
+                let next = match end_cursor {
+                    Some(cursor) if conn.has_next_page => {
+                        Some(transaction_block::Cursor::decode_cursor(&cursor)?)
+                    }
+                    _ => None,
+                };
+
+                Ok((conn.edges.into_iter().map(|edge| edge.node).collect(), next))
+            }
+        })
+    }
+
+    /// Events that match the optional `filter`, as the checkpoints they are emitted in are
+    /// indexed.
+    ///
+    /// We currently do not support filtering by emitting module and event type at the same time
+    /// so if both are provided in one filter, the subscription will error.
+    async fn events<'ctx>(
+        &self,
+        ctx: &'ctx Context<'ctx>,
+        filter: Option<EventFilter>,
+    ) -> Result<impl Stream<Item = Result<Event>> + 'ctx> {
+        let filter = filter.unwrap_or_default();
+        stream_new_checkpoints(ctx, move |after_cp, hi_cp, after| {
+            let filter = EventFilter {
+                after_checkpoint: Some(after_cp),
+                ..filter.clone()
+            };
+
+            async move {
+                let page = page_after(ctx, after)?;
+                let conn = Event::paginate(ctx.data_unchecked(), page, filter, hi_cp)
+                    .await
+                    .extend()?;
+
+                let next = match conn.edges.last() {
+                    Some(last) if conn.has_next_page => {
+                        Some(event::Cursor::decode_cursor(&last.cursor)?)
+                    }
+                    _ => None,
+                };
+
+                Ok((conn.edges.into_iter().map(|edge| edge.node).collect(), next))
+            }
+        })
+    }
+}
+
+impl SubscriptionPermits {
+    pub(crate) fn new(max_subscriptions: u32) -> Self {
+        Self(Arc::new(Semaphore::new(max_subscriptions as usize)))
+    }
+}
+
+/// Stream the results that `fetch_page` finds in each range of checkpoints that is indexed after
+/// the stream is created. `fetch_page` is called with the exclusive lower bound and the inclusive
+/// upper bound of the range, and the cursor to continue from within the range, if any. It returns
+/// a page of results and the cursor for the next page, or `None` if the range has been exhausted.
+/// Each page is streamed before the next one is fetched. If `fetch_page` fails, the error is
+/// streamed and the rest of that range is skipped. The stream ends when the watermark stops being
+/// updated.
+///
+/// The stream holds on to one of the subscription permits for as long as it is alive, and fails to
+/// be created if there are none left.
+fn stream_new_checkpoints<'ctx, T, C, F, Fut>(
+    ctx: &'ctx Context<'ctx>,
+    fetch_page: F,
+) -> Result<impl Stream<Item = Result<T>> + 'ctx>
+where
+    T: Send + 'ctx,
+    C: Send + 'ctx,
+    F: Fn(u64, u64, Option<C>) -> Fut + Send + Sync + 'ctx,
+    Fut: Future<Output = Result<(Vec<T>, Option<C>)>> + Send + 'ctx,
+{
+    let permits: &SubscriptionPermits = ctx.data()?;
+    let permit = permits.0.clone().try_acquire_owned().map_err(|_| {
+        let limits = &ctx.data_unchecked::<ServiceConfig>().limits;
+        Error::Client(format!(
+            "Too many active subscriptions, the limit is {}",
+            limits.max_subscriptions
+        ))
+        .extend()
+    })?;
+
+    let receiver: &watch::Receiver<Option<Watermark>> = ctx.data()?;
+    let ranges = stream::unfold(
+        (receiver.clone(), None),
+        |(mut receiver, mut after_cp): (_, Option<u64>)| async move {
+            loop {
+                let watermark = *receiver.borrow_and_update();
+                match (after_cp, watermark) {
+                    // Start tracking from the first known watermark, rather than from the very
+                    // first checkpoint.
+                    (None, Some(Watermark { hi_cp, .. })) => after_cp = Some(hi_cp),
+                    (Some(after), Some(Watermark { hi_cp, .. })) if hi_cp > after => {
+                        return Some(((after, hi_cp), (receiver, Some(hi_cp))));
+                    }
+                    _ => {}
+                }
+
+                receiver.changed().await.ok()?;
+            }
+        },
+    );
+
+    let fetch_page = Arc::new(fetch_page);
+    let pages = ranges.flat_map(move |(after_cp, hi_cp)| {
+        let fetch_page = fetch_page.clone();
+        // The state is the cursor to fetch the next page from, or `None` once the range is done.
+        stream::unfold(Some(None), move |after| {
+            let fetch_page = fetch_page.clone();
+            async move {
+                match fetch_page(after_cp, hi_cp, after?).await {
+                    Ok((results, next)) => Some((Ok(results), next.map(Some))),
+                    Err(e) => Some((Err(e), None)),
+                }
+            }
+        })
+    });
+
+    Ok(pages.flat_map(move |page| {
+        // The permit is released when the stream is dropped.
+        let _permit = &permit;
+        stream::iter(match page {
+            Ok(results) => results.into_iter().map(Ok).collect(),
+            Err(e) => vec![Err(e)],
+        })
+    }))
+}
+
+/// A page of up to the maximum page size, following the `after` cursor, if there is one.
+fn page_after<C>(ctx: &Context<'_>, after: Option<C>) -> Result<Page<C>> {
+    let config: &ServiceConfig = ctx.data_unchecked();
+    let limit = config.limits.max_page_size as u64;
+    Page::from_params(config, Some(limit), after, None, None)
+}
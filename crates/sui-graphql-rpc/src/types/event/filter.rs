@@ -41,4 +41,8 @@ pub(crate) struct EventFilter {
     // Enhancement (post-MVP)
     // pub start_time
     // pub end_time
+    /// Limit to events from transactions in checkpoints strictly after this one. Not exposed in
+    /// the schema, but used by subscriptions to only look at newly indexed checkpoints.
+    #[graphql(skip)]
+    pub after_checkpoint: Option<u64>,
 }
@@ -120,8 +120,13 @@ pub(crate) fn add_bounds(
     mut query: RawQuery,
     tx_digest_filter: &Option<Digest>,
     page: &Page<Cursor>,
+    tx_lo: Option<i64>,
     tx_hi: i64,
 ) -> RawQuery {
+    if let Some(tx_lo) = tx_lo {
+        query = filter!(query, format!("tx_sequence_number >= {}", tx_lo));
+    }
+
     query = filter!(query, format!("tx_sequence_number < {}", tx_hi));
 
     if let Some(after) = page.after() {
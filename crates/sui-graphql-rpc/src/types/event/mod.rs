@@ -164,9 +164,20 @@ impl Event {
                         .filter(dsl::sequence_number.eq(checkpoint_viewed_at as i64))
                 }).await?;
 
+                // Transactions in checkpoints after `after_checkpoint` start from the total number
+                // of transactions up to and including it.
+                let tx_lo: Option<i64> = if let Some(after_cp) = filter.after_checkpoint {
+                    Some(conn.first(move || {
+                        dsl::checkpoints.select(dsl::network_total_transactions)
+                            .filter(dsl::sequence_number.eq(after_cp as i64))
+                    }).await?)
+                } else {
+                    None
+                };
+
                 let (prev, next, mut events): (bool, bool, Vec<StoredEvent>) =
                     if let Some(filter_query) =  query_constraint {
-                        let query = add_bounds(filter_query, &filter.transaction_digest, &page, tx_hi);
+                        let query = add_bounds(filter_query, &filter.transaction_digest, &page, tx_lo, tx_hi);
 
                         let (prev, next, results) =
                             page.paginate_raw_query::<EvLookup>(conn, checkpoint_viewed_at, query).await?;
@@ -200,7 +211,7 @@ impl Event {
                     } else {
                         // No filter is provided so we add bounds to the basic `SELECT * FROM
                         // events` query and call it a day.
-                        let query = add_bounds(query!("SELECT * FROM events"), &filter.transaction_digest, &page, tx_hi);
+                        let query = add_bounds(query!("SELECT * FROM events"), &filter.transaction_digest, &page, tx_lo, tx_hi);
                         let (prev, next, events_iter) = page.paginate_raw_query::<StoredEvent>(conn, checkpoint_viewed_at, query).await?;
                         let events = events_iter.collect::<Vec<StoredEvent>>();
                         (prev, next, events)
@@ -14,6 +14,7 @@ use sui_graphql_rpc::config::Limits;
 use sui_graphql_rpc::config::ServiceConfig;
 use sui_graphql_rpc::test_infra::cluster::prep_executor_cluster;
 use sui_graphql_rpc::test_infra::cluster::start_cluster;
+use sui_graphql_rpc::test_infra::cluster::Cluster;
 use sui_types::digests::ChainIdentifier;
 use sui_types::gas_coin::GAS;
 use sui_types::transaction::CallArg;
@@ -852,3 +853,195 @@ async fn test_payload_using_vars_mutation_passes() {
 
     assert!(res.errors().is_empty(), "{:#?}", res.errors());
 }
+
+type Socket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Open a WebSocket to the cluster's GraphQL service for subscriptions, and initialize the
+/// connection.
+async fn connect_subscriptions(cluster: &Cluster) -> Socket {
+    use tokio_tungstenite::tungstenite::{client::IntoClientRequest, http::HeaderValue};
+
+    let config = &cluster.network.graphql_connection_config;
+    let mut request = format!("ws://{}:{}/graphql/subscriptions", config.host, config.port)
+        .into_client_request()
+        .unwrap();
+    request.headers_mut().insert(
+        "Sec-WebSocket-Protocol",
+        HeaderValue::from_static("graphql-transport-ws"),
+    );
+    let (mut socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+
+    send(&mut socket, json!({"type": "connection_init"})).await;
+    assert_eq!(receive(&mut socket).await["type"], "connection_ack");
+    socket
+}
+
+async fn send(socket: &mut Socket, message: serde_json::Value) {
+    use futures::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    socket
+        .send(Message::Text(message.to_string()))
+        .await
+        .unwrap();
+}
+
+async fn receive(socket: &mut Socket) -> serde_json::Value {
+    use futures::StreamExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(30), socket.next())
+            .await
+            .expect("Timed out waiting for a message")
+            .unwrap()
+            .unwrap();
+        if let Message::Text(text) = message {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+async fn subscribe(socket: &mut Socket, id: &str, query: &str) {
+    send(
+        socket,
+        json!({"id": id, "type": "subscribe", "payload": {"query": query}}),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_transaction_subscription() {
+    let cluster = start_cluster(ServiceConfig::test_defaults()).await;
+    cluster
+        .wait_for_checkpoint_catchup(1, Duration::from_secs(30))
+        .await;
+    let mut socket = connect_subscriptions(&cluster).await;
+
+    let wallet = &cluster.network.validator_fullnode_handle.wallet;
+    let addresses = wallet.get_addresses();
+    let (sender, recipient) = (addresses[0], addresses[1]);
+    send(
+        &mut socket,
+        json!({
+            "id": "1",
+            "type": "subscribe",
+            "payload": {
+                "query": "subscription ($sender: SuiAddress!) { \
+                    transactions(filter: { sentAddress: $sender }) { digest } \
+                }",
+                "variables": { "sender": sender.to_string() },
+            },
+        }),
+    )
+    .await;
+
+    // Wait for the subscription to start tracking the watermark, before sending a transaction.
+    sleep(Duration::from_secs(1)).await;
+    let tx = cluster
+        .network
+        .validator_fullnode_handle
+        .test_transaction_builder()
+        .await
+        .transfer_sui(Some(1_000), recipient)
+        .build();
+    let response = wallet
+        .execute_transaction_must_succeed(wallet.sign_transaction(&tx))
+        .await;
+
+    let message = receive(&mut socket).await;
+    assert_eq!(message["type"], "next", "{message}");
+    assert_eq!(message["id"], "1");
+    assert_eq!(
+        message["payload"]["data"]["transactions"]["digest"],
+        response.digest.to_string()
+    );
+}
+
+#[tokio::test]
+async fn test_subscription_pages_and_limit() {
+    // Pages of one checkpoint, so that every range of more than one checkpoint is paged through.
+    let cluster = start_cluster(ServiceConfig {
+        limits: Limits {
+            default_page_size: 1,
+            max_page_size: 1,
+            max_subscriptions: 1,
+            ..Default::default()
+        },
+        ..ServiceConfig::test_defaults()
+    })
+    .await;
+    cluster
+        .wait_for_checkpoint_catchup(1, Duration::from_secs(30))
+        .await;
+    let mut socket = connect_subscriptions(&cluster).await;
+    let query = "subscription { checkpoints { sequenceNumber } }";
+
+    subscribe(&mut socket, "1", query).await;
+    sleep(Duration::from_secs(1)).await;
+    subscribe(&mut socket, "2", query).await;
+
+    // Make sure that new checkpoints are created.
+    for _ in 0..3 {
+        transfer_sui(&cluster).await;
+    }
+
+    // The second subscription is over the limit, and the first one streams every checkpoint.
+    let mut rejected = false;
+    let mut checkpoints = vec![];
+    while !rejected || checkpoints.len() < 3 {
+        let message = receive(&mut socket).await;
+        match message["id"].as_str() {
+            Some("1") if message["type"] == "next" => checkpoints.push(
+                message["payload"]["data"]["checkpoints"]["sequenceNumber"]
+                    .as_u64()
+                    .unwrap(),
+            ),
+            Some("2") if message["type"] != "complete" => {
+                assert!(
+                    message
+                        .to_string()
+                        .contains("Too many active subscriptions"),
+                    "{message}"
+                );
+                rejected = true;
+            }
+            _ => {}
+        }
+    }
+    for pair in checkpoints.windows(2) {
+        assert_eq!(pair[0] + 1, pair[1], "{checkpoints:?}");
+    }
+
+    // Once the first subscription is done, there is room for another one.
+    send(&mut socket, json!({"id": "1", "type": "complete"})).await;
+    sleep(Duration::from_secs(1)).await;
+    subscribe(&mut socket, "3", query).await;
+    sleep(Duration::from_secs(1)).await;
+    transfer_sui(&cluster).await;
+    loop {
+        let message = receive(&mut socket).await;
+        if message["id"] == "3" {
+            assert_eq!(message["type"], "next", "{message}");
+            assert!(message["payload"]["errors"].is_null(), "{message}");
+            break;
+        }
+    }
+}
+
+/// Send some SUI between two of the cluster's addresses, and wait for it to be executed.
+async fn transfer_sui(cluster: &Cluster) {
+    let wallet = &cluster.network.validator_fullnode_handle.wallet;
+    let recipient = wallet.get_addresses()[1];
+    let tx = cluster
+        .network
+        .validator_fullnode_handle
+        .test_transaction_builder()
+        .await
+        .transfer_sui(Some(1_000), recipient)
+        .build();
+    wallet
+        .execute_transaction_must_succeed(wallet.sign_transaction(&tx))
+        .await;
+}
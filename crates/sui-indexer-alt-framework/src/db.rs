@@ -2,21 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::anyhow;
+use async_trait::async_trait;
 use diesel::migration::{self, Migration, MigrationSource, MigrationVersion};
 use diesel::pg::Pg;
 use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use diesel_async::{
     pooled_connection::{
         bb8::{Pool, PooledConnection, RunError},
         AsyncDieselConnectionManager, PoolError,
     },
-    AsyncPgConnection, RunQueryDsl,
+    AsyncConnection, AsyncPgConnection, RunQueryDsl,
 };
 use diesel_migrations::{embed_migrations, EmbeddedMigrations};
 use std::time::Duration;
 use tracing::info;
 use url::Url;
 
+use crate::store::{
+    self, CommitterWatermark, PrunerWatermark, ReaderWatermark, ScopedBoxFuture, Store,
+    StoredWatermark, TransactionalStore,
+};
+
 /// Migrations for schema that the indexer framework needs, regardless of the specific data being
 /// indexed.
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
@@ -164,6 +171,76 @@ impl Db {
     }
 }
 
+#[async_trait]
+impl store::Connection for Connection<'_> {
+    async fn watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<StoredWatermark>> {
+        Ok(StoredWatermark::get(self, pipeline).await?)
+    }
+
+    async fn committer_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<CommitterWatermark<'static>>> {
+        Ok(CommitterWatermark::get(self, pipeline).await?)
+    }
+
+    async fn pruner_watermark(
+        &mut self,
+        pipeline: &'static str,
+        delay: Duration,
+    ) -> anyhow::Result<Option<PrunerWatermark<'static>>> {
+        Ok(PrunerWatermark::get(self, pipeline, delay).await?)
+    }
+
+    async fn set_committer_watermark(
+        &mut self,
+        watermark: &CommitterWatermark<'_>,
+    ) -> anyhow::Result<bool> {
+        Ok(watermark.update(self).await?)
+    }
+
+    async fn set_reader_watermark(
+        &mut self,
+        watermark: &ReaderWatermark<'_>,
+    ) -> anyhow::Result<bool> {
+        Ok(watermark.update(self).await?)
+    }
+
+    async fn set_pruner_watermark(
+        &mut self,
+        watermark: &PrunerWatermark<'_>,
+    ) -> anyhow::Result<bool> {
+        Ok(watermark.update(self).await?)
+    }
+}
+
+#[async_trait]
+impl Store for Db {
+    type Connection<'c> = Connection<'c>;
+
+    async fn connect<'c>(&'c self) -> anyhow::Result<Self::Connection<'c>> {
+        Ok(Db::connect(self).await?)
+    }
+}
+
+#[async_trait]
+impl TransactionalStore for Db {
+    async fn transaction<'a, R, F>(&self, f: F) -> anyhow::Result<R>
+    where
+        R: Send + 'a,
+        F: Send + 'a,
+        F: for<'r> FnOnce(
+            &'r mut Self::Connection<'_>,
+        ) -> ScopedBoxFuture<'a, 'r, anyhow::Result<R>>,
+    {
+        let mut conn = Db::connect(self).await?;
+        AsyncConnection::transaction(&mut conn, |conn| f(conn).scope_boxed()).await
+    }
+}
+
 impl Default for DbArgs {
     fn default() -> Self {
         Self {
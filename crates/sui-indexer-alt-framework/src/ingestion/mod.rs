@@ -27,7 +27,7 @@ mod local_client;
 mod regulator;
 mod remote_client;
 #[cfg(test)]
-mod test_utils;
+pub(crate) mod test_utils;
 
 #[derive(clap::Args, Clone, Debug)]
 pub struct ClientArgs {
@@ -13,17 +13,18 @@ use pipeline::{
     sequential::{self, SequentialConfig},
     Processor,
 };
+use store::{CommitterWatermark, Connection, Store, TransactionalStore};
 use task::graceful_shutdown;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
-use watermarks::CommitterWatermark;
 
 pub mod db;
 pub mod ingestion;
 pub(crate) mod metrics;
 pub mod pipeline;
 pub(crate) mod schema;
+pub mod store;
 pub mod task;
 pub(crate) mod watermarks;
 
@@ -55,9 +56,10 @@ pub struct IndexerArgs {
     pub metrics_address: SocketAddr,
 }
 
-pub struct Indexer {
-    /// Connection pool to the database.
-    db: Db,
+pub struct Indexer<S: Store = Db> {
+    /// The store that pipelines write their data and watermarks to (by default, a connection pool
+    /// to the database).
+    store: S,
 
     /// Prometheus Metrics.
     metrics: Arc<IndexerMetrics>,
@@ -98,7 +100,7 @@ pub struct Indexer {
     handles: Vec<JoinHandle<()>>,
 }
 
-impl Indexer {
+impl Indexer<Db> {
     /// Create a new instance of the indexer framework. `db_args`, `indexer_args,`, `client_args`,
     /// and `ingestion_config` contain configurations for the following, respectively:
     ///
@@ -122,14 +124,6 @@ impl Indexer {
         migrations: &'static EmbeddedMigrations,
         cancel: CancellationToken,
     ) -> Result<Self> {
-        let IndexerArgs {
-            first_checkpoint,
-            last_checkpoint,
-            pipeline,
-            skip_watermark,
-            metrics_address,
-        } = indexer_args;
-
         let db = Db::new(db_args)
             .await
             .context("Failed to connect to database")?;
@@ -139,8 +133,60 @@ impl Indexer {
             .await
             .context("Failed to run pending migrations")?;
 
-        let (metrics, metrics_service) =
-            MetricsService::new(metrics_address, db.clone(), cancel.clone())?;
+        Self::new_impl(
+            db.clone(),
+            Some(db),
+            indexer_args,
+            client_args,
+            ingestion_config,
+            cancel,
+        )
+    }
+
+    /// The database connection pool used by the indexer.
+    pub fn db(&self) -> &Db {
+        &self.store
+    }
+}
+
+impl<S: Store> Indexer<S> {
+    /// Create a new instance of the indexer framework that writes to `store`, rather than to a
+    /// database. The remaining arguments are the same as for [Indexer::new]. The store is expected
+    /// to be ready to accept writes, and any setup it requires must already have been performed.
+    pub fn new_with_store(
+        store: S,
+        indexer_args: IndexerArgs,
+        client_args: ClientArgs,
+        ingestion_config: IngestionConfig,
+        cancel: CancellationToken,
+    ) -> Result<Self> {
+        Self::new_impl(
+            store,
+            None,
+            indexer_args,
+            client_args,
+            ingestion_config,
+            cancel,
+        )
+    }
+
+    fn new_impl(
+        store: S,
+        db: Option<Db>,
+        indexer_args: IndexerArgs,
+        client_args: ClientArgs,
+        ingestion_config: IngestionConfig,
+        cancel: CancellationToken,
+    ) -> Result<Self> {
+        let IndexerArgs {
+            first_checkpoint,
+            last_checkpoint,
+            pipeline,
+            skip_watermark,
+            metrics_address,
+        } = indexer_args;
+
+        let (metrics, metrics_service) = MetricsService::new(metrics_address, db, cancel.clone())?;
 
         let ingestion_service = IngestionService::new(
             client_args,
@@ -150,7 +196,7 @@ impl Indexer {
         )?;
 
         Ok(Self {
-            db,
+            store,
             metrics,
             metrics_service,
             ingestion_service,
@@ -169,9 +215,9 @@ impl Indexer {
         })
     }
 
-    /// The database connection pool used by the indexer.
-    pub fn db(&self) -> &Db {
-        &self.db
+    /// The store that the indexer's pipelines write to.
+    pub fn store(&self) -> &S {
+        &self.store
     }
 
     /// The ingestion client used by the indexer to fetch checkpoints.
@@ -185,7 +231,7 @@ impl Indexer {
     /// Concurrent pipelines commit checkpoint data out-of-order to maximise throughput, and they
     /// keep the watermark table up-to-date with the highest point they can guarantee all data
     /// exists for, for their pipeline.
-    pub async fn concurrent_pipeline<H: concurrent::Handler + Send + Sync + 'static>(
+    pub async fn concurrent_pipeline<H: concurrent::Handler<Store = S> + Send + Sync + 'static>(
         &mut self,
         handler: H,
         config: ConcurrentConfig,
@@ -206,7 +252,7 @@ impl Indexer {
             watermark,
             config,
             self.skip_watermark,
-            self.db.clone(),
+            self.store.clone(),
             self.ingestion_service.subscribe().0,
             self.metrics.clone(),
             self.cancel.clone(),
@@ -215,52 +261,6 @@ impl Indexer {
         Ok(())
     }
 
-    /// Adds a new pipeline to this indexer and starts it up. Although their tasks have started,
-    /// they will be idle until the ingestion service starts, and serves it checkpoint data.
-    ///
-    /// Sequential pipelines commit checkpoint data in-order which sacrifices throughput, but may
-    /// be required to handle pipelines that modify data in-place (where each update is not an
-    /// insert, but could be a modification of an existing row, where ordering between updates is
-    /// important).
-    ///
-    /// The pipeline can optionally be configured to lag behind the ingestion service by a fixed
-    /// number of checkpoints (configured by `checkpoint_lag`).
-    pub async fn sequential_pipeline<H: sequential::Handler + Send + Sync + 'static>(
-        &mut self,
-        handler: H,
-        config: SequentialConfig,
-    ) -> Result<()> {
-        let Some(watermark) = self.add_pipeline::<H>().await? else {
-            return Ok(());
-        };
-
-        if self.skip_watermark {
-            warn!(
-                pipeline = H::NAME,
-                "--skip-watermarks enabled and ignored for sequential pipeline"
-            );
-        }
-
-        // For a sequential pipeline, data must be written in the order of checkpoints.
-        // Hence, we do not allow the first_checkpoint override to be in arbitrary positions.
-        self.check_first_checkpoint_consistency::<H>(&watermark)?;
-
-        let (checkpoint_rx, watermark_tx) = self.ingestion_service.subscribe();
-
-        self.handles.push(sequential::pipeline(
-            handler,
-            watermark,
-            config,
-            self.db.clone(),
-            checkpoint_rx,
-            watermark_tx,
-            self.metrics.clone(),
-            self.cancel.clone(),
-        ));
-
-        Ok(())
-    }
-
     /// Checks that the first checkpoint override is consistent with the watermark for the pipeline.
     /// If the watermark does not exist, the override can be anything. If the watermark exists, the
     /// override must not leave any gap in the data: it can be in the past, or at the tip of the
@@ -357,9 +357,14 @@ impl Indexer {
             }
         }
 
-        let mut conn = self.db.connect().await.context("Failed DB connection")?;
+        let mut conn = self
+            .store
+            .connect()
+            .await
+            .context("Failed to connect to store")?;
 
-        let watermark = CommitterWatermark::get(&mut conn, P::NAME)
+        let watermark = conn
+            .committer_watermark(P::NAME)
             .await
             .with_context(|| format!("Failed to get watermark for {}", P::NAME))?;
 
@@ -373,6 +378,54 @@ impl Indexer {
     }
 }
 
+impl<S: TransactionalStore> Indexer<S> {
+    /// Adds a new pipeline to this indexer and starts it up. Although their tasks have started,
+    /// they will be idle until the ingestion service starts, and serves it checkpoint data.
+    ///
+    /// Sequential pipelines commit checkpoint data in-order which sacrifices throughput, but may
+    /// be required to handle pipelines that modify data in-place (where each update is not an
+    /// insert, but could be a modification of an existing row, where ordering between updates is
+    /// important).
+    ///
+    /// The pipeline can optionally be configured to lag behind the ingestion service by a fixed
+    /// number of checkpoints (configured by `checkpoint_lag`).
+    pub async fn sequential_pipeline<H: sequential::Handler<Store = S> + Send + Sync + 'static>(
+        &mut self,
+        handler: H,
+        config: SequentialConfig,
+    ) -> Result<()> {
+        let Some(watermark) = self.add_pipeline::<H>().await? else {
+            return Ok(());
+        };
+
+        if self.skip_watermark {
+            warn!(
+                pipeline = H::NAME,
+                "--skip-watermarks enabled and ignored for sequential pipeline"
+            );
+        }
+
+        // For a sequential pipeline, data must be written in the order of checkpoints.
+        // Hence, we do not allow the first_checkpoint override to be in arbitrary positions.
+        self.check_first_checkpoint_consistency::<H>(&watermark)?;
+
+        let (checkpoint_rx, watermark_tx) = self.ingestion_service.subscribe();
+
+        self.handles.push(sequential::pipeline(
+            handler,
+            watermark,
+            config,
+            self.store.clone(),
+            checkpoint_rx,
+            watermark_tx,
+            self.metrics.clone(),
+            self.cancel.clone(),
+        ));
+
+        Ok(())
+    }
+}
+
 impl Default for IndexerArgs {
     fn default() -> Self {
         Self {
@@ -384,3 +437,91 @@ impl Default for IndexerArgs {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sui_field_count::FieldCount;
+    use sui_types::full_checkpoint_content::CheckpointData;
+
+    use crate::ingestion::test_utils::test_checkpoint_data;
+    use crate::store::mem::{MemConnection, MemStore};
+
+    use super::*;
+
+    #[derive(FieldCount)]
+    struct StoredCheckpoint {
+        sequence_number: u64,
+    }
+
+    /// A pipeline that records the sequence number of every checkpoint it sees.
+    struct Checkpoints;
+
+    impl Processor for Checkpoints {
+        const NAME: &'static str = "checkpoints";
+
+        type Value = StoredCheckpoint;
+
+        fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
+            Ok(vec![StoredCheckpoint {
+                sequence_number: checkpoint.checkpoint_summary.sequence_number,
+            }])
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl concurrent::Handler for Checkpoints {
+        type Store = MemStore<Vec<u64>>;
+
+        async fn commit(
+            values: &[Self::Value],
+            conn: &mut MemConnection<'_, Vec<u64>>,
+        ) -> Result<usize> {
+            conn.data().extend(values.iter().map(|v| v.sequence_number));
+            Ok(values.len())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_pipeline_with_mem_store() {
+        let dir = tempfile::tempdir().unwrap();
+        for cp in 0..=4 {
+            let path = dir.path().join(format!("{cp}.chk"));
+            tokio::fs::write(path, test_checkpoint_data(cp))
+                .await
+                .unwrap();
+        }
+
+        let store = MemStore::<Vec<u64>>::default();
+        let mut indexer = Indexer::new_with_store(
+            store.clone(),
+            IndexerArgs {
+                last_checkpoint: Some(4),
+                metrics_address: "127.0.0.1:0".parse().unwrap(),
+                ..Default::default()
+            },
+            ClientArgs {
+                remote_store_url: None,
+                local_ingestion_path: Some(dir.path().to_owned()),
+            },
+            IngestionConfig::default(),
+            CancellationToken::new(),
+        )
+        .unwrap();
+
+        indexer
+            .concurrent_pipeline(Checkpoints, ConcurrentConfig::default())
+            .await
+            .unwrap();
+
+        // Ingestion stops after the last checkpoint, and the pipeline winds down once it has
+        // committed all the data and watermarks.
+        indexer.run().await.unwrap().await.unwrap();
+
+        let mut data = store.data().await;
+        data.sort();
+        assert_eq!(data, vec![0, 1, 2, 3, 4]);
+
+        let watermark = store.watermark(Checkpoints::NAME).await.unwrap();
+        assert_eq!(watermark.checkpoint_hi_inclusive, 4);
+    }
+}
@@ -131,16 +131,19 @@ struct DbConnectionStatsCollector {
 impl MetricsService {
     /// Create a new metrics service, exposing Mysten-wide metrics, and Indexer-specific metrics.
     /// Returns the Indexer-specific metrics and the service itself (which must be run with
-    /// [Self::run]).
+    /// [Self::run]). Connection pool statistics are also exposed if the indexer is backed by a
+    /// `db`.
     pub(crate) fn new(
         addr: SocketAddr,
-        db: Db,
+        db: Option<Db>,
         cancel: CancellationToken,
     ) -> Result<(Arc<IndexerMetrics>, MetricsService)> {
         let registry = Registry::new_custom(Some("indexer_alt".to_string()), None)?;
 
         let metrics = IndexerMetrics::new(&registry);
-        registry.register(Box::new(DbConnectionStatsCollector::new(db)))?;
+        if let Some(db) = db {
+            registry.register(Box::new(DbConnectionStatsCollector::new(db)))?;
+        }
 
         let service = Self {
             addr,
@@ -224,8 +224,8 @@ mod tests {
     use sui_types::full_checkpoint_content::CheckpointData;
 
     use crate::{
-        db,
         pipeline::{concurrent::max_chunk_rows, Processor},
+        store::mem::{MemConnection, MemStore},
     };
 
     use super::*;
@@ -255,10 +255,12 @@ mod tests {
 
     #[async_trait::async_trait]
     impl Handler for TestHandler {
+        type Store = MemStore;
+
         const MAX_PENDING_ROWS: usize = 10000;
         async fn commit(
             _values: &[Self::Value],
-            _conn: &mut db::Connection<'_>,
+            _conn: &mut MemConnection<'_, ()>,
         ) -> anyhow::Result<usize> {
             tokio::time::sleep(Duration::from_millis(1000)).await;
             Ok(0)
@@ -16,18 +16,17 @@ use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 use crate::{
-    db::Db,
     metrics::IndexerMetrics,
     pipeline::{
         CommitterConfig, WatermarkPart, LOUD_WATERMARK_UPDATE_INTERVAL, WARN_PENDING_WATERMARKS,
     },
-    watermarks::CommitterWatermark,
+    store::{CommitterWatermark, Connection, Store},
 };
 
 use super::Handler;
 
 /// The watermark task is responsible for keeping track of a pipeline's out-of-order commits and
-/// updating its row in the `watermarks` table when a continuous run of checkpoints have landed
+/// updating its watermark in the store when a continuous run of checkpoints have landed
 /// since the last watermark update.
 ///
 /// It receives watermark "parts" that detail the proportion of each checkpoint's data that has
@@ -51,7 +50,7 @@ pub(super) fn commit_watermark<H: Handler + 'static>(
     config: CommitterConfig,
     skip_watermark: bool,
     mut rx: mpsc::Receiver<Vec<WatermarkPart>>,
-    db: Db,
+    store: H::Store,
     metrics: Arc<IndexerMetrics>,
     cancel: CancellationToken,
 ) -> JoinHandle<()> {
@@ -100,8 +99,8 @@ pub(super) fn commit_watermark<H: Handler + 'static>(
                         );
                     }
 
-                    let Ok(mut conn) = db.connect().await else {
-                        warn!(pipeline = H::NAME, "Commit watermark task failed to get connection for DB");
+                    let Ok(mut conn) = store.connect().await else {
+                        warn!(pipeline = H::NAME, "Commit watermark task failed to get connection for store");
                         continue;
                     };
 
@@ -187,7 +186,7 @@ pub(super) fn commit_watermark<H: Handler + 'static>(
 
                         // TODO: If initial_watermark is empty, when we update watermark
                         // for the first time, we should also update the low watermark.
-                        match watermark.update(&mut conn).await {
+                        match conn.set_committer_watermark(&watermark).await {
                             // If there's an issue updating the watermark, log it but keep going,
                             // it's OK for the watermark to lag from a correctness perspective.
                             Err(e) => {
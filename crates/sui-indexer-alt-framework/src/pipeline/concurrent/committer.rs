@@ -10,9 +10,9 @@ use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 use crate::{
-    db::Db,
     metrics::IndexerMetrics,
     pipeline::{Break, CommitterConfig, WatermarkPart},
+    store::Store,
     task::TrySpawnStreamExt,
 };
 
@@ -25,7 +25,7 @@ const INITIAL_RETRY_INTERVAL: Duration = Duration::from_millis(100);
 const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(1);
 
 /// The committer task is responsible for writing batches of rows to the database. It receives
-/// batches on `rx` and writes them out to the `db` concurrently (`config.write_concurrency`
+/// batches on `rx` and writes them out to the `store` concurrently (`config.write_concurrency`
 /// controls the degree of fan-out).
 ///
 /// The writing of each batch will be repeatedly retried on an exponential back-off until it
@@ -39,7 +39,7 @@ pub(super) fn committer<H: Handler + 'static>(
     skip_watermark: bool,
     rx: mpsc::Receiver<Batched<H>>,
     tx: mpsc::Sender<Vec<WatermarkPart>>,
-    db: Db,
+    store: H::Store,
     metrics: Arc<IndexerMetrics>,
     cancel: CancellationToken,
 ) -> JoinHandle<()> {
@@ -50,13 +50,13 @@ pub(super) fn committer<H: Handler + 'static>(
             .try_for_each_spawned(config.write_concurrency, |Batched { values, watermark }| {
                 let values = Arc::new(values);
                 let tx = tx.clone();
-                let db = db.clone();
+                let store = store.clone();
                 let metrics = metrics.clone();
                 let cancel = cancel.clone();
 
-                // Repeatedly try to get a connection to the DB and write the batch. Use an
-                // exponential backoff in case the failure is due to contention over the DB
-                // connection pool.
+                // Repeatedly try to get a connection to the store and write the batch. Use an
+                // exponential backoff in case the failure is due to contention over the store's
+                // connections.
                 let backoff = ExponentialBackoff {
                     initial_interval: INITIAL_RETRY_INTERVAL,
                     current_interval: INITIAL_RETRY_INTERVAL,
@@ -68,7 +68,7 @@ pub(super) fn committer<H: Handler + 'static>(
                 use backoff::Error as BE;
                 let commit = move || {
                     let values = values.clone();
-                    let db = db.clone();
+                    let store = store.clone();
                     let metrics = metrics.clone();
                     async move {
                         if values.is_empty() {
@@ -85,10 +85,10 @@ pub(super) fn committer<H: Handler + 'static>(
                             .with_label_values(&[H::NAME])
                             .start_timer();
 
-                        let mut conn = db.connect().await.map_err(|e| {
+                        let mut conn = store.connect().await.map_err(|e| {
                             warn!(
                                 pipeline = H::NAME,
-                                "Committed failed to get connection for DB"
+                                "Committed failed to get connection for store"
                             );
                             BE::transient(Break::Err(e.into()))
                         })?;
@@ -10,9 +10,8 @@ use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
 use crate::{
-    db::{self, Db},
     metrics::IndexerMetrics,
-    watermarks::CommitterWatermark,
+    store::{CommitterWatermark, Store},
 };
 
 use super::{processor::processor, CommitterConfig, Processor, WatermarkPart, PIPELINE_BUFFER};
@@ -34,7 +33,8 @@ mod reader_watermark;
 const MAX_WATERMARK_UPDATES: usize = 10_000;
 
 /// Handlers implement the logic for a given indexing pipeline: How to process checkpoint data (by
-/// implementing [Processor]) into rows for their table, and how to write those rows to the database.
+/// implementing [Processor]) into rows for their table, and how to write those rows to the
+/// [Store] that the pipeline commits to.
 ///
 /// The handler is also responsible for tuning the various parameters of the pipeline (provided as
 /// associated values). Reasonable defaults have been chosen to balance concurrency with memory
@@ -60,14 +60,23 @@ pub trait Handler: Processor<Value: FieldCount> {
     /// If there are more than this many rows pending, the committer applies backpressure.
     const MAX_PENDING_ROWS: usize = 5000;
 
-    /// Take a chunk of values and commit them to the database, returning the number of rows
+    /// The store that this handler's data and the pipeline's watermarks are written to.
+    type Store: Store;
+
+    /// Take a chunk of values and commit them to the store, returning the number of rows
     /// affected.
-    async fn commit(values: &[Self::Value], conn: &mut db::Connection<'_>)
-        -> anyhow::Result<usize>;
+    async fn commit(
+        values: &[Self::Value],
+        conn: &mut <Self::Store as Store>::Connection<'_>,
+    ) -> anyhow::Result<usize>;
 
-    /// Clean up data between checkpoints `_from` and `_to` (inclusive) in the database, returning
+    /// Clean up data between checkpoints `_from` and `_to` (inclusive) in the store, returning
     /// the number of rows affected. This function is optional, and defaults to not pruning at all.
-    async fn prune(_from: u64, _to: u64, _conn: &mut db::Connection<'_>) -> anyhow::Result<usize> {
+    async fn prune(
+        _from: u64,
+        _to: u64,
+        _conn: &mut <Self::Store as Store>::Connection<'_>,
+    ) -> anyhow::Result<usize> {
         Ok(0)
     }
 }
@@ -166,9 +175,9 @@ impl Default for PrunerConfig {
 /// either because it received the checkpoints out-of-order or because of variance in processing
 /// time.
 ///
-/// The pipeline also maintains a row in the `watermarks` table for the pipeline which tracks the
-/// watermark below which all data has been committed (modulo pruning), as long as `skip_watermark`
-/// is not true.
+/// The pipeline also maintains a watermark for the pipeline in its store, which tracks the
+/// checkpoint below which all data has been committed (modulo pruning), as long as
+/// `skip_watermark` is not true.
 ///
 /// Checkpoint data is fed into the pipeline through the `checkpoint_rx` channel, and internal
 /// channels are created to communicate between its various components. The pipeline can be
@@ -179,7 +188,7 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
     initial_commit_watermark: Option<CommitterWatermark<'static>>,
     config: ConcurrentConfig,
     skip_watermark: bool,
-    db: Db,
+    store: H::Store,
     checkpoint_rx: mpsc::Receiver<Arc<CheckpointData>>,
     metrics: Arc<IndexerMetrics>,
     cancel: CancellationToken,
@@ -224,7 +233,7 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
         skip_watermark,
         committer_rx,
         committer_tx,
-        db.clone(),
+        store.clone(),
         metrics.clone(),
         cancel.clone(),
     );
@@ -234,19 +243,19 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
         committer_config,
         skip_watermark,
         watermark_rx,
-        db.clone(),
+        store.clone(),
         metrics.clone(),
         cancel,
     );
 
     let reader_watermark = reader_watermark::<H>(
         pruner_config.clone(),
-        db.clone(),
+        store.clone(),
         metrics.clone(),
         pruner_cancel.clone(),
     );
 
-    let pruner = pruner::<H>(pruner_config, db, metrics, pruner_cancel.clone());
+    let pruner = pruner::<H>(pruner_config, store, metrics, pruner_cancel.clone());
 
     tokio::spawn(async move {
         let (_, _, _, _) = futures::join!(processor, collector, committer, commit_watermark);
@@ -11,14 +11,15 @@ use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 use crate::{
-    db::Db, metrics::IndexerMetrics, pipeline::LOUD_WATERMARK_UPDATE_INTERVAL,
-    watermarks::PrunerWatermark,
+    metrics::IndexerMetrics,
+    pipeline::LOUD_WATERMARK_UPDATE_INTERVAL,
+    store::{Connection, Store},
 };
 
 use super::{Handler, PrunerConfig};
 
-/// The pruner task is responsible for deleting old data from the database. It will periodically
-/// check the `watermarks` table to see if there is any data that should be pruned -- between
+/// The pruner task is responsible for deleting old data from the store. It will periodically
+/// check the pipeline's watermark to see if there is any data that should be pruned -- between
 /// `pruner_hi` (inclusive), and `reader_lo` (exclusive).
 ///
 /// To ensure that the pruner does not interfere with reads that are still in flight, it respects
@@ -33,7 +34,7 @@ use super::{Handler, PrunerConfig};
 /// will shutdown immediately.
 pub(super) fn pruner<H: Handler + 'static>(
     config: Option<PrunerConfig>,
-    db: Db,
+    store: H::Store,
     metrics: Arc<IndexerMetrics>,
     cancel: CancellationToken,
 ) -> JoinHandle<()> {
@@ -54,7 +55,7 @@ pub(super) fn pruner<H: Handler + 'static>(
         let mut next_loud_watermark_update = 0;
 
         'outer: loop {
-            // (1) Get the latest pruning bounds from the database.
+            // (1) Get the latest pruning bounds from the store.
             let mut watermark = tokio::select! {
                 _ = cancel.cancelled() => {
                     info!(pipeline = H::NAME, "Shutdown received");
@@ -67,12 +68,12 @@ pub(super) fn pruner<H: Handler + 'static>(
                         .with_label_values(&[H::NAME])
                         .start_timer();
 
-                    let Ok(mut conn) = db.connect().await else {
+                    let Ok(mut conn) = store.connect().await else {
                         warn!(pipeline = H::NAME, "Pruner failed to connect, while fetching watermark");
                         continue;
                     };
 
-                    match PrunerWatermark::get(&mut conn, H::NAME, config.delay()).await {
+                    match conn.pruner_watermark(H::NAME, config.delay()).await {
                         Ok(Some(current)) => {
                             guard.stop_and_record();
                             current
@@ -123,7 +124,7 @@ pub(super) fn pruner<H: Handler + 'static>(
                     .with_label_values(&[H::NAME])
                     .start_timer();
 
-                let Ok(mut conn) = db.connect().await else {
+                let Ok(mut conn) = store.connect().await else {
                     warn!(
                         pipeline = H::NAME,
                         "Pruner failed to connect, while pruning"
@@ -168,7 +169,7 @@ pub(super) fn pruner<H: Handler + 'static>(
                 .with_label_values(&[H::NAME])
                 .start_timer();
 
-            let Ok(mut conn) = db.connect().await else {
+            let Ok(mut conn) = store.connect().await else {
                 warn!(
                     pipeline = H::NAME,
                     "Pruner failed to connect, while updating watermark"
@@ -176,7 +177,7 @@ pub(super) fn pruner<H: Handler + 'static>(
                 continue;
             };
 
-            match watermark.update(&mut conn).await {
+            match conn.set_pruner_watermark(&watermark).await {
                 Err(e) => {
                     let elapsed = guard.stop_and_record();
                     error!(
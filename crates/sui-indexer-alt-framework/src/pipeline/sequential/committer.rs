@@ -3,7 +3,6 @@
 
 use std::{cmp::Ordering, collections::BTreeMap, sync::Arc};
 
-use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
 use tokio::{
     sync::mpsc,
     task::JoinHandle,
@@ -13,15 +12,14 @@ use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
 use crate::{
-    db::Db,
     metrics::IndexerMetrics,
     pipeline::{Indexed, LOUD_WATERMARK_UPDATE_INTERVAL, WARN_PENDING_WATERMARKS},
-    watermarks::CommitterWatermark,
+    store::{CommitterWatermark, Connection, ScopedFutureExt, TransactionalStore},
 };
 
 use super::{Handler, SequentialConfig};
 
-/// The committer task gathers rows into batches and writes them to the database.
+/// The committer task gathers rows into batches and writes them to the store.
 ///
 /// Data arrives out of order, grouped by checkpoint, on `rx`. The task orders them and waits to
 /// write them until either a configural polling interval has passed (controlled by
@@ -30,7 +28,7 @@ use super::{Handler, SequentialConfig};
 ///
 /// Writes are performed on checkpoint boundaries (more than one checkpoint can be present in a
 /// single write), in a single transaction that includes all row updates and an update to the
-/// watermark table.
+/// pipeline's watermark.
 ///
 /// The committer can be configured to lag behind the ingestion serice by a fixed number of
 /// checkpoints (configured by `checkpoint_lag`). A value of `0` means no lag.
@@ -44,7 +42,7 @@ pub(super) fn committer<H: Handler + 'static>(
     watermark: Option<CommitterWatermark<'static>>,
     mut rx: mpsc::Receiver<Indexed<H>>,
     tx: mpsc::UnboundedSender<(&'static str, u64)>,
-    db: Db,
+    store: H::Store,
     metrics: Arc<IndexerMetrics>,
     cancel: CancellationToken,
 ) -> JoinHandle<()> {
@@ -218,25 +216,16 @@ pub(super) fn committer<H: Handler + 'static>(
                         .with_label_values(&[H::NAME])
                         .start_timer();
 
-                    let Ok(mut conn) = db.connect().await else {
-                        warn!(pipeline = H::NAME, "Failed to get connection for DB");
-                        continue;
-                    };
-
                     // Write all the object updates out along with the watermark update, in a
                     // single transaction. The handler's `commit` implementation is responsible for
                     // chunking up the writes into a manageable size.
-                    let affected = conn.transaction::<_, anyhow::Error, _>(|conn| async {
+                    let affected = store.transaction(|conn| async {
                         // TODO: If initial_watermark is empty, when we update watermark
                         // for the first time, we should also update the low watermark.
-                        watermark.update(conn).await?;
+                        conn.set_committer_watermark(&watermark).await?;
                         H::commit(&batch, conn).await
                     }.scope_boxed()).await;
 
-                    // Drop the connection eagerly to avoid it holding on to references borrowed by
-                    // the transaction closure.
-                    drop(conn);
-
                     let elapsed = guard.stop_and_record();
 
                     let affected = match affected {
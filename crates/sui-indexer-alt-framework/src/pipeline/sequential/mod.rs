@@ -9,9 +9,8 @@ use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
 use crate::{
-    db::{self, Db},
     metrics::IndexerMetrics,
-    watermarks::CommitterWatermark,
+    store::{CommitterWatermark, Store, TransactionalStore},
 };
 
 use super::{processor::processor, CommitterConfig, Processor, PIPELINE_BUFFER};
@@ -22,7 +21,8 @@ mod committer;
 
 /// Handlers implement the logic for a given indexing pipeline: How to process checkpoint data (by
 /// implementing [Processor]) into rows for their table, how to combine multiple rows into a single
-/// DB operation, and then how to write those rows atomically to the database.
+/// write operation, and then how to write those rows atomically to the [TransactionalStore] that
+/// the pipeline commits to.
 ///
 /// The handler is also responsible for tuning the various parameters of the pipeline (provided as
 /// associated values).
@@ -56,9 +56,15 @@ pub trait Handler: Processor {
     /// guaranteed to be presented to the batch in checkpoint order.
     fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>);
 
-    /// Take a batch of values and commit them to the database, returning the number of rows
+    /// The store that this handler's data and the pipeline's watermarks are written to.
+    type Store: TransactionalStore;
+
+    /// Take a batch of values and commit them to the store, returning the number of rows
     /// affected.
-    async fn commit(batch: &Self::Batch, conn: &mut db::Connection<'_>) -> anyhow::Result<usize>;
+    async fn commit(
+        batch: &Self::Batch,
+        conn: &mut <Self::Store as Store>::Connection<'_>,
+    ) -> anyhow::Result<usize>;
 }
 
 /// Configuration for a sequential pipeline
@@ -100,7 +106,7 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
     handler: H,
     initial_watermark: Option<CommitterWatermark<'static>>,
     config: SequentialConfig,
-    db: Db,
+    store: H::Store,
     checkpoint_rx: mpsc::Receiver<Arc<CheckpointData>>,
     watermark_tx: mpsc::UnboundedSender<(&'static str, u64)>,
     metrics: Arc<IndexerMetrics>,
@@ -121,7 +127,7 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
         initial_watermark,
         committer_rx,
         watermark_tx,
-        db.clone(),
+        store,
         metrics.clone(),
         cancel.clone(),
     );
@@ -0,0 +1,328 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::{Mutex, MutexGuard};
+
+use super::{
+    CommitterWatermark, Connection, PrunerWatermark, ReaderWatermark, ScopedBoxFuture, Store,
+    StoredWatermark, TransactionalStore,
+};
+
+/// An in-memory [Store], mainly intended for tests. Alongside watermarks, it holds an arbitrary
+/// piece of data, `D`, that handlers write to through [MemConnection::data].
+///
+/// Access to the store is serialized: a connection holds an exclusive lock on the whole store
+/// until it is dropped, and transactions are implemented by restoring a snapshot of the store if
+/// they fail.
+pub struct MemStore<D = ()> {
+    state: Arc<Mutex<MemState<D>>>,
+}
+
+pub struct MemConnection<'c, D> {
+    state: MutexGuard<'c, MemState<D>>,
+}
+
+#[derive(Clone)]
+struct MemState<D> {
+    watermarks: BTreeMap<String, StoredWatermark>,
+    data: D,
+}
+
+impl<D> MemStore<D> {
+    pub fn new(data: D) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MemState {
+                watermarks: BTreeMap::new(),
+                data,
+            })),
+        }
+    }
+}
+
+impl<D: Clone> MemStore<D> {
+    /// A copy of the data currently in the store.
+    pub async fn data(&self) -> D {
+        self.state.lock().await.data.clone()
+    }
+
+    /// A copy of the watermark currently stored for `pipeline`, if there is one.
+    pub async fn watermark(&self, pipeline: &str) -> Option<StoredWatermark> {
+        self.state.lock().await.watermarks.get(pipeline).cloned()
+    }
+}
+
+impl<D> MemConnection<'_, D> {
+    /// The data in the store, for handlers to read from and write to.
+    pub fn data(&mut self) -> &mut D {
+        &mut self.state.data
+    }
+}
+
+impl<D: Default> Default for MemStore<D> {
+    fn default() -> Self {
+        Self::new(D::default())
+    }
+}
+
+impl<D> Clone for MemStore<D> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl<D: Send> Connection for MemConnection<'_, D> {
+    async fn watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<StoredWatermark>> {
+        Ok(self.state.watermarks.get(pipeline).cloned())
+    }
+
+    async fn committer_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<CommitterWatermark<'static>>> {
+        Ok(self
+            .state
+            .watermarks
+            .get(pipeline)
+            .map(|w| CommitterWatermark {
+                pipeline: pipeline.into(),
+                epoch_hi_inclusive: w.epoch_hi_inclusive,
+                checkpoint_hi_inclusive: w.checkpoint_hi_inclusive,
+                tx_hi: w.tx_hi,
+                timestamp_ms_hi_inclusive: w.timestamp_ms_hi_inclusive,
+            }))
+    }
+
+    async fn pruner_watermark(
+        &mut self,
+        pipeline: &'static str,
+        delay: Duration,
+    ) -> anyhow::Result<Option<PrunerWatermark<'static>>> {
+        let now = Utc::now().naive_utc();
+        Ok(self
+            .state
+            .watermarks
+            .get(pipeline)
+            .map(|w| PrunerWatermark {
+                pipeline: pipeline.into(),
+                wait_for: delay.as_millis() as i64 + (w.pruner_timestamp - now).num_milliseconds(),
+                reader_lo: w.reader_lo,
+                pruner_hi: w.pruner_hi,
+            }))
+    }
+
+    async fn set_committer_watermark(
+        &mut self,
+        watermark: &CommitterWatermark<'_>,
+    ) -> anyhow::Result<bool> {
+        let stored = match self.state.watermarks.entry(watermark.pipeline.to_string()) {
+            Entry::Vacant(entry) => {
+                entry.insert(StoredWatermark::from(watermark.clone()));
+                return Ok(true);
+            }
+
+            Entry::Occupied(entry) => entry.into_mut(),
+        };
+
+        if stored.checkpoint_hi_inclusive >= watermark.checkpoint_hi_inclusive {
+            return Ok(false);
+        }
+
+        stored.epoch_hi_inclusive = watermark.epoch_hi_inclusive;
+        stored.checkpoint_hi_inclusive = watermark.checkpoint_hi_inclusive;
+        stored.tx_hi = watermark.tx_hi;
+        stored.timestamp_ms_hi_inclusive = watermark.timestamp_ms_hi_inclusive;
+        Ok(true)
+    }
+
+    async fn set_reader_watermark(
+        &mut self,
+        watermark: &ReaderWatermark<'_>,
+    ) -> anyhow::Result<bool> {
+        let Some(stored) = self.state.watermarks.get_mut(watermark.pipeline.as_ref()) else {
+            return Ok(false);
+        };
+
+        if stored.reader_lo >= watermark.reader_lo {
+            return Ok(false);
+        }
+
+        stored.reader_lo = watermark.reader_lo;
+        stored.pruner_timestamp = Utc::now().naive_utc();
+        Ok(true)
+    }
+
+    async fn set_pruner_watermark(
+        &mut self,
+        watermark: &PrunerWatermark<'_>,
+    ) -> anyhow::Result<bool> {
+        let Some(stored) = self.state.watermarks.get_mut(watermark.pipeline.as_ref()) else {
+            return Ok(false);
+        };
+
+        stored.pruner_hi = watermark.pruner_hi;
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl<D: Send + 'static> Store for MemStore<D> {
+    type Connection<'c> = MemConnection<'c, D>;
+
+    async fn connect<'c>(&'c self) -> anyhow::Result<Self::Connection<'c>> {
+        Ok(MemConnection {
+            state: self.state.lock().await,
+        })
+    }
+}
+
+#[async_trait]
+impl<D: Clone + Send + 'static> TransactionalStore for MemStore<D> {
+    async fn transaction<'a, R, F>(&self, f: F) -> anyhow::Result<R>
+    where
+        R: Send + 'a,
+        F: Send + 'a,
+        F: for<'r> FnOnce(
+            &'r mut Self::Connection<'_>,
+        ) -> ScopedBoxFuture<'a, 'r, anyhow::Result<R>>,
+    {
+        let mut conn = self.connect().await?;
+        let snapshot = conn.state.clone();
+
+        let result = f(&mut conn).await;
+        if result.is_err() {
+            *conn.state = snapshot;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::ScopedFutureExt;
+
+    fn committer_watermark(checkpoint: i64) -> CommitterWatermark<'static> {
+        CommitterWatermark {
+            checkpoint_hi_inclusive: checkpoint,
+            ..CommitterWatermark::initial("pipeline".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_committer_watermark_only_increases() {
+        let store = MemStore::<()>::default();
+        let mut conn = store.connect().await.unwrap();
+
+        assert!(conn
+            .committer_watermark("pipeline")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(conn
+            .set_committer_watermark(&committer_watermark(10))
+            .await
+            .unwrap());
+        assert!(!conn
+            .set_committer_watermark(&committer_watermark(5))
+            .await
+            .unwrap());
+
+        let watermark = conn.committer_watermark("pipeline").await.unwrap().unwrap();
+        assert_eq!(watermark.checkpoint_hi_inclusive, 10);
+    }
+
+    #[tokio::test]
+    async fn test_reader_and_pruner_watermarks() {
+        let store = MemStore::<()>::default();
+        let mut conn = store.connect().await.unwrap();
+
+        // Reader watermarks can only be set for pipelines that have committed data.
+        let reader = ReaderWatermark::new("pipeline", 5);
+        assert!(!conn.set_reader_watermark(&reader).await.unwrap());
+
+        conn.set_committer_watermark(&committer_watermark(10))
+            .await
+            .unwrap();
+        assert!(conn.set_reader_watermark(&reader).await.unwrap());
+        assert!(!conn.set_reader_watermark(&reader).await.unwrap());
+
+        // The reader watermark was just updated, so the pruner has to wait out the delay.
+        let delay = Duration::from_secs(60);
+        let mut pruner = conn
+            .pruner_watermark("pipeline", delay)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(pruner.wait_for().is_some_and(|wait_for| wait_for <= delay));
+        assert_eq!(pruner.next_chunk(10), (0, 5));
+
+        pruner.pruner_hi = 5;
+        assert!(conn.set_pruner_watermark(&pruner).await.unwrap());
+        let pruner = conn
+            .pruner_watermark("pipeline", Duration::ZERO)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(pruner.is_empty());
+        assert!(pruner.wait_for().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transaction_rollback() {
+        let store = MemStore::new(vec![1u64]);
+
+        let result: anyhow::Result<()> = store
+            .transaction(|conn| {
+                async move {
+                    conn.data().push(2);
+                    conn.set_committer_watermark(&committer_watermark(10))
+                        .await?;
+                    anyhow::bail!("failed");
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(store.data().await, vec![1]);
+        assert!(store.watermark("pipeline").await.is_none());
+
+        store
+            .transaction(|conn| {
+                async move {
+                    conn.data().push(2);
+                    conn.set_committer_watermark(&committer_watermark(10))
+                        .await?;
+                    Ok(())
+                }
+                .scope_boxed()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(store.data().await, vec![1, 2]);
+        assert_eq!(
+            store
+                .watermark("pipeline")
+                .await
+                .unwrap()
+                .checkpoint_hi_inclusive,
+            10
+        );
+    }
+}
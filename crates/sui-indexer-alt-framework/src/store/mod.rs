@@ -0,0 +1,110 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{future::Future, pin::Pin, time::Duration};
+
+use async_trait::async_trait;
+
+pub use crate::watermarks::{
+    CommitterWatermark, PrunerWatermark, ReaderWatermark, StoredWatermark,
+};
+
+pub mod mem;
+
+/// A boxed future that borrows a connection for `'r`, and can also borrow data that outlives it
+/// for `'a`. Closures passed to [TransactionalStore::transaction] return these, so that they can
+/// capture references to their environment while being generic over the connection's lifetime.
+pub type ScopedBoxFuture<'a, 'r, T> = Pin<Box<dyn ScopedFuture<'a, 'r, Output = T> + Send + 'r>>;
+
+/// A future that lives for `'r`. The default `Bound` is only well-formed if `'a` outlives `'r`,
+/// which lets functions that are generic over `'r` assume as much.
+pub trait ScopedFuture<'a, 'r, Bound = &'r &'a ()>: Future {}
+
+impl<'a: 'r, 'r, F: Future + 'r> ScopedFuture<'a, 'r> for F {}
+
+pub trait ScopedFutureExt: Future + Sized {
+    /// Box this future, to return it from a [TransactionalStore::transaction] closure.
+    fn scope_boxed<'a, 'r>(self) -> ScopedBoxFuture<'a, 'r, Self::Output>
+    where
+        Self: Send + 'r,
+    {
+        Box::pin(self)
+    }
+}
+
+impl<F: Future> ScopedFutureExt for F {}
+
+/// A connection to a [Store]. Pipelines use it to read and write their watermarks, and it is
+/// passed to handlers to write (and prune) the data they produce.
+///
+/// All watermark updates are conditional: they only take effect if they make progress relative to
+/// the watermark that is currently stored, and they report whether the update took effect.
+#[async_trait]
+pub trait Connection: Send {
+    /// The full watermark row for `pipeline`, or `None` if the pipeline has never written one.
+    async fn watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<StoredWatermark>>;
+
+    /// The high watermark for `pipeline`, or `None` if the pipeline has never written one.
+    async fn committer_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<CommitterWatermark<'static>>>;
+
+    /// The range that the pruner still has to prune for `pipeline`, along with how long to wait
+    /// before acting on it, based on the time at which the reader low watermark was last updated
+    /// and the configured `delay`. Returns `None` if the pipeline has never written a watermark.
+    async fn pruner_watermark(
+        &mut self,
+        pipeline: &'static str,
+        delay: Duration,
+    ) -> anyhow::Result<Option<PrunerWatermark<'static>>>;
+
+    /// Upsert the high watermark, as long as it raises the stored watermark.
+    async fn set_committer_watermark(
+        &mut self,
+        watermark: &CommitterWatermark<'_>,
+    ) -> anyhow::Result<bool>;
+
+    /// Update the reader low watermark of an existing watermark, as long as it raises it, and
+    /// record the store's current time as the time this update happened.
+    async fn set_reader_watermark(
+        &mut self,
+        watermark: &ReaderWatermark<'_>,
+    ) -> anyhow::Result<bool>;
+
+    /// Update the pruner high watermark (only) of an existing watermark.
+    async fn set_pruner_watermark(
+        &mut self,
+        watermark: &PrunerWatermark<'_>,
+    ) -> anyhow::Result<bool>;
+}
+
+/// Where an indexer writes its data and watermarks. Instances are cloned to share access to the
+/// same underlying store between the tasks of each pipeline.
+#[async_trait]
+pub trait Store: Clone + Send + Sync + 'static {
+    type Connection<'c>: Connection
+    where
+        Self: 'c;
+
+    /// Get a connection to the store, failing if one could not be established.
+    async fn connect<'c>(&'c self) -> anyhow::Result<Self::Connection<'c>>;
+}
+
+/// A [Store] that can perform a sequence of reads and writes atomically. Sequential pipelines
+/// require this, to write their data and their watermark together.
+#[async_trait]
+pub trait TransactionalStore: Store {
+    /// Run `f` in a transaction: its writes are all committed if it succeeds, and none of them
+    /// are if it fails.
+    async fn transaction<'a, R, F>(&self, f: F) -> anyhow::Result<R>
+    where
+        R: Send + 'a,
+        F: Send + 'a,
+        F: for<'r> FnOnce(
+            &'r mut Self::Connection<'_>,
+        ) -> ScopedBoxFuture<'a, 'r, anyhow::Result<R>>;
+}
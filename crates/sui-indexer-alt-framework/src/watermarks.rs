@@ -10,9 +10,10 @@ use sui_field_count::FieldCount;
 
 use crate::{db::Connection, schema::watermarks};
 
+/// All the watermarks tracked for a pipeline.
 #[derive(Insertable, Selectable, Queryable, Debug, Clone, FieldCount)]
 #[diesel(table_name = watermarks)]
-pub(crate) struct StoredWatermark {
+pub struct StoredWatermark {
     pub pipeline: String,
     pub epoch_hi_inclusive: i64,
     pub checkpoint_hi_inclusive: i64,
@@ -26,7 +27,7 @@ pub(crate) struct StoredWatermark {
 /// Fields that the committer is responsible for setting.
 #[derive(AsChangeset, Selectable, Queryable, Debug, Clone, FieldCount)]
 #[diesel(table_name = watermarks)]
-pub(crate) struct CommitterWatermark<'p> {
+pub struct CommitterWatermark<'p> {
     pub pipeline: Cow<'p, str>,
     pub epoch_hi_inclusive: i64,
     pub checkpoint_hi_inclusive: i64,
@@ -34,16 +35,18 @@ pub(crate) struct CommitterWatermark<'p> {
     pub timestamp_ms_hi_inclusive: i64,
 }
 
+/// Fields that the reader watermark task is responsible for setting.
 #[derive(AsChangeset, Selectable, Queryable, Debug, Clone, FieldCount)]
 #[diesel(table_name = watermarks)]
-pub(crate) struct ReaderWatermark<'p> {
+pub struct ReaderWatermark<'p> {
     pub pipeline: Cow<'p, str>,
     pub reader_lo: i64,
 }
 
+/// The region that the pruner still has to prune, as seen by the pruner task.
 #[derive(Queryable, Debug, Clone, FieldCount)]
 #[diesel(table_name = watermarks)]
-pub(crate) struct PrunerWatermark<'p> {
+pub struct PrunerWatermark<'p> {
     /// The pipeline in question
     pub pipeline: Cow<'p, str>,
 
@@ -90,7 +93,7 @@ impl CommitterWatermark<'static> {
 
 impl<'p> CommitterWatermark<'p> {
     /// A new watermark with the given pipeline name indicating zero progress.
-    pub(crate) fn initial(pipeline: Cow<'p, str>) -> Self {
+    pub fn initial(pipeline: Cow<'p, str>) -> Self {
         CommitterWatermark {
             pipeline,
             epoch_hi_inclusive: 0,
@@ -101,7 +104,7 @@ impl<'p> CommitterWatermark<'p> {
     }
 
     /// The consensus timestamp associated with this checkpoint.
-    pub(crate) fn timestamp(&self) -> DateTime<Utc> {
+    pub fn timestamp(&self) -> DateTime<Utc> {
         DateTime::from_timestamp_millis(self.timestamp_ms_hi_inclusive).unwrap_or_default()
     }
 
@@ -124,7 +127,7 @@ impl<'p> CommitterWatermark<'p> {
 }
 
 impl<'p> ReaderWatermark<'p> {
-    pub(crate) fn new(pipeline: impl Into<Cow<'p, str>>, reader_lo: u64) -> Self {
+    pub fn new(pipeline: impl Into<Cow<'p, str>>, reader_lo: u64) -> Self {
         ReaderWatermark {
             pipeline: pipeline.into(),
             reader_lo: reader_lo as i64,
@@ -186,17 +189,17 @@ impl PrunerWatermark<'static> {
 impl<'p> PrunerWatermark<'p> {
     /// How long to wait before the pruner can act on this information, or `None`, if there is no
     /// need to wait.
-    pub(crate) fn wait_for(&self) -> Option<Duration> {
+    pub fn wait_for(&self) -> Option<Duration> {
         (self.wait_for > 0).then(|| Duration::from_millis(self.wait_for as u64))
     }
 
     /// Whether the pruner has any work left to do on the range in this watermark.
-    pub(crate) fn is_empty(&self) -> bool {
+    pub fn is_empty(&self) -> bool {
         self.pruner_hi >= self.reader_lo
     }
 
     /// The next chunk that the pruner should work on, to advance the watermark.
-    pub(crate) fn next_chunk(&mut self, size: u64) -> (u64, u64) {
+    pub fn next_chunk(&mut self, size: u64) -> (u64, u64) {
         let from = self.pruner_hi as u64;
         let to = (from + size).min(self.reader_lo as u64);
         (from, to)
@@ -121,6 +121,8 @@ impl Processor for KvEpochEnds {
 impl Handler for KvEpochEnds {
     const MIN_EAGER_ROWS: usize = 1;
 
+    type Store = db::Db;
+
     async fn commit(values: &[Self::Value], conn: &mut db::Connection<'_>) -> Result<usize> {
         Ok(diesel::insert_into(kv_epoch_ends::table)
             .values(values)
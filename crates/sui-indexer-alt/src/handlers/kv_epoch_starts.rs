@@ -68,6 +68,8 @@ impl Processor for KvEpochStarts {
 impl Handler for KvEpochStarts {
     const MIN_EAGER_ROWS: usize = 1;
 
+    type Store = db::Db;
+
     async fn commit(values: &[Self::Value], conn: &mut db::Connection<'_>) -> Result<usize> {
         Ok(diesel::insert_into(kv_epoch_starts::table)
             .values(values)
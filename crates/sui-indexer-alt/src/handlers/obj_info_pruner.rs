@@ -29,6 +29,8 @@ impl Processor for ObjInfoPruner {
 
 #[async_trait::async_trait]
 impl Handler for ObjInfoPruner {
+    type Store = db::Db;
+
     async fn commit(values: &[Self::Value], conn: &mut db::Connection<'_>) -> Result<usize> {
         // For each (object_id, cp_sequence_number_exclusive), delete all entries in obj_info with
         // cp_sequence_number less than cp_sequence_number_exclusive that match the object_id.
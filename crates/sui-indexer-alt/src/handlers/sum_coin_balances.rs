@@ -146,6 +146,8 @@ impl Handler for SumCoinBalances {
         }
     }
 
+    type Store = db::Db;
+
     async fn commit(batch: &Self::Batch, conn: &mut db::Connection<'_>) -> anyhow::Result<usize> {
         let mut updates = vec![];
         let mut deletes = vec![];
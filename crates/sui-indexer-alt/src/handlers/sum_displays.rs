@@ -69,6 +69,8 @@ impl Handler for SumDisplays {
         }
     }
 
+    type Store = db::Db;
+
     async fn commit(batch: &Self::Batch, conn: &mut db::Connection<'_>) -> Result<usize> {
         let values: Vec<_> = batch.values().cloned().collect();
         let updates = values
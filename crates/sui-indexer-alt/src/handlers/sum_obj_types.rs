@@ -145,6 +145,8 @@ impl Handler for SumObjTypes {
         }
     }
 
+    type Store = db::Db;
+
     async fn commit(values: &Self::Batch, conn: &mut db::Connection<'_>) -> anyhow::Result<usize> {
         let mut updates = vec![];
         let mut deletes = vec![];
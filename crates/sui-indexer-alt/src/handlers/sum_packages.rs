@@ -65,6 +65,8 @@ impl Handler for SumPackages {
         }
     }
 
+    type Store = db::Db;
+
     async fn commit(batch: &Self::Batch, conn: &mut db::Connection<'_>) -> Result<usize> {
         let values: Vec<_> = batch.values().cloned().collect();
         let updates = values.chunks(MAX_INSERT_CHUNK_ROWS).map(|chunk| {
@@ -56,20 +56,37 @@ impl<L> JsonRpcService<L> {
         remote_fw_config: Option<RemoteFirewallConfig>,
         policy_config: Option<PolicyConfig>,
         traffic_controller_metrics: TrafficControllerMetrics,
+    ) -> Self {
+        let traffic_controller = policy_config.clone().map(|policy| {
+            Arc::new(TrafficController::init(
+                policy,
+                traffic_controller_metrics,
+                remote_fw_config,
+            ))
+        });
+        Self::new_with_traffic_controller(
+            methods,
+            rpc_router,
+            logger,
+            traffic_controller,
+            policy_config.map(|policy| policy.client_id_source),
+        )
+    }
+
+    pub fn new_with_traffic_controller(
+        methods: Methods,
+        rpc_router: RpcRouter,
+        logger: L,
+        traffic_controller: Option<Arc<TrafficController>>,
+        client_id_source: Option<ClientIdSource>,
     ) -> Self {
         Self {
             methods,
             rpc_router,
             logger,
             id_provider: Arc::new(RandomIntegerIdProvider),
-            traffic_controller: policy_config.clone().map(|policy| {
-                Arc::new(TrafficController::init(
-                    policy,
-                    traffic_controller_metrics,
-                    remote_fw_config,
-                ))
-            }),
-            client_id_source: policy_config.map(|policy| policy.client_id_source),
+            traffic_controller,
+            client_id_source,
         }
     }
 }
@@ -212,9 +229,10 @@ async fn process_raw_request<L: Logger>(
         }
 
         // handle response tallying
+        let method = request.method.to_string();
         let response = process_request(request, api_version, service.call_data()).await;
         if let Some(traffic_controller) = &service.traffic_controller {
-            handle_traffic_resp(traffic_controller.clone(), client, &response);
+            handle_traffic_resp(traffic_controller.clone(), client, method, &response);
         }
 
         response
@@ -246,6 +264,7 @@ async fn handle_traffic_req(
 fn handle_traffic_resp(
     traffic_controller: Arc<TrafficController>,
     client: Option<IpAddr>,
+    method: String,
     response: &MethodResponse,
 ) {
     let error = response.error_code.map(ErrorCode::from);
@@ -264,7 +283,9 @@ fn handle_traffic_resp(
         // traffic and incentivize high volume clients to choose a
         // suitable rpc provider (or run their own). Later we may want
         // to provide a weight distribution based on the method being called.
+        // Policies that support it can weigh requests by `request_type` instead.
         spam_weight: Weight::one(),
+        request_type: Some(method),
         timestamp: SystemTime::now(),
     });
 }
@@ -4,6 +4,7 @@
 use std::env;
 use std::net::SocketAddr;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use axum::body::Body;
 use hyper::header::HeaderName;
@@ -13,6 +14,7 @@ use hyper::Request;
 use jsonrpsee::RpcModule;
 use prometheus::Registry;
 use sui_core::traffic_controller::metrics::TrafficControllerMetrics;
+use sui_core::traffic_controller::TrafficController;
 use sui_types::traffic_control::PolicyConfig;
 use sui_types::traffic_control::RemoteFirewallConfig;
 use tokio::runtime::Handle;
@@ -62,6 +64,7 @@ pub struct JsonRpcServerBuilder {
     registry: Registry,
     policy_config: Option<PolicyConfig>,
     firewall_config: Option<RemoteFirewallConfig>,
+    traffic_controller: Option<Arc<TrafficController>>,
 }
 
 pub fn sui_rpc_doc(version: &str) -> Project {
@@ -90,9 +93,16 @@ impl JsonRpcServerBuilder {
             registry: prometheus_registry.clone(),
             policy_config,
             firewall_config,
+            traffic_controller: None,
         }
     }
 
+    /// Use an existing traffic controller instead of spawning one from the
+    /// policy config, so that the caller can also inspect and manage it.
+    pub fn with_traffic_controller(&mut self, traffic_controller: Arc<TrafficController>) {
+        self.traffic_controller = Some(traffic_controller);
+    }
+
     pub fn register_module<T: SuiRpcModule>(&mut self, module: T) -> Result<(), Error> {
         self.rpc_doc.add_module(T::rpc_doc_module());
         Ok(self.module.merge(module.rpc())?)
@@ -186,20 +196,32 @@ impl JsonRpcServerBuilder {
         let methods_names = module.method_names().collect::<Vec<_>>();
 
         let metrics_logger = MetricsLogger::new(&self.registry, &methods_names);
-        let traffic_controller_metrics = TrafficControllerMetrics::new(&self.registry);
 
         let middleware = tower::ServiceBuilder::new()
             .layer(Self::trace_layer())
             .layer(Self::cors()?);
 
-        let service = crate::axum_router::JsonRpcService::new(
-            module.into(),
-            rpc_router,
-            metrics_logger,
-            self.firewall_config.clone(),
-            self.policy_config.clone(),
-            traffic_controller_metrics,
-        );
+        let service = match &self.traffic_controller {
+            Some(traffic_controller) => {
+                crate::axum_router::JsonRpcService::new_with_traffic_controller(
+                    module.into(),
+                    rpc_router,
+                    metrics_logger,
+                    Some(traffic_controller.clone()),
+                    self.policy_config
+                        .as_ref()
+                        .map(|policy| policy.client_id_source.clone()),
+                )
+            }
+            None => crate::axum_router::JsonRpcService::new(
+                module.into(),
+                rpc_router,
+                metrics_logger,
+                self.firewall_config.clone(),
+                self.policy_config.clone(),
+                TrafficControllerMetrics::new(&self.registry),
+            ),
+        };
 
         let mut router = axum::Router::new();
 
@@ -0,0 +1,582 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::keystore::{
+    load_aliases, save_aliases, validate_alias, AccountKeystore, Alias, FileBasedKeystore,
+};
+use crate::random_names::random_name;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, ensure, Context};
+use argon2::{Algorithm, Argon2, Params, Version};
+use fastcrypto::encoding::{Base64, Encoding};
+use once_cell::sync::OnceCell;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair};
+pub use zeroize::Zeroizing;
+
+/// Environment variable the keystore passphrase is read from, before falling back to prompting
+/// for it.
+pub const SUI_KEYSTORE_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_PASSPHRASE";
+
+const ENCRYPTED_KEYSTORE_VERSION: u64 = 1;
+const KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+/// Encrypted as the passphrase check of a keystore, see [PassphraseCheck].
+const PASSPHRASE_CHECK_PLAINTEXT: &[u8] = b"sui keystore passphrase check";
+
+/// Provides the passphrase of an encrypted keystore when its keys are first needed.
+pub type PassphraseProvider =
+    Arc<dyn Fn() -> Result<Zeroizing<String>, anyhow::Error> + Send + Sync>;
+
+/// Argon2id parameters used to derive the encryption key from the passphrase.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory cost, in KiB
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+/// Contents of an encrypted keystore file. Public keys are stored in the clear, so that the
+/// addresses of a keystore can be listed without its passphrase.
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystoreFile {
+    version: u64,
+    kdf: KdfParams,
+    /// Base64 encoded salt of the key derivation
+    salt: String,
+    /// Missing from keystores that were written before a passphrase was first provided to them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    passphrase_check: Option<PassphraseCheck>,
+    keys: Vec<EncryptedKey>,
+}
+
+/// A known plaintext encrypted under the derived key, used to check the passphrase, including for
+/// keystores that have no keys to check it against yet.
+#[derive(Serialize, Deserialize, Clone)]
+struct PassphraseCheck {
+    /// Base64 encoded nonce
+    nonce: String,
+    /// Base64 encoded ciphertext of [PASSPHRASE_CHECK_PLAINTEXT]
+    ciphertext: String,
+}
+
+/// A private key, encrypted with AES-256-GCM under the derived key. The public key is bound to the
+/// ciphertext as associated data.
+#[derive(Serialize, Deserialize, Clone)]
+struct EncryptedKey {
+    /// Base64 encoded `flag || pubkey`
+    public_key: String,
+    /// Base64 encoded nonce
+    nonce: String,
+    /// Base64 encoded ciphertext of the Base64 encoded `flag || privkey`
+    ciphertext: String,
+}
+
+/// Keys decrypted with the passphrase, along with the derived key used to encrypt new keys.
+struct UnlockedKeys {
+    key: Zeroizing<[u8; KEY_LENGTH]>,
+    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+}
+
+/// A file based keystore whose private keys are encrypted at rest with a passphrase. The
+/// passphrase is only asked for the first time a private key is needed, e.g. to sign.
+pub struct EncryptedFileKeystore {
+    path: PathBuf,
+    kdf: KdfParams,
+    salt: Vec<u8>,
+    /// Set the first time the keystore is unlocked, if the file did not have one.
+    passphrase_check: OnceCell<PassphraseCheck>,
+    encrypted_keys: BTreeMap<SuiAddress, EncryptedKey>,
+    public_keys: BTreeMap<SuiAddress, PublicKey>,
+    aliases: BTreeMap<SuiAddress, Alias>,
+    passphrase: PassphraseProvider,
+    unlocked: OnceCell<UnlockedKeys>,
+}
+
+impl Debug for EncryptedFileKeystore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptedFileKeystore")
+            .field("path", &self.path)
+            .field("addresses", &self.public_keys.keys().collect::<Vec<_>>())
+            .field("unlocked", &self.unlocked.get().is_some())
+            .finish()
+    }
+}
+
+impl Serialize for EncryptedFileKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.path.to_str().unwrap_or(""))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedFileKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        EncryptedFileKeystore::new(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for EncryptedFileKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        let key = self
+            .get_key(address)
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        Ok(Signature::new_hashed(msg, key))
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        let key = self
+            .get_key(address)
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        Ok(Signature::new_secure(&IntentMessage::new(intent, msg), key))
+    }
+
+    fn add_key(&mut self, alias: Option<String>, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        let address: SuiAddress = (&keypair.public()).into();
+        let alias = self.create_alias(alias)?;
+        self.unlock()?;
+        let unlocked = self.unlocked.get_mut().expect("keystore was just unlocked");
+        let encrypted_key = encrypt_key(&unlocked.key, &keypair)?;
+
+        self.aliases.insert(
+            address,
+            Alias {
+                alias,
+                public_key_base64: keypair.public().encode_base64(),
+            },
+        );
+        self.encrypted_keys.insert(address, encrypted_key);
+        self.public_keys.insert(address, keypair.public());
+        unlocked.keys.insert(address, keypair);
+        self.save()
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.public_keys.values().cloned().collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        if !self.public_keys.contains_key(address) {
+            bail!("Cannot find key for address: [{address}]");
+        }
+        self.unlocked_keys()?
+            .keys
+            .get(address)
+            .ok_or_else(|| anyhow!("Cannot find key for address: [{address}]"))
+    }
+
+    fn addresses_with_alias(&self) -> Vec<(&SuiAddress, &Alias)> {
+        self.aliases.iter().collect::<Vec<_>>()
+    }
+
+    fn aliases(&self) -> Vec<&Alias> {
+        self.aliases.values().collect()
+    }
+
+    fn aliases_mut(&mut self) -> Vec<&mut Alias> {
+        self.aliases.values_mut().collect()
+    }
+
+    fn get_alias_by_address(&self, address: &SuiAddress) -> Result<String, anyhow::Error> {
+        match self.aliases.get(address) {
+            Some(alias) => Ok(alias.alias.clone()),
+            None => bail!("Cannot find alias for address {address}"),
+        }
+    }
+
+    fn get_address_by_alias(&self, alias: String) -> Result<&SuiAddress, anyhow::Error> {
+        self.addresses_with_alias()
+            .iter()
+            .find(|x| x.1.alias == alias)
+            .ok_or_else(|| anyhow!("Cannot resolve alias {alias} to an address"))
+            .map(|x| x.0)
+    }
+
+    fn create_alias(&self, alias: Option<String>) -> Result<String, anyhow::Error> {
+        match alias {
+            Some(a) if self.alias_exists(&a) => {
+                bail!("Alias {a} already exists. Please choose another alias.")
+            }
+            Some(a) => validate_alias(&a),
+            None => Ok(random_name(
+                &self
+                    .alias_names()
+                    .into_iter()
+                    .map(|x| x.to_string())
+                    .collect::<HashSet<_>>(),
+            )),
+        }
+    }
+
+    fn update_alias(
+        &mut self,
+        old_alias: &str,
+        new_alias: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        let new_alias_name = self.update_alias_value(old_alias, new_alias)?;
+        save_aliases(&self.path, &self.aliases)?;
+        Ok(new_alias_name)
+    }
+}
+
+impl EncryptedFileKeystore {
+    /// Open the encrypted keystore at `path`, or start a new one if there is no file at `path`. The
+    /// passphrase is read from `SUI_KEYSTORE_PASSPHRASE`, or prompted for if it is not set.
+    pub fn new(path: &Path) -> Result<Self, anyhow::Error> {
+        Self::new_with_passphrase(path, Arc::new(default_passphrase))
+    }
+
+    pub fn new_with_passphrase(
+        path: &Path,
+        passphrase: PassphraseProvider,
+    ) -> Result<Self, anyhow::Error> {
+        let file = if path.exists() {
+            let contents = fs::read(path)
+                .with_context(|| format!("Cannot open the keystore file: {}", path.display()))?;
+            serde_json::from_slice::<EncryptedKeystoreFile>(&contents).with_context(|| {
+                format!(
+                    "Cannot deserialize the encrypted keystore file: {}",
+                    path.display()
+                )
+            })?
+        } else {
+            let mut salt = [0u8; SALT_LENGTH];
+            OsRng.fill_bytes(&mut salt);
+            EncryptedKeystoreFile {
+                version: ENCRYPTED_KEYSTORE_VERSION,
+                kdf: KdfParams::default(),
+                salt: Base64::encode(salt),
+                passphrase_check: None,
+                keys: vec![],
+            }
+        };
+        ensure!(
+            file.version == ENCRYPTED_KEYSTORE_VERSION,
+            "Unsupported encrypted keystore version {} in {}",
+            file.version,
+            path.display()
+        );
+
+        let mut encrypted_keys = BTreeMap::new();
+        let mut public_keys = BTreeMap::new();
+        for key in file.keys {
+            let public_key = PublicKey::decode_base64(&key.public_key)
+                .map_err(|e| anyhow!("Invalid keystore file: {}. {}", path.display(), e))?;
+            let address = SuiAddress::from(&public_key);
+            public_keys.insert(address, public_key);
+            encrypted_keys.insert(address, key);
+        }
+        let aliases = load_aliases(path, &public_keys)?;
+        let salt = Base64::decode(&file.salt)
+            .map_err(|e| anyhow!("Invalid keystore file: {}. {}", path.display(), e))?;
+
+        let passphrase_check = match file.passphrase_check {
+            Some(check) => OnceCell::with_value(check),
+            None => OnceCell::new(),
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            kdf: file.kdf,
+            salt,
+            passphrase_check,
+            encrypted_keys,
+            public_keys,
+            aliases,
+            passphrase,
+            unlocked: OnceCell::new(),
+        })
+    }
+
+    /// Set the key derivation parameters of a keystore that has no keys yet. Existing keystores
+    /// keep the parameters their keys were encrypted with.
+    pub fn with_kdf_params(mut self, kdf: KdfParams) -> Result<Self, anyhow::Error> {
+        ensure!(
+            self.encrypted_keys.is_empty(),
+            "Cannot change the key derivation parameters of a keystore with keys"
+        );
+        self.kdf = kdf;
+        Ok(self)
+    }
+
+    /// Encrypt the plaintext keystore at `path` in place with `passphrase`. Aliases are kept.
+    pub fn encrypt_file_keystore(
+        path: &Path,
+        passphrase: Zeroizing<String>,
+        kdf: KdfParams,
+    ) -> Result<Self, anyhow::Error> {
+        ensure!(
+            !Self::is_encrypted(path),
+            "The keystore file {} is already encrypted",
+            path.display()
+        );
+        let plaintext = FileBasedKeystore::new(&path.to_path_buf())?;
+
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let key = derive_key(&kdf, &salt, &passphrase)?;
+
+        let mut encrypted_keys = BTreeMap::new();
+        let mut public_keys = BTreeMap::new();
+        let mut keys = BTreeMap::new();
+        for keypair in plaintext.key_pairs() {
+            let address = SuiAddress::from(&keypair.public());
+            encrypted_keys.insert(address, encrypt_key(&key, keypair)?);
+            public_keys.insert(address, keypair.public());
+            keys.insert(address, keypair.copy());
+        }
+        let aliases = plaintext
+            .addresses_with_alias()
+            .into_iter()
+            .map(|(address, alias)| (*address, alias.clone()))
+            .collect();
+
+        let keystore = Self {
+            path: path.to_path_buf(),
+            kdf,
+            salt: salt.to_vec(),
+            passphrase_check: OnceCell::with_value(encrypt_passphrase_check(&key)?),
+            encrypted_keys,
+            public_keys,
+            aliases,
+            passphrase: Arc::new(move || Ok::<_, anyhow::Error>(passphrase.clone())),
+            unlocked: OnceCell::with_value(UnlockedKeys { key, keys }),
+        };
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    /// Whether the keystore file at `path` is encrypted.
+    pub fn is_encrypted(path: &Path) -> bool {
+        fs::read(path).is_ok_and(|contents| {
+            serde_json::from_slice::<EncryptedKeystoreFile>(&contents).is_ok()
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.unlocked.get().is_some()
+    }
+
+    /// Decrypt the private keys, asking for the passphrase if they have not been decrypted yet.
+    pub fn unlock(&self) -> Result<(), anyhow::Error> {
+        self.unlocked_keys().map(|_| ())
+    }
+
+    fn unlocked_keys(&self) -> Result<&UnlockedKeys, anyhow::Error> {
+        self.unlocked.get_or_try_init(|| {
+            let passphrase = (self.passphrase)()?;
+            let key = derive_key(&self.kdf, &self.salt, &passphrase)?;
+            match self.passphrase_check.get() {
+                Some(check) => verify_passphrase_check(&key, check)?,
+                None => {
+                    let _ = self.passphrase_check.set(encrypt_passphrase_check(&key)?);
+                }
+            }
+            let keys = self
+                .encrypted_keys
+                .iter()
+                .map(|(address, encrypted_key)| {
+                    Ok((*address, decrypt_key(&key, encrypted_key, address)?))
+                })
+                .collect::<Result<_, anyhow::Error>>()?;
+            Ok(UnlockedKeys { key, keys })
+        })
+    }
+
+    /// Keys are written to a temporary file first, so that the keystore is never left partially
+    /// written. A keystore is unlocked before it is first written, so that the file always records
+    /// the check of the passphrase it was created with.
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        if self.passphrase_check.get().is_none() {
+            self.unlock()?;
+        }
+        save_aliases(&self.path, &self.aliases)?;
+        let file = EncryptedKeystoreFile {
+            version: ENCRYPTED_KEYSTORE_VERSION,
+            kdf: self.kdf,
+            salt: Base64::encode(&self.salt),
+            passphrase_check: self.passphrase_check.get().cloned(),
+            keys: self.encrypted_keys.values().cloned().collect(),
+        };
+        let store = serde_json::to_string_pretty(&file).with_context(|| {
+            format!("Cannot serialize keystore to file: {}", self.path.display())
+        })?;
+        let mut tmp_path = self.path.clone();
+        tmp_path.set_extension("keystore.tmp");
+        fs::write(&tmp_path, store)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+fn default_passphrase() -> Result<Zeroizing<String>, anyhow::Error> {
+    if let Ok(passphrase) = std::env::var(SUI_KEYSTORE_PASSPHRASE_ENV) {
+        return Ok(Zeroizing::new(passphrase));
+    }
+    if !std::io::stdin().is_terminal() {
+        bail!("The keystore is encrypted. Set {SUI_KEYSTORE_PASSPHRASE_ENV} to unlock it.");
+    }
+    let passphrase = inquire::Password::new("Keystore passphrase:")
+        .without_confirmation()
+        .prompt()?;
+    Ok(Zeroizing::new(passphrase))
+}
+
+fn derive_key(
+    kdf: &KdfParams,
+    salt: &[u8],
+    passphrase: &str,
+) -> Result<Zeroizing<[u8; KEY_LENGTH]>, anyhow::Error> {
+    let params = Params::new(
+        kdf.memory_kib,
+        kdf.iterations,
+        kdf.parallelism,
+        Some(KEY_LENGTH),
+    )
+    .map_err(|e| anyhow!("Invalid key derivation parameters: {e}"))?;
+    let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| anyhow!("Cannot derive the keystore encryption key: {e}"))?;
+    Ok(key)
+}
+
+fn encrypt_passphrase_check(key: &[u8; KEY_LENGTH]) -> Result<PassphraseCheck, anyhow::Error> {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = Aes256Gcm::new_from_slice(key)
+        .map_err(|_| anyhow!("Invalid keystore encryption key"))?
+        .encrypt(Nonce::from_slice(&nonce), PASSPHRASE_CHECK_PLAINTEXT)
+        .map_err(|_| anyhow!("Cannot encrypt passphrase check"))?;
+    Ok(PassphraseCheck {
+        nonce: Base64::encode(nonce),
+        ciphertext: Base64::encode(ciphertext),
+    })
+}
+
+fn verify_passphrase_check(
+    key: &[u8; KEY_LENGTH],
+    check: &PassphraseCheck,
+) -> Result<(), anyhow::Error> {
+    let nonce = Base64::decode(&check.nonce)
+        .map_err(|e| anyhow!("Invalid nonce for passphrase check: {e}"))?;
+    ensure!(
+        nonce.len() == NONCE_LENGTH,
+        "Invalid nonce for passphrase check"
+    );
+    let ciphertext = Base64::decode(&check.ciphertext)
+        .map_err(|e| anyhow!("Invalid ciphertext for passphrase check: {e}"))?;
+    let plaintext = Aes256Gcm::new_from_slice(key)
+        .map_err(|_| anyhow!("Invalid keystore encryption key"))?
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| anyhow!("Incorrect keystore passphrase"))?;
+    ensure!(
+        plaintext == PASSPHRASE_CHECK_PLAINTEXT,
+        "Incorrect keystore passphrase"
+    );
+    Ok(())
+}
+
+fn encrypt_key(
+    key: &[u8; KEY_LENGTH],
+    keypair: &SuiKeyPair,
+) -> Result<EncryptedKey, anyhow::Error> {
+    let public_key = keypair.public().encode_base64();
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut nonce);
+    let private_key = Zeroizing::new(keypair.encode_base64());
+    let ciphertext = Aes256Gcm::new_from_slice(key)
+        .map_err(|_| anyhow!("Invalid keystore encryption key"))?
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: private_key.as_bytes(),
+                aad: public_key.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("Cannot encrypt key"))?;
+    Ok(EncryptedKey {
+        public_key,
+        nonce: Base64::encode(nonce),
+        ciphertext: Base64::encode(ciphertext),
+    })
+}
+
+fn decrypt_key(
+    key: &[u8; KEY_LENGTH],
+    encrypted_key: &EncryptedKey,
+    address: &SuiAddress,
+) -> Result<SuiKeyPair, anyhow::Error> {
+    let nonce = Base64::decode(&encrypted_key.nonce)
+        .map_err(|e| anyhow!("Invalid nonce for key of {address}: {e}"))?;
+    ensure!(
+        nonce.len() == NONCE_LENGTH,
+        "Invalid nonce for key of {address}"
+    );
+    let ciphertext = Base64::decode(&encrypted_key.ciphertext)
+        .map_err(|e| anyhow!("Invalid ciphertext for key of {address}: {e}"))?;
+    let private_key = Aes256Gcm::new_from_slice(key)
+        .map_err(|_| anyhow!("Invalid keystore encryption key"))?
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: encrypted_key.public_key.as_bytes(),
+            },
+        )
+        .map(Zeroizing::new)
+        .map_err(|_| anyhow!("Incorrect keystore passphrase"))?;
+    let keypair = std::str::from_utf8(&private_key)
+        .map_err(|e| anyhow!("Invalid key for {address}: {e}"))
+        .and_then(|private_key| {
+            SuiKeyPair::decode_base64(private_key)
+                .map_err(|e| anyhow!("Invalid key for {address}: {e}"))
+        })?;
+    ensure!(
+        SuiAddress::from(&keypair.public()) == *address,
+        "Key for {address} does not match its public key"
+    );
+    Ok(keypair)
+}
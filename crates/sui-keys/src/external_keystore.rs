@@ -0,0 +1,428 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A keystore whose private keys are held by an external signer, such as a hardware wallet or a
+//! custodial service, that runs as a subprocess of the CLI.
+//!
+//! The signer reads requests from its stdin and writes responses to its stdout, one JSON object
+//! per line. Every request has an `id`, a `method` and, except for `keys`, `params`. The response
+//! to a request has the same `id`, and either a `result` or an `error` message:
+//!
+//! ```text
+//! > {"id":0,"method":"keys"}
+//! < {"id":0,"result":{"keys":[{"public_key":"<base64 flag || pubkey>","alias":"ledger-0"}]}}
+//! > {"id":1,"method":"sign_hashed","params":{"address":"0x...","msg":"<base64 bytes>"}}
+//! < {"id":1,"result":{"signature":"<base64 flag || signature || pubkey>"}}
+//! > {"id":2,"method":"sign_secure","params":{"address":"0x...","intent_message":"<base64 BCS>"}}
+//! < {"id":2,"error":"rejected by user"}
+//! ```
+//!
+//! - `keys` lists the public keys the signer can sign for. Aliases are optional.
+//! - `sign_hashed` signs `msg` as is.
+//! - `sign_secure` signs the Blake2b-256 hash of `intent_message`, the BCS serialized
+//!   `IntentMessage`. Signers can decode it to show what is being signed, e.g. a transaction.
+//!
+//! The signer is started when the keystore is opened, and its stdin is closed when the keystore is
+//! dropped, after which it should exit. A signer that does not answer a request within the
+//! configured timeout is killed, and restarted for the next request.
+
+use crate::keystore::{AccountKeystore, Alias};
+use anyhow::{anyhow, bail, Context};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::traits::VerifyingKey;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{
+    EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair, SuiSignature, SuiSignatureInner,
+};
+
+const SIGNER_EXIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How to start the external signer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExternalSignerConfig {
+    pub command: PathBuf,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// How long to wait for the signer to answer a request, e.g. while the user confirms it on a
+    /// hardware wallet. Defaults to 60 seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl ExternalSignerConfig {
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(60))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SignerRequest {
+    pub id: u64,
+    #[serde(flatten)]
+    pub method: SignerMethod,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum SignerMethod {
+    Keys,
+    SignHashed {
+        address: SuiAddress,
+        /// Base64 encoded message
+        msg: String,
+    },
+    SignSecure {
+        address: SuiAddress,
+        /// Base64 encoded BCS bytes of the intent message
+        intent_message: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SignerResponse {
+    pub id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KeysResult {
+    pub keys: Vec<SignerKey>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SignerKey {
+    /// Base64 encoded `flag || pubkey`
+    pub public_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SignatureResult {
+    pub signature: Signature,
+}
+
+struct SignerProcess {
+    child: Child,
+    /// Only taken when the process is dropped.
+    stdin: Option<ChildStdin>,
+    /// Lines of the signer's stdout, read by a background thread so that reads can time out.
+    stdout: Receiver<io::Result<String>>,
+    timeout: Duration,
+    next_id: u64,
+}
+
+impl SignerProcess {
+    fn spawn(config: &ExternalSignerConfig) -> Result<Self, anyhow::Error> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .with_context(|| {
+                format!(
+                    "Cannot start the external signer: {}",
+                    config.command.display()
+                )
+            })?;
+        let stdin = child.stdin.take();
+        let reader = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        let (sender, stdout) = mpsc::channel();
+        // The thread exits once the signer closes its stdout, or the process is dropped.
+        std::thread::spawn(move || {
+            for line in reader.lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            stdin,
+            stdout,
+            timeout: config.timeout(),
+            next_id: 0,
+        })
+    }
+
+    fn request(&mut self, method: SignerMethod) -> Result<serde_json::Value, anyhow::Error> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let mut request = serde_json::to_string(&SignerRequest { id, method })?;
+        request.push('\n');
+        let stdin = self.stdin.as_mut().expect("stdin is only taken on drop");
+        stdin.write_all(request.as_bytes())?;
+        stdin.flush()?;
+
+        let line = match self.stdout.recv_timeout(self.timeout) {
+            Ok(line) => line?,
+            Err(RecvTimeoutError::Timeout) => bail!(
+                "The external signer did not answer within {} seconds",
+                self.timeout.as_secs()
+            ),
+            Err(RecvTimeoutError::Disconnected) => bail!("The external signer exited"),
+        };
+        let response: SignerResponse = serde_json::from_str(&line)
+            .with_context(|| format!("Invalid response from the external signer: {line}"))?;
+        if response.id != id {
+            bail!(
+                "The external signer answered request {} instead of {id}",
+                response.id
+            );
+        }
+        match (response.result, response.error) {
+            (_, Some(error)) => bail!("The external signer failed: {error}"),
+            (Some(result), None) => Ok(result),
+            (None, None) => bail!("The external signer returned neither a result nor an error"),
+        }
+    }
+}
+
+impl Drop for SignerProcess {
+    fn drop(&mut self) {
+        // Closing stdin asks the signer to exit. Signers that don't exit in time are killed.
+        drop(self.stdin.take());
+        let deadline = Instant::now() + SIGNER_EXIT_TIMEOUT;
+        while Instant::now() < deadline {
+            if !matches!(self.child.try_wait(), Ok(None)) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A keystore that delegates signing to an external signer. Keys cannot be added to it or
+/// exported from it, and their aliases are set by the signer.
+pub struct ExternalKeystore {
+    config: ExternalSignerConfig,
+    keys: BTreeMap<SuiAddress, PublicKey>,
+    aliases: BTreeMap<SuiAddress, Alias>,
+    /// The signer process. It is restarted for the next request if it fails.
+    signer: Mutex<Option<SignerProcess>>,
+}
+
+impl Serialize for ExternalKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.config.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExternalKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        ExternalKeystore::new(ExternalSignerConfig::deserialize(deserializer)?)
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for ExternalKeystore {
+    fn add_key(
+        &mut self,
+        _alias: Option<String>,
+        _keypair: SuiKeyPair,
+    ) -> Result<(), anyhow::Error> {
+        bail!("Keys cannot be added to an external signer keystore")
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.keys.values().cloned().collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        bail!("The private key of {address} is held by an external signer")
+    }
+
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        let public_key = self.public_key(address)?;
+        let signature = self.sign(SignerMethod::SignHashed {
+            address: *address,
+            msg: Base64::encode(msg),
+        })?;
+        verify_hashed(&signature, msg, public_key).map_err(|e| {
+            signature::Error::from_source(format!(
+                "The external signer returned an invalid signature for {address}: {e}"
+            ))
+        })?;
+        Ok(signature)
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        self.public_key(address)?;
+        let intent_message = IntentMessage::new(intent, msg);
+        let bytes = bcs::to_bytes(&intent_message).map_err(signature::Error::from_source)?;
+        let signature = self.sign(SignerMethod::SignSecure {
+            address: *address,
+            intent_message: Base64::encode(bytes),
+        })?;
+        signature
+            .verify_secure(&intent_message, *address, signature.scheme())
+            .map_err(|e| {
+                signature::Error::from_source(format!(
+                    "The external signer returned an invalid signature: {e}"
+                ))
+            })?;
+        Ok(signature)
+    }
+
+    fn addresses_with_alias(&self) -> Vec<(&SuiAddress, &Alias)> {
+        self.aliases.iter().collect::<Vec<_>>()
+    }
+
+    fn aliases(&self) -> Vec<&Alias> {
+        self.aliases.values().collect()
+    }
+
+    fn aliases_mut(&mut self) -> Vec<&mut Alias> {
+        self.aliases.values_mut().collect()
+    }
+
+    fn get_alias_by_address(&self, address: &SuiAddress) -> Result<String, anyhow::Error> {
+        match self.aliases.get(address) {
+            Some(alias) => Ok(alias.alias.clone()),
+            None => bail!("Cannot find alias for address {address}"),
+        }
+    }
+
+    fn get_address_by_alias(&self, alias: String) -> Result<&SuiAddress, anyhow::Error> {
+        self.addresses_with_alias()
+            .iter()
+            .find(|x| x.1.alias == alias)
+            .ok_or_else(|| anyhow!("Cannot resolve alias {alias} to an address"))
+            .map(|x| x.0)
+    }
+
+    fn create_alias(&self, _alias: Option<String>) -> Result<String, anyhow::Error> {
+        bail!("Aliases of an external signer keystore are set by the signer")
+    }
+
+    fn update_alias(
+        &mut self,
+        _old_alias: &str,
+        _new_alias: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        bail!("Aliases of an external signer keystore are set by the signer")
+    }
+}
+
+impl ExternalKeystore {
+    /// Start the external signer, and ask it for its keys.
+    pub fn new(config: ExternalSignerConfig) -> Result<Self, anyhow::Error> {
+        let mut signer = SignerProcess::spawn(&config)?;
+        let result: KeysResult = serde_json::from_value(signer.request(SignerMethod::Keys)?)
+            .context("Invalid keys from the external signer")?;
+
+        let mut keys = BTreeMap::new();
+        let mut aliases = BTreeMap::new();
+        for key in result.keys {
+            let public_key = PublicKey::decode_base64(&key.public_key)
+                .map_err(|e| anyhow!("Invalid public key from the external signer: {e}"))?;
+            let address = SuiAddress::from(&public_key);
+            let alias = key.alias.unwrap_or_else(|| {
+                let address = address.to_string();
+                format!("external-{}", &address[2..10])
+            });
+            aliases.insert(
+                address,
+                Alias {
+                    alias,
+                    public_key_base64: public_key.encode_base64(),
+                },
+            );
+            keys.insert(address, public_key);
+        }
+
+        Ok(Self {
+            config,
+            keys,
+            aliases,
+            signer: Mutex::new(Some(signer)),
+        })
+    }
+
+    pub fn config(&self) -> &ExternalSignerConfig {
+        &self.config
+    }
+
+    fn public_key(&self, address: &SuiAddress) -> Result<&PublicKey, signature::Error> {
+        self.keys.get(address).ok_or_else(|| {
+            signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+        })
+    }
+
+    fn sign(&self, method: SignerMethod) -> Result<Signature, signature::Error> {
+        let mut signer = self.signer.lock().unwrap();
+        let result = match signer.take() {
+            Some(process) => Ok(process),
+            None => SignerProcess::spawn(&self.config),
+        }
+        .and_then(|mut process| {
+            let result = process.request(method)?;
+            // A process that failed is dropped, and a new one started for the next request.
+            *signer = Some(process);
+            Ok(result)
+        })
+        .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        serde_json::from_value::<SignatureResult>(result)
+            .map(|result| result.signature)
+            .map_err(|e| {
+                signature::Error::from_source(format!(
+                    "Invalid signature from the external signer: {e}"
+                ))
+            })
+    }
+}
+
+/// Check that `signature` is a valid signature of `msg` as is, by `public_key`.
+fn verify_hashed(
+    signature: &Signature,
+    msg: &[u8],
+    public_key: &PublicKey,
+) -> Result<(), anyhow::Error> {
+    fn verify<S: SuiSignatureInner>(signature: &S, msg: &[u8]) -> Result<(), anyhow::Error> {
+        let (signature, public_key) = signature.get_verification_inputs()?;
+        public_key.verify(msg, &signature)?;
+        Ok(())
+    }
+
+    if signature.scheme() != public_key.scheme()
+        || signature.public_key_bytes() != public_key.as_ref()
+    {
+        bail!("signed with a different key");
+    }
+    match signature {
+        Signature::Ed25519SuiSignature(signature) => verify(signature, msg),
+        Signature::Secp256k1SuiSignature(signature) => verify(signature, msg),
+        Signature::Secp256r1SuiSignature(signature) => verify(signature, msg),
+    }
+}
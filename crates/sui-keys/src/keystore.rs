@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::encrypted_keystore::EncryptedFileKeystore;
+use crate::external_keystore::{ExternalKeystore, ExternalSignerConfig};
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
 use crate::random_names::{random_name, random_names};
 use anyhow::{anyhow, bail, ensure, Context};
@@ -23,11 +25,13 @@ use sui_types::crypto::{
     enum_dispatch, EncodeDecodeBase64, PublicKey, Signature, SignatureScheme, SuiKeyPair,
 };
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 #[enum_dispatch(AccountKeystore)]
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    Encrypted(EncryptedFileKeystore),
+    External(ExternalKeystore),
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -150,6 +154,58 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::Encrypted(file) => {
+                writeln!(writer, "Keystore Type : Encrypted File")?;
+                write!(writer, "Keystore Path : {:?}", file.path())?;
+                write!(f, "{}", writer)
+            }
+            Keystore::External(external) => {
+                writeln!(writer, "Keystore Type : External Signer")?;
+                write!(writer, "Signer Command : {:?}", external.config().command)?;
+                write!(f, "{}", writer)
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Keystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        /// Same representation as `Keystore`. A file keystore that was encrypted after it was
+        /// configured is opened as an encrypted keystore.
+        #[derive(Deserialize)]
+        enum KeystoreConfig {
+            File(PathBuf),
+            InMem(InMemKeystore),
+            Encrypted(PathBuf),
+            External(ExternalSignerConfig),
+        }
+
+        match KeystoreConfig::deserialize(deserializer)? {
+            KeystoreConfig::File(path) => Keystore::open(&path).map_err(D::Error::custom),
+            KeystoreConfig::InMem(keystore) => Ok(Keystore::InMem(keystore)),
+            KeystoreConfig::Encrypted(path) => EncryptedFileKeystore::new(&path)
+                .map(Keystore::Encrypted)
+                .map_err(D::Error::custom),
+            KeystoreConfig::External(config) => ExternalKeystore::new(config)
+                .map(Keystore::External)
+                .map_err(D::Error::custom),
+        }
+    }
+}
+
+impl Keystore {
+    /// Open the keystore file at `path`, as an encrypted keystore if the file is encrypted, or as a
+    /// plaintext keystore otherwise.
+    pub fn open(path: &Path) -> Result<Self, anyhow::Error> {
+        if EncryptedFileKeystore::is_encrypted(path) {
+            Ok(Keystore::Encrypted(EncryptedFileKeystore::new(path)?))
+        } else {
+            Ok(Keystore::File(FileBasedKeystore::new(&path.to_path_buf())?))
         }
     }
 }
@@ -310,6 +366,11 @@ impl AccountKeystore for FileBasedKeystore {
 
 impl FileBasedKeystore {
     pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        ensure!(
+            !EncryptedFileKeystore::is_encrypted(path),
+            "The keystore file {} is encrypted, and must be opened as an encrypted keystore",
+            path.display()
+        );
         let keys = if path.exists() {
             let reader =
                 BufReader::new(File::open(path).with_context(|| {
@@ -330,67 +391,11 @@ impl FileBasedKeystore {
             BTreeMap::new()
         };
 
-        // check aliases
-        let mut aliases_path = path.clone();
-        aliases_path.set_extension("aliases");
-
-        let aliases = if aliases_path.exists() {
-            let reader = BufReader::new(File::open(&aliases_path).with_context(|| {
-                format!(
-                    "Cannot open aliases file in keystore: {}",
-                    aliases_path.display()
-                )
-            })?);
-
-            let aliases: Vec<Alias> = serde_json::from_reader(reader).with_context(|| {
-                format!(
-                    "Cannot deserialize aliases file in keystore: {}",
-                    aliases_path.display(),
-                )
-            })?;
-
-            aliases
-                .into_iter()
-                .map(|alias| {
-                    let key = PublicKey::decode_base64(&alias.public_key_base64);
-                    key.map(|k| (Into::<SuiAddress>::into(&k), alias))
-                })
-                .collect::<Result<BTreeMap<_, _>, _>>()
-                .map_err(|e| {
-                    anyhow!(
-                        "Invalid aliases file in keystore: {}. {}",
-                        aliases_path.display(),
-                        e
-                    )
-                })?
-        } else if keys.is_empty() {
-            BTreeMap::new()
-        } else {
-            let names: Vec<String> = random_names(HashSet::new(), keys.len());
-            let aliases = keys
-                .iter()
-                .zip(names)
-                .map(|((sui_address, skp), alias)| {
-                    let public_key_base64 = skp.public().encode_base64();
-                    (
-                        *sui_address,
-                        Alias {
-                            alias,
-                            public_key_base64,
-                        },
-                    )
-                })
-                .collect::<BTreeMap<_, _>>();
-            let aliases_store = serde_json::to_string_pretty(&aliases.values().collect::<Vec<_>>())
-                .with_context(|| {
-                    format!(
-                        "Cannot serialize aliases to file in keystore: {}",
-                        aliases_path.display()
-                    )
-                })?;
-            fs::write(aliases_path, aliases_store)?;
-            aliases
-        };
+        let public_keys = keys
+            .iter()
+            .map(|(address, key)| (*address, key.public()))
+            .collect();
+        let aliases = load_aliases(path, &public_keys)?;
 
         Ok(Self {
             keys,
@@ -399,24 +404,17 @@ impl FileBasedKeystore {
         })
     }
 
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
     pub fn set_path(&mut self, path: &Path) {
         self.path = Some(path.to_path_buf());
     }
 
     pub fn save_aliases(&self) -> Result<(), anyhow::Error> {
         if let Some(path) = &self.path {
-            let aliases_store =
-                serde_json::to_string_pretty(&self.aliases.values().collect::<Vec<_>>())
-                    .with_context(|| {
-                        format!(
-                            "Cannot serialize aliases to file in keystore: {}",
-                            path.display()
-                        )
-                    })?;
-
-            let mut aliases_path = path.clone();
-            aliases_path.set_extension("aliases");
-            fs::write(aliases_path, aliases_store)?
+            save_aliases(path, &self.aliases)?;
         }
         Ok(())
     }
@@ -602,7 +600,89 @@ impl InMemKeystore {
     }
 }
 
-fn validate_alias(alias: &str) -> Result<String, anyhow::Error> {
+/// The path of the aliases file that goes with the keystore file at `keystore_path`.
+pub(crate) fn aliases_path(keystore_path: &Path) -> PathBuf {
+    let mut aliases_path = keystore_path.to_path_buf();
+    aliases_path.set_extension("aliases");
+    aliases_path
+}
+
+/// Read the aliases of the keys in the keystore file at `keystore_path`. If there is no aliases
+/// file yet, random aliases are generated for the keys and saved.
+pub(crate) fn load_aliases(
+    keystore_path: &Path,
+    public_keys: &BTreeMap<SuiAddress, PublicKey>,
+) -> Result<BTreeMap<SuiAddress, Alias>, anyhow::Error> {
+    let aliases_path = aliases_path(keystore_path);
+
+    if aliases_path.exists() {
+        let reader = BufReader::new(File::open(&aliases_path).with_context(|| {
+            format!(
+                "Cannot open aliases file in keystore: {}",
+                aliases_path.display()
+            )
+        })?);
+
+        let aliases: Vec<Alias> = serde_json::from_reader(reader).with_context(|| {
+            format!(
+                "Cannot deserialize aliases file in keystore: {}",
+                aliases_path.display(),
+            )
+        })?;
+
+        aliases
+            .into_iter()
+            .map(|alias| {
+                let key = PublicKey::decode_base64(&alias.public_key_base64);
+                key.map(|k| (Into::<SuiAddress>::into(&k), alias))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| {
+                anyhow!(
+                    "Invalid aliases file in keystore: {}. {}",
+                    aliases_path.display(),
+                    e
+                )
+            })
+    } else if public_keys.is_empty() {
+        Ok(BTreeMap::new())
+    } else {
+        let names: Vec<String> = random_names(HashSet::new(), public_keys.len());
+        let aliases = public_keys
+            .iter()
+            .zip(names)
+            .map(|((sui_address, public_key), alias)| {
+                let public_key_base64 = public_key.encode_base64();
+                (
+                    *sui_address,
+                    Alias {
+                        alias,
+                        public_key_base64,
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        save_aliases(keystore_path, &aliases)?;
+        Ok(aliases)
+    }
+}
+
+pub(crate) fn save_aliases(
+    keystore_path: &Path,
+    aliases: &BTreeMap<SuiAddress, Alias>,
+) -> Result<(), anyhow::Error> {
+    let aliases_store = serde_json::to_string_pretty(&aliases.values().collect::<Vec<_>>())
+        .with_context(|| {
+            format!(
+                "Cannot serialize aliases to file in keystore: {}",
+                keystore_path.display()
+            )
+        })?;
+    fs::write(aliases_path(keystore_path), aliases_store)?;
+    Ok(())
+}
+
+pub(crate) fn validate_alias(alias: &str) -> Result<String, anyhow::Error> {
     let re = Regex::new(r"^[A-Za-z][A-Za-z0-9-_\.]*$")
         .map_err(|_| anyhow!("Cannot build the regex needed to validate the alias naming"))?;
     let alias = alias.trim();
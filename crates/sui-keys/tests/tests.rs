@@ -3,14 +3,20 @@
 
 use std::fs;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use fastcrypto::hash::HashFunction;
 use fastcrypto::traits::EncodeDecodeBase64;
 use sui_keys::key_derive::generate_new_key;
 use tempfile::TempDir;
 
+use sui_keys::encrypted_keystore::{EncryptedFileKeystore, KdfParams, Zeroizing};
+#[cfg(unix)]
+use sui_keys::external_keystore::SignerMethod;
+use sui_keys::external_keystore::{ExternalKeystore, ExternalSignerConfig};
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
-use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignatureInner};
+use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignature, SuiSignatureInner};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -266,3 +272,291 @@ fn get_alias_by_address_test() {
     let address = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
     assert!(keystore.get_alias_by_address(&address.0).is_err())
 }
+
+/// Cheap key derivation parameters, to keep tests fast.
+const TEST_KDF_PARAMS: KdfParams = KdfParams {
+    memory_kib: 8,
+    iterations: 1,
+    parallelism: 1,
+};
+
+fn open_encrypted_keystore(
+    path: &std::path::Path,
+    passphrase: &'static str,
+    prompts: Arc<AtomicUsize>,
+) -> EncryptedFileKeystore {
+    EncryptedFileKeystore::new_with_passphrase(
+        path,
+        Arc::new(move || {
+            prompts.fetch_add(1, Ordering::Relaxed);
+            Ok(Zeroizing::new(passphrase.to_string()))
+        }),
+    )
+    .unwrap()
+}
+
+#[test]
+fn encrypted_keystore_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let prompts = Arc::new(AtomicUsize::new(0));
+    let mut keystore = Keystore::from(
+        open_encrypted_keystore(&keystore_path, "correct horse", prompts.clone())
+            .with_kdf_params(TEST_KDF_PARAMS)
+            .unwrap(),
+    );
+    let (address, _, _) = keystore
+        .generate_and_add_new_key(
+            SignatureScheme::ED25519,
+            Some("alice".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+    let signature = keystore.sign_hashed(&address, b"hello").unwrap();
+
+    // Private keys are not stored in plaintext.
+    let contents = fs::read_to_string(&keystore_path).unwrap();
+    let private_key = keystore.get_key(&address).unwrap().encode_base64();
+    assert!(!contents.contains(&private_key));
+    assert!(Keystore::open(&keystore_path)
+        .unwrap()
+        .to_string()
+        .contains("Encrypted"));
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+
+    // Addresses and aliases are available without the passphrase.
+    let prompts = Arc::new(AtomicUsize::new(0));
+    let keystore = open_encrypted_keystore(&keystore_path, "correct horse", prompts.clone());
+    assert_eq!(keystore.addresses(), vec![address]);
+    assert_eq!(keystore.get_alias_by_address(&address).unwrap(), "alice");
+    assert!(!keystore.is_unlocked());
+    assert_eq!(prompts.load(Ordering::Relaxed), 0);
+
+    // The passphrase is asked for once, when a key is first needed.
+    assert_eq!(keystore.sign_hashed(&address, b"hello").unwrap(), signature);
+    assert_eq!(keystore.sign_hashed(&address, b"hello").unwrap(), signature);
+    assert_eq!(prompts.load(Ordering::Relaxed), 1);
+
+    let keystore = open_encrypted_keystore(&keystore_path, "wrong", Arc::default());
+    assert!(keystore.sign_hashed(&address, b"hello").is_err());
+    assert!(keystore.get_key(&address).is_err());
+}
+
+#[test]
+fn empty_encrypted_keystore_checks_passphrase_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    open_encrypted_keystore(&keystore_path, "correct horse", Arc::default())
+        .with_kdf_params(TEST_KDF_PARAMS)
+        .unwrap()
+        .save()
+        .unwrap();
+
+    // The keystore has no keys to check the passphrase against, but it is still rejected.
+    let (_, keypair, _, _) = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
+    let mut keystore = open_encrypted_keystore(&keystore_path, "wrong", Arc::default());
+    assert!(keystore.unlock().is_err());
+    assert!(keystore.add_key(None, keypair.copy()).is_err());
+    assert!(keystore.addresses().is_empty());
+
+    let mut keystore = open_encrypted_keystore(&keystore_path, "correct horse", Arc::default());
+    keystore.add_key(None, keypair).unwrap();
+    assert_eq!(keystore.addresses().len(), 1);
+}
+
+#[test]
+fn encrypt_file_keystore_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = FileBasedKeystore::new(&keystore_path).unwrap();
+    let (address, _, _) = keystore
+        .generate_and_add_new_key(
+            SignatureScheme::ED25519,
+            Some("bob".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+    let private_key = keystore.get_key(&address).unwrap().encode_base64();
+
+    EncryptedFileKeystore::encrypt_file_keystore(
+        &keystore_path,
+        Zeroizing::new("passphrase".to_string()),
+        TEST_KDF_PARAMS,
+    )
+    .unwrap();
+    assert!(!fs::read_to_string(&keystore_path)
+        .unwrap()
+        .contains(&private_key));
+
+    let keystore = open_encrypted_keystore(&keystore_path, "passphrase", Arc::default());
+    assert_eq!(keystore.get_alias_by_address(&address).unwrap(), "bob");
+    assert_eq!(
+        keystore.get_key(&address).unwrap().encode_base64(),
+        private_key
+    );
+
+    // Encrypting twice is an error.
+    assert!(EncryptedFileKeystore::encrypt_file_keystore(
+        &keystore_path,
+        Zeroizing::new("passphrase".to_string()),
+        TEST_KDF_PARAMS,
+    )
+    .is_err());
+}
+
+/// Answers an external signer request with the keys of `keystore`.
+#[cfg(unix)]
+fn handle_signer_request(
+    keystore: &InMemKeystore,
+    method: SignerMethod,
+) -> Result<serde_json::Value, String> {
+    use fastcrypto::encoding::{Base64, Encoding};
+    use sui_keys::external_keystore::{KeysResult, SignatureResult, SignerKey};
+    use sui_types::crypto::Signature;
+
+    let result = match method {
+        SignerMethod::Keys => serde_json::to_value(KeysResult {
+            keys: keystore
+                .keys()
+                .iter()
+                .map(|public_key| SignerKey {
+                    public_key: public_key.encode_base64(),
+                    alias: keystore.get_alias_by_address(&public_key.into()).ok(),
+                })
+                .collect(),
+        }),
+        SignerMethod::SignHashed { address, msg } => {
+            let msg = Base64::decode(&msg).map_err(|e| e.to_string())?;
+            let signature = keystore
+                .sign_hashed(&address, &msg)
+                .map_err(|e| e.to_string())?;
+            serde_json::to_value(SignatureResult { signature })
+        }
+        SignerMethod::SignSecure {
+            address,
+            intent_message,
+        } => {
+            let intent_message = Base64::decode(&intent_message).map_err(|e| e.to_string())?;
+            let key = keystore.get_key(&address).map_err(|e| e.to_string())?;
+            let digest = DefaultHash::digest(&intent_message).digest;
+            let signature = Signature::new_hashed(&digest, key);
+            serde_json::to_value(SignatureResult { signature })
+        }
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Starts a fake external signer holding the keys of `InMemKeystore::new_insecure_for_tests`.
+///
+/// The signer process only relays its stdin and stdout through two fifos in `dir`, to a thread of
+/// the test that answers the requests.
+#[cfg(unix)]
+fn fake_external_signer(dir: &std::path::Path, num_keys: usize) -> ExternalSignerConfig {
+    use std::io::{BufRead, BufReader, Write};
+    use sui_keys::external_keystore::{SignerRequest, SignerResponse};
+
+    let requests = dir.join("requests");
+    let responses = dir.join("responses");
+    for fifo in [&requests, &responses] {
+        let status = std::process::Command::new("mkfifo")
+            .arg(fifo)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    let (requests_path, responses_path) = (requests.clone(), responses.clone());
+    std::thread::spawn(move || {
+        let keystore = InMemKeystore::new_insecure_for_tests(num_keys);
+        let requests = BufReader::new(fs::File::open(requests_path).unwrap());
+        let mut responses = fs::OpenOptions::new()
+            .write(true)
+            .open(responses_path)
+            .unwrap();
+        // Returns once the signer closes the requests fifo, which closes the responses fifo in
+        // turn and lets the signer exit.
+        for line in requests.lines() {
+            let request: SignerRequest = serde_json::from_str(&line.unwrap()).unwrap();
+            let (result, error) = match handle_signer_request(&keystore, request.method) {
+                Ok(result) => (Some(result), None),
+                Err(error) => (None, Some(error)),
+            };
+            let response = SignerResponse {
+                id: request.id,
+                result,
+                error,
+            };
+            serde_json::to_writer(&mut responses, &response).unwrap();
+            writeln!(responses).unwrap();
+            responses.flush().unwrap();
+        }
+    });
+
+    ExternalSignerConfig {
+        command: "sh".into(),
+        // Asynchronous commands read from /dev/null unless redirected explicitly.
+        args: vec![
+            "-c".to_string(),
+            r#"exec 3<&0; cat <&3 >"$0" & exec cat <"$1""#.to_string(),
+            requests.display().to_string(),
+            responses.display().to_string(),
+        ],
+        timeout_secs: None,
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn external_keystore_test() {
+    use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut keystore =
+        Keystore::from(ExternalKeystore::new(fake_external_signer(temp_dir.path(), 2)).unwrap());
+    assert_eq!(keystore.keys().len(), 2);
+    assert_eq!(keystore.aliases().len(), 2);
+
+    let address = keystore.addresses()[0];
+    let msg = PersonalMessage {
+        message: b"hello".to_vec(),
+    };
+    let signature = keystore
+        .sign_secure(&address, &msg, Intent::personal_message())
+        .unwrap();
+    signature
+        .verify_secure(
+            &IntentMessage::new(Intent::personal_message(), msg),
+            address,
+            SignatureScheme::ED25519,
+        )
+        .unwrap();
+
+    // Private keys never leave the signer.
+    assert!(keystore.get_key(&address).is_err());
+    let (_, keypair, _, _) = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
+    assert!(keystore.add_key(None, keypair).is_err());
+
+    // Signatures of raw messages are verified against the key of the address.
+    keystore.sign_hashed(&address, b"hello").unwrap();
+
+    // The signer can only sign for its own keys.
+    assert!(keystore
+        .sign_hashed(&SuiAddress::random_for_testing_only(), b"hello")
+        .is_err());
+}
+
+#[test]
+#[cfg(unix)]
+fn external_keystore_timeout_test() {
+    // A signer that never answers.
+    let err = ExternalKeystore::new(ExternalSignerConfig {
+        command: "sleep".into(),
+        args: vec!["10".to_string()],
+        timeout_secs: Some(1),
+    })
+    .err()
+    .unwrap();
+    assert!(err.to_string().contains("did not answer"), "{err}");
+}
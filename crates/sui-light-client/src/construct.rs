@@ -45,7 +45,37 @@ pub fn construct_proof(targets: ProofTarget, data: &CheckpointData) -> anyhow::R
         .events
         .iter()
         .map(|(eid, _)| eid.tx_digest);
-    let mut all_tx = object_tx.chain(event_tx);
+
+    // Removed and replaced objects are identified by the version they had before the
+    // transaction, so find the transaction in the checkpoint that consumed that version.
+    let consumed_tx = this_proof
+        .targets
+        .removed_objects
+        .iter()
+        .map(|(object_ref, _)| object_ref)
+        .chain(
+            this_proof
+                .targets
+                .replaced_objects
+                .iter()
+                .map(|(old, _)| old),
+        )
+        .map(|object_ref| {
+            data.transactions
+                .iter()
+                .find(|t| {
+                    t.effects
+                        .modified_at_versions()
+                        .contains(&(object_ref.0, object_ref.1))
+                })
+                .map(|t| *t.effects.transaction_digest())
+                .ok_or(anyhow!(
+                    "No transaction consumed the object in checkpoint data"
+                ))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut all_tx = object_tx.chain(event_tx).chain(consumed_tx);
 
     // Get the first tx ID
     let target_tx_id = if let Some(first_tx) = all_tx.next() {
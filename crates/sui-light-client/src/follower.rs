@@ -0,0 +1,515 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A long-running checkpoint follower.
+//!
+//! The follower starts from a trusted root (a genesis committee or a trusted end-of-epoch
+//! checkpoint), then walks every subsequent checkpoint in order. Each checkpoint is verified
+//! against the committee of its epoch and must link to its predecessor by digest. When an
+//! end-of-epoch checkpoint is reached the next committee is extracted from it and appended to a
+//! verified committee chain, which is persisted to a local directory so that following can resume
+//! after a restart without re-walking history.
+//!
+//! Once a checkpoint has been followed, objects and events from it can be served as verified
+//! lookups: the follower fetches the full checkpoint data again, constructs a proof for the
+//! requested targets and checks it with `verify_proof` before returning anything.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, ensure};
+use async_trait::async_trait;
+use object_store::path::Path as ObjectPath;
+use object_store::{parse_url, ObjectStore};
+use serde::{Deserialize, Serialize};
+use sui_rpc_api::CheckpointData;
+use sui_types::{
+    base_types::ObjectID,
+    committee::{Committee, EpochId},
+    digests::{CheckpointDigest, TransactionDigest},
+    effects::{TransactionEffectsAPI, TransactionEvents},
+    event::EventID,
+    messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber, EndOfEpochData},
+    object::Object,
+};
+use url::Url;
+
+use crate::construct::construct_proof;
+use crate::proof::{verify_proof, ProofTarget};
+
+/// Name of the file, inside the store directory, holding the follower state.
+const FOLLOWER_STATE_FILE: &str = "follower_state.bcs";
+
+/// A source of full checkpoint data, indexed by sequence number.
+#[async_trait]
+pub trait CheckpointSource: Send + Sync {
+    /// Fetch the full checkpoint with sequence number `seq`. Returns `Ok(None)` if the checkpoint
+    /// is not available (yet), and `Err` if it exists but could not be read.
+    async fn get_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<CheckpointData>>;
+}
+
+/// Reads checkpoints from a local directory of `<seq>.chk` files, in the format written by the
+/// ingestion pipeline (and by `simulacrum` when a data ingestion path is set).
+pub struct LocalCheckpointSource {
+    dir: PathBuf,
+}
+
+impl LocalCheckpointSource {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl CheckpointSource for LocalCheckpointSource {
+    async fn get_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<CheckpointData>> {
+        let path = self.dir.join(format!("{}.chk", seq));
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        decode_checkpoint(&bytes).map(Some)
+    }
+}
+
+/// Reads checkpoints from a remote object store of `<seq>.chk` blobs, such as the public
+/// checkpoint buckets.
+pub struct ObjectStoreCheckpointSource {
+    store: Box<dyn ObjectStore>,
+}
+
+impl ObjectStoreCheckpointSource {
+    pub fn new(url: &str) -> anyhow::Result<Self> {
+        let url = Url::parse(url).map_err(|_| anyhow!("Cannot parse object store URL"))?;
+        let (store, _) = parse_url(&url)?;
+        Ok(Self { store })
+    }
+}
+
+#[async_trait]
+impl CheckpointSource for ObjectStoreCheckpointSource {
+    async fn get_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<CheckpointData>> {
+        let path = ObjectPath::from(format!("{}.chk", seq));
+        let response = match self.store.get(&path).await {
+            Ok(response) => response,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let bytes = response.bytes().await?;
+        decode_checkpoint(&bytes).map(Some)
+    }
+}
+
+fn decode_checkpoint(bytes: &[u8]) -> anyhow::Result<CheckpointData> {
+    let (_, data) = bcs::from_bytes::<(u8, CheckpointData)>(bytes)
+        .map_err(|e| anyhow!("Unable to parse checkpoint file: {e}"))?;
+    Ok(data)
+}
+
+/// The root of trust the follower starts from.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum TrustRoot {
+    /// The committee of the first epoch, as taken from a trusted genesis. Following starts from
+    /// checkpoint 0.
+    Genesis(Committee),
+
+    /// An end-of-epoch checkpoint that is trusted without verification. Following starts from
+    /// the checkpoint after it, using the next epoch committee it declares.
+    Checkpoint(CertifiedCheckpointSummary),
+}
+
+impl TrustRoot {
+    fn committee(&self) -> anyhow::Result<Committee> {
+        match self {
+            TrustRoot::Genesis(committee) => Ok(committee.clone()),
+            TrustRoot::Checkpoint(summary) => next_committee(summary),
+        }
+    }
+
+    /// Whether `self` and `other` designate the same root of trust.
+    fn same_as(&self, other: &TrustRoot) -> bool {
+        match (self, other) {
+            (TrustRoot::Genesis(a), TrustRoot::Genesis(b)) => a == b,
+            (TrustRoot::Checkpoint(a), TrustRoot::Checkpoint(b)) => a.digest() == b.digest(),
+            _ => false,
+        }
+    }
+}
+
+/// Extract the next epoch committee from an end-of-epoch checkpoint summary.
+fn next_committee(summary: &CertifiedCheckpointSummary) -> anyhow::Result<Committee> {
+    let Some(EndOfEpochData {
+        next_epoch_committee,
+        ..
+    }) = &summary.end_of_epoch_data
+    else {
+        bail!(
+            "Checkpoint {} is not an end-of-epoch checkpoint",
+            summary.sequence_number
+        );
+    };
+
+    let epoch = summary
+        .epoch()
+        .checked_add(1)
+        .ok_or(anyhow!("Epoch overflow"))?;
+    Ok(Committee::new(
+        epoch,
+        next_epoch_committee.iter().cloned().collect(),
+    ))
+}
+
+/// A chain of committees, each certified by the end-of-epoch checkpoint of the epoch before it,
+/// back to a trusted root.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommitteeChain {
+    root: TrustRoot,
+
+    /// Verified end-of-epoch checkpoints, in epoch order, following the root.
+    end_of_epoch_checkpoints: Vec<CertifiedCheckpointSummary>,
+
+    /// Committees derived from the root and the end-of-epoch checkpoints, in epoch order.
+    #[serde(skip)]
+    committees: Vec<Committee>,
+}
+
+impl CommitteeChain {
+    /// Create a chain holding only the committee of the trusted root.
+    pub fn new(root: TrustRoot) -> anyhow::Result<Self> {
+        let committee = root.committee()?;
+        Ok(Self {
+            root,
+            end_of_epoch_checkpoints: vec![],
+            committees: vec![committee],
+        })
+    }
+
+    /// The trusted root this chain was started from.
+    pub fn root(&self) -> &TrustRoot {
+        &self.root
+    }
+
+    /// The latest verified committee.
+    pub fn current_committee(&self) -> &Committee {
+        self.committees
+            .last()
+            .expect("A committee chain always holds the root committee")
+    }
+
+    /// The verified committee for `epoch`, if it is covered by this chain.
+    pub fn committee(&self, epoch: EpochId) -> Option<&Committee> {
+        let first = self.committees.first()?.epoch;
+        let index = epoch.checked_sub(first)?;
+        self.committees.get(index as usize)
+    }
+
+    /// The verified end-of-epoch checkpoints that extend the chain past its root.
+    pub fn end_of_epoch_checkpoints(&self) -> &[CertifiedCheckpointSummary] {
+        &self.end_of_epoch_checkpoints
+    }
+
+    /// Verify `summary` as the end-of-epoch checkpoint of the current epoch, and extend the chain
+    /// with the committee it certifies.
+    pub fn append(&mut self, summary: CertifiedCheckpointSummary) -> anyhow::Result<()> {
+        let committee = self.current_committee();
+        ensure!(
+            summary.epoch() == committee.epoch,
+            "Checkpoint {} is from epoch {}, expected epoch {}",
+            summary.sequence_number,
+            summary.epoch(),
+            committee.epoch
+        );
+        summary.verify_authority_signatures(committee)?;
+
+        let next = next_committee(&summary)?;
+        self.end_of_epoch_checkpoints.push(summary);
+        self.committees.push(next);
+        Ok(())
+    }
+
+    /// Rebuild the derived committees from the root, re-verifying every end-of-epoch checkpoint.
+    /// Used after loading a chain from disk, so that a tampered store is never trusted.
+    fn reverify(self) -> anyhow::Result<Self> {
+        let mut chain = Self::new(self.root)?;
+        for summary in self.end_of_epoch_checkpoints {
+            chain.append(summary)?;
+        }
+        Ok(chain)
+    }
+}
+
+/// The persisted state of a follower.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FollowerState {
+    chain: CommitteeChain,
+
+    /// Sequence number of the next checkpoint to follow.
+    next_checkpoint: CheckpointSequenceNumber,
+
+    /// Digest of the last followed checkpoint, that the next one must link to.
+    last_digest: Option<CheckpointDigest>,
+}
+
+/// Local directory store for the follower state.
+pub struct FollowerStore {
+    dir: PathBuf,
+}
+
+impl FollowerStore {
+    pub fn new(dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    fn load(&self) -> anyhow::Result<Option<FollowerState>> {
+        let path = self.dir.join(FOLLOWER_STATE_FILE);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let state: FollowerState =
+            bcs::from_bytes(&bytes).map_err(|e| anyhow!("Unable to parse follower state: {e}"))?;
+        let chain = state.chain.reverify()?;
+        Ok(Some(FollowerState { chain, ..state }))
+    }
+
+    fn save(&self, state: &FollowerState) -> anyhow::Result<()> {
+        // Write to a temporary file first and rename it into place, so that a crash mid-write
+        // never leaves a truncated state behind.
+        let path = self.dir.join(FOLLOWER_STATE_FILE);
+        let tmp = self.dir.join(format!("{FOLLOWER_STATE_FILE}.tmp"));
+        let bytes =
+            bcs::to_bytes(state).map_err(|_| anyhow!("Unable to serialize follower state"))?;
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+/// Follows checkpoints from a [`CheckpointSource`], maintaining a persisted, verified committee
+/// chain and serving verified object and event lookups for followed checkpoints.
+pub struct CheckpointFollower<S> {
+    source: S,
+    store: FollowerStore,
+    state: FollowerState,
+}
+
+impl<S: CheckpointSource> CheckpointFollower<S> {
+    /// Open a follower backed by `store`. If the store already holds state, following resumes
+    /// from it (after checking that it was started from `root`, and re-verifying the stored
+    /// committee chain); otherwise it starts from `root`.
+    pub fn new(source: S, store: FollowerStore, root: TrustRoot) -> anyhow::Result<Self> {
+        let state = match store.load()? {
+            Some(state) => {
+                ensure!(
+                    state.chain.root().same_as(&root),
+                    "Follower state in {} was started from a different trust root",
+                    store.path().display()
+                );
+                state
+            }
+            None => {
+                let (next_checkpoint, last_digest) = match &root {
+                    TrustRoot::Genesis(_) => (0, None),
+                    TrustRoot::Checkpoint(summary) => {
+                        (summary.sequence_number + 1, Some(*summary.digest()))
+                    }
+                };
+                let state = FollowerState {
+                    chain: CommitteeChain::new(root)?,
+                    next_checkpoint,
+                    last_digest,
+                };
+                store.save(&state)?;
+                state
+            }
+        };
+
+        Ok(Self {
+            source,
+            store,
+            state,
+        })
+    }
+
+    /// The verified committee chain so far.
+    pub fn committee_chain(&self) -> &CommitteeChain {
+        &self.state.chain
+    }
+
+    /// The committee of the epoch currently being followed.
+    pub fn current_committee(&self) -> &Committee {
+        self.state.chain.current_committee()
+    }
+
+    /// The sequence number of the next checkpoint to be followed. All checkpoints below it have
+    /// been verified.
+    pub fn next_checkpoint(&self) -> CheckpointSequenceNumber {
+        self.state.next_checkpoint
+    }
+
+    /// Follow a single checkpoint. Returns its verified summary, or `None` if the next checkpoint
+    /// is not available from the source yet.
+    pub async fn step(&mut self) -> anyhow::Result<Option<CertifiedCheckpointSummary>> {
+        let seq = self.state.next_checkpoint;
+        let Some(data) = self.source.get_checkpoint(seq).await? else {
+            return Ok(None);
+        };
+
+        let summary = data.checkpoint_summary;
+        ensure!(
+            summary.sequence_number == seq,
+            "Expected checkpoint {seq}, got {}",
+            summary.sequence_number
+        );
+        ensure!(
+            summary.previous_digest == self.state.last_digest,
+            "Checkpoint {seq} does not link to the previously followed checkpoint"
+        );
+
+        let committee = self.state.chain.current_committee();
+        summary.verify_with_contents(committee, Some(&data.checkpoint_contents))?;
+
+        if summary.end_of_epoch_data.is_some() {
+            self.state.chain.append(summary.clone())?;
+        }
+
+        self.state.next_checkpoint = seq + 1;
+        self.state.last_digest = Some(*summary.digest());
+        self.store.save(&self.state)?;
+
+        Ok(Some(summary))
+    }
+
+    /// Follow checkpoints until every checkpoint up to and including `seq` has been verified,
+    /// or until the source runs out of checkpoints. Returns the next checkpoint to be followed.
+    pub async fn follow_to(
+        &mut self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CheckpointSequenceNumber> {
+        while self.state.next_checkpoint <= seq {
+            if self.step().await?.is_none() {
+                break;
+            }
+        }
+        Ok(self.state.next_checkpoint)
+    }
+
+    /// Follow checkpoints forever, waiting `poll_interval` whenever the source has no new
+    /// checkpoint. Only returns on error.
+    pub async fn run(&mut self, poll_interval: Duration) -> anyhow::Result<()> {
+        loop {
+            if self.step().await?.is_none() {
+                tokio::time::sleep(poll_interval).await;
+            }
+        }
+    }
+
+    /// Fetch checkpoint `seq` from the source, making sure it has already been followed, and
+    /// return it with the verified committee of its epoch.
+    async fn followed_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<(CheckpointData, &Committee)> {
+        ensure!(
+            seq < self.state.next_checkpoint,
+            "Checkpoint {seq} has not been followed yet"
+        );
+        let data = self
+            .source
+            .get_checkpoint(seq)
+            .await?
+            .ok_or(anyhow!("Checkpoint {seq} not found"))?;
+        let committee = self
+            .state
+            .chain
+            .committee(data.checkpoint_summary.epoch())
+            .ok_or(anyhow!(
+                "No verified committee for epoch {}",
+                data.checkpoint_summary.epoch()
+            ))?;
+        Ok((data, committee))
+    }
+
+    /// Return the version of object `id` written in checkpoint `seq`, verified through a proof
+    /// against the committee chain.
+    pub async fn get_verified_object(
+        &self,
+        seq: CheckpointSequenceNumber,
+        id: ObjectID,
+    ) -> anyhow::Result<Object> {
+        let (data, committee) = self.followed_checkpoint(seq).await?;
+
+        // The latest write of the object in this checkpoint
+        let object = data
+            .transactions
+            .iter()
+            .rev()
+            .flat_map(|tx| tx.output_objects.iter())
+            .find(|object| object.id() == id)
+            .ok_or(anyhow!("Object {id} not written in checkpoint {seq}"))?
+            .clone();
+
+        let targets = ProofTarget::new().add_object(object.compute_object_reference(), object);
+        let proof = construct_proof(targets, &data)?;
+        verify_proof(committee, &proof)?;
+
+        let (_, object) = proof.targets.objects.into_iter().next().unwrap();
+        Ok(object)
+    }
+
+    /// Return the events emitted by transaction `digest` in checkpoint `seq`, verified through a
+    /// proof against the committee chain.
+    pub async fn get_verified_events(
+        &self,
+        seq: CheckpointSequenceNumber,
+        digest: TransactionDigest,
+    ) -> anyhow::Result<Option<TransactionEvents>> {
+        let (data, committee) = self.followed_checkpoint(seq).await?;
+
+        let tx = data
+            .transactions
+            .iter()
+            .find(|tx| tx.effects.transaction_digest() == &digest)
+            .ok_or(anyhow!(
+                "Transaction {digest} not found in checkpoint {seq}"
+            ))?;
+
+        let Some(events) = tx.events.clone() else {
+            return Ok(None);
+        };
+        if events.data.is_empty() {
+            return Ok(None);
+        }
+
+        let targets =
+            events
+                .data
+                .iter()
+                .enumerate()
+                .fold(ProofTarget::new(), |targets, (i, event)| {
+                    targets.add_event(EventID::from((digest, i as u64)), event.clone())
+                });
+        let proof = construct_proof(targets, &data)?;
+        verify_proof(committee, &proof)?;
+
+        Ok(proof.contents_proof.and_then(|contents| contents.events))
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod construct;
+pub mod follower;
 pub mod proof;
 
 #[doc(inline)]
@@ -6,6 +6,9 @@ use async_trait::async_trait;
 use move_core_types::account_address::AccountAddress;
 use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionBlockResponseOptions};
 
+use sui_light_client::follower::{
+    CheckpointFollower, FollowerStore, ObjectStoreCheckpointSource, TrustRoot,
+};
 use sui_rpc_api::CheckpointData;
 use sui_types::{
     base_types::ObjectID,
@@ -26,7 +29,7 @@ use sui_sdk::SuiClientBuilder;
 
 use clap::{Parser, Subcommand};
 use std::{collections::HashMap, fs, io::Write, path::PathBuf, str::FromStr, sync::Mutex};
-use std::{io::Read, sync::Arc};
+use std::{io::Read, sync::Arc, time::Duration};
 
 use log::info;
 use object_store::parse_url;
@@ -89,6 +92,13 @@ enum SCommands {
     /// Sync all end-of-epoch checkpoints
     Sync {},
 
+    /// Continuously follow and verify checkpoints, starting from genesis
+    Follow {
+        /// Seconds to wait between polls when no new checkpoint is available
+        #[arg(short, long, default_value_t = 5)]
+        poll_interval: u64,
+    },
+
     /// Checks a specific transaction using the light client
     Transaction {
         /// Transaction hash
@@ -526,6 +536,42 @@ async fn get_verified_object(config: &Config, id: ObjectID) -> anyhow::Result<Ob
     Ok(object)
 }
 
+async fn follow_checkpoints(config: &Config, poll_interval: Duration) -> anyhow::Result<()> {
+    let mut genesis_path = config.checkpoint_summary_dir.clone();
+    genesis_path.push(&config.genesis_filename);
+    let genesis_committee = Genesis::load(&genesis_path)?
+        .committee()
+        .map_err(|e| anyhow!(format!("Cannot load Genesis: {e}")))?;
+
+    let mut store_path = config.checkpoint_summary_dir.clone();
+    store_path.push("follower");
+
+    let source = ObjectStoreCheckpointSource::new(&config.object_store_url)?;
+    let store = FollowerStore::new(store_path)?;
+    let mut follower =
+        CheckpointFollower::new(source, store, TrustRoot::Genesis(genesis_committee))?;
+
+    println!(
+        "Following from checkpoint {} in epoch {}",
+        follower.next_checkpoint(),
+        follower.current_committee().epoch
+    );
+
+    loop {
+        match follower.step().await? {
+            Some(summary) if summary.end_of_epoch_data.is_some() => {
+                println!(
+                    "Epoch: {} Checkpoint ID: {}",
+                    summary.epoch(),
+                    summary.digest()
+                );
+            }
+            Some(_) => {}
+            None => tokio::time::sleep(poll_interval).await,
+        }
+    }
+}
+
 #[tokio::main]
 pub async fn main() {
     env_logger::init();
@@ -621,6 +667,12 @@ pub async fn main() {
                 .await
                 .expect("Failed to sync checkpoints");
         }
+
+        Some(SCommands::Follow { poll_interval }) => {
+            follow_checkpoints(&config, Duration::from_secs(poll_interval))
+                .await
+                .expect("Failed to follow checkpoints");
+        }
         _ => {}
     }
 }
@@ -7,13 +7,23 @@ use serde::{Deserialize, Serialize};
 use sui_types::{
     base_types::ObjectRef,
     committee::Committee,
-    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    effects::{ObjectRemoveKind, TransactionEffects, TransactionEffectsAPI, TransactionEvents},
     event::{Event, EventID},
     messages_checkpoint::{CertifiedCheckpointSummary, CheckpointContents, EndOfEpochData},
     object::Object,
     transaction::Transaction,
 };
 
+/// How an object was removed from the live object set by a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObjectRemoval {
+    /// The object was deleted.
+    Deleted,
+
+    /// The object was wrapped into another object.
+    Wrapped,
+}
+
 /// Define aspect of Sui state that need to be certified in a proof
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub struct ProofTarget {
@@ -23,6 +33,14 @@ pub struct ProofTarget {
     /// Events that need to be certified.
     pub events: Vec<(EventID, Event)>,
 
+    /// Objects that need to be certified as removed, by the reference they had before removal
+    /// and the kind of removal.
+    pub removed_objects: Vec<(ObjectRef, ObjectRemoval)>,
+
+    /// Object references that need to be certified as replaced by a newer version, as pairs of
+    /// the old and the new reference.
+    pub replaced_objects: Vec<(ObjectRef, ObjectRef)>,
+
     /// The next committee being certified.
     pub committee: Option<Committee>,
 }
@@ -49,6 +67,21 @@ impl ProofTarget {
         self
     }
 
+    /// Add an object to be certified as deleted or wrapped, by the reference it had right before
+    /// the transaction that removed it. A verified proof will ensure that the transaction consumed
+    /// exactly this version of the object and did not leave it in the live object set.
+    pub fn add_removed_object(mut self, object_ref: ObjectRef, removal: ObjectRemoval) -> Self {
+        self.removed_objects.push((object_ref, removal));
+        self
+    }
+
+    /// Add an object reference to be certified as replaced by a newer version. A verified proof
+    /// will ensure that the transaction consumed `old_ref` and wrote `new_ref` in its place.
+    pub fn add_replaced_object(mut self, old_ref: ObjectRef, new_ref: ObjectRef) -> Self {
+        self.replaced_objects.push((old_ref, new_ref));
+        self
+    }
+
     /// Add the next committee to be certified. A verified proof will ensure that the next
     /// committee is correct.
     pub fn set_committee(mut self, committee: Committee) -> Self {
@@ -144,7 +177,10 @@ pub fn verify_proof(committee: &Committee, proof: &Proof) -> anyhow::Result<()>
     // Non empty object or event targets require the optional contents proof
     // If it is not present return an error
 
-    if (!proof.targets.objects.is_empty() || !proof.targets.events.is_empty())
+    if (!proof.targets.objects.is_empty()
+        || !proof.targets.events.is_empty()
+        || !proof.targets.removed_objects.is_empty()
+        || !proof.targets.replaced_objects.is_empty())
         && proof.contents_proof.is_none()
     {
         return Err(anyhow!("Contents proof is missing"));
@@ -227,6 +263,67 @@ pub fn verify_proof(committee: &Committee, proof: &Proof) -> anyhow::Result<()>
         }
 
         // MILESTONE 7: Object references are correct and in the effects
+
+        // Removed and replaced objects must have been inputs of the transaction at exactly the
+        // version given in the target.
+        let modified_at_versions = contents_proof.effects.modified_at_versions();
+        let check_consumed = |object_ref: &ObjectRef| -> anyhow::Result<()> {
+            if !modified_at_versions.contains(&(object_ref.0, object_ref.1)) {
+                return Err(anyhow!("Object version not consumed by the transaction"));
+            }
+            // Effects v1 do not record the digest of input objects, so only the ID and
+            // version can be checked against them.
+            if let TransactionEffects::V2(_) = &contents_proof.effects {
+                if !contents_proof
+                    .effects
+                    .old_object_metadata()
+                    .iter()
+                    .any(|(old_ref, _)| old_ref == object_ref)
+                {
+                    return Err(anyhow!("Object digest does not match the consumed object"));
+                }
+            }
+            Ok(())
+        };
+
+        let removed_objects = contents_proof.effects.all_removed_objects();
+        for (object_ref, removal) in &proof.targets.removed_objects {
+            check_consumed(object_ref)?;
+
+            // Was it removed in the requested way?
+            let removed = removed_objects.iter().any(|(removed_ref, kind)| {
+                removed_ref.0 == object_ref.0
+                    && matches!(
+                        (kind, removal),
+                        (ObjectRemoveKind::Delete, ObjectRemoval::Deleted)
+                            | (ObjectRemoveKind::Wrap, ObjectRemoval::Wrapped)
+                    )
+            });
+            if !removed {
+                return Err(anyhow!("Object not removed by the transaction"));
+            }
+        }
+
+        // MILESTONE 8: Removed objects were consumed and removed by the transaction
+
+        for (old_ref, new_ref) in &proof.targets.replaced_objects {
+            if old_ref.0 != new_ref.0 || old_ref.1 >= new_ref.1 {
+                return Err(anyhow!(
+                    "New reference is not a later version of the object"
+                ));
+            }
+
+            check_consumed(old_ref)?;
+
+            if !changed_objects
+                .iter()
+                .any(|(effects_object_ref, _, _)| effects_object_ref == new_ref)
+            {
+                return Err(anyhow!("New object reference not found"));
+            }
+        }
+
+        // MILESTONE 9: Replaced objects were consumed and written at the new version
     }
 
     Ok(())
@@ -4,11 +4,20 @@
 use anyhow::anyhow;
 
 use sui_light_client::construct::construct_proof;
-use sui_light_client::proof::{verify_proof, Proof, ProofTarget};
+use sui_light_client::proof::{verify_proof, ObjectRemoval, Proof, ProofTarget};
 
+use simulacrum::Simulacrum;
 use sui_types::event::{Event, EventID};
 
-use sui_types::{committee::Committee, effects::TransactionEffectsAPI, object::Object};
+use sui_types::{
+    base_types::ObjectRef,
+    committee::Committee,
+    effects::TransactionEffectsAPI,
+    object::Object,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    storage::ReadStore,
+    transaction::{Command, ObjectArg, Transaction, TransactionData},
+};
 
 use sui_rpc_api::CheckpointData;
 
@@ -271,3 +280,132 @@ async fn test_event_target_fail_bad_event() {
 
     assert!(verify_proof(&committee, &event_proof).is_err());
 }
+
+/// Merge one of the sender's coins into another in a simulacrum. Returns the checkpoint the merge
+/// was included in, the references of the destination coin before and after the merge, and the
+/// reference of the merged (deleted) coin before the merge.
+fn merge_coins_checkpoint() -> (Committee, CheckpointData, ObjectRef, ObjectRef, ObjectRef) {
+    let mut sim = Simulacrum::new();
+    let (sender, key) = sim.keystore().accounts().next().unwrap();
+    let sender = *sender;
+
+    let coins: Vec<_> = sim
+        .store()
+        .owned_objects(sender)
+        .filter(|object| object.is_gas_coin())
+        .map(|object| object.compute_object_reference())
+        .collect();
+    let (gas, target, merged) = (coins[0], coins[1], coins[2]);
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let target_arg = builder.obj(ObjectArg::ImmOrOwnedObject(target)).unwrap();
+        let merged_arg = builder.obj(ObjectArg::ImmOrOwnedObject(merged)).unwrap();
+        builder.command(Command::MergeCoins(target_arg, vec![merged_arg]));
+        builder.finish()
+    };
+    let tx_data = TransactionData::new_programmable(
+        sender,
+        vec![gas],
+        pt,
+        1_000_000_000,
+        sim.reference_gas_price(),
+    );
+    let tx = Transaction::from_data_and_signer(tx_data, vec![key]);
+
+    let effects = sim.execute_transaction(tx).unwrap().0;
+    assert!(effects.status().is_ok());
+    let new_target = effects
+        .mutated()
+        .into_iter()
+        .find(|(object_ref, _)| object_ref.0 == target.0)
+        .unwrap()
+        .0;
+
+    let checkpoint = sim.create_checkpoint();
+    let contents = sim
+        .store()
+        .get_checkpoint_contents(&checkpoint.content_digest)
+        .unwrap();
+    let committee = sim.store().get_committee_by_epoch(0).unwrap();
+    let data = sim.get_checkpoint_data(checkpoint, contents).unwrap();
+
+    (committee, data, target, new_target, merged)
+}
+
+#[tokio::test]
+async fn test_removed_object_success() {
+    let (committee, data, _, _, merged) = merge_coins_checkpoint();
+
+    let target = ProofTarget::new().add_removed_object(merged, ObjectRemoval::Deleted);
+    let removal_proof = construct_proof(target, &data).unwrap();
+
+    assert!(verify_proof(&committee, &removal_proof).is_ok());
+}
+
+#[tokio::test]
+async fn test_removed_object_fail_wrong_kind() {
+    let (committee, data, _, _, merged) = merge_coins_checkpoint();
+
+    let target = ProofTarget::new().add_removed_object(merged, ObjectRemoval::Wrapped); // WRONG
+    let removal_proof = construct_proof(target, &data).unwrap();
+
+    assert!(verify_proof(&committee, &removal_proof).is_err());
+}
+
+#[tokio::test]
+async fn test_removed_object_fail_not_removed() {
+    let (committee, data, target_ref, _, _) = merge_coins_checkpoint();
+
+    // The destination coin was consumed, but it still exists
+    let target = ProofTarget::new().add_removed_object(target_ref, ObjectRemoval::Deleted); // WRONG
+    let removal_proof = construct_proof(target, &data).unwrap();
+
+    assert!(verify_proof(&committee, &removal_proof).is_err());
+}
+
+#[tokio::test]
+async fn test_removed_object_fail_wrong_version() {
+    let (committee, data, _, _, merged) = merge_coins_checkpoint();
+
+    let target = ProofTarget::new().add_removed_object(merged, ObjectRemoval::Deleted);
+    let mut removal_proof = construct_proof(target, &data).unwrap();
+
+    // No transaction consumed this version
+    let mut wrong_ref = merged;
+    wrong_ref.1 = wrong_ref.1.next(); // WRONG
+    assert!(construct_proof(
+        ProofTarget::new().add_removed_object(wrong_ref, ObjectRemoval::Deleted),
+        &data
+    )
+    .is_err());
+
+    removal_proof.targets.removed_objects[0].0 = wrong_ref;
+    assert!(verify_proof(&committee, &removal_proof).is_err());
+}
+
+#[tokio::test]
+async fn test_replaced_object_success() {
+    let (committee, data, old_ref, new_ref, _) = merge_coins_checkpoint();
+
+    let target = ProofTarget::new().add_replaced_object(old_ref, new_ref);
+    let replace_proof = construct_proof(target, &data).unwrap();
+
+    assert!(verify_proof(&committee, &replace_proof).is_ok());
+}
+
+#[tokio::test]
+async fn test_replaced_object_fail_wrong_new_ref() {
+    let (committee, data, old_ref, mut new_ref, merged) = merge_coins_checkpoint();
+
+    // The merged coin was not replaced by the destination coin
+    let target = ProofTarget::new().add_replaced_object(merged, new_ref); // WRONG
+    let replace_proof = construct_proof(target, &data).unwrap();
+    assert!(verify_proof(&committee, &replace_proof).is_err());
+
+    // The new version does not exist
+    new_ref.1 = new_ref.1.next(); // WRONG
+    let target = ProofTarget::new().add_replaced_object(old_ref, new_ref);
+    let replace_proof = construct_proof(target, &data).unwrap();
+    assert!(verify_proof(&committee, &replace_proof).is_err());
+}
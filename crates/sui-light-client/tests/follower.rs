@@ -0,0 +1,251 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use simulacrum::Simulacrum;
+use sui_light_client::follower::{
+    CheckpointFollower, FollowerStore, LocalCheckpointSource, TrustRoot,
+};
+use sui_types::{base_types::SuiAddress, effects::TransactionEffectsAPI, gas_coin::GasCoin};
+use tempfile::tempdir;
+
+/// Run a simulacrum through a few epochs, writing its checkpoints to `dir`, and return it
+/// together with the address that received a transfer in the last epoch.
+fn simulate(dir: &std::path::Path, epochs: u64) -> (Simulacrum, SuiAddress) {
+    let mut sim = Simulacrum::new();
+    sim.set_data_ingestion_path(dir.to_path_buf());
+
+    for _ in 0..epochs {
+        sim.create_checkpoint();
+        sim.advance_epoch(/* create_random_state */ false);
+    }
+
+    let recipient = SuiAddress::random_for_testing_only();
+    let (tx, _) = sim.transfer_txn(recipient);
+    sim.execute_transaction(tx).unwrap();
+    sim.create_checkpoint();
+
+    (sim, recipient)
+}
+
+fn genesis_root(sim: &Simulacrum) -> TrustRoot {
+    TrustRoot::Genesis(sim.store().get_committee_by_epoch(0).unwrap())
+}
+
+#[tokio::test]
+async fn test_follow_committee_chain() {
+    let data = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    let (sim, _) = simulate(data.path(), 3);
+    let latest = sim.store().get_highest_checkpint().unwrap();
+
+    let mut follower = CheckpointFollower::new(
+        LocalCheckpointSource::new(data.path()),
+        FollowerStore::new(state.path()).unwrap(),
+        genesis_root(&sim),
+    )
+    .unwrap();
+
+    let next = follower.follow_to(u64::MAX).await.unwrap();
+    assert_eq!(next, latest.sequence_number + 1);
+    assert_eq!(follower.current_committee().epoch, 3);
+    assert_eq!(
+        follower.committee_chain().end_of_epoch_checkpoints().len(),
+        3
+    );
+    for epoch in 0..=3 {
+        assert_eq!(
+            follower.committee_chain().committee(epoch).unwrap(),
+            &sim.store().get_committee_by_epoch(epoch).unwrap()
+        );
+    }
+
+    // Nothing more to follow
+    assert!(follower.step().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_follow_resumes_from_store() {
+    let data = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    let (sim, _) = simulate(data.path(), 2);
+
+    let mut follower = CheckpointFollower::new(
+        LocalCheckpointSource::new(data.path()),
+        FollowerStore::new(state.path()).unwrap(),
+        genesis_root(&sim),
+    )
+    .unwrap();
+    let next = follower.follow_to(u64::MAX).await.unwrap();
+    drop(follower);
+
+    let follower = CheckpointFollower::new(
+        LocalCheckpointSource::new(data.path()),
+        FollowerStore::new(state.path()).unwrap(),
+        genesis_root(&sim),
+    )
+    .unwrap();
+    assert_eq!(follower.next_checkpoint(), next);
+    assert_eq!(follower.current_committee().epoch, 2);
+}
+
+#[tokio::test]
+async fn test_follow_rejects_state_from_another_network() {
+    let data = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    let (sim, _) = simulate(data.path(), 1);
+
+    // Follow an unrelated network into a separate store
+    let other_data = tempdir().unwrap();
+    let other_state = tempdir().unwrap();
+    let (other_sim, _) = simulate(other_data.path(), 1);
+    let mut other = CheckpointFollower::new(
+        LocalCheckpointSource::new(other_data.path()),
+        FollowerStore::new(other_state.path()).unwrap(),
+        genesis_root(&other_sim),
+    )
+    .unwrap();
+    other.follow_to(u64::MAX).await.unwrap();
+    drop(other);
+
+    // Swap its (internally consistent) state in for this network's
+    std::fs::copy(
+        other_state.path().join("follower_state.bcs"),
+        state.path().join("follower_state.bcs"),
+    )
+    .unwrap();
+
+    assert!(CheckpointFollower::new(
+        LocalCheckpointSource::new(data.path()),
+        FollowerStore::new(state.path()).unwrap(),
+        genesis_root(&sim),
+    )
+    .is_err());
+}
+
+#[tokio::test]
+async fn test_follow_from_trusted_checkpoint() {
+    let data = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    let (sim, _) = simulate(data.path(), 3);
+
+    // Trust the end of epoch 1, and follow from there
+    let trusted = sim
+        .store()
+        .get_checkpoint_by_sequence_number(
+            (0..)
+                .find(|seq| {
+                    let summary = sim.store().get_checkpoint_by_sequence_number(*seq).unwrap();
+                    summary.epoch() == 1 && summary.end_of_epoch_data.is_some()
+                })
+                .unwrap(),
+        )
+        .unwrap();
+
+    let mut follower = CheckpointFollower::new(
+        LocalCheckpointSource::new(data.path()),
+        FollowerStore::new(state.path()).unwrap(),
+        TrustRoot::Checkpoint(trusted.into_inner()),
+    )
+    .unwrap();
+
+    follower.follow_to(u64::MAX).await.unwrap();
+    assert_eq!(follower.current_committee().epoch, 3);
+    assert!(follower.committee_chain().committee(1).is_none());
+}
+
+#[tokio::test]
+async fn test_follow_rejects_wrong_genesis() {
+    let data = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    simulate(data.path(), 1);
+
+    // A committee from an unrelated chain cannot verify these checkpoints
+    let other = Simulacrum::new();
+    let mut follower = CheckpointFollower::new(
+        LocalCheckpointSource::new(data.path()),
+        FollowerStore::new(state.path()).unwrap(),
+        TrustRoot::Genesis(other.store().get_committee_by_epoch(0).unwrap()),
+    )
+    .unwrap();
+
+    assert!(follower.step().await.is_err());
+    assert_eq!(follower.next_checkpoint(), 0);
+}
+
+#[tokio::test]
+async fn test_verified_lookups() {
+    let data = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    let (sim, recipient) = simulate(data.path(), 1);
+    let latest = sim.store().get_highest_checkpint().unwrap();
+
+    let mut follower = CheckpointFollower::new(
+        LocalCheckpointSource::new(data.path()),
+        FollowerStore::new(state.path()).unwrap(),
+        genesis_root(&sim),
+    )
+    .unwrap();
+
+    let coin = sim.store().owned_objects(recipient).next().unwrap();
+
+    // Lookups are only served for followed checkpoints
+    assert!(follower
+        .get_verified_object(latest.sequence_number, coin.id())
+        .await
+        .is_err());
+
+    follower.follow_to(latest.sequence_number).await.unwrap();
+
+    let object = follower
+        .get_verified_object(latest.sequence_number, coin.id())
+        .await
+        .unwrap();
+    assert_eq!(
+        object.compute_object_reference(),
+        coin.compute_object_reference()
+    );
+    assert!(GasCoin::try_from(&object).is_ok());
+
+    // A plain transfer emits no events
+    let tx = sim
+        .store()
+        .get_checkpoint_contents(&latest.content_digest)
+        .unwrap()
+        .iter()
+        .next()
+        .unwrap()
+        .transaction;
+    let effects = sim.store().get_transaction_effects(&tx).unwrap();
+    assert!(effects.events_digest().is_none());
+    let events = follower
+        .get_verified_events(latest.sequence_number, tx)
+        .await
+        .unwrap();
+    assert!(events.is_none());
+
+    // The change epoch transaction at the end of epoch 0 emits the epoch's system events
+    let end_of_epoch = (0..)
+        .map(|seq| sim.store().get_checkpoint_by_sequence_number(seq).unwrap())
+        .find(|summary| summary.end_of_epoch_data.is_some())
+        .unwrap();
+    let tx = sim
+        .store()
+        .get_checkpoint_contents(&end_of_epoch.content_digest)
+        .unwrap()
+        .iter()
+        .last()
+        .unwrap()
+        .transaction;
+    let expected = sim
+        .store()
+        .get_transaction_events_by_tx_digest(&tx)
+        .unwrap();
+    assert!(!expected.data.is_empty());
+
+    let events = follower
+        .get_verified_events(end_of_epoch.sequence_number, tx)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(events.digest(), expected.digest());
+}
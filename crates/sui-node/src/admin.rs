@@ -9,13 +9,17 @@ use axum::{
     Router,
 };
 use base64::Engine;
-use humantime::parse_duration;
+use humantime::{format_duration, parse_duration};
 use serde::Deserialize;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     str::FromStr,
 };
+use sui_config::transaction_deny_config::TransactionDenyConfig;
+use sui_core::traffic_controller::blocklist_store::{from_unix_ms, BlocklistEntry};
+use sui_core::traffic_controller::TrafficController;
 use sui_types::{
     base_types::AuthorityName,
     crypto::{RandomnessPartialSignature, RandomnessRound, RandomnessSignature},
@@ -68,6 +72,23 @@ use tracing::info;
 // Inject a full signature from another node, bypassing validity checks.
 //
 //  $ curl 'http://127.0.0.1:1337/randomness-inject-full-sig?round=123&sigs=base64encodedsig'
+//
+// Reload the transaction deny rules from the rules file of the transaction deny config.
+//
+//  $ curl -X POST 'http://127.0.0.1:1337/reload-transaction-rules'
+//
+// List the clients currently blocked by the traffic controller, with reason and expiry.
+//
+//  $ curl 'http://127.0.0.1:1337/traffic-control/blocklist'
+//
+// Block a client for one hour. Set `proxied=true` to block a proxied client IP instead
+// of a connection IP. Policies blocking the same client later can only extend the block.
+//
+//  $ curl -X POST 'http://127.0.0.1:1337/traffic-control/block?ip=1.2.3.4&ttl=1h&reason=abuse'
+//
+// Lift any block on a client, whether issued by a policy or manually.
+//
+//  $ curl -X POST 'http://127.0.0.1:1337/traffic-control/unblock?ip=1.2.3.4'
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -80,6 +101,10 @@ const NODE_CONFIG: &str = "/node-config";
 const RANDOMNESS_PARTIAL_SIGS_ROUTE: &str = "/randomness-partial-sigs";
 const RANDOMNESS_INJECT_PARTIAL_SIGS_ROUTE: &str = "/randomness-inject-partial-sigs";
 const RANDOMNESS_INJECT_FULL_SIG_ROUTE: &str = "/randomness-inject-full-sig";
+const RELOAD_TRANSACTION_RULES_ROUTE: &str = "/reload-transaction-rules";
+const TRAFFIC_CONTROL_BLOCKLIST_ROUTE: &str = "/traffic-control/blocklist";
+const TRAFFIC_CONTROL_BLOCK_ROUTE: &str = "/traffic-control/block";
+const TRAFFIC_CONTROL_UNBLOCK_ROUTE: &str = "/traffic-control/unblock";
 
 struct AppState {
     node: Arc<SuiNode>,
@@ -119,6 +144,16 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
             RANDOMNESS_INJECT_FULL_SIG_ROUTE,
             post(randomness_inject_full_sig),
         )
+        .route(
+            RELOAD_TRANSACTION_RULES_ROUTE,
+            post(reload_transaction_rules),
+        )
+        .route(
+            TRAFFIC_CONTROL_BLOCKLIST_ROUTE,
+            get(traffic_control_blocklist),
+        )
+        .route(TRAFFIC_CONTROL_BLOCK_ROUTE, post(traffic_control_block))
+        .route(TRAFFIC_CONTROL_UNBLOCK_ROUTE, post(traffic_control_unblock))
         .with_state(Arc::new(app_state));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -235,6 +270,150 @@ async fn set_filter(
     }
 }
 
+async fn reload_transaction_rules(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    reload_rules(&state.node.state().config.transaction_deny_config)
+}
+
+fn reload_rules(deny_config: &TransactionDenyConfig) -> (StatusCode, String) {
+    match deny_config.reload_rules() {
+        Ok(count) => {
+            info!(count, "Transaction rules reloaded");
+            (
+                StatusCode::OK,
+                format!("{count} transaction rules in effect\n"),
+            )
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:?}\n")),
+    }
+}
+
+fn format_blocklist_entry(entry: &BlocklistEntry) -> String {
+    let expires_in = from_unix_ms(entry.expires_at_ms)
+        .duration_since(SystemTime::now())
+        .unwrap_or_default();
+    format!(
+        "{} proxied={} reason={:?} expires_at_ms={} expires_in={}\n",
+        entry.ip,
+        entry.proxied,
+        entry.reason,
+        entry.expires_at_ms,
+        format_duration(Duration::from_secs(expires_in.as_secs())),
+    )
+}
+
+fn traffic_control_disabled() -> (StatusCode, String) {
+    (
+        StatusCode::NOT_FOUND,
+        "traffic control is not enabled\n".to_string(),
+    )
+}
+
+async fn traffic_control_blocklist(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match state.node.traffic_controller() {
+        Some(traffic_controller) => list_blocks(&traffic_controller),
+        None => traffic_control_disabled(),
+    }
+}
+
+fn list_blocks(traffic_controller: &TrafficController) -> (StatusCode, String) {
+    let output: String = traffic_controller
+        .list_blocks()
+        .iter()
+        .map(format_blocklist_entry)
+        .collect();
+    (StatusCode::OK, output)
+}
+
+#[derive(Deserialize)]
+struct BlockClient {
+    ip: String,
+    ttl: String,
+    reason: Option<String>,
+    #[serde(default)]
+    proxied: bool,
+}
+
+async fn traffic_control_block(
+    State(state): State<Arc<AppState>>,
+    Query(args): Query<BlockClient>,
+) -> (StatusCode, String) {
+    match state.node.traffic_controller() {
+        Some(traffic_controller) => block_client(&traffic_controller, args).await,
+        None => traffic_control_disabled(),
+    }
+}
+
+async fn block_client(
+    traffic_controller: &TrafficController,
+    args: BlockClient,
+) -> (StatusCode, String) {
+    let BlockClient {
+        ip,
+        ttl,
+        reason,
+        proxied,
+    } = args;
+
+    let ip = match IpAddr::from_str(&ip) {
+        Ok(ip) => ip,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()),
+    };
+    let ttl = match parse_duration(&ttl) {
+        Ok(ttl) => ttl,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()),
+    };
+    let reason = reason.unwrap_or_else(|| "manual".to_string());
+
+    match traffic_controller.block(ip, proxied, ttl, reason).await {
+        Ok(entry) => (
+            StatusCode::OK,
+            format!("blocked {}", format_blocklist_entry(&entry)),
+        ),
+        Err(err) => (StatusCode::BAD_REQUEST, format!("{err}\n")),
+    }
+}
+
+#[derive(Deserialize)]
+struct UnblockClient {
+    ip: String,
+}
+
+async fn traffic_control_unblock(
+    State(state): State<Arc<AppState>>,
+    Query(args): Query<UnblockClient>,
+) -> (StatusCode, String) {
+    match state.node.traffic_controller() {
+        Some(traffic_controller) => unblock_client(&traffic_controller, args).await,
+        None => traffic_control_disabled(),
+    }
+}
+
+async fn unblock_client(
+    traffic_controller: &TrafficController,
+    args: UnblockClient,
+) -> (StatusCode, String) {
+    let UnblockClient { ip } = args;
+
+    let ip = match IpAddr::from_str(&ip) {
+        Ok(ip) => ip,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()),
+    };
+
+    match traffic_controller.unblock(ip).await {
+        Ok(unblocked) if unblocked.is_empty() => {
+            (StatusCode::NOT_FOUND, format!("{ip} is not blocked\n"))
+        }
+        Ok(unblocked) => (
+            StatusCode::OK,
+            unblocked
+                .iter()
+                .map(|entry| format!("unblocked {}", format_blocklist_entry(entry)))
+                .collect(),
+        ),
+        Err(err) => (StatusCode::BAD_REQUEST, format!("{err}\n")),
+    }
+}
+
 async fn capabilities(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
     let epoch_store = state.node.state().load_epoch_store_one_call_per_task();
 
@@ -443,3 +622,98 @@ async fn randomness_inject_full_sig(
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_config::transaction_deny_config::{
+        RuleAction, TransactionDenyConfigBuilder, TransactionRule,
+    };
+    use sui_types::traffic_control::PolicyConfig;
+
+    #[test]
+    fn reload_transaction_rules_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rules.yaml");
+        std::fs::write(&path, "- name: deny-all\n  action: deny\n").unwrap();
+        let deny_config = TransactionDenyConfigBuilder::new()
+            .rules_file(path.clone())
+            .build();
+
+        let (status, body) = reload_rules(&deny_config);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "1 transaction rules in effect\n");
+        assert_eq!(
+            *deny_config.rules(),
+            vec![TransactionRule::new("deny-all", RuleAction::Deny)]
+        );
+
+        std::fs::write(&path, "[]\n").unwrap();
+        let (status, body) = reload_rules(&deny_config);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "0 transaction rules in effect\n");
+        assert!(deny_config.rules().is_empty());
+
+        // A broken file is reported, and the rules in effect are kept.
+        std::fs::write(&path, "- name: deny-all\n  action: block\n").unwrap();
+        let (status, body) = reload_rules(&deny_config);
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(body.contains("Failed to parse transaction rules"), "{body}");
+        assert!(deny_config.rules().is_empty());
+    }
+
+    #[tokio::test]
+    async fn traffic_control_block_and_unblock() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy_config = PolicyConfig {
+            dry_run: false,
+            blocklist_persistence_path: Some(dir.path().join("blocklist.json")),
+            ..Default::default()
+        };
+        let traffic_controller = TrafficController::init_for_test(policy_config.clone(), None);
+        let client: IpAddr = "10.0.0.1".parse().unwrap();
+        let block = |ip: &str, ttl: &str| BlockClient {
+            ip: ip.to_string(),
+            ttl: ttl.to_string(),
+            reason: None,
+            proxied: false,
+        };
+
+        let (status, _) = block_client(&traffic_controller, block("10.0.0", "1h")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = block_client(&traffic_controller, block("10.0.0.1", "soon")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            list_blocks(&traffic_controller),
+            (StatusCode::OK, String::new())
+        );
+
+        let (status, body) = block_client(&traffic_controller, block("10.0.0.1", "1h")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(
+            body.starts_with("blocked 10.0.0.1 proxied=false reason=\"manual\""),
+            "{body}"
+        );
+        assert!(!traffic_controller.check(&Some(client), &None).await);
+        let (status, body) = list_blocks(&traffic_controller);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.lines().count(), 1);
+
+        // The block is persisted by the time the handler returns
+        let restarted = TrafficController::init_for_test(policy_config.clone(), None);
+        assert!(!restarted.check(&Some(client), &None).await);
+
+        let unblock = || UnblockClient {
+            ip: "10.0.0.1".to_string(),
+        };
+        let (status, body) = unblock_client(&traffic_controller, unblock()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.starts_with("unblocked 10.0.0.1"), "{body}");
+        assert!(traffic_controller.check(&Some(client), &None).await);
+        let (status, _) = unblock_client(&traffic_controller, unblock()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let restarted = TrafficController::init_for_test(policy_config, None);
+        assert!(restarted.check(&Some(client), &None).await);
+    }
+}
@@ -35,6 +35,7 @@ use sui_core::execution_cache::build_execution_cache;
 use sui_core::state_accumulator::StateAccumulatorMetrics;
 use sui_core::storage::RestReadStore;
 use sui_core::traffic_controller::metrics::TrafficControllerMetrics;
+use sui_core::traffic_controller::TrafficController;
 use sui_json_rpc::bridge_api::BridgeReadApi;
 use sui_json_rpc_api::JsonRpcMetrics;
 use sui_network::randomness;
@@ -264,6 +265,11 @@ pub struct SuiNode {
     // TODO: Eventually we can make this auth aggregator a shared reference so that this
     // update will automatically propagate to other uses.
     auth_agg: Arc<ArcSwap<AuthorityAggregator<NetworkAuthorityClient>>>,
+
+    /// Traffic controller shared by the validator gRPC service or the fullnode
+    /// JSON-RPC server, kept here so that it can be managed through the admin
+    /// interface. Set only if a policy config is provided.
+    traffic_controller: Option<Arc<TrafficController>>,
 }
 
 impl fmt::Debug for SuiNode {
@@ -435,6 +441,11 @@ impl SuiNode {
             );
             config.supported_protocol_versions = Some(SupportedProtocolVersions::SYSTEM_DEFAULT);
         }
+        let rule_count = config
+            .transaction_deny_config
+            .reload_rules()
+            .map_err(|err| anyhow!("Invalid transaction rules: {err:?}"))?;
+        info!("{rule_count} transaction rules in effect");
 
         let run_with_range = config.run_with_range;
         let is_validator = config.consensus_config().is_some();
@@ -474,6 +485,9 @@ impl SuiNode {
         let is_genesis = perpetual_tables
             .database_is_empty()
             .expect("Database read should not fail at init.");
+        // Migrations can rewrite whole tables, so they run off the async runtime.
+        let tables = perpetual_tables.clone();
+        tokio::task::spawn_blocking(move || tables.run_migrations(is_genesis)).await??;
 
         let store =
             AuthorityStore::open(perpetual_tables, &genesis, &config, &prometheus_registry).await?;
@@ -762,6 +776,16 @@ impl SuiNode {
             None
         };
 
+        // Created once for the lifetime of the node, so that blocks are kept
+        // when the validator gRPC service is restarted across epochs.
+        let traffic_controller = config.policy_config.clone().map(|policy| {
+            Arc::new(TrafficController::init(
+                policy,
+                TrafficControllerMetrics::new(&prometheus_registry),
+                config.firewall_config.clone(),
+            ))
+        });
+
         let http_server = build_http_server(
             state.clone(),
             state_sync_store,
@@ -770,6 +794,7 @@ impl SuiNode {
             &prometheus_registry,
             custom_rpc_runtime,
             software_version,
+            traffic_controller.clone(),
         )
         .await?;
 
@@ -817,6 +842,7 @@ impl SuiNode {
                 connection_monitor_status.clone(),
                 &registry_service,
                 sui_node_metrics.clone(),
+                traffic_controller.clone(),
             )
             .await?;
             // This is only needed during cold start.
@@ -859,6 +885,8 @@ impl SuiNode {
             shutdown_channel_tx: shutdown_channel,
 
             auth_agg,
+
+            traffic_controller,
         };
 
         info!("SuiNode started!");
@@ -1195,6 +1223,7 @@ impl SuiNode {
         connection_monitor_status: Arc<ConnectionMonitorStatus>,
         registry_service: &RegistryService,
         sui_node_metrics: Arc<SuiNodeMetrics>,
+        traffic_controller: Option<Arc<TrafficController>>,
     ) -> Result<ValidatorComponents> {
         let mut config_clone = config.clone();
         let consensus_config = config_clone
@@ -1231,6 +1260,7 @@ impl SuiNode {
             &config,
             state.clone(),
             consensus_adapter.clone(),
+            traffic_controller,
             &registry_service.default_registry(),
         )
         .await?;
@@ -1460,15 +1490,18 @@ impl SuiNode {
         config: &NodeConfig,
         state: Arc<AuthorityState>,
         consensus_adapter: Arc<ConsensusAdapter>,
+        traffic_controller: Option<Arc<TrafficController>>,
         prometheus_registry: &Registry,
     ) -> Result<tokio::task::JoinHandle<Result<()>>> {
-        let validator_service = ValidatorService::new(
+        let validator_service = ValidatorService::new_with_traffic_controller(
             state.clone(),
             consensus_adapter,
             Arc::new(ValidatorServiceMetrics::new(prometheus_registry)),
-            TrafficControllerMetrics::new(prometheus_registry),
-            config.policy_config.clone(),
-            config.firewall_config.clone(),
+            traffic_controller,
+            config
+                .policy_config
+                .as_ref()
+                .map(|policy| policy.client_id_source.clone()),
         );
 
         let mut server_conf = mysten_network::config::Config::new();
@@ -1499,6 +1532,10 @@ impl SuiNode {
         self.state.clone()
     }
 
+    pub fn traffic_controller(&self) -> Option<Arc<TrafficController>> {
+        self.traffic_controller.clone()
+    }
+
     // Only used for testing because of how epoch store is loaded.
     pub fn reference_gas_price_for_testing(&self) -> Result<u64, anyhow::Error> {
         self.state.reference_gas_price_for_testing()
@@ -1806,6 +1843,7 @@ impl SuiNode {
                             self.connection_monitor_status.clone(),
                             &self.registry_service,
                             self.metrics.clone(),
+                            self.traffic_controller.clone(),
                         )
                         .await?,
                     )
@@ -2007,6 +2045,7 @@ pub async fn build_http_server(
     prometheus_registry: &Registry,
     _custom_runtime: Option<Handle>,
     software_version: &'static str,
+    traffic_controller: Option<Arc<TrafficController>>,
 ) -> Result<Option<tokio::task::JoinHandle<()>>> {
     // Validators do not expose these APIs
     if config.consensus_config().is_some() {
@@ -2022,6 +2061,9 @@ pub async fn build_http_server(
             config.policy_config.clone(),
             config.firewall_config.clone(),
         );
+        if let Some(traffic_controller) = traffic_controller {
+            server.with_traffic_controller(traffic_controller);
+        }
 
         let kv_store = build_kv_store(&state, config, prometheus_registry)?;
 
@@ -0,0 +1,397 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reading checkpoints from `sui-archival` archives on local disk.
+//!
+//! Archives only hold the transactions and effects of each checkpoint, not the objects they read
+//! and wrote. Those are rebuilt by re-executing every archived transaction in order, starting from
+//! genesis, so the checkpoints read from an archive look the same as the ones written by data
+//! ingestion.
+
+use crate::types::ReplayEngineError;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use sui_archival::{
+    read_manifest_from_bytes, FileMetadata, FileType, Manifest, CHECKPOINT_FILE_MAGIC,
+    MANIFEST_FILENAME, SUMMARY_FILE_MAGIC,
+};
+use sui_execution::Executor;
+use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
+use sui_storage::make_iterator;
+use sui_storage::object_store::util::path_to_filesystem;
+use sui_types::base_types::ObjectID;
+use sui_types::committee::EpochId;
+use sui_types::digests::{ChainIdentifier, TransactionDigest};
+use sui_types::effects::{InputSharedObject, TransactionEffects, TransactionEffectsAPI};
+use sui_types::full_checkpoint_content::{CheckpointData, CheckpointTransaction};
+use sui_types::gas::SuiGasStatus;
+use sui_types::in_memory_storage::InMemoryStorage;
+use sui_types::message_envelope::Message;
+use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, FullCheckpointContents};
+use sui_types::metrics::LimitsMetrics;
+use sui_types::object::Object;
+use sui_types::sui_system_state::{get_sui_system_state, SuiSystemState, SuiSystemStateTrait};
+use sui_types::transaction::{
+    CheckedInputObjects, GenesisObject, InputObjectKind, InputObjects, ObjectReadResult,
+    ObjectReadResultKind, Transaction, TransactionData, TransactionDataAPI, TransactionKind,
+};
+
+/// A checkpoint as it is stored in an archive.
+type ArchivedCheckpoint = (CertifiedCheckpointSummary, FullCheckpointContents);
+
+/// Read the checkpoints of the archive in the directory at `path`, in sequence number order.
+///
+/// The archive files are read one checkpoint at a time, as the returned iterator advances. Every
+/// transaction is re-executed as it is read, and its effects must match the archived ones.
+pub(crate) fn read_archive(
+    path: &Path,
+) -> Result<impl Iterator<Item = Result<CheckpointData, ReplayEngineError>>, ReplayEngineError> {
+    let manifest = std::fs::read(path.join(MANIFEST_FILENAME))
+        .map_err(anyhow::Error::from)
+        .and_then(read_manifest_from_bytes)
+        .map_err(|e| read_error(path, e))?;
+    let files = checkpoint_files(&manifest).map_err(|e| read_error(path, e))?;
+
+    let path = path.to_path_buf();
+    let mut executor = ArchiveExecutor::default();
+    Ok(files
+        .into_iter()
+        .flat_map(move |(summary_file, contents_file)| {
+            match read_checkpoint_files(&path, &summary_file, &contents_file) {
+                Ok(checkpoints) => Box::new(checkpoints)
+                    as Box<dyn Iterator<Item = Result<ArchivedCheckpoint, ReplayEngineError>>>,
+                Err(e) => Box::new(std::iter::once(Err(e))),
+            }
+        })
+        .map(move |checkpoint| {
+            let (summary, contents) = checkpoint?;
+            executor.execute_checkpoint(summary, contents)
+        }))
+}
+
+fn read_error(path: &Path, err: impl Display) -> ReplayEngineError {
+    ReplayEngineError::UnableToReadCheckpoints {
+        path: path.display().to_string(),
+        err: err.to_string(),
+    }
+}
+
+/// The summary and contents files of the archive, paired up and in checkpoint order. The files
+/// must cover every checkpoint from genesis onwards, without gaps.
+fn checkpoint_files(manifest: &Manifest) -> anyhow::Result<Vec<(FileMetadata, FileMetadata)>> {
+    let (mut summary_files, mut contents_files): (Vec<_>, Vec<_>) = manifest
+        .files()
+        .into_iter()
+        .partition(|file| file.file_type == FileType::CheckpointSummary);
+    summary_files.sort_by_key(|file| file.checkpoint_seq_range.start);
+    contents_files.sort_by_key(|file| file.checkpoint_seq_range.start);
+    anyhow::ensure!(
+        summary_files.len() == contents_files.len(),
+        "{} summary files, but {} contents files",
+        summary_files.len(),
+        contents_files.len()
+    );
+
+    let mut next_checkpoint = 0;
+    for (summary_file, contents_file) in summary_files.iter().zip(&contents_files) {
+        let range = &summary_file.checkpoint_seq_range;
+        anyhow::ensure!(
+            *range == contents_file.checkpoint_seq_range,
+            "summary file for checkpoints {range:?} has contents file for checkpoints {:?}",
+            contents_file.checkpoint_seq_range
+        );
+        anyhow::ensure!(
+            range.start == next_checkpoint,
+            "expected a file starting at checkpoint {next_checkpoint}, found checkpoints {range:?}"
+        );
+        next_checkpoint = range.end;
+    }
+    Ok(summary_files.into_iter().zip(contents_files).collect())
+}
+
+/// Iterate over the checkpoints in one pair of summary and contents files.
+fn read_checkpoint_files(
+    root: &Path,
+    summary_file: &FileMetadata,
+    contents_file: &FileMetadata,
+) -> Result<impl Iterator<Item = Result<ArchivedCheckpoint, ReplayEngineError>>, ReplayEngineError>
+{
+    let file_path = |file: &FileMetadata| {
+        path_to_filesystem(root.to_path_buf(), &file.file_path()).map_err(|e| read_error(root, e))
+    };
+    let summary_path = file_path(summary_file)?;
+    let contents_path = file_path(contents_file)?;
+    let summaries = File::open(&summary_path)
+        .map_err(anyhow::Error::from)
+        .and_then(|file| make_iterator::<CertifiedCheckpointSummary, _>(SUMMARY_FILE_MAGIC, file))
+        .map_err(|e| read_error(&summary_path, e))?;
+    let contents = File::open(&contents_path)
+        .map_err(anyhow::Error::from)
+        .and_then(|file| make_iterator::<FullCheckpointContents, _>(CHECKPOINT_FILE_MAGIC, file))
+        .map_err(|e| read_error(&contents_path, e))?;
+
+    // Blobs that fail to decode end the file early, so check that every checkpoint is present
+    let mut checkpoints = summaries.zip(contents);
+    Ok(summary_file
+        .checkpoint_seq_range
+        .clone()
+        .map(move |sequence_number| {
+            let error = |err: String| read_error(&contents_path, err);
+            let (summary, contents) = checkpoints
+                .next()
+                .ok_or_else(|| error(format!("checkpoint {sequence_number} is missing")))?;
+            if summary.sequence_number != sequence_number {
+                return Err(error(format!(
+                    "expected checkpoint {sequence_number}, found {}",
+                    summary.sequence_number
+                )));
+            }
+            contents
+                .verify_digests(summary.content_digest)
+                .map_err(|e| error(format!("checkpoint {sequence_number}: {e}")))?;
+            Ok((summary, contents))
+        }))
+}
+
+/// Parameters for executing the transactions of an epoch, from the system state it started with.
+struct EpochParams {
+    epoch: EpochId,
+    epoch_start_timestamp_ms: u64,
+    reference_gas_price: u64,
+    protocol_config: ProtocolConfig,
+    executor: Arc<dyn Executor + Send + Sync>,
+}
+
+impl EpochParams {
+    fn new(system_state: SuiSystemState, chain: Chain) -> Result<Self, ReplayEngineError> {
+        let protocol_config = ProtocolConfig::get_for_version(
+            ProtocolVersion::new(system_state.protocol_version()),
+            chain,
+        );
+        let executor = sui_execution::executor(&protocol_config, /* silent */ true, None)?;
+        Ok(Self {
+            epoch: system_state.epoch(),
+            epoch_start_timestamp_ms: system_state.epoch_start_timestamp_ms(),
+            reference_gas_price: system_state.reference_gas_price(),
+            protocol_config,
+            executor,
+        })
+    }
+}
+
+/// Re-executes archived transactions in order, keeping the latest version of every live object.
+struct ArchiveExecutor {
+    store: InMemoryStorage,
+    chain: Chain,
+    /// Unknown until the genesis transaction is executed
+    epoch: Option<EpochParams>,
+    metrics: Arc<LimitsMetrics>,
+    /// The transaction that deleted each shared object, for later transactions that still take
+    /// the object as an input
+    deleted_shared_objects: HashMap<ObjectID, TransactionDigest>,
+}
+
+impl Default for ArchiveExecutor {
+    fn default() -> Self {
+        Self {
+            store: InMemoryStorage::default(),
+            chain: Chain::Unknown,
+            epoch: None,
+            metrics: Arc::new(LimitsMetrics::new(&prometheus::Registry::new())),
+            deleted_shared_objects: HashMap::new(),
+        }
+    }
+}
+
+impl ArchiveExecutor {
+    fn execute_checkpoint(
+        &mut self,
+        summary: CertifiedCheckpointSummary,
+        contents: FullCheckpointContents,
+    ) -> Result<CheckpointData, ReplayEngineError> {
+        if summary.sequence_number == 0 {
+            self.chain = ChainIdentifier::from(*summary.digest()).chain();
+        }
+        let checkpoint_contents = contents.checkpoint_contents();
+        let transactions = contents
+            .into_iter()
+            .map(|data| self.execute_transaction(data.transaction, data.effects))
+            .collect::<Result<_, _>>()?;
+        Ok(CheckpointData {
+            checkpoint_summary: summary,
+            checkpoint_contents,
+            transactions,
+        })
+    }
+
+    fn execute_transaction(
+        &mut self,
+        transaction: Transaction,
+        archived_effects: TransactionEffects,
+    ) -> Result<CheckpointTransaction, ReplayEngineError> {
+        let digest = *transaction.digest();
+        let tx_data = transaction.transaction_data();
+        let (kind, signer, gas) = tx_data.execution_parts();
+        let changes_epoch = tx_data.is_end_of_epoch_tx() || tx_data.is_genesis_tx();
+
+        if let TransactionKind::Genesis(genesis) = &kind {
+            // The epoch genesis starts is described by the system state among its objects
+            let objects: Vec<_> = genesis
+                .objects
+                .iter()
+                .map(|GenesisObject::RawObject { data, owner }| {
+                    Object::new_from_genesis(data.clone(), owner.clone(), digest)
+                })
+                .collect();
+            let system_state = get_sui_system_state(&objects.as_slice())?;
+            self.epoch = Some(EpochParams::new(system_state, self.chain)?);
+        }
+        let Some(epoch) = &self.epoch else {
+            return Err(ReplayEngineError::TransactionNotSupported {
+                digest,
+                reason: "The archive does not start with the genesis transaction".to_string(),
+            });
+        };
+
+        let input_objects = self.input_objects(tx_data, &archived_effects)?;
+        let gas_status = if kind.is_system_tx() {
+            SuiGasStatus::new_unmetered()
+        } else {
+            SuiGasStatus::new(
+                tx_data.gas_budget(),
+                tx_data.gas_price(),
+                epoch.reference_gas_price,
+                &epoch.protocol_config,
+            )?
+        };
+        let (inner, _, effects, _) = epoch.executor.execute_transaction_to_effects(
+            &self.store,
+            &epoch.protocol_config,
+            self.metrics.clone(),
+            /* enable_expensive_checks */ false,
+            &HashSet::new(),
+            &epoch.epoch,
+            epoch.epoch_start_timestamp_ms,
+            CheckedInputObjects::new_for_replay(input_objects),
+            gas,
+            gas_status,
+            kind,
+            signer,
+            digest,
+        );
+        if effects.digest() != archived_effects.digest() {
+            return Err(ReplayEngineError::ArchivedEffectsMismatch {
+                digest,
+                archived: archived_effects.digest(),
+                local: effects.digest(),
+            });
+        }
+
+        let input_objects = effects
+            .modified_at_versions()
+            .into_iter()
+            .map(|(id, version)| {
+                self.store
+                    .get_object(&id)
+                    .filter(|object| object.version() == version)
+                    .cloned()
+                    .ok_or(ReplayEngineError::ObjectVersionNotFound { id, version })
+            })
+            .collect::<Result<_, _>>()?;
+        let output_objects = effects
+            .all_changed_objects()
+            .into_iter()
+            .map(|((id, version, _), _, _)| {
+                inner
+                    .written
+                    .get(&id)
+                    .cloned()
+                    .ok_or(ReplayEngineError::ObjectVersionNotFound { id, version })
+            })
+            .collect::<Result<_, _>>()?;
+
+        for ((id, _, _), _) in effects.all_removed_objects() {
+            if let Some(object) = self.store.remove_object(id) {
+                if object.is_shared() {
+                    self.deleted_shared_objects.insert(id, digest);
+                }
+            }
+        }
+        self.store.finish(inner.written);
+        if changes_epoch {
+            let system_state = get_sui_system_state(&self.store)?;
+            self.epoch = Some(EpochParams::new(system_state, self.chain)?);
+        }
+
+        Ok(CheckpointTransaction {
+            transaction,
+            events: effects.events_digest().map(|_| inner.events),
+            effects,
+            input_objects,
+            output_objects,
+        })
+    }
+
+    /// The transaction's inputs, as they were when it was executed. Shared objects are read at the
+    /// versions the archived effects say they were assigned.
+    fn input_objects(
+        &self,
+        tx_data: &TransactionData,
+        effects: &TransactionEffects,
+    ) -> Result<InputObjects, ReplayEngineError> {
+        let shared_objects: HashMap<_, _> = effects
+            .input_shared_objects()
+            .into_iter()
+            .map(|object| (object.id_and_version().0, object))
+            .collect();
+        let at_version = |id: &ObjectID, version| {
+            self.store
+                .get_object(id)
+                .filter(|object| object.version() == version)
+                .map(|object| ObjectReadResultKind::Object(object.clone()))
+                .ok_or(ReplayEngineError::ObjectVersionNotFound { id: *id, version })
+        };
+
+        tx_data
+            .input_objects()?
+            .into_iter()
+            .map(|kind| {
+                let object = match &kind {
+                    InputObjectKind::MovePackage(id) => self
+                        .store
+                        .get_object(id)
+                        .map(|object| ObjectReadResultKind::Object(object.clone()))
+                        .ok_or(ReplayEngineError::ObjectNotExist { id: *id })?,
+                    InputObjectKind::ImmOrOwnedMoveObject((id, version, _)) => {
+                        at_version(id, *version)?
+                    }
+                    InputObjectKind::SharedMoveObject { id, .. } => match shared_objects.get(id) {
+                        Some(
+                            InputSharedObject::Mutate((_, version, _))
+                            | InputSharedObject::ReadOnly((_, version, _)),
+                        ) => at_version(id, *version)?,
+                        Some(
+                            InputSharedObject::ReadDeleted(_, version)
+                            | InputSharedObject::MutateDeleted(_, version),
+                        ) => {
+                            let deleted_by = self
+                                .deleted_shared_objects
+                                .get(id)
+                                .ok_or(ReplayEngineError::ObjectNotExist { id: *id })?;
+                            ObjectReadResultKind::DeletedSharedObject(*version, *deleted_by)
+                        }
+                        Some(InputSharedObject::Cancelled(_, version)) => {
+                            ObjectReadResultKind::CancelledTransactionSharedObject(*version)
+                        }
+                        None => return Err(ReplayEngineError::ObjectNotExist { id: *id }),
+                    },
+                };
+                Ok(ObjectReadResult::new(kind, object))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(InputObjects::new)
+    }
+}
@@ -1,19 +1,22 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::replay::{ExecutionSandboxState, LocalExec};
+use crate::diff::{EffectsDiff, GasComponentDiff};
+use crate::replay::{ExecutionSandboxState, LocalExec, Storage};
 use crate::types::ReplayEngineError;
-use futures::future::join_all;
+use futures::future::{join_all, try_join_all};
 use futures::FutureExt;
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::time::Duration;
 use sui_config::node::ExpensiveSafetyCheckConfig;
 use sui_types::base_types::TransactionDigest;
 use tokio::time::Instant;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 /// Given a list of transaction digests, replay them in parallel using `num_tasks` tasks.
 /// If `terminate_early` is true, the replay will terminate early if any transaction fails;
@@ -168,38 +171,314 @@ async fn execute_transaction(
     expensive_safety_check_config: ExpensiveSafetyCheckConfig,
     use_authority: bool,
 ) -> Result<ExecutionSandboxState, ReplayEngineError> {
-    *executor = loop {
-        match executor.clone().reset_for_new_execution_with_client().await {
-            Ok(executor) => break executor,
-            Err(err) => {
-                error!("Failed to reset executor: {:?}. Retrying in 3s", err);
-                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+    let sandbox_state = replay_transaction(
+        executor,
+        digest,
+        expensive_safety_check_config,
+        use_authority,
+        ReplayVersions::default(),
+    )
+    .await?;
+    sandbox_state.check_effects()?;
+    Ok(sandbox_state)
+}
+
+/// Number of attempts at an operation failing with transient errors before giving up on it.
+const MAX_ATTEMPTS: usize = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(3);
+
+/// Whether `err` comes from the network and may go away when retrying. Any other error is
+/// deterministic and is returned right away.
+fn is_transient(err: &ReplayEngineError) -> bool {
+    matches!(
+        err,
+        ReplayEngineError::SuiRpcRequestTimeout
+            | ReplayEngineError::SuiRpcError { .. }
+            | ReplayEngineError::ErrorQueryingSystemEvents { .. }
+            | ReplayEngineError::UnableToQuerySystemEvents { .. }
+            | ReplayEngineError::UnableToGetDynamicFieldLoadedObjects { .. }
+    )
+}
+
+/// Replay a transaction with a freshly reset executor, retrying transient errors up to
+/// `MAX_ATTEMPTS` times, without checking the resulting effects.
+async fn replay_transaction(
+    executor: &mut LocalExec,
+    digest: &TransactionDigest,
+    expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+    use_authority: bool,
+    versions: ReplayVersions,
+) -> Result<ExecutionSandboxState, ReplayEngineError> {
+    if executor.is_remote_replay() {
+        let mut attempt = 1;
+        *executor = loop {
+            match executor.clone().reset_for_new_execution_with_client().await {
+                Ok(executor) => break executor,
+                Err(err) if is_transient(&err) && attempt < MAX_ATTEMPTS => {
+                    warn!(
+                        "Failed to reset executor (attempt {attempt}/{MAX_ATTEMPTS}): {:?}. Retrying in {:?}",
+                        err, RETRY_DELAY
+                    );
+                    attempt += 1;
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+                Err(err) => return Err(err),
             }
-        }
-    };
-    let sandbox_state = loop {
+        };
+    } else {
+        // Local sources can't be reset, but packages must not be carried over between replays
+        executor.storage = Storage::default();
+    }
+    executor.framework_path = versions.framework_path;
+
+    let mut attempt = 1;
+    loop {
         let result = executor
             .execute_transaction(
                 digest,
                 expensive_safety_check_config.clone(),
                 use_authority,
-                None,
-                None,
+                versions.executor_version,
+                versions.protocol_version,
                 None,
                 None,
             )
             .await;
         match result {
-            Ok(sandbox_state) => break sandbox_state,
-            err @ Err(ReplayEngineError::TransactionNotSupported { .. }) => {
-                return err;
+            Err(err) if is_transient(&err) && attempt < MAX_ATTEMPTS => {
+                warn!(
+                    "Failed to execute transaction {digest} (attempt {attempt}/{MAX_ATTEMPTS}): {:?}. Retrying in {:?}",
+                    err, RETRY_DELAY
+                );
+                attempt += 1;
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Executor and protocol versions, and framework build, to replay a transaction with. Unset
+/// versions default to the ones originally used for the transaction, and the system packages to
+/// the ones of its protocol version.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayVersions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub executor_version: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<i64>,
+    /// Local build of the system packages, laid out like `sui-framework/packages_compiled`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub framework_path: Option<PathBuf>,
+}
+
+/// Replay a transaction under `versions` and diff its effects against a `baseline`: either a
+/// replay of the same transaction under other versions, or the on-chain effects if no baseline is
+/// given.
+pub async fn diff_transaction(
+    executor: &mut LocalExec,
+    digest: &TransactionDigest,
+    expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+    use_authority: bool,
+    versions: ReplayVersions,
+    baseline: Option<ReplayVersions>,
+) -> Result<EffectsDiff, ReplayEngineError> {
+    let actual = replay_transaction(
+        executor,
+        digest,
+        expensive_safety_check_config.clone(),
+        use_authority,
+        versions,
+    )
+    .await?;
+    let Some(baseline) = baseline else {
+        return Ok(EffectsDiff::from_sandbox(&actual));
+    };
+    let expected = replay_transaction(
+        executor,
+        digest,
+        expensive_safety_check_config,
+        use_authority,
+        baseline,
+    )
+    .await?;
+    Ok(EffectsDiff::between_sandboxes(&expected, &actual))
+}
+
+/// Sum of the differences in each gas cost component, over all diffed transactions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasDelta {
+    pub computation_cost: i128,
+    pub storage_cost: i128,
+    pub storage_rebate: i128,
+    pub non_refundable_storage_fee: i128,
+}
+
+/// A transaction that could not be diffed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedDiff {
+    pub tx_digest: TransactionDigest,
+    pub error: String,
+}
+
+/// Effects diffs aggregated over a range of checkpoints.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffReport {
+    pub checkpoint_start: u64,
+    pub checkpoint_end: u64,
+    pub versions: ReplayVersions,
+    /// Versions the replays were diffed against, or the on-chain effects if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<ReplayVersions>,
+    pub total: u64,
+    /// Transactions whose effects are identical to the baseline
+    pub matched: u64,
+    /// Transactions that cannot be replayed, such as genesis
+    pub unsupported: u64,
+    pub status_changes: u64,
+    pub gas_changes: u64,
+    pub object_changes: u64,
+    pub event_changes: u64,
+    pub gas_delta: GasDelta,
+    pub failed: Vec<FailedDiff>,
+    pub diffs: Vec<EffectsDiff>,
+}
+
+impl DiffReport {
+    /// Whether every supported transaction was replayed and matched the baseline.
+    pub fn is_clean(&self) -> bool {
+        self.failed.is_empty() && self.diffs.is_empty()
+    }
+
+    fn record(
+        &mut self,
+        tx_digest: TransactionDigest,
+        result: Result<EffectsDiff, ReplayEngineError>,
+    ) {
+        self.total += 1;
+        let diff = match result {
+            Ok(diff) => diff,
+            Err(ReplayEngineError::TransactionNotSupported { .. }) => {
+                self.unsupported += 1;
+                return;
             }
             Err(err) => {
-                error!("Failed to execute transaction: {:?}. Retrying in 3s", err);
-                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                self.failed.push(FailedDiff {
+                    tx_digest,
+                    error: err.to_string(),
+                });
+                return;
             }
+        };
+        if diff.is_empty() {
+            self.matched += 1;
+            return;
         }
+
+        self.status_changes += diff.status.is_some() as u64;
+        self.gas_changes += !diff.gas.is_empty() as u64;
+        self.object_changes += !diff.objects.is_empty() as u64;
+        self.event_changes += !diff.events.is_empty() as u64;
+        let delta =
+            |component: &Option<GasComponentDiff>| component.as_ref().map_or(0, |c| c.delta);
+        self.gas_delta.computation_cost += delta(&diff.gas.computation_cost);
+        self.gas_delta.storage_cost += delta(&diff.gas.storage_cost);
+        self.gas_delta.storage_rebate += delta(&diff.gas.storage_rebate);
+        self.gas_delta.non_refundable_storage_fee += delta(&diff.gas.non_refundable_storage_fee);
+        self.diffs.push(diff);
+    }
+}
+
+/// Replay every transaction in the checkpoints `start..=end` under `versions` using `num_tasks`
+/// tasks, and aggregate the diffs of their effects against `baseline` (see [`diff_transaction`]).
+pub async fn batch_diff_checkpoints(
+    start: u64,
+    end: u64,
+    num_tasks: u64,
+    rpc_url: String,
+    expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+    use_authority: bool,
+    versions: ReplayVersions,
+    baseline: Option<ReplayVersions>,
+) -> Result<DiffReport, ReplayEngineError> {
+    let executor = LocalExec::new_from_fn_url(&rpc_url).await?;
+    let mut tx_digests = vec![];
+    for checkpoint in start..=end {
+        tx_digests.extend(executor.get_checkpoint_txs(checkpoint).await?);
+    }
+    let executors =
+        try_join_all((0..num_tasks).map(|_| LocalExec::new_from_fn_url(&rpc_url))).await?;
+    let report = DiffReport {
+        checkpoint_start: start,
+        checkpoint_end: end,
+        versions,
+        baseline,
+        ..Default::default()
     };
-    sandbox_state.check_effects()?;
-    Ok(sandbox_state)
+    Ok(diff_transactions(
+        executors,
+        tx_digests,
+        expensive_safety_check_config,
+        use_authority,
+        report,
+    )
+    .await)
+}
+
+/// Diff the transactions in `tx_digests` under the versions of `report`, running one task per
+/// executor, and record the results into `report`. Transactions that fail to replay are recorded
+/// as such rather than interrupting the others.
+pub(crate) async fn diff_transactions(
+    executors: Vec<LocalExec>,
+    tx_digests: Vec<TransactionDigest>,
+    expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+    use_authority: bool,
+    report: DiffReport,
+) -> DiffReport {
+    let provider = Arc::new(TransactionDigestProvider::new(tx_digests.into_iter()));
+    let versions = report.versions.clone();
+    let baseline = report.baseline.clone();
+    let report = Mutex::new(report);
+
+    let cur_time = Instant::now();
+    let tasks = executors.into_iter().map(|mut executor| {
+        let provider = provider.clone();
+        let report = &report;
+        let expensive_safety_check_config = expensive_safety_check_config.clone();
+        let versions = &versions;
+        let baseline = &baseline;
+        async move {
+            let total_count = provider.get_total_count();
+            while let Some((index, digest)) = provider.next_digest() {
+                info!(
+                    "[{}/{}] Diffing transaction {:?}...",
+                    index, total_count, digest
+                );
+                let result = diff_transaction(
+                    &mut executor,
+                    &digest,
+                    expensive_safety_check_config.clone(),
+                    use_authority,
+                    versions.clone(),
+                    baseline.clone(),
+                )
+                .await;
+                report.lock().record(digest, result);
+            }
+        }
+    });
+    join_all(tasks).await;
+
+    let mut report = report.into_inner();
+    report.diffs.sort_by_key(|diff| diff.tx_digest);
+    report.failed.sort_by_key(|failed| failed.tx_digest);
+    info!(
+        "Finished diffing {} transactions, took {:?}: {} matched, {} differed, {} failed",
+        report.total,
+        cur_time.elapsed(),
+        report.matched,
+        report.diffs.len(),
+        report.failed.len(),
+    );
+    report
 }
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::archive;
 use crate::types::ReplayEngineError;
 use crate::types::EPOCH_CHANGE_STRUCT_TAG;
 use async_trait::async_trait;
@@ -9,10 +10,13 @@ use lru::LruCache;
 use move_core_types::language_storage::StructTag;
 use parking_lot::RwLock;
 use rand::Rng;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::Arc;
 use sui_core::authority::NodeStateDump;
+use sui_framework::BuiltInFramework;
 use sui_json_rpc_api::QUERY_MAX_RESULT_LIMIT;
 use sui_json_rpc_types::EventFilter;
 use sui_json_rpc_types::SuiEvent;
@@ -21,12 +25,19 @@ use sui_json_rpc_types::SuiObjectData;
 use sui_json_rpc_types::SuiObjectDataOptions;
 use sui_json_rpc_types::SuiObjectResponse;
 use sui_json_rpc_types::SuiPastObjectResponse;
+use sui_json_rpc_types::SuiTransactionBlockEffects;
 use sui_json_rpc_types::SuiTransactionBlockResponse;
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_sdk::SuiClient;
+use sui_storage::blob::Blob;
 use sui_types::base_types::{ObjectID, SequenceNumber, VersionNumber};
-use sui_types::digests::TransactionDigest;
+use sui_types::committee::EpochId;
+use sui_types::digests::{ChainIdentifier, TransactionDigest};
+use sui_types::event::{Event, EventID, SystemEpochInfoEvent};
+use sui_types::full_checkpoint_content::{CheckpointData, CheckpointTransaction};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::Object;
+use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
 use sui_types::transaction::SenderSignedData;
 use sui_types::transaction::TransactionDataAPI;
 use sui_types::transaction::{EndOfEpochTransactionKind, TransactionKind};
@@ -96,13 +107,14 @@ pub(crate) trait DataFetcher {
 pub enum Fetchers {
     Remote(RemoteFetcher),
     NodeStateDump(NodeStateDumpFetcher),
+    Checkpoint(CheckpointFetcher),
 }
 
 impl Fetchers {
     pub fn as_remote(&self) -> &RemoteFetcher {
         match self {
             Fetchers::Remote(q) => q,
-            Fetchers::NodeStateDump(_) => panic!("not a remote fetcher"),
+            Fetchers::NodeStateDump(_) | Fetchers::Checkpoint(_) => panic!("not a remote fetcher"),
         }
     }
 
@@ -114,16 +126,25 @@ impl Fetchers {
                 q.clear_cache_for_new_task();
                 q
             }
-            Fetchers::NodeStateDump(_) => panic!("not a remote fetcher"),
+            Fetchers::NodeStateDump(_) | Fetchers::Checkpoint(_) => panic!("not a remote fetcher"),
         }
     }
 
     pub fn as_node_state_dump(&self) -> &NodeStateDumpFetcher {
         match self {
-            Fetchers::Remote(_) => panic!("not a node state dump fetcher"),
+            Fetchers::Remote(_) | Fetchers::Checkpoint(_) => {
+                panic!("not a node state dump fetcher")
+            }
             Fetchers::NodeStateDump(q) => q,
         }
     }
+
+    pub fn as_checkpoint(&self) -> &CheckpointFetcher {
+        match self {
+            Fetchers::Remote(_) | Fetchers::NodeStateDump(_) => panic!("not a checkpoint fetcher"),
+            Fetchers::Checkpoint(q) => q,
+        }
+    }
 }
 
 #[async_trait]
@@ -136,6 +157,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.multi_get_versioned(objects).await,
             Fetchers::NodeStateDump(q) => q.multi_get_versioned(objects).await,
+            Fetchers::Checkpoint(q) => q.multi_get_versioned(objects).await,
         }
     }
 
@@ -146,6 +168,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.multi_get_latest(objects).await,
             Fetchers::NodeStateDump(q) => q.multi_get_latest(objects).await,
+            Fetchers::Checkpoint(q) => q.multi_get_latest(objects).await,
         }
     }
 
@@ -156,6 +179,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_checkpoint_txs(id).await,
             Fetchers::NodeStateDump(q) => q.get_checkpoint_txs(id).await,
+            Fetchers::Checkpoint(q) => q.get_checkpoint_txs(id).await,
         }
     }
 
@@ -166,6 +190,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_transaction(tx_digest).await,
             Fetchers::NodeStateDump(q) => q.get_transaction(tx_digest).await,
+            Fetchers::Checkpoint(q) => q.get_transaction(tx_digest).await,
         }
     }
 
@@ -176,6 +201,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_loaded_child_objects(tx_digest).await,
             Fetchers::NodeStateDump(q) => q.get_loaded_child_objects(tx_digest).await,
+            Fetchers::Checkpoint(q) => q.get_loaded_child_objects(tx_digest).await,
         }
     }
 
@@ -183,6 +209,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_latest_checkpoint_sequence_number().await,
             Fetchers::NodeStateDump(q) => q.get_latest_checkpoint_sequence_number().await,
+            Fetchers::Checkpoint(q) => q.get_latest_checkpoint_sequence_number().await,
         }
     }
 
@@ -200,6 +227,10 @@ impl DataFetcher for Fetchers {
                 q.fetch_random_transaction(checkpoint_id_start, checkpoint_id_end)
                     .await
             }
+            Fetchers::Checkpoint(q) => {
+                q.fetch_random_transaction(checkpoint_id_start, checkpoint_id_end)
+                    .await
+            }
         }
     }
 
@@ -210,6 +241,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
             Fetchers::NodeStateDump(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
+            Fetchers::Checkpoint(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
         }
     }
 
@@ -220,12 +252,14 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_epoch_change_events(reverse).await,
             Fetchers::NodeStateDump(q) => q.get_epoch_change_events(reverse).await,
+            Fetchers::Checkpoint(q) => q.get_epoch_change_events(reverse).await,
         }
     }
     async fn get_chain_id(&self) -> Result<String, ReplayEngineError> {
         match self {
             Fetchers::Remote(q) => q.get_chain_id().await,
             Fetchers::NodeStateDump(q) => q.get_chain_id().await,
+            Fetchers::Checkpoint(q) => q.get_chain_id().await,
         }
     }
     async fn get_child_object(
@@ -236,6 +270,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_child_object(object_id, version_upper_bound).await,
             Fetchers::NodeStateDump(q) => q.get_child_object(object_id, version_upper_bound).await,
+            Fetchers::Checkpoint(q) => q.get_child_object(object_id, version_upper_bound).await,
         }
     }
 }
@@ -824,3 +859,404 @@ impl DataFetcher for NodeStateDumpFetcher {
         unimplemented!("get child object is not implemented for state dump");
     }
 }
+
+/// Information about an epoch, taken from the system state at the start of the epoch.
+#[derive(Clone, Debug)]
+pub struct CheckpointEpochInfo {
+    pub protocol_version: u64,
+    pub reference_gas_price: u64,
+    pub epoch_start_timestamp_ms: u64,
+    /// The first checkpoint of the epoch
+    pub checkpoint_start: CheckpointSequenceNumber,
+    /// The last checkpoint of the epoch, if the epoch has ended
+    pub checkpoint_end: Option<CheckpointSequenceNumber>,
+    /// The genesis or end of epoch transaction which started this epoch
+    pub epoch_change_tx: TransactionDigest,
+}
+
+#[derive(Default)]
+struct CheckpointIndex {
+    chain_id: Option<String>,
+    checkpoint_txs: BTreeMap<CheckpointSequenceNumber, Vec<TransactionDigest>>,
+    transactions:
+        HashMap<TransactionDigest, (CheckpointSequenceNumber, u64, CheckpointTransaction)>,
+    // Every version of every object that was an input or output of a transaction
+    objects: BTreeMap<(ObjectID, SequenceNumber), Object>,
+    epochs: BTreeMap<EpochId, CheckpointEpochInfo>,
+    // System package versions in effect for each protocol version
+    system_packages: BTreeMap<u64, BTreeMap<ObjectID, SequenceNumber>>,
+    epoch_change_events: Vec<SuiEvent>,
+}
+
+/// Fetches data from checkpoint files written by data ingestion (`<sequence number>.chk`),
+/// without any access to the network.
+///
+/// The objects a transaction reads are reconstructed from the input and output objects of the
+/// transactions in earlier checkpoints, so every object version a replayed transaction depends
+/// on must have been read or written in one of the loaded checkpoints. In particular, system
+/// packages are only known from genesis, or from the epoch changes that upgraded them.
+/// Archives written by `sui-archival` only contain transactions and effects, so their objects are
+/// rebuilt by re-executing the archive from genesis when it is loaded.
+#[derive(Clone)]
+pub struct CheckpointFetcher {
+    index: Arc<CheckpointIndex>,
+}
+
+impl CheckpointFetcher {
+    /// Load every checkpoint file in the directory at `path`.
+    ///
+    /// `chain_id` overrides the chain identifier, which is otherwise derived from checkpoint 0 and
+    /// is unknown if that checkpoint is not part of the loaded files.
+    pub fn from_dir(path: &Path, chain_id: Option<String>) -> Result<Self, ReplayEngineError> {
+        let read_error = |err: String| ReplayEngineError::UnableToReadCheckpoints {
+            path: path.display().to_string(),
+            err,
+        };
+
+        let mut files = BTreeMap::new();
+        for entry in std::fs::read_dir(path).map_err(|e| read_error(e.to_string()))? {
+            let file_path = entry.map_err(|e| read_error(e.to_string()))?.path();
+            if file_path.extension().and_then(|ext| ext.to_str()) != Some("chk") {
+                continue;
+            }
+            let Some(sequence_number) = file_path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<CheckpointSequenceNumber>().ok())
+            else {
+                continue;
+            };
+            files.insert(sequence_number, file_path);
+        }
+
+        let checkpoints = files.into_values().map(|file_path| {
+            let bytes = std::fs::read(&file_path).map_err(|e| read_error(e.to_string()))?;
+            Blob::from_bytes::<CheckpointData>(&bytes)
+                .map_err(|e| read_error(format!("invalid checkpoint {}: {e}", file_path.display())))
+        });
+        Self::index(checkpoints, chain_id)
+    }
+
+    /// Load the `sui-archival` archive in the directory at `path`, which holds a `MANIFEST` and the
+    /// checkpoint files it lists. The archive must start at genesis.
+    ///
+    /// Archives do not contain objects, so they are rebuilt by re-executing every archived
+    /// transaction, and loading fails if any of them produces different effects.
+    pub fn from_archive(path: &Path) -> Result<Self, ReplayEngineError> {
+        Self::index(archive::read_archive(path)?, None)
+    }
+
+    /// Index `checkpoints`, which must already be in sequence number order. Each one is read only
+    /// when it is indexed, so they are never all in memory at once.
+    fn index(
+        checkpoints: impl IntoIterator<Item = Result<CheckpointData, ReplayEngineError>>,
+        chain_id: Option<String>,
+    ) -> Result<Self, ReplayEngineError> {
+        let system_package_ids = BuiltInFramework::all_package_ids();
+        let mut current_system_packages = BTreeMap::new();
+        let mut index = CheckpointIndex::default();
+
+        for checkpoint in checkpoints {
+            let checkpoint = checkpoint?;
+            let summary = &checkpoint.checkpoint_summary;
+            let sequence_number = summary.sequence_number;
+            let timestamp_ms = summary.timestamp_ms;
+            if sequence_number == 0 {
+                index.chain_id = Some(ChainIdentifier::from(*summary.digest()).to_string());
+            }
+            if summary.end_of_epoch_data.is_some() {
+                if let Some(info) = index.epochs.get_mut(&summary.epoch) {
+                    info.checkpoint_end = Some(sequence_number);
+                }
+            }
+
+            let mut digests = vec![];
+            for tx in checkpoint.transactions {
+                let digest = *tx.transaction.digest();
+                for object in tx.input_objects.iter().chain(tx.output_objects.iter()) {
+                    index
+                        .objects
+                        .insert((object.id(), object.version()), object.clone());
+                }
+                for object in &tx.output_objects {
+                    if object.is_package() && system_package_ids.contains(&object.id()) {
+                        current_system_packages.insert(object.id(), object.version());
+                    }
+                }
+
+                let kind = tx.transaction.transaction_data().kind();
+                if kind.is_end_of_epoch_tx() || matches!(kind, TransactionKind::Genesis(_)) {
+                    // The system state written by this transaction describes the epoch it starts
+                    if let Ok(system_state) = get_sui_system_state(&tx.output_objects.as_slice()) {
+                        index.epochs.insert(
+                            system_state.epoch(),
+                            CheckpointEpochInfo {
+                                protocol_version: system_state.protocol_version(),
+                                reference_gas_price: system_state.reference_gas_price(),
+                                epoch_start_timestamp_ms: system_state.epoch_start_timestamp_ms(),
+                                checkpoint_start: sequence_number + 1,
+                                checkpoint_end: None,
+                                epoch_change_tx: digest,
+                            },
+                        );
+                        index.system_packages.insert(
+                            system_state.protocol_version(),
+                            current_system_packages.clone(),
+                        );
+                    }
+
+                    let events = tx.events.iter().flat_map(|events| events.data.iter());
+                    for (event_seq, event) in events.enumerate() {
+                        if let Some(event) =
+                            epoch_change_event(event, digest, event_seq, timestamp_ms)
+                        {
+                            index.epoch_change_events.push(event);
+                        }
+                    }
+                }
+
+                digests.push(digest);
+                index
+                    .transactions
+                    .insert(digest, (sequence_number, timestamp_ms, tx));
+            }
+            index.checkpoint_txs.insert(sequence_number, digests);
+        }
+
+        // Genesis is part of checkpoint 0, along with the rest of epoch 0
+        if let Some(info) = index.epochs.get_mut(&0) {
+            info.checkpoint_start = 0;
+        }
+        if chain_id.is_some() {
+            index.chain_id = chain_id;
+        }
+
+        Ok(Self {
+            index: Arc::new(index),
+        })
+    }
+
+    /// The sequence numbers of the loaded checkpoints, in order.
+    pub fn checkpoints(&self) -> impl Iterator<Item = CheckpointSequenceNumber> + '_ {
+        self.index.checkpoint_txs.keys().copied()
+    }
+
+    /// The epochs whose start is part of the loaded checkpoints.
+    pub fn epochs(&self) -> &BTreeMap<EpochId, CheckpointEpochInfo> {
+        &self.index.epochs
+    }
+
+    /// The versions of the system packages in effect for each protocol version.
+    pub fn system_package_versions(&self) -> &BTreeMap<u64, BTreeMap<ObjectID, SequenceNumber>> {
+        &self.index.system_packages
+    }
+
+    /// The transaction with the given digest, the checkpoint it was included in, and that
+    /// checkpoint's timestamp.
+    pub fn checkpoint_transaction(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<(CheckpointSequenceNumber, u64, &CheckpointTransaction), ReplayEngineError> {
+        self.index
+            .transactions
+            .get(tx_digest)
+            .map(|(sequence_number, timestamp_ms, tx)| (*sequence_number, *timestamp_ms, tx))
+            .ok_or(ReplayEngineError::TransactionNotFound { digest: *tx_digest })
+    }
+
+    fn object_versions(
+        &self,
+        object_id: &ObjectID,
+        version_upper_bound: SequenceNumber,
+    ) -> impl DoubleEndedIterator<Item = &Object> {
+        self.index
+            .objects
+            .range((*object_id, SequenceNumber::MIN)..=(*object_id, version_upper_bound))
+            .map(|(_, object)| object)
+    }
+}
+
+/// Convert a `SystemEpochInfoEvent` into the form returned by the RPC event API.
+fn epoch_change_event(
+    event: &Event,
+    tx_digest: TransactionDigest,
+    event_seq: usize,
+    timestamp_ms: u64,
+) -> Option<SuiEvent> {
+    if !event.is_system_epoch_info_event() {
+        return None;
+    }
+    let info: SystemEpochInfoEvent = bcs::from_bytes(&event.contents).ok()?;
+    let parsed_json = serde_json::json!({
+        "epoch": info.epoch.to_string(),
+        "protocol_version": info.protocol_version.to_string(),
+        "reference_gas_price": info.reference_gas_price.to_string(),
+        "total_stake": info.total_stake.to_string(),
+        "storage_fund_reinvestment": info.storage_fund_reinvestment.to_string(),
+        "storage_charge": info.storage_charge.to_string(),
+        "storage_rebate": info.storage_rebate.to_string(),
+        "storage_fund_balance": info.storage_fund_balance.to_string(),
+        "stake_subsidy_amount": info.stake_subsidy_amount.to_string(),
+        "total_gas_fees": info.total_gas_fees.to_string(),
+        "total_stake_rewards_distributed": info.total_stake_rewards_distributed.to_string(),
+        "leftover_storage_fund_inflow": info.leftover_storage_fund_inflow.to_string(),
+    });
+
+    Some(SuiEvent {
+        id: EventID {
+            tx_digest,
+            event_seq: event_seq as u64,
+        },
+        package_id: event.package_id,
+        transaction_module: event.transaction_module.clone(),
+        sender: event.sender,
+        type_: event.type_.clone(),
+        parsed_json,
+        bcs: event.contents.clone(),
+        timestamp_ms: Some(timestamp_ms),
+    })
+}
+
+#[async_trait]
+impl DataFetcher for CheckpointFetcher {
+    async fn multi_get_versioned(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Object>, ReplayEngineError> {
+        objects
+            .iter()
+            .map(|(id, version)| {
+                self.index.objects.get(&(*id, *version)).cloned().ok_or(
+                    ReplayEngineError::ObjectVersionNotFound {
+                        id: *id,
+                        version: *version,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    async fn multi_get_latest(
+        &self,
+        objects: &[ObjectID],
+    ) -> Result<Vec<Object>, ReplayEngineError> {
+        objects
+            .iter()
+            .map(|id| {
+                self.object_versions(id, SequenceNumber::MAX)
+                    .next_back()
+                    .cloned()
+                    .ok_or(ReplayEngineError::ObjectNotExist { id: *id })
+            })
+            .collect()
+    }
+
+    async fn get_checkpoint_txs(
+        &self,
+        id: u64,
+    ) -> Result<Vec<TransactionDigest>, ReplayEngineError> {
+        self.index
+            .checkpoint_txs
+            .get(&id)
+            .cloned()
+            .ok_or(ReplayEngineError::CheckpointNotFound {
+                sequence_number: id,
+            })
+    }
+
+    async fn get_transaction(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<SuiTransactionBlockResponse, ReplayEngineError> {
+        // Only the fields that can be derived without resolving Move types are filled in
+        let (sequence_number, timestamp_ms, tx) = self.checkpoint_transaction(tx_digest)?;
+        let mut response = SuiTransactionBlockResponse::new(*tx_digest);
+        response.raw_transaction = bcs::to_bytes(tx.transaction.data())
+            .map_err(|e| ReplayEngineError::GeneralError { err: e.to_string() })?;
+        response.effects = Some(SuiTransactionBlockEffects::try_from(tx.effects.clone())?);
+        response.checkpoint = Some(sequence_number);
+        response.timestamp_ms = Some(timestamp_ms);
+        Ok(response)
+    }
+
+    async fn get_loaded_child_objects(
+        &self,
+        _: &TransactionDigest,
+    ) -> Result<Vec<(ObjectID, SequenceNumber)>, ReplayEngineError> {
+        Ok(vec![])
+    }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<u64, ReplayEngineError> {
+        self.index
+            .checkpoint_txs
+            .keys()
+            .next_back()
+            .copied()
+            .ok_or(ReplayEngineError::CheckpointNotFound { sequence_number: 0 })
+    }
+
+    async fn fetch_random_transaction(
+        &self,
+        checkpoint_id_start_inclusive: Option<u64>,
+        checkpoint_id_end_inclusive: Option<u64>,
+    ) -> Result<TransactionDigest, ReplayEngineError> {
+        let start = checkpoint_id_start_inclusive.unwrap_or(1);
+        let end = checkpoint_id_end_inclusive.unwrap_or(u64::MAX);
+        let txs: Vec<_> = self
+            .index
+            .checkpoint_txs
+            .range(start..=end)
+            .flat_map(|(_, digests)| digests)
+            .collect();
+        if txs.is_empty() {
+            return Err(ReplayEngineError::CheckpointNotFound {
+                sequence_number: start,
+            });
+        }
+
+        let tx_idx = rand::thread_rng().gen_range(0..txs.len());
+        Ok(*txs[tx_idx])
+    }
+
+    async fn get_epoch_start_timestamp_and_rgp(
+        &self,
+        epoch_id: u64,
+    ) -> Result<(u64, u64), ReplayEngineError> {
+        self.index
+            .epochs
+            .get(&epoch_id)
+            .map(|info| (info.epoch_start_timestamp_ms, info.reference_gas_price))
+            .ok_or(ReplayEngineError::EventNotFound { epoch: epoch_id })
+    }
+
+    async fn get_epoch_change_events(
+        &self,
+        reverse: bool,
+    ) -> Result<Vec<SuiEvent>, ReplayEngineError> {
+        let mut events = self.index.epoch_change_events.clone();
+        if reverse {
+            events.reverse();
+        }
+        Ok(events)
+    }
+
+    async fn get_chain_id(&self) -> Result<String, ReplayEngineError> {
+        self.index
+            .chain_id
+            .clone()
+            .ok_or(ReplayEngineError::UnableToGetChainId {
+                err: "checkpoint 0 was not loaded and no chain id was provided".to_string(),
+            })
+    }
+
+    async fn get_child_object(
+        &self,
+        object_id: &ObjectID,
+        version_upper_bound: VersionNumber,
+    ) -> Result<Object, ReplayEngineError> {
+        self.object_versions(object_id, version_upper_bound)
+            .next_back()
+            .cloned()
+            .ok_or(ReplayEngineError::ObjectNotExist { id: *object_id })
+    }
+}
@@ -0,0 +1,328 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Machine readable diffs between two executions of the same transaction, for example between the
+//! on-chain effects and the effects of a local replay, or between replays under two protocol or
+//! executor versions, or between replays with the on-chain system packages and with a local build
+//! of the framework.
+
+use crate::replay::ExecutionSandboxState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use sui_json_rpc_types::{
+    OwnedObjectRef, SuiExecutionStatus, SuiObjectRef, SuiTransactionBlockEffects,
+    SuiTransactionBlockEffectsAPI,
+};
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::{ObjectDigest, TransactionDigest, TransactionEventsDigest};
+use sui_types::effects::TransactionEvents;
+use sui_types::gas::GasCostSummary;
+use sui_types::object::Owner;
+use sui_types::storage::{DeleteKind, WriteKind};
+
+/// A value that differs between the expected and the actual execution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change<T> {
+    pub expected: T,
+    pub actual: T,
+}
+
+impl<T: PartialEq + Clone> Change<T> {
+    fn between(expected: &T, actual: &T) -> Option<Self> {
+        (expected != actual).then(|| Self {
+            expected: expected.clone(),
+            actual: actual.clone(),
+        })
+    }
+}
+
+/// Difference in one component of the gas cost summary.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasComponentDiff {
+    pub expected: u64,
+    pub actual: u64,
+    /// `actual - expected`
+    pub delta: i128,
+}
+
+impl GasComponentDiff {
+    fn between(expected: u64, actual: u64) -> Option<Self> {
+        (expected != actual).then_some(Self {
+            expected,
+            actual,
+            delta: actual as i128 - expected as i128,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasCostDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub computation_cost: Option<GasComponentDiff>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_cost: Option<GasComponentDiff>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_rebate: Option<GasComponentDiff>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub non_refundable_storage_fee: Option<GasComponentDiff>,
+}
+
+impl GasCostDiff {
+    fn between(expected: &GasCostSummary, actual: &GasCostSummary) -> Self {
+        Self {
+            computation_cost: GasComponentDiff::between(
+                expected.computation_cost,
+                actual.computation_cost,
+            ),
+            storage_cost: GasComponentDiff::between(expected.storage_cost, actual.storage_cost),
+            storage_rebate: GasComponentDiff::between(
+                expected.storage_rebate,
+                actual.storage_rebate,
+            ),
+            non_refundable_storage_fee: GasComponentDiff::between(
+                expected.non_refundable_storage_fee,
+                actual.non_refundable_storage_fee,
+            ),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectChangeKind {
+    Created,
+    Mutated,
+    Unwrapped,
+    Deleted,
+    Wrapped,
+    UnwrappedThenDeleted,
+}
+
+/// How a transaction changed an object, as recorded in its effects.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectChange {
+    pub kind: ObjectChangeKind,
+    pub version: SequenceNumber,
+    pub digest: ObjectDigest,
+    /// The owner of the object after the transaction, unless it was removed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Owner>,
+}
+
+/// An object whose change differs between the two executions. A missing side means that the
+/// object was not changed by that execution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectDiff {
+    pub object_id: ObjectID,
+    pub expected: Option<ObjectChange>,
+    pub actual: Option<ObjectChange>,
+}
+
+/// An event emitted by a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventSummary {
+    pub type_: String,
+    pub contents: Vec<u8>,
+}
+
+/// An event that differs between the two executions, by its position in the emitted events.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventDiff {
+    pub index: usize,
+    pub expected: Option<EventSummary>,
+    pub actual: Option<EventSummary>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventsDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<Change<Option<TransactionEventsDigest>>>,
+    /// Only populated when the events themselves, and not just their digest, are known for both
+    /// executions.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<EventDiff>,
+}
+
+impl EventsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.digest.is_none() && self.events.is_empty()
+    }
+}
+
+/// Everything that differs between the effects of two executions of a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EffectsDiff {
+    pub tx_digest: TransactionDigest,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Change<SuiExecutionStatus>>,
+    #[serde(skip_serializing_if = "GasCostDiff::is_empty")]
+    pub gas: GasCostDiff,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub objects: Vec<ObjectDiff>,
+    #[serde(skip_serializing_if = "EventsDiff::is_empty")]
+    pub events: EventsDiff,
+    /// Any other difference in the effects, such as in dependencies or shared object versions
+    pub other_differences: bool,
+}
+
+impl EffectsDiff {
+    /// Diff `actual` against `expected`, including the emitted events if they are known for both.
+    pub fn new(
+        tx_digest: TransactionDigest,
+        expected: &SuiTransactionBlockEffects,
+        actual: &SuiTransactionBlockEffects,
+        expected_events: Option<&TransactionEvents>,
+        actual_events: Option<&TransactionEvents>,
+    ) -> Self {
+        let expected_objects = object_changes(expected);
+        let actual_objects = object_changes(actual);
+        let object_ids: BTreeSet<_> = expected_objects
+            .keys()
+            .chain(actual_objects.keys())
+            .copied()
+            .collect();
+        let objects = object_ids
+            .into_iter()
+            .filter_map(|object_id| {
+                let expected = expected_objects.get(&object_id).cloned();
+                let actual = actual_objects.get(&object_id).cloned();
+                (expected != actual).then_some(ObjectDiff {
+                    object_id,
+                    expected,
+                    actual,
+                })
+            })
+            .collect();
+
+        let events = EventsDiff {
+            digest: Change::between(
+                &expected.events_digest().copied(),
+                &actual.events_digest().copied(),
+            ),
+            events: match (expected_events, actual_events) {
+                (Some(expected), Some(actual)) => event_diffs(expected, actual),
+                _ => vec![],
+            },
+        };
+
+        let mut diff = Self {
+            tx_digest,
+            status: Change::between(expected.status(), actual.status()),
+            gas: GasCostDiff::between(expected.gas_cost_summary(), actual.gas_cost_summary()),
+            objects,
+            events,
+            other_differences: false,
+        };
+        diff.other_differences = diff.is_empty() && expected != actual;
+        diff
+    }
+
+    /// Diff the effects of a local replay against the on-chain effects, and the events it emitted
+    /// against the on-chain events if those were fetched.
+    pub fn from_sandbox(state: &ExecutionSandboxState) -> Self {
+        Self::new(
+            state.transaction_info.tx_digest,
+            &state.transaction_info.effects,
+            &state.local_exec_effects,
+            state.transaction_info.events.as_ref(),
+            state
+                .local_exec_temporary_store
+                .as_ref()
+                .map(|store| &store.events),
+        )
+    }
+
+    /// Diff the effects of two local replays of the same transaction, for example under two
+    /// different protocol versions.
+    pub fn between_sandboxes(
+        expected: &ExecutionSandboxState,
+        actual: &ExecutionSandboxState,
+    ) -> Self {
+        Self::new(
+            expected.transaction_info.tx_digest,
+            &expected.local_exec_effects,
+            &actual.local_exec_effects,
+            expected
+                .local_exec_temporary_store
+                .as_ref()
+                .map(|store| &store.events),
+            actual
+                .local_exec_temporary_store
+                .as_ref()
+                .map(|store| &store.events),
+        )
+    }
+
+    /// Whether the two executions produced the same effects.
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.gas.is_empty()
+            && self.objects.is_empty()
+            && self.events.is_empty()
+            && !self.other_differences
+    }
+}
+
+fn object_changes(effects: &SuiTransactionBlockEffects) -> BTreeMap<ObjectID, ObjectChange> {
+    let written = effects.all_changed_objects().into_iter().map(
+        |(OwnedObjectRef { owner, reference }, kind)| {
+            let kind = match kind {
+                WriteKind::Create => ObjectChangeKind::Created,
+                WriteKind::Mutate => ObjectChangeKind::Mutated,
+                WriteKind::Unwrap => ObjectChangeKind::Unwrapped,
+            };
+            (reference, kind, Some(owner.clone()))
+        },
+    );
+    let removed = effects
+        .all_deleted_objects()
+        .into_iter()
+        .map(|(reference, kind)| {
+            let kind = match kind {
+                DeleteKind::Normal => ObjectChangeKind::Deleted,
+                DeleteKind::Wrap => ObjectChangeKind::Wrapped,
+                DeleteKind::UnwrapThenDelete => ObjectChangeKind::UnwrappedThenDeleted,
+            };
+            (reference, kind, None)
+        });
+
+    written
+        .chain(removed)
+        .map(|(reference, kind, owner): (&SuiObjectRef, _, _)| {
+            (
+                reference.object_id,
+                ObjectChange {
+                    kind,
+                    version: reference.version,
+                    digest: reference.digest,
+                    owner,
+                },
+            )
+        })
+        .collect()
+}
+
+fn event_diffs(expected: &TransactionEvents, actual: &TransactionEvents) -> Vec<EventDiff> {
+    let summary = |events: &TransactionEvents, index: usize| {
+        events.data.get(index).map(|event| EventSummary {
+            type_: event.type_.to_canonical_string(/* with_prefix */ true),
+            contents: event.contents.clone(),
+        })
+    };
+
+    (0..expected.data.len().max(actual.data.len()))
+        .filter_map(|index| {
+            let expected = summary(expected, index);
+            let actual = summary(actual, index);
+            (expected != actual).then_some(EventDiff {
+                index,
+                expected,
+                actual,
+            })
+        })
+        .collect()
+}
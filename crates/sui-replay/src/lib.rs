@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_recursion::async_recursion;
-use clap::Parser;
+use clap::{Args, Parser};
 use config::ReplayableNetworkConfigSet;
 use fuzz::ReplayFuzzer;
 use fuzz::ReplayFuzzerConfig;
@@ -16,6 +16,7 @@ use sui_types::message_envelope::Message;
 use tracing::warn;
 use transaction_provider::{FuzzStartPoint, TransactionSource};
 
+use crate::batch_replay::ReplayVersions;
 use crate::config::get_rpc_url;
 use crate::replay::ExecutionSandboxState;
 use crate::replay::LocalExec;
@@ -30,9 +31,11 @@ use sui_protocol_config::Chain;
 use sui_types::digests::TransactionDigest;
 use tracing::{error, info};
 
+mod archive;
 pub mod batch_replay;
 pub mod config;
 mod data_fetcher;
+pub mod diff;
 mod displays;
 pub mod fuzz;
 pub mod fuzz_mutations;
@@ -110,6 +113,62 @@ pub enum ReplayToolCommand {
         config_objects: Option<Vec<String>>,
     },
 
+    /// Replay a transaction and write a JSON report of how its effects differ from the on-chain
+    /// effects, or from a replay under the baseline versions if any is given
+    #[command(name = "dt")]
+    DiffTransaction {
+        #[arg(long, short)]
+        tx_digest: String,
+        /// Optional version of the executor to use, if not specified defaults to the one originally used for the transaction.
+        #[arg(long, short, allow_hyphen_values = true)]
+        executor_version: Option<i64>,
+        /// Optional protocol version to use, if not specified defaults to the one originally used for the transaction.
+        #[arg(long, short, allow_hyphen_values = true)]
+        protocol_version: Option<i64>,
+        /// Optional local build of the system packages to use instead of the ones of the protocol
+        /// version, laid out like `sui-framework/packages_compiled`.
+        #[arg(long)]
+        framework_path: Option<PathBuf>,
+        #[command(flatten)]
+        baseline: DiffBaseline,
+        /// File to write the JSON report to, printed to stdout if not specified
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Replay all transactions in a range of checkpoints and write a JSON report aggregating how
+    /// their effects differ from the on-chain effects, or from replays under the baseline versions
+    /// if any is given. Fails if any transaction differs.
+    #[command(name = "dc")]
+    DiffCheckpoints {
+        #[arg(long, short)]
+        start: u64,
+        #[arg(long, short)]
+        end: u64,
+        /// Optional version of the executor to use, if not specified defaults to the one originally used for each transaction.
+        #[arg(long, allow_hyphen_values = true)]
+        executor_version: Option<i64>,
+        /// Optional protocol version to use, if not specified defaults to the one originally used for each transaction.
+        #[arg(long, short, allow_hyphen_values = true)]
+        protocol_version: Option<i64>,
+        /// Optional local build of the system packages to use instead of the ones of the protocol
+        /// version, laid out like `sui-framework/packages_compiled`.
+        #[arg(long)]
+        framework_path: Option<PathBuf>,
+        #[command(flatten)]
+        baseline: DiffBaseline,
+        #[arg(
+            long,
+            short,
+            default_value = "16",
+            help = "Number of tasks to run in parallel"
+        )]
+        num_tasks: u64,
+        /// File to write the JSON report to, printed to stdout if not specified
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
     /// Replay transactions listed in a file
     #[command(name = "rb")]
     ReplayBatch {
@@ -182,6 +241,35 @@ pub enum ReplayToolCommand {
         max_tasks: u64,
     },
 
+    /// Replay transactions from checkpoint files written by data ingestion, or from a `sui-archival`
+    /// archive, without accessing the network. Objects are reconstructed from the checkpoints
+    /// themselves, so the files must cover the checkpoints that wrote the objects the replayed
+    /// transactions read.
+    #[command(name = "lc")]
+    ReplayLocalCheckpoints {
+        /// Directory containing the `<sequence number>.chk` checkpoint files, or the archive
+        #[arg(long, short)]
+        path: PathBuf,
+        /// The directory is a `sui-archival` archive starting at genesis. Its transactions are
+        /// re-executed from genesis to rebuild their objects, which can take a while.
+        #[arg(long, conflicts_with = "chain_id")]
+        archive: bool,
+        /// Only replay this transaction, instead of every transaction in the checkpoint range
+        #[arg(long)]
+        tx_digest: Option<String>,
+        /// First checkpoint to replay, defaults to the first loaded checkpoint
+        #[arg(long, short)]
+        start: Option<u64>,
+        /// Last checkpoint to replay, defaults to the last loaded checkpoint
+        #[arg(long, short)]
+        end: Option<u64>,
+        /// Chain identifier of the network, only needed if checkpoint 0 is not among the files
+        #[arg(long)]
+        chain_id: Option<String>,
+        #[arg(long, short)]
+        terminate_early: bool,
+    },
+
     /// Run the replay based fuzzer
     #[command(name = "fz")]
     Fuzz {
@@ -197,6 +285,43 @@ pub enum ReplayToolCommand {
     Report,
 }
 
+/// Versions and framework build to replay transactions with when diffing against another replay,
+/// rather than against the on-chain effects.
+#[derive(Args, Clone, Debug, Default)]
+pub struct DiffBaseline {
+    /// Executor version of the baseline replay
+    #[arg(long, allow_hyphen_values = true)]
+    pub baseline_executor_version: Option<i64>,
+    /// Protocol version of the baseline replay
+    #[arg(long, allow_hyphen_values = true)]
+    pub baseline_protocol_version: Option<i64>,
+    /// Local build of the system packages used by the baseline replay
+    #[arg(long)]
+    pub baseline_framework_path: Option<PathBuf>,
+}
+
+impl DiffBaseline {
+    fn versions(&self) -> Option<ReplayVersions> {
+        (self.baseline_executor_version.is_some()
+            || self.baseline_protocol_version.is_some()
+            || self.baseline_framework_path.is_some())
+        .then(|| ReplayVersions {
+            executor_version: self.baseline_executor_version,
+            protocol_version: self.baseline_protocol_version,
+            framework_path: self.baseline_framework_path.clone(),
+        })
+    }
+}
+
+fn write_report<T: serde::Serialize>(report: &T, output: Option<PathBuf>) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(report)?;
+    match output {
+        Some(path) => std::fs::write(path, json)?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
 #[async_recursion]
 pub async fn execute_replay_command(
     rpc_url: Option<String>,
@@ -291,6 +416,121 @@ pub async fn execute_replay_command(
             info!("Execution finished successfully. Local and on-chain effects match.");
             Some((1u64, 1u64))
         }
+        ReplayToolCommand::ReplayLocalCheckpoints {
+            path,
+            archive,
+            tx_digest,
+            start,
+            end,
+            chain_id,
+            terminate_early,
+        } => {
+            let mut lx = if archive {
+                LocalExec::new_for_archive(&path).await?
+            } else {
+                LocalExec::new_for_checkpoints(&path, chain_id).await?
+            };
+
+            if let Some(tx_digest) = tx_digest {
+                let tx_digest = TransactionDigest::from_str(&tx_digest)?;
+                info!("Executing tx: {}", tx_digest);
+                let sandbox_state = lx
+                    .execute_transaction(&tx_digest, safety, use_authority, None, None, None, None)
+                    .await?;
+                sandbox_state.check_effects()?;
+                info!("Execution finished successfully. Local and on-chain effects match.");
+                return Ok(Some((1u64, 1u64)));
+            }
+
+            let checkpoints: Vec<_> = lx
+                .fetcher
+                .as_checkpoint()
+                .checkpoints()
+                .filter(|seq| start.map_or(true, |start| *seq >= start))
+                .filter(|seq| end.map_or(true, |end| *seq <= end))
+                .collect();
+            info!(
+                "Executing {} checkpoints from {}",
+                checkpoints.len(),
+                path.display()
+            );
+            let (succeeded, total) = lx
+                .execute_all_in_checkpoints(&checkpoints, &safety, terminate_early, use_authority)
+                .await?;
+            info!(
+                "Executed {} checkpoints @ {}/{} total TXs succeeded",
+                checkpoints.len(),
+                succeeded,
+                total
+            );
+            Some((succeeded, total))
+        }
+        ReplayToolCommand::DiffTransaction {
+            tx_digest,
+            executor_version,
+            protocol_version,
+            framework_path,
+            baseline,
+            output,
+        } => {
+            let tx_digest = TransactionDigest::from_str(&tx_digest)?;
+            info!("Diffing tx: {}", tx_digest);
+            let mut lx =
+                LocalExec::new_from_fn_url(&get_rpc_url(rpc_url, cfg_path, chain)?).await?;
+            let diff = batch_replay::diff_transaction(
+                &mut lx,
+                &tx_digest,
+                safety,
+                use_authority,
+                ReplayVersions {
+                    executor_version,
+                    protocol_version,
+                    framework_path,
+                },
+                baseline.versions(),
+            )
+            .await?;
+            write_report(&diff, output)?;
+            Some((diff.is_empty() as u64, 1u64))
+        }
+        ReplayToolCommand::DiffCheckpoints {
+            start,
+            end,
+            executor_version,
+            protocol_version,
+            framework_path,
+            baseline,
+            num_tasks,
+            output,
+        } => {
+            assert!(start <= end, "Start checkpoint must be <= end checkpoint");
+            assert!(num_tasks > 0, "Num tasks must be > 0");
+            let report = batch_replay::batch_diff_checkpoints(
+                start,
+                end,
+                num_tasks,
+                get_rpc_url(rpc_url, cfg_path, chain)?,
+                safety,
+                use_authority,
+                ReplayVersions {
+                    executor_version,
+                    protocol_version,
+                    framework_path,
+                },
+                baseline.versions(),
+            )
+            .await?;
+            write_report(&report, output)?;
+            if !report.is_clean() {
+                anyhow::bail!(
+                    "{} of {} transactions differ and {} failed to replay",
+                    report.diffs.len(),
+                    report.total,
+                    report.failed.len()
+                );
+            }
+            Some((report.matched, report.total))
+        }
         ReplayToolCommand::ReplayBatch {
             path,
             terminate_early,
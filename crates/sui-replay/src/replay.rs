@@ -4,7 +4,8 @@
 use crate::chain_from_chain_id;
 use crate::{
     data_fetcher::{
-        extract_epoch_and_version, DataFetcher, Fetchers, NodeStateDumpFetcher, RemoteFetcher,
+        extract_epoch_and_version, CheckpointFetcher, DataFetcher, Fetchers, NodeStateDumpFetcher,
+        RemoteFetcher,
     },
     displays::{
         transaction_displays::{transform_command_results_to_annotated, FullPTB},
@@ -25,7 +26,7 @@ use serde::{Deserialize, Serialize};
 use similar::{ChangeTag, TextDiff};
 use std::{
     collections::{BTreeMap, HashSet},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
     sync::Mutex,
 };
@@ -34,9 +35,10 @@ use sui_core::authority::NodeStateDump;
 use sui_execution::Executor;
 use sui_framework::BuiltInFramework;
 use sui_json_rpc_types::{
-    SuiExecutionStatus, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+    SuiExecutionStatus, SuiObjectRef, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockEvents,
 };
-use sui_protocol_config::{Chain, ProtocolConfig};
+use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
 use sui_sdk::{SuiClient, SuiClientBuilder};
 use sui_types::in_memory_storage::InMemoryStorage;
 use sui_types::message_envelope::Message;
@@ -47,7 +49,9 @@ use sui_types::{
     base_types::{ObjectID, ObjectRef, SequenceNumber, VersionNumber},
     committee::EpochId,
     digests::{ObjectDigest, TransactionDigest},
+    effects::TransactionEvents,
     error::{ExecutionError, SuiError, SuiResult},
+    event::Event,
     executable_transaction::VerifiedExecutableTransaction,
     gas::SuiGasStatus,
     inner_temporary_store::InnerTemporaryStore,
@@ -243,6 +247,9 @@ pub struct LocalExec {
     // filepath or the default current directory and name format for the profile output
     pub enable_profiler: Option<PathBuf>,
     pub config_and_versions: Option<Vec<(ObjectID, SequenceNumber)>>,
+    // One can optionally replace the system packages with a local build of the framework, laid out
+    // like `sui-framework/packages_compiled`
+    pub framework_path: Option<PathBuf>,
     // Retry policies due to RPC errors
     pub num_retries_for_timeout: u32,
     pub sleep_period_for_timeout: std::time::Duration,
@@ -391,6 +398,7 @@ impl LocalExec {
             protocol_version: None,
             enable_profiler: None,
             config_and_versions: None,
+            framework_path: None,
         })
     }
 
@@ -434,9 +442,82 @@ impl LocalExec {
             protocol_version: None,
             enable_profiler: None,
             config_and_versions: None,
+            framework_path: None,
         })
     }
 
+    /// Replay from the checkpoint files in the directory at `path`, without accessing the network.
+    ///
+    /// `chain_id` is only needed if checkpoint 0 is not part of the files.
+    pub async fn new_for_checkpoints(
+        path: &Path,
+        chain_id: Option<String>,
+    ) -> Result<Self, ReplayEngineError> {
+        Ok(Self::new_for_checkpoint_fetcher(
+            CheckpointFetcher::from_dir(path, chain_id)?,
+        ))
+    }
+
+    /// Replay from the `sui-archival` archive in the directory at `path`, without accessing the
+    /// network. The archive is re-executed from genesis to rebuild its objects when it is loaded.
+    pub async fn new_for_archive(path: &Path) -> Result<Self, ReplayEngineError> {
+        Ok(Self::new_for_checkpoint_fetcher(
+            CheckpointFetcher::from_archive(path)?,
+        ))
+    }
+
+    fn new_for_checkpoint_fetcher(fetcher: CheckpointFetcher) -> Self {
+        // Use a throwaway metrics registry for local execution.
+        let registry = prometheus::Registry::new();
+        let metrics = Arc::new(LimitsMetrics::new(&registry));
+
+        // The protocol version tables come straight from the epoch changes in the checkpoints,
+        // rather than from querying epoch change events
+        let mut protocol_version_epoch_table: BTreeMap<u64, ProtocolVersionSummary> =
+            BTreeMap::new();
+        for (epoch, info) in fetcher.epochs() {
+            protocol_version_epoch_table
+                .entry(info.protocol_version)
+                .and_modify(|summary| {
+                    summary.epoch_end = *epoch;
+                    summary.checkpoint_end = info.checkpoint_end;
+                })
+                .or_insert(ProtocolVersionSummary {
+                    protocol_version: info.protocol_version,
+                    epoch_start: *epoch,
+                    epoch_end: *epoch,
+                    checkpoint_start: Some(info.checkpoint_start),
+                    checkpoint_end: info.checkpoint_end,
+                    epoch_change_tx: info.epoch_change_tx,
+                });
+        }
+        let current_protocol_version = protocol_version_epoch_table
+            .keys()
+            .next_back()
+            .copied()
+            .unwrap_or_default();
+        let protocol_version_system_package_table = fetcher.system_package_versions().clone();
+
+        Self {
+            client: None,
+            protocol_version_epoch_table,
+            protocol_version_system_package_table,
+            current_protocol_version,
+            exec_store_events: Arc::new(Mutex::new(Vec::new())),
+            metrics,
+            storage: Storage::default(),
+            fetcher: Fetchers::Checkpoint(fetcher),
+            // TODO: make these configurable
+            num_retries_for_timeout: RPC_TIMEOUT_ERR_NUM_RETRIES,
+            sleep_period_for_timeout: RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD,
+            executor_version: None,
+            protocol_version: None,
+            enable_profiler: None,
+            config_and_versions: None,
+            framework_path: None,
+        }
+    }
+
     pub async fn multi_download_and_store(
         &mut self,
         objs: &[(ObjectID, SequenceNumber)],
@@ -480,6 +561,10 @@ impl LocalExec {
                 self.system_package_versions_for_protocol_version(protocol_version)?;
             self.multi_download(&syst_packages).await?
         };
+        let syst_packages_objs = match &self.framework_path {
+            Some(path) => override_system_packages(path, syst_packages_objs)?,
+            None => syst_packages_objs,
+        };
 
         // Download latest version of all packages that are not system packages
         // This is okay since the versions can never change
@@ -873,10 +958,13 @@ impl LocalExec {
         );
         }
 
-        let tx_info = if self.is_remote_replay() {
-            self.resolve_tx_components(tx_digest).await?
-        } else {
-            self.resolve_tx_components_from_dump(tx_digest).await?
+        let tx_info = match &self.fetcher {
+            Fetchers::Remote(_) => self.resolve_tx_components(tx_digest).await?,
+            Fetchers::NodeStateDump(_) => self.resolve_tx_components_from_dump(tx_digest).await?,
+            Fetchers::Checkpoint(_) => {
+                self.resolve_tx_components_from_checkpoint(tx_digest)
+                    .await?
+            }
         };
         self.execution_engine_execute_with_tx_info_impl(
             &tx_info,
@@ -1096,7 +1184,7 @@ impl LocalExec {
         protocol_version: u64,
     ) -> Result<Vec<(ObjectID, SequenceNumber)>, ReplayEngineError> {
         match &self.fetcher {
-            Fetchers::Remote(_) => Ok(self
+            Fetchers::Remote(_) | Fetchers::Checkpoint(_) => Ok(self
                 .protocol_version_system_package_table
                 .get(&protocol_version)
                 .ok_or(ReplayEngineError::FrameworkObjectVersionTableNotPopulated {
@@ -1484,19 +1572,7 @@ impl LocalExec {
         // Download the objects at the version right before the execution of this TX
         let modified_at_versions: Vec<(ObjectID, SequenceNumber)> = effects.modified_at_versions();
 
-        let shared_object_refs: Vec<ObjectRef> = effects
-            .shared_objects()
-            .iter()
-            .map(|so_ref| {
-                if so_ref.digest == ObjectDigest::OBJECT_DIGEST_DELETED {
-                    unimplemented!(
-                        "Replay of deleted shared object transactions is not supported yet"
-                    );
-                } else {
-                    so_ref.to_object_ref()
-                }
-            })
-            .collect();
+        let shared_object_refs = shared_object_refs(tx_digest, effects.shared_objects())?;
         let gas_data = match tx_info.clone().transaction.unwrap().data {
             sui_json_rpc_types::SuiTransactionBlockData::V1(tx) => tx.gas_data,
         };
@@ -1532,6 +1608,7 @@ impl LocalExec {
             executed_epoch: epoch_id,
             dependencies: effects.dependencies().to_vec(),
             effects: SuiTransactionBlockEffects::V1(effects),
+            events: tx_info.events.map(transaction_events),
             receiving_objs,
             config_objects,
             // Find the protocol version for this epoch
@@ -1552,20 +1629,59 @@ impl LocalExec {
         assert!(!self.is_remote_replay());
 
         let dp = self.fetcher.as_node_state_dump();
+        let effects =
+            SuiTransactionBlockEffects::try_from(dp.node_state_dump.computed_effects.clone())?;
 
-        let sender = dp
-            .node_state_dump
-            .sender_signed_data
-            .transaction_data()
-            .sender();
-        let orig_tx = dp.node_state_dump.sender_signed_data.clone();
-        let effects = dp.node_state_dump.computed_effects.clone();
-        let effects = SuiTransactionBlockEffects::try_from(effects).unwrap();
-        // Config objects don't show up in the node state dump so they need to be provided.
-        let config_objects = self.add_config_objects_if_needed(effects.status());
+        self.resolve_tx_components_from_local_data(
+            tx_digest,
+            dp.node_state_dump.sender_signed_data.clone(),
+            effects,
+            // Events are not part of the dump
+            None,
+            dp.node_state_dump.executed_epoch,
+            Some(dp.node_state_dump.protocol_version.into()),
+        )
+        .await
+    }
 
-        // Fetch full transaction content
-        //let tx_info = self.fetcher.get_transaction(tx_digest).await?;
+    async fn resolve_tx_components_from_checkpoint(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<OnChainTransactionInfo, ReplayEngineError> {
+        let (_, _, tx) = self
+            .fetcher
+            .as_checkpoint()
+            .checkpoint_transaction(tx_digest)?;
+        let effects = SuiTransactionBlockEffects::try_from(tx.effects.clone())?;
+        let epoch_id = effects.executed_epoch();
+
+        // The protocol version tables are populated from the checkpoints on creation
+        self.resolve_tx_components_from_local_data(
+            tx_digest,
+            tx.transaction.data().clone(),
+            effects,
+            tx.events.clone(),
+            epoch_id,
+            None,
+        )
+        .await
+    }
+
+    /// Builds the transaction info from a transaction and its effects that are available locally,
+    /// as opposed to fetched from an RPC node. The protocol version is looked up from the epoch
+    /// if it's not given.
+    async fn resolve_tx_components_from_local_data(
+        &self,
+        tx_digest: &TransactionDigest,
+        orig_tx: SenderSignedData,
+        effects: SuiTransactionBlockEffects,
+        events: Option<TransactionEvents>,
+        epoch_id: EpochId,
+        protocol_version: Option<ProtocolVersion>,
+    ) -> Result<OnChainTransactionInfo, ReplayEngineError> {
+        let sender = orig_tx.transaction_data().sender();
+        // Config objects don't show up in local data so they need to be provided.
+        let config_objects = self.add_config_objects_if_needed(effects.status());
 
         let input_objs = orig_tx
             .transaction_data()
@@ -1576,19 +1692,7 @@ impl LocalExec {
         // Download the objects at the version right before the execution of this TX
         let modified_at_versions: Vec<(ObjectID, SequenceNumber)> = effects.modified_at_versions();
 
-        let shared_object_refs: Vec<ObjectRef> = effects
-            .shared_objects()
-            .iter()
-            .map(|so_ref| {
-                if so_ref.digest == ObjectDigest::OBJECT_DIGEST_DELETED {
-                    unimplemented!(
-                        "Replay of deleted shared object transactions is not supported yet"
-                    );
-                } else {
-                    so_ref.to_object_ref()
-                }
-            })
-            .collect();
+        let shared_object_refs = shared_object_refs(tx_digest, effects.shared_objects())?;
         let gas_data = orig_tx.transaction_data().gas_data();
         let gas_object_refs: Vec<_> = gas_data.clone().payment.into_iter().collect();
         let receiving_objs = orig_tx
@@ -1598,12 +1702,12 @@ impl LocalExec {
             .map(|(obj_id, version, _)| (obj_id, version))
             .collect();
 
-        let epoch_id = dp.node_state_dump.executed_epoch;
-
         let chain = chain_from_chain_id(self.fetcher.get_chain_id().await?.as_str());
 
-        let protocol_config =
-            ProtocolConfig::get_for_version(dp.node_state_dump.protocol_version.into(), chain);
+        let protocol_version = match protocol_version {
+            Some(version) => ProtocolConfig::get_for_version(version, chain).version,
+            None => self.get_protocol_config(epoch_id, chain).await?.version,
+        };
         // Extract the epoch start timestamp
         let (epoch_start_timestamp, reference_gas_price) = self
             .get_epoch_start_timestamp_and_rgp(epoch_id, tx_digest)
@@ -1621,9 +1725,10 @@ impl LocalExec {
             executed_epoch: epoch_id,
             dependencies: effects.dependencies().to_vec(),
             effects,
+            events,
             receiving_objs,
             config_objects,
-            protocol_version: protocol_config.version,
+            protocol_version,
             tx_digest: *tx_digest,
             epoch_start_timestamp,
             sender_signed_data: orig_tx.clone(),
@@ -2166,6 +2271,101 @@ pub fn get_executor(
         .expect("Creating an executor should not fail here")
 }
 
+fn shared_object_refs(
+    tx_digest: &TransactionDigest,
+    shared_objects: &[SuiObjectRef],
+) -> Result<Vec<ObjectRef>, ReplayEngineError> {
+    shared_objects
+        .iter()
+        .map(|so_ref| {
+            if so_ref.digest == ObjectDigest::OBJECT_DIGEST_DELETED {
+                Err(ReplayEngineError::TransactionNotSupported {
+                    digest: *tx_digest,
+                    reason: "Replay of deleted shared object transactions is not supported yet"
+                        .to_string(),
+                })
+            } else {
+                Ok(so_ref.to_object_ref())
+            }
+        })
+        .collect()
+}
+
+/// The events emitted by a transaction, as fetched from an RPC node.
+fn transaction_events(events: SuiTransactionBlockEvents) -> TransactionEvents {
+    TransactionEvents {
+        data: events
+            .data
+            .into_iter()
+            .map(|event| Event {
+                package_id: event.package_id,
+                transaction_module: event.transaction_module,
+                sender: event.sender,
+                type_: event.type_,
+                contents: event.bcs,
+            })
+            .collect(),
+    }
+}
+
+/// Load a local build of the system packages from the directory at `path`, laid out like
+/// `sui-framework/packages_compiled`: one file per package holding the BCS-encoded bytes of its
+/// modules.
+pub fn load_framework_packages(
+    path: &Path,
+) -> Result<BTreeMap<ObjectID, Vec<CompiledModule>>, ReplayEngineError> {
+    let error = |err: String| ReplayEngineError::UnableToReadFramework {
+        path: path.display().to_string(),
+        err,
+    };
+    let mut packages = BTreeMap::new();
+    for entry in std::fs::read_dir(path).map_err(|e| error(e.to_string()))? {
+        let file = entry.map_err(|e| error(e.to_string()))?.path();
+        let bytes = std::fs::read(&file).map_err(|e| error(e.to_string()))?;
+        let modules = bcs::from_bytes::<Vec<Vec<u8>>>(&bytes)
+            .map_err(|e| error(format!("{}: {e}", file.display())))?
+            .iter()
+            .map(|b| CompiledModule::deserialize_with_defaults(b))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| error(format!("{}: {e}", file.display())))?;
+        let Some(module) = modules.first() else {
+            return Err(error(format!("{} has no modules", file.display())));
+        };
+        let id = ObjectID::from(*module.address());
+        if !BuiltInFramework::all_package_ids().contains(&id) {
+            return Err(error(format!(
+                "{} is not a system package: {id}",
+                file.display()
+            )));
+        }
+        packages.insert(id, modules);
+    }
+    Ok(packages)
+}
+
+/// Replace the system packages in `objs` with the local build at `path`. The replaced packages
+/// keep their on-chain version, so that they are used wherever the on-chain ones would be.
+fn override_system_packages(
+    path: &Path,
+    objs: Vec<Object>,
+) -> Result<Vec<Object>, ReplayEngineError> {
+    let mut local = load_framework_packages(path)?;
+    Ok(objs
+        .into_iter()
+        .map(|obj| match local.remove(&obj.id()) {
+            Some(modules) => Object::new_system_package(
+                &modules,
+                obj.version(),
+                BuiltInFramework::get_package_by_id(&obj.id())
+                    .dependencies()
+                    .to_vec(),
+                obj.previous_transaction,
+            ),
+            None => obj,
+        })
+        .collect())
+}
+
 fn parse_effect_error_for_denied_coins(status: &SuiExecutionStatus) -> Option<String> {
     let SuiExecutionStatus::Failure { error } = status else {
         return None;
@@ -1,16 +1,33 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::batch_replay::{diff_transaction, diff_transactions, DiffReport, ReplayVersions};
 use crate::config::ReplayableNetworkConfigSet;
+use crate::diff::EffectsDiff;
 use crate::types::ReplayEngineError;
 use crate::types::{MAX_CONCURRENT_REQUESTS, RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD};
 use crate::LocalExec;
+use simulacrum::Simulacrum;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sui_archival::read_manifest_from_bytes;
+use sui_archival::writer::ArchiveWriter;
 use sui_config::node::ExpensiveSafetyCheckConfig;
+use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+use sui_framework::BuiltInFramework;
 use sui_json_rpc_api::QUERY_MAX_RESULT_LIMIT;
-use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
+use sui_json_rpc_types::{SuiTransactionBlockEffects, SuiTransactionBlockResponseOptions};
 use sui_sdk::{SuiClient, SuiClientBuilder};
-use sui_types::base_types::SuiAddress;
+use sui_storage::blob::Blob;
+use sui_storage::{FileCompression, StorageFormat};
+use sui_types::base_types::{ExecutionData, SuiAddress};
 use sui_types::digests::TransactionDigest;
+use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::messages_checkpoint::{
+    CheckpointSequenceNumber, FullCheckpointContents, VerifiedCheckpoint,
+    VerifiedCheckpointContents,
+};
+use sui_types::storage::{SharedInMemoryStore, WriteStore};
 
 /// Keep searching for non-system TXs in the checkppints for this long
 /// Very unlikely to take this long, but we want to be sure we find one
@@ -98,6 +115,280 @@ async fn verify_latest_tx_replay_impl() {
     }
 }
 
+/// Replays a transaction from checkpoint files, without accessing the network
+#[tokio::test]
+async fn replay_from_local_checkpoints() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut sim = Simulacrum::new();
+    sim.set_data_ingestion_path(dir.path().to_path_buf());
+    // Transactions from epoch 0 are not supported by replay
+    sim.advance_epoch(/* create_random_state */ false);
+
+    let (tx, _) = sim.transfer_txn(SuiAddress::random_for_testing_only());
+    let tx_digest = *tx.digest();
+    sim.execute_transaction(tx).unwrap();
+    sim.create_checkpoint();
+
+    for use_authority in [true, false] {
+        LocalExec::new_for_checkpoints(dir.path(), None)
+            .await
+            .unwrap()
+            .execute_transaction(
+                &tx_digest,
+                ExpensiveSafetyCheckConfig::default(),
+                use_authority,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+            .check_effects()
+            .unwrap();
+    }
+}
+
+/// Replays a transaction from a `sui-archival` archive, whose objects are rebuilt by re-executing
+/// the archive from genesis
+#[tokio::test]
+async fn replay_from_local_archive() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut sim = Simulacrum::new();
+    sim.set_data_ingestion_path(dir.path().to_path_buf());
+    sim.advance_epoch(/* create_random_state */ false);
+
+    let (tx, _) = sim.transfer_txn(SuiAddress::random_for_testing_only());
+    let tx_digest = *tx.digest();
+    sim.execute_transaction(tx).unwrap();
+    let checkpoint = sim.create_checkpoint();
+    // The archive writer only archives a checkpoint once the next one is available
+    sim.create_checkpoint();
+
+    let archive = tempfile::tempdir().unwrap();
+    let archive_path = write_archive(dir.path(), archive.path(), checkpoint.sequence_number).await;
+
+    let mut executor = LocalExec::new_for_archive(&archive_path).await.unwrap();
+    assert!(executor
+        .fetcher
+        .as_checkpoint()
+        .checkpoints()
+        .eq(0..=checkpoint.sequence_number));
+    executor
+        .execute_transaction(
+            &tx_digest,
+            ExpensiveSafetyCheckConfig::default(),
+            false,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap()
+        .check_effects()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn diff_replayed_effects() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut sim = Simulacrum::new();
+    sim.set_data_ingestion_path(dir.path().to_path_buf());
+    sim.advance_epoch(/* create_random_state */ false);
+
+    let (tx, _) = sim.transfer_txn(SuiAddress::random_for_testing_only());
+    let tx_digest = *tx.digest();
+    sim.execute_transaction(tx).unwrap();
+    sim.create_checkpoint();
+
+    let mut sandbox_state = LocalExec::new_for_checkpoints(dir.path(), None)
+        .await
+        .unwrap()
+        .execute_transaction(
+            &tx_digest,
+            ExpensiveSafetyCheckConfig::default(),
+            false,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let diff = EffectsDiff::from_sandbox(&sandbox_state);
+    assert!(diff.is_empty(), "{diff:?}");
+
+    let SuiTransactionBlockEffects::V1(effects) = &mut sandbox_state.local_exec_effects;
+    effects.gas_used.computation_cost += 10;
+    effects.created.clear();
+    let diff = EffectsDiff::from_sandbox(&sandbox_state);
+    assert_eq!(diff.tx_digest, tx_digest);
+    assert!(diff.status.is_none());
+    assert_eq!(diff.gas.computation_cost.as_ref().unwrap().delta, 10);
+    assert!(diff.gas.storage_cost.is_none());
+    assert_eq!(diff.objects.len(), 1);
+    assert!(diff.objects[0].expected.is_some());
+    assert!(diff.objects[0].actual.is_none());
+}
+
+/// A transaction that fails to replay is recorded in the report, and does not hold up the others
+#[tokio::test]
+async fn diff_report_records_failed_transactions() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut sim = Simulacrum::new();
+    sim.set_data_ingestion_path(dir.path().to_path_buf());
+    sim.advance_epoch(/* create_random_state */ false);
+
+    let (tx, _) = sim.transfer_txn(SuiAddress::random_for_testing_only());
+    let tx_digest = *tx.digest();
+    sim.execute_transaction(tx).unwrap();
+    sim.create_checkpoint();
+
+    // Not part of the checkpoints, so replaying it fails every time
+    let missing = TransactionDigest::random();
+    let executor = LocalExec::new_for_checkpoints(dir.path(), None)
+        .await
+        .unwrap();
+    let report = diff_transactions(
+        vec![executor],
+        vec![missing, tx_digest],
+        ExpensiveSafetyCheckConfig::default(),
+        false,
+        DiffReport::default(),
+    )
+    .await;
+
+    assert_eq!(report.total, 2);
+    assert_eq!(report.matched, 1);
+    assert!(report.diffs.is_empty());
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].tx_digest, missing);
+    assert!(!report.is_clean());
+}
+
+#[tokio::test]
+async fn diff_against_framework_build() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut sim = Simulacrum::new();
+    sim.set_data_ingestion_path(dir.path().to_path_buf());
+    sim.advance_epoch(/* create_random_state */ false);
+
+    let (tx, _) = sim.transfer_txn(SuiAddress::random_for_testing_only());
+    let tx_digest = *tx.digest();
+    sim.execute_transaction(tx).unwrap();
+    sim.create_checkpoint();
+
+    // A build of the same framework the transaction ran with does not change its effects
+    let framework = tempfile::tempdir().unwrap();
+    for package in BuiltInFramework::iter_system_packages() {
+        std::fs::write(
+            framework.path().join(package.id().to_hex_uncompressed()),
+            bcs::to_bytes(package.bytes()).unwrap(),
+        )
+        .unwrap();
+    }
+    let mut executor = LocalExec::new_for_checkpoints(dir.path(), None)
+        .await
+        .unwrap();
+    let versions = ReplayVersions {
+        framework_path: Some(framework.path().to_path_buf()),
+        ..Default::default()
+    };
+    let diff = diff_transaction(
+        &mut executor,
+        &tx_digest,
+        ExpensiveSafetyCheckConfig::default(),
+        false,
+        versions.clone(),
+        Some(ReplayVersions::default()),
+    )
+    .await
+    .unwrap();
+    assert!(diff.is_empty(), "{diff:?}");
+
+    // The build is actually loaded when replaying
+    std::fs::write(framework.path().join("broken"), [1, 2, 3]).unwrap();
+    let err = diff_transaction(
+        &mut executor,
+        &tx_digest,
+        ExpensiveSafetyCheckConfig::default(),
+        false,
+        versions,
+        None,
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        matches!(err, ReplayEngineError::UnableToReadFramework { .. }),
+        "{err:?}"
+    );
+}
+
+/// Archive the checkpoint files in `checkpoints` with `sui-archival`, writing the archive under
+/// `dir`, and wait until every checkpoint up to `last` is archived. Returns the archive's path.
+async fn write_archive(checkpoints: &Path, dir: &Path, last: CheckpointSequenceNumber) -> PathBuf {
+    let store = SharedInMemoryStore::default();
+    for sequence_number in 0.. {
+        let Ok(bytes) = std::fs::read(checkpoints.join(format!("{sequence_number}.chk"))) else {
+            break;
+        };
+        let checkpoint: CheckpointData = Blob::from_bytes(&bytes).unwrap();
+        let summary = VerifiedCheckpoint::new_unchecked(checkpoint.checkpoint_summary);
+        let contents = FullCheckpointContents::from_contents_and_execution_data(
+            checkpoint.checkpoint_contents,
+            checkpoint
+                .transactions
+                .into_iter()
+                .map(|tx| ExecutionData::new(tx.transaction, tx.effects)),
+        );
+        store.insert_checkpoint(&summary).unwrap();
+        store
+            .insert_checkpoint_contents(
+                &summary,
+                VerifiedCheckpointContents::new_unchecked(contents),
+            )
+            .unwrap();
+    }
+
+    let store_config = |name: &str| ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(dir.join(name)),
+        ..Default::default()
+    };
+    let writer = ArchiveWriter::new(
+        store_config("staging"),
+        store_config("archive"),
+        FileCompression::Zstd,
+        StorageFormat::Blob,
+        Duration::from_secs(60),
+        // Cut a new file for every checkpoint
+        0,
+        &prometheus::Registry::new(),
+    )
+    .await
+    .unwrap();
+    let kill = writer.start(store).await.unwrap();
+
+    let archive = dir.join("archive");
+    tokio::time::timeout(Duration::from_secs(60), async {
+        loop {
+            let next_checkpoint = std::fs::read(archive.join("MANIFEST"))
+                .ok()
+                .and_then(|bytes| read_manifest_from_bytes(bytes).ok())
+                .map_or(0, |manifest| manifest.next_checkpoint_seq_num());
+            if next_checkpoint > last {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("checkpoints were not archived in time");
+    kill.send(()).unwrap();
+    archive
+}
+
 async fn extract_one_system_tx(
     rpc_client: &SuiClient,
     mut txs: Vec<TransactionDigest>,
@@ -13,7 +13,8 @@ use sui_json_rpc_types::SuiTransactionBlockEffects;
 use sui_protocol_config::{Chain, ProtocolVersion};
 use sui_sdk::error::Error as SuiRpcError;
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, VersionNumber};
-use sui_types::digests::{ObjectDigest, TransactionDigest};
+use sui_types::digests::{ObjectDigest, TransactionDigest, TransactionEffectsDigest};
+use sui_types::effects::TransactionEvents;
 use sui_types::error::{SuiError, SuiObjectResponseError, SuiResult, UserInputError};
 use sui_types::object::Object;
 use sui_types::transaction::{InputObjectKind, SenderSignedData, TransactionKind};
@@ -59,6 +60,9 @@ pub struct OnChainTransactionInfo {
     // 2. This data structure is not bcs/bincode friendly. It makes it much more expensive to
     // store the sandbox state for batch replay.
     pub effects: SuiTransactionBlockEffects,
+    /// Events emitted on-chain, if they are known
+    #[serde(skip)]
+    pub events: Option<TransactionEvents>,
     pub protocol_version: ProtocolVersion,
     pub epoch_start_timestamp: u64,
     pub reference_gas_price: u64,
@@ -199,6 +203,27 @@ pub enum ReplayEngineError {
 
     #[error("Unable to get chain id: {}", err)]
     UnableToGetChainId { err: String },
+
+    #[error("Unable to read checkpoints from {}: {}", path, err)]
+    UnableToReadCheckpoints { path: String, err: String },
+
+    #[error("Unable to read framework packages from {}: {}", path, err)]
+    UnableToReadFramework { path: String, err: String },
+
+    #[error("Checkpoint {sequence_number} not found")]
+    CheckpointNotFound { sequence_number: u64 },
+
+    #[error("Transaction {digest} not found")]
+    TransactionNotFound { digest: TransactionDigest },
+
+    #[error(
+        "Re-executing transaction {digest} gave effects {local}, but the archive has {archived}"
+    )]
+    ArchivedEffectsMismatch {
+        digest: TransactionDigest,
+        archived: TransactionEffectsDigest,
+        local: TransactionEffectsDigest,
+    },
 }
 
 impl From<SuiObjectResponseError> for ReplayEngineError {
@@ -4,7 +4,8 @@
 use axum::extract::State;
 use axum::{Extension, Json};
 use axum_extra::extract::WithRejection;
-use futures::{future::join_all, StreamExt};
+use futures::future::{join_all, try_join_all};
+use futures::StreamExt;
 
 use sui_sdk::rpc_types::StakeStatus;
 use sui_sdk::{SuiClient, SUI_COIN_TYPE};
@@ -79,7 +80,14 @@ async fn get_balances(
         let account_type = sub_account.account_type.clone();
         get_sub_account_balances(account_type, &ctx.client, address).await
     } else if !currencies.0.is_empty() {
-        let balance_futures = currencies.0.iter().map(|currency| {
+        let currencies = try_join_all(
+            currencies
+                .0
+                .iter()
+                .map(|currency| ctx.coin_metadata_cache.resolve_currency(currency)),
+        )
+        .await?;
+        let balance_futures = currencies.iter().map(|currency| {
             let coin_type = currency.metadata.clone().coin_type.clone();
             async move {
                 (
@@ -8,7 +8,7 @@ use axum::{Extension, Json};
 use axum_extra::extract::WithRejection;
 use fastcrypto::encoding::{Encoding, Hex};
 use fastcrypto::hash::HashFunction;
-use futures::StreamExt;
+use futures::{future, StreamExt};
 
 use shared_crypto::intent::{Intent, IntentMessage};
 use sui_json_rpc_types::{
@@ -16,7 +16,7 @@ use sui_json_rpc_types::{
     SuiTransactionBlockResponseOptions,
 };
 use sui_sdk::rpc_types::SuiExecutionStatus;
-use sui_types::base_types::{ObjectRef, SuiAddress};
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
 use sui_types::crypto::{DefaultHash, SignatureScheme, ToFromBytes};
 use sui_types::error::SuiError;
 use sui_types::signature::{GenericSignature, VerifyParams};
@@ -239,7 +239,12 @@ pub async fn metadata(
     let budget = option.budget;
     let sender = option.internal_operation.sender();
     let currency = match &option.internal_operation {
-        InternalOperation::PayCoin { currency, .. } => Some(currency.clone()),
+        InternalOperation::PayCoin { currency, .. } => Some(
+            context
+                .coin_metadata_cache
+                .resolve_currency(currency)
+                .await?,
+        ),
         _ => None,
     };
     let coin_type = currency.as_ref().map(|c| c.metadata.coin_type.clone());
@@ -300,20 +305,18 @@ pub async fn metadata(
                 return Err(Error::InvalidInput("No active stake to withdraw".into()));
             }
 
-            let responses = context
-                .client
-                .read_api()
-                .multi_get_object_with_options(stake_ids, SuiObjectDataOptions::default())
-                .await?;
-            let stake_refs = responses
-                .into_iter()
-                .map(|stake| stake.into_object().map(|o| o.object_ref()))
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(SuiError::from)?;
-
-            (Some(0), stake_refs)
+            (Some(0), get_object_refs(&context, stake_ids).await?)
+        }
+        InternalOperation::PartialWithdrawStake { .. }
+        | InternalOperation::MergeCoins { .. }
+        | InternalOperation::SplitCoins { .. }
+        | InternalOperation::MoveCall { .. } => {
+            let object_ids = option.internal_operation.input_objects();
+            (Some(0), get_object_refs(&context, object_ids).await?)
         }
     };
+    // Objects used by the operation cannot also be used to pay for gas.
+    let exclude = objects.iter().map(|(id, _, _)| *id).collect::<Vec<_>>();
 
     // Get budget for suggested_fee and metadata.budget
     let budget = match budget {
@@ -355,7 +358,7 @@ pub async fn metadata(
         context
             .client
             .coin_read_api()
-            .select_coins(sender, None, total_amount.into(), vec![])
+            .select_coins(sender, None, total_amount.into(), exclude.clone())
             .await
             .ok()
     } else {
@@ -370,6 +373,7 @@ pub async fn metadata(
             .client
             .coin_read_api()
             .get_coins_stream(sender, None)
+            .filter(|coin| future::ready(!exclude.contains(&coin.coin_object_id)))
             .collect::<Vec<_>>()
             .await
     };
@@ -395,6 +399,23 @@ pub async fn metadata(
     })
 }
 
+/// Fetch the latest references of the given objects.
+async fn get_object_refs(
+    context: &OnlineServerContext,
+    object_ids: Vec<ObjectID>,
+) -> Result<Vec<ObjectRef>, Error> {
+    let responses = context
+        .client
+        .read_api()
+        .multi_get_object_with_options(object_ids, SuiObjectDataOptions::default())
+        .await?;
+    Ok(responses
+        .into_iter()
+        .map(|object| object.into_object().map(|o| o.object_ref()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(SuiError::from)?)
+}
+
 ///  This is run as a sanity check before signing (after /construction/payloads)
 /// and before broadcast (after /construction/combine).
 ///
@@ -83,6 +83,9 @@ pub enum Error {
     #[error("Retries exhausted while getting balance. try again.")]
     #[strum(props(retriable = "true"))]
     RetryExhausted(String),
+
+    #[error("Unsupported currency: {0}")]
+    UnsupportedCurrency(String),
 }
 
 impl Serialize for ErrorType {
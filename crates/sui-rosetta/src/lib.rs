@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
 use std::string::ToString;
@@ -15,6 +16,8 @@ use tokio::sync::Mutex;
 use tracing::info;
 
 use sui_sdk::{SuiClient, SUI_COIN_TYPE};
+use sui_types::gas_coin::GAS;
+use sui_types::parse_sui_type_tag;
 
 use crate::errors::Error;
 use crate::errors::Error::MissingMetadata;
@@ -49,8 +52,11 @@ pub struct RosettaOnlineServer {
 }
 
 impl RosettaOnlineServer {
-    pub fn new(env: SuiEnv, client: SuiClient) -> Self {
-        let coin_cache = CoinMetadataCache::new(client.clone(), NonZeroUsize::new(1000).unwrap());
+    /// Create an online server supporting SUI and the given coin types. Any coin type with
+    /// `CoinMetadata` is supported if `coin_types` is empty.
+    pub fn new(env: SuiEnv, client: SuiClient, coin_types: Vec<TypeTag>) -> Self {
+        let coin_cache = CoinMetadataCache::new(client.clone(), NonZeroUsize::new(1000).unwrap())
+            .with_supported_coin_types(coin_types);
         let blocks = Arc::new(CheckpointBlockProvider::new(
             client.clone(),
             coin_cache.clone(),
@@ -121,6 +127,8 @@ impl RosettaOfflineServer {
 pub struct CoinMetadataCache {
     client: SuiClient,
     metadata: Arc<Mutex<LruCache<TypeTag, Currency>>>,
+    /// Coin types supported on top of SUI, all coin types are supported if empty.
+    supported_coin_types: Arc<Vec<TypeTag>>,
 }
 
 impl CoinMetadataCache {
@@ -128,9 +136,48 @@ impl CoinMetadataCache {
         Self {
             client,
             metadata: Arc::new(Mutex::new(LruCache::new(size))),
+            supported_coin_types: Default::default(),
         }
     }
 
+    pub fn with_supported_coin_types(mut self, coin_types: Vec<TypeTag>) -> Self {
+        let mut seen = HashSet::new();
+        let coin_types = coin_types
+            .into_iter()
+            .filter(|coin_type| *coin_type != GAS::type_tag() && seen.insert(coin_type.clone()))
+            .collect();
+        self.supported_coin_types = Arc::new(coin_types);
+        self
+    }
+
+    pub fn is_supported(&self, coin_type: &TypeTag) -> bool {
+        self.supported_coin_types.is_empty()
+            || *coin_type == GAS::type_tag()
+            || self.supported_coin_types.contains(coin_type)
+    }
+
+    /// Resolve a currency requested by a client, its symbol and decimals are taken from the
+    /// coin type's `CoinMetadata`.
+    pub async fn resolve_currency(&self, currency: &Currency) -> Result<Currency, Error> {
+        let coin_type = parse_sui_type_tag(&currency.metadata.coin_type)
+            .map_err(|e| Error::InvalidInput(format!("Invalid coin type: {e}")))?;
+        if !self.is_supported(&coin_type) {
+            return Err(Error::UnsupportedCurrency(
+                currency.metadata.coin_type.clone(),
+            ));
+        }
+        let resolved = if coin_type == GAS::type_tag() {
+            SUI.clone()
+        } else {
+            self.get_currency(&coin_type).await?
+        };
+        Ok(Currency {
+            symbol: resolved.symbol,
+            decimals: resolved.decimals,
+            metadata: currency.metadata.clone(),
+        })
+    }
+
     pub async fn get_currency(&self, type_tag: &TypeTag) -> Result<Currency, Error> {
         let mut cache = self.metadata.lock().await;
         if !cache.contains(type_tag) {
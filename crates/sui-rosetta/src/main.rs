@@ -3,8 +3,6 @@
 
 use std::collections::BTreeMap;
 use std::fs;
-use std::fs::File;
-use std::io::BufReader;
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
@@ -12,15 +10,16 @@ use std::time::Duration;
 use anyhow::anyhow;
 use clap::Parser;
 use fastcrypto::encoding::{Encoding, Hex};
-use fastcrypto::traits::EncodeDecodeBase64;
+use move_core_types::language_storage::TypeTag;
 use serde_json::{json, Value};
 use sui_config::{sui_config_dir, Config, NodeConfig, SUI_FULLNODE_CONFIG, SUI_KEYSTORE_FILENAME};
+use sui_keys::keystore::{AccountKeystore, Keystore};
 use sui_node::SuiNode;
 use sui_rosetta::types::{CurveType, PrefundedAccount, SuiEnv};
 use sui_rosetta::{RosettaOfflineServer, RosettaOnlineServer, SUI};
 use sui_sdk::{SuiClient, SuiClientBuilder};
-use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{KeypairTraits, SuiKeyPair, ToFromBytes};
+use sui_types::parse_sui_type_tag;
 use tracing::info;
 use tracing::log::warn;
 
@@ -46,6 +45,10 @@ pub enum RosettaServerCommand {
         full_node_url: String,
         #[clap(long, default_value = "/data")]
         data_path: PathBuf,
+        /// Coin types supported in addition to SUI, e.g. `0x...::usdc::USDC`. Can be repeated,
+        /// all coin types are supported if none is given.
+        #[clap(long = "coin-type", value_parser = parse_sui_type_tag)]
+        coin_types: Vec<TypeTag>,
     },
     StartOnlineServer {
         #[clap(long, default_value = "localnet")]
@@ -56,6 +59,10 @@ pub enum RosettaServerCommand {
         node_config: Option<PathBuf>,
         #[clap(long, default_value = "/data")]
         data_path: PathBuf,
+        /// Coin types supported in addition to SUI, e.g. `0x...::usdc::USDC`. Can be repeated,
+        /// all coin types are supported if none is given.
+        #[clap(long = "coin-type", value_parser = parse_sui_type_tag)]
+        coin_types: Vec<TypeTag>,
     },
     StartOfflineServer {
         #[clap(long, default_value = "localnet")]
@@ -136,6 +143,7 @@ impl RosettaServerCommand {
                 addr,
                 full_node_url,
                 data_path,
+                coin_types,
             } => {
                 info!(
                     "Starting Rosetta Online Server with remove Sui full node [{full_node_url}]."
@@ -143,7 +151,7 @@ impl RosettaServerCommand {
                 let sui_client = wait_for_sui_client(full_node_url).await;
                 let rosetta_path = data_path.join("rosetta_db");
                 info!("Rosetta db path : {rosetta_path:?}");
-                let rosetta = RosettaOnlineServer::new(env, sui_client);
+                let rosetta = RosettaOnlineServer::new(env, sui_client, coin_types);
                 rosetta.serve(addr).await;
             }
 
@@ -152,6 +160,7 @@ impl RosettaServerCommand {
                 addr,
                 node_config,
                 data_path,
+                coin_types,
             } => {
                 info!("Starting Rosetta Online Server with embedded Sui full node.");
                 info!("Data directory path: {data_path:?}");
@@ -176,7 +185,7 @@ impl RosettaServerCommand {
 
                 let rosetta_path = data_path.join("rosetta_db");
                 info!("Rosetta db path : {rosetta_path:?}");
-                let rosetta = RosettaOnlineServer::new(env, sui_client);
+                let rosetta = RosettaOnlineServer::new(env, sui_client, coin_types);
                 rosetta.serve(addr).await;
             }
         };
@@ -204,16 +213,12 @@ async fn wait_for_sui_client(rpc_address: String) -> SuiClient {
 /// PrefundedAccount will be written to the rosetta-cli config file for testing.
 ///
 fn read_prefunded_account(path: &Path) -> Result<Vec<PrefundedAccount>, anyhow::Error> {
-    let reader = BufReader::new(File::open(path).unwrap());
-    let kp_strings: Vec<String> = serde_json::from_reader(reader).unwrap();
-    let keys = kp_strings
-        .iter()
-        .map(|kpstr| {
-            let key = SuiKeyPair::decode_base64(kpstr);
-            key.map(|k| (SuiAddress::from(&k.public()), k))
-        })
-        .collect::<Result<BTreeMap<_, _>, _>>()
-        .unwrap();
+    let keystore = Keystore::open(path)?;
+    let keys = keystore
+        .addresses()
+        .into_iter()
+        .map(|address| Ok((address, keystore.get_key(&address)?.copy())))
+        .collect::<Result<BTreeMap<_, _>, anyhow::Error>>()?;
 
     Ok(keys
         .into_iter()
@@ -241,12 +246,12 @@ fn read_prefunded_account(path: &Path) -> Result<Vec<PrefundedAccount>, anyhow::
 
 #[test]
 fn test_read_keystore() {
-    use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+    use sui_keys::encrypted_keystore::{EncryptedFileKeystore, KdfParams, Zeroizing};
     use sui_types::crypto::SignatureScheme;
 
     let temp_dir = tempfile::tempdir().unwrap();
     let path = temp_dir.path().join("sui.keystore");
-    let mut ks = Keystore::from(FileBasedKeystore::new(&path).unwrap());
+    let mut ks = Keystore::open(&path).unwrap();
     let key1 = ks
         .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
         .unwrap();
@@ -271,6 +276,21 @@ fn test_read_keystore() {
     let schema2: SignatureScheme = acc2.curve_type.into();
     assert!(matches!(schema1, SignatureScheme::ED25519));
     assert!(matches!(schema2, SignatureScheme::Secp256k1));
+
+    // Without its passphrase, an encrypted keystore can't be read, but that is not fatal.
+    EncryptedFileKeystore::encrypt_file_keystore(
+        &path,
+        Zeroizing::new("passphrase".to_string()),
+        KdfParams {
+            memory_kib: 8,
+            iterations: 1,
+            parallelism: 1,
+        },
+    )
+    .unwrap();
+    if std::env::var(sui_keys::encrypted_keystore::SUI_KEYSTORE_PASSPHRASE_ENV).is_err() {
+        assert!(read_prefunded_account(&path).is_err());
+    }
 }
 
 #[tokio::main]
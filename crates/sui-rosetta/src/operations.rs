@@ -1,14 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::ops::Not;
 use std::str::FromStr;
 use std::vec;
 
 use anyhow::anyhow;
+use move_core_types::annotated_value::MoveTypeLayout;
 use move_core_types::ident_str;
-use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use move_core_types::resolver::ModuleResolver;
 use serde::Deserialize;
 use serde::Serialize;
@@ -16,22 +18,24 @@ use serde::Serialize;
 use sui_json_rpc_types::SuiProgrammableMoveCall;
 use sui_json_rpc_types::SuiProgrammableTransactionBlock;
 use sui_json_rpc_types::{BalanceChange, SuiArgument};
-use sui_json_rpc_types::{SuiCallArg, SuiCommand};
+use sui_json_rpc_types::{SuiCallArg, SuiCommand, SuiObjectArg};
 use sui_sdk::rpc_types::{
     SuiTransactionBlockData, SuiTransactionBlockDataAPI, SuiTransactionBlockEffectsAPI,
     SuiTransactionBlockKind, SuiTransactionBlockResponse,
 };
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
 use sui_types::gas_coin::GasCoin;
-use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
+use sui_types::governance::{
+    ADD_STAKE_FUN_NAME, SPLIT_STAKE_FUN_NAME, STAKING_POOL_MODULE_NAME, WITHDRAW_STAKE_FUN_NAME,
+};
 use sui_types::object::Owner;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::TransactionData;
-use sui_types::{SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID};
+use sui_types::{parse_sui_type_tag, SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID};
 
 use crate::types::{
     AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, Currency,
-    InternalOperation, OperationIdentifier, OperationStatus, OperationType,
+    InternalOperation, MoveCallArgument, OperationIdentifier, OperationStatus, OperationType,
 };
 use crate::{CoinMetadataCache, Error, SUI};
 
@@ -104,6 +108,10 @@ impl Operations {
             OperationType::PayCoin => self.pay_coin_ops_to_internal(),
             OperationType::Stake => self.stake_ops_to_internal(),
             OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal(),
+            OperationType::PartialWithdrawStake => self.partial_withdraw_stake_ops_to_internal(),
+            OperationType::MergeCoins => self.merge_coins_ops_to_internal(),
+            OperationType::SplitCoins => self.split_coins_ops_to_internal(),
+            OperationType::MoveCall => self.move_call_ops_to_internal(),
             op => Err(Error::UnsupportedOperation(op)),
         }
     }
@@ -246,6 +254,127 @@ impl Operations {
         Ok(InternalOperation::WithdrawStake { sender, stake_ids })
     }
 
+    fn partial_withdraw_stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, metadata) = self.single_op_to_internal(OperationType::PartialWithdrawStake)?;
+        let OperationMetadata::PartialWithdrawStake { stake_id, amount } = metadata else {
+            return Err(Error::InvalidInput(
+                "Cannot find partial withdraw stake info from metadata.".into(),
+            ));
+        };
+        if amount == 0 {
+            return Err(Error::InvalidInput(
+                "Partial withdraw stake amount should be positive.".into(),
+            ));
+        }
+        Ok(InternalOperation::PartialWithdrawStake {
+            sender,
+            stake_id,
+            amount,
+        })
+    }
+
+    fn merge_coins_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, metadata) = self.single_op_to_internal(OperationType::MergeCoins)?;
+        let OperationMetadata::MergeCoins {
+            primary_coin,
+            coins,
+        } = metadata
+        else {
+            return Err(Error::InvalidInput(
+                "Cannot find merge coins info from metadata.".into(),
+            ));
+        };
+        if coins.is_empty() {
+            return Err(Error::InvalidInput("No coins to merge.".into()));
+        }
+        Ok(InternalOperation::MergeCoins {
+            sender,
+            primary_coin,
+            coins,
+        })
+    }
+
+    fn split_coins_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, metadata) = self.single_op_to_internal(OperationType::SplitCoins)?;
+        let OperationMetadata::SplitCoins { coin, amounts } = metadata else {
+            return Err(Error::InvalidInput(
+                "Cannot find split coins info from metadata.".into(),
+            ));
+        };
+        if amounts.is_empty() {
+            return Err(Error::InvalidInput("No amounts to split.".into()));
+        }
+        Ok(InternalOperation::SplitCoins {
+            sender,
+            coin,
+            amounts,
+        })
+    }
+
+    fn move_call_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, metadata) = self.single_op_to_internal(OperationType::MoveCall)?;
+        let OperationMetadata::MoveCall {
+            package,
+            module,
+            function,
+            type_arguments,
+            arguments,
+        } = metadata
+        else {
+            return Err(Error::InvalidInput(
+                "Cannot find Move call info from metadata.".into(),
+            ));
+        };
+        for name in [&module, &function] {
+            if !Identifier::is_valid(name) {
+                return Err(Error::InvalidInput(format!(
+                    "Invalid Move identifier [{name}]."
+                )));
+            }
+        }
+        for type_ in &type_arguments {
+            parse_sui_type_tag(type_).map_err(|e| {
+                Error::InvalidInput(format!("Invalid type argument [{type_}]: {e}"))
+            })?;
+        }
+        Ok(InternalOperation::MoveCall {
+            sender,
+            package,
+            module,
+            function,
+            type_arguments,
+            arguments,
+        })
+    }
+
+    /// Extract the sender and metadata of an operation type that is expected to appear exactly
+    /// once in the operations.
+    fn single_op_to_internal(
+        self,
+        type_: OperationType,
+    ) -> Result<(SuiAddress, OperationMetadata), Error> {
+        let mut ops = self
+            .0
+            .into_iter()
+            .filter(|op| op.type_ == type_)
+            .collect::<Vec<_>>();
+        if ops.len() != 1 {
+            return Err(Error::MalformedOperationError(format!(
+                "{type_:?} should only have one operation."
+            )));
+        }
+        // Checked above, safe to unwrap.
+        let op = ops.pop().unwrap();
+        let sender = op
+            .account
+            .ok_or_else(|| Error::MissingInput("Sender address".to_string()))?
+            .address;
+        let metadata = op
+            .metadata
+            .ok_or_else(|| Error::MissingInput(format!("{type_:?} metadata")))?;
+        Ok((sender, metadata))
+    }
+
     fn from_transaction(
         tx: SuiTransactionBlockKind,
         sender: SuiAddress,
@@ -269,6 +398,7 @@ impl Operations {
         #[derive(Debug)]
         enum KnownValue {
             GasCoin(u64),
+            SplitStake(ObjectID, u64),
         }
         fn resolve_result(
             known_results: &[Vec<KnownValue>],
@@ -287,10 +417,14 @@ impl Operations {
         ) -> Option<Vec<KnownValue>> {
             match coin {
                 SuiArgument::Result(i) => {
-                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, 0)?;
+                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, 0)? else {
+                        return None;
+                    };
                 }
                 SuiArgument::NestedResult(i, j) => {
-                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, j)?;
+                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, j)? else {
+                        return None;
+                    };
                 }
                 SuiArgument::GasCoin => (),
                 // Might not be a SUI coin
@@ -329,11 +463,17 @@ impl Operations {
             for obj in objs {
                 let value = match *obj {
                     SuiArgument::Result(i) => {
-                        let KnownValue::GasCoin(value) = resolve_result(known_results, i, 0)?;
+                        let KnownValue::GasCoin(value) = resolve_result(known_results, i, 0)?
+                        else {
+                            return None;
+                        };
                         value
                     }
                     SuiArgument::NestedResult(i, j) => {
-                        let KnownValue::GasCoin(value) = resolve_result(known_results, i, j)?;
+                        let KnownValue::GasCoin(value) = resolve_result(known_results, i, j)?
+                        else {
+                            return None;
+                        };
                         value
                     }
                     SuiArgument::GasCoin | SuiArgument::Input(_) => return None,
@@ -353,7 +493,9 @@ impl Operations {
                 [_, coin, validator] => {
                     let amount = match coin {
                         SuiArgument::Result(i) =>{
-                            let KnownValue::GasCoin(value) = resolve_result(known_results, *i, 0).ok_or_else(||anyhow!("Cannot resolve Gas coin value at Result({i})"))?;
+                            let KnownValue::GasCoin(value) = resolve_result(known_results, *i, 0).ok_or_else(||anyhow!("Cannot resolve Gas coin value at Result({i})"))? else {
+                                return Ok(None);
+                            };
                             value
                         },
                         _ => return Ok(None),
@@ -395,18 +537,182 @@ impl Operations {
             };
             Ok(id.cloned())
         }
+
+        fn split_stake_call(
+            inputs: &[SuiCallArg],
+            call: &SuiProgrammableMoveCall,
+        ) -> Option<Vec<KnownValue>> {
+            let [SuiArgument::Input(stake), SuiArgument::Input(amount)] = call.arguments[..] else {
+                return None;
+            };
+            let stake_id = inputs.get(stake as usize)?.object()?;
+            let amount = pure_u64(inputs.get(amount as usize)?)?;
+            Some(vec![KnownValue::SplitStake(*stake_id, amount)])
+        }
+
+        fn partial_unstake_call(
+            known_results: &[Vec<KnownValue>],
+            call: &SuiProgrammableMoveCall,
+        ) -> Option<(ObjectID, u64)> {
+            let [_, SuiArgument::Result(i)] = call.arguments[..] else {
+                return None;
+            };
+            let KnownValue::SplitStake(stake_id, amount) = resolve_result(known_results, i, 0)?
+            else {
+                return None;
+            };
+            Some((*stake_id, *amount))
+        }
+
+        fn merge_coins(
+            inputs: &[SuiCallArg],
+            primary_coin: SuiArgument,
+            coins: &[SuiArgument],
+        ) -> Option<(ObjectID, Vec<ObjectID>)> {
+            let input_object = |arg: &SuiArgument| match arg {
+                SuiArgument::Input(i) => inputs.get(*i as usize)?.object().copied(),
+                SuiArgument::GasCoin | SuiArgument::Result(_) | SuiArgument::NestedResult(_, _) => {
+                    None
+                }
+            };
+            let primary_coin = input_object(&primary_coin)?;
+            let coins = coins.iter().map(input_object).collect::<Option<_>>()?;
+            Some((primary_coin, coins))
+        }
+        fn coin_split(
+            inputs: &[SuiCallArg],
+            commands: &[SuiCommand],
+            sender: SuiAddress,
+        ) -> Option<(ObjectID, Vec<u64>)> {
+            let [split, transfer] = commands else {
+                return None;
+            };
+            let SuiCommand::SplitCoins(SuiArgument::Input(coin), amounts) = split else {
+                return None;
+            };
+            let SuiCommand::TransferObjects(objs, SuiArgument::Input(recipient)) = transfer else {
+                return None;
+            };
+            let coin_id = inputs.get(*coin as usize)?.object()?;
+            let recipient = inputs
+                .get(*recipient as usize)?
+                .pure()?
+                .to_sui_address()
+                .ok()?;
+            // Every split coin is transferred back to the sender, in order.
+            let transfers_all_back = recipient == sender
+                && objs.len() == amounts.len()
+                && objs
+                    .iter()
+                    .enumerate()
+                    .all(|(j, obj)| *obj == SuiArgument::NestedResult(0, j as u16));
+            if !transfers_all_back {
+                return None;
+            }
+            let mut used_inputs = BTreeSet::from([*coin, *recipient]);
+            let amounts = amounts
+                .iter()
+                .map(|amount| match amount {
+                    SuiArgument::Input(i) => {
+                        used_inputs.insert(*i);
+                        pure_u64(inputs.get(*i as usize)?)
+                    }
+                    SuiArgument::GasCoin
+                    | SuiArgument::Result(_)
+                    | SuiArgument::NestedResult(_, _) => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            // No other inputs, such as the currency of a PayCoin transaction.
+            (used_inputs.len() == inputs.len()).then_some((*coin_id, amounts))
+        }
+        fn move_call(
+            inputs: &[SuiCallArg],
+            call: &SuiProgrammableMoveCall,
+        ) -> Option<OperationMetadata> {
+            let mut used_inputs = BTreeSet::new();
+            let arguments = call
+                .arguments
+                .iter()
+                .map(|arg| {
+                    let SuiArgument::Input(i) = arg else {
+                        return None;
+                    };
+                    used_inputs.insert(*i);
+                    Some(match inputs.get(*i as usize)? {
+                        input @ SuiCallArg::Pure(_) => MoveCallArgument::Pure(pure_value(input)?),
+                        SuiCallArg::Object(SuiObjectArg::ImmOrOwnedObject {
+                            object_id, ..
+                        }) => MoveCallArgument::Object(*object_id),
+                        SuiCallArg::Object(SuiObjectArg::SharedObject {
+                            object_id,
+                            initial_shared_version,
+                            mutable,
+                        }) => MoveCallArgument::SharedObject {
+                            id: *object_id,
+                            initial_shared_version: *initial_shared_version,
+                            mutable: *mutable,
+                        },
+                        SuiCallArg::Object(SuiObjectArg::Receiving { .. }) => return None,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            (used_inputs.len() == inputs.len()).then(|| OperationMetadata::MoveCall {
+                package: call.package,
+                module: call.module.clone(),
+                function: call.function.clone(),
+                type_arguments: call.type_arguments.clone(),
+                arguments,
+            })
+        }
         let SuiProgrammableTransactionBlock { inputs, commands } = &pt;
+        // See InternalOperation::try_into_data, explicit coin splits are built as a payment of the
+        // split coins back to the sender.
+        if let Some((coin, amounts)) = coin_split(inputs, commands, sender) {
+            return Ok(vec![Operation {
+                operation_identifier: Default::default(),
+                type_: OperationType::SplitCoins,
+                status,
+                account: Some(sender.into()),
+                amount: None,
+                coin_change: None,
+                metadata: Some(OperationMetadata::SplitCoins { coin, amounts }),
+            }]);
+        }
+        // A lone call to a function that is not otherwise understood is reported as a custom Move
+        // call operation, as long as it can be rebuilt from the operation.
+        if let [SuiCommand::MoveCall(m)] = &commands[..] {
+            if !Self::is_stake_call(m) && !Self::is_unstake_call(m) {
+                if let Some(metadata) = move_call(inputs, m) {
+                    return Ok(vec![Operation {
+                        operation_identifier: Default::default(),
+                        type_: OperationType::MoveCall,
+                        status,
+                        account: Some(sender.into()),
+                        amount: None,
+                        coin_change: None,
+                        metadata: Some(metadata),
+                    }]);
+                }
+            }
+        }
         let mut known_results: Vec<Vec<KnownValue>> = vec![];
         let mut aggregated_recipients: HashMap<SuiAddress, u64> = HashMap::new();
         let mut needs_generic = false;
         let mut operations = vec![];
         let mut stake_ids = vec![];
-        let mut currency: Option<Currency> = None;
+        let mut partial_withdrawals = vec![];
+        let mut merged_coins = vec![];
         for command in commands {
             let result = match command {
                 SuiCommand::SplitCoins(coin, amounts) => {
                     split_coins(inputs, &known_results, *coin, amounts)
                 }
+                SuiCommand::MergeCoins(primary_coin, coins) => {
+                    merge_coins(inputs, *primary_coin, coins).map(|merge| {
+                        merged_coins.push(merge);
+                        vec![]
+                    })
+                }
                 SuiCommand::TransferObjects(objs, addr) => transfer_object(
                     &mut aggregated_recipients,
                     inputs,
@@ -429,9 +735,16 @@ impl Operations {
                         vec![]
                     })
                 }
+                SuiCommand::MoveCall(m) if Self::is_split_stake_call(m) => {
+                    split_stake_call(inputs, m)
+                }
                 SuiCommand::MoveCall(m) if Self::is_unstake_call(m) => {
-                    let stake_id = unstake_call(inputs, m)?;
-                    stake_ids.push(stake_id);
+                    if let Some(withdrawal) = partial_unstake_call(&known_results, m) {
+                        partial_withdrawals.push(withdrawal);
+                    } else {
+                        let stake_id = unstake_call(inputs, m)?;
+                        stake_ids.push(stake_id);
+                    }
                     Some(vec![])
                 }
                 _ => None,
@@ -444,38 +757,25 @@ impl Operations {
             }
         }
 
+        // See InternalOperation::try_into_data, PayCoin transactions carry their currency in the
+        // last input.
+        let currency: Option<Currency> = inputs
+            .iter()
+            .last()
+            .and_then(pure_bytes)
+            .and_then(|bytes| bcs::from_bytes::<String>(&bytes).ok())
+            .and_then(|bcs_str| serde_json::from_str(&bcs_str).ok());
+
         if !needs_generic && !aggregated_recipients.is_empty() {
             let total_paid: u64 = aggregated_recipients.values().copied().sum();
             operations.extend(
                 aggregated_recipients
                     .into_iter()
-                    .map(|(recipient, amount)| {
-                        currency = inputs.iter().last().and_then(|arg| {
-                            if let SuiCallArg::Pure(value) = arg {
-                                let bytes = value
-                                    .value()
-                                    .to_json_value()
-                                    .as_array()?
-                                    .clone()
-                                    .into_iter()
-                                    .map(|v| v.as_u64().map(|n| n as u8))
-                                    .collect::<Option<Vec<u8>>>()?;
-                                bcs::from_bytes::<String>(&bytes)
-                                    .ok()
-                                    .and_then(|bcs_str| serde_json::from_str(&bcs_str).ok())
-                            } else {
-                                None
-                            }
-                        });
-                        match currency {
-                            Some(_) => Operation::pay_coin(
-                                status,
-                                recipient,
-                                amount.into(),
-                                currency.clone(),
-                            ),
-                            None => Operation::pay_sui(status, recipient, amount.into()),
+                    .map(|(recipient, amount)| match currency {
+                        Some(_) => {
+                            Operation::pay_coin(status, recipient, amount.into(), currency.clone())
                         }
+                        None => Operation::pay_sui(status, recipient, amount.into()),
                     }),
             );
             match currency {
@@ -487,6 +787,33 @@ impl Operations {
                 )),
                 _ => operations.push(Operation::pay_sui(status, sender, -(total_paid as i128))),
             }
+        } else if !needs_generic && merged_coins.len() == 1 && operations.is_empty() {
+            // Checked above, safe to unwrap.
+            let (primary_coin, coins) = merged_coins.pop().unwrap();
+            operations.push(Operation {
+                operation_identifier: Default::default(),
+                type_: OperationType::MergeCoins,
+                status,
+                account: Some(sender.into()),
+                amount: None,
+                coin_change: None,
+                metadata: Some(OperationMetadata::MergeCoins {
+                    primary_coin,
+                    coins,
+                }),
+            });
+        } else if !needs_generic && !partial_withdrawals.is_empty() {
+            operations.extend(partial_withdrawals.into_iter().map(|(stake_id, amount)| {
+                Operation {
+                    operation_identifier: Default::default(),
+                    type_: OperationType::PartialWithdrawStake,
+                    status,
+                    account: Some(sender.into()),
+                    amount: None,
+                    coin_change: None,
+                    metadata: Some(OperationMetadata::PartialWithdrawStake { stake_id, amount }),
+                }
+            }));
         } else if !stake_ids.is_empty() {
             let stake_ids = stake_ids.into_iter().flatten().collect::<Vec<_>>();
             let metadata = stake_ids
@@ -524,6 +851,12 @@ impl Operations {
             && tx.function == WITHDRAW_STAKE_FUN_NAME.as_str()
     }
 
+    fn is_split_stake_call(tx: &SuiProgrammableMoveCall) -> bool {
+        tx.package == SUI_SYSTEM_PACKAGE_ID
+            && tx.module == STAKING_POOL_MODULE_NAME.as_str()
+            && tx.function == SPLIT_STAKE_FUN_NAME.as_str()
+    }
+
     fn process_balance_change(
         gas_owner: SuiAddress,
         gas_used: i128,
@@ -654,6 +987,9 @@ impl Operations {
             .balance_changes
             .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?
         {
+            if !cache.is_supported(&balance_change.coin_type) {
+                continue;
+            }
             if let Ok(currency) = cache.get_currency(&balance_change.coin_type).await {
                 if !currency.symbol.is_empty() {
                     balance_changes.push((balance_change.clone(), currency));
@@ -722,6 +1058,52 @@ impl Operations {
     }
 }
 
+/// Raw bytes of a pure input, pure inputs whose type could not be resolved are represented as an
+/// array of bytes.
+fn pure_bytes(arg: &SuiCallArg) -> Option<Vec<u8>> {
+    arg.pure()?
+        .to_json_value()
+        .as_array()?
+        .iter()
+        .map(|v| v.as_u64().map(|n| n as u8))
+        .collect()
+}
+
+/// BCS bytes of a pure input, pure inputs whose type was resolved are serialized back using the
+/// layout of their type.
+fn pure_value(arg: &SuiCallArg) -> Option<Vec<u8>> {
+    let SuiCallArg::Pure(value) = arg else {
+        return None;
+    };
+    match value.value_type() {
+        Some(type_) => value.value().to_bcs_bytes(&pure_layout(&type_)?).ok(),
+        None => pure_bytes(arg),
+    }
+}
+
+fn pure_layout(type_: &TypeTag) -> Option<MoveTypeLayout> {
+    Some(match type_ {
+        TypeTag::Bool => MoveTypeLayout::Bool,
+        TypeTag::U8 => MoveTypeLayout::U8,
+        TypeTag::U16 => MoveTypeLayout::U16,
+        TypeTag::U32 => MoveTypeLayout::U32,
+        TypeTag::U64 => MoveTypeLayout::U64,
+        TypeTag::U128 => MoveTypeLayout::U128,
+        TypeTag::U256 => MoveTypeLayout::U256,
+        TypeTag::Address => MoveTypeLayout::Address,
+        TypeTag::Vector(inner) => MoveTypeLayout::Vector(Box::new(pure_layout(inner)?)),
+        // Strings, IDs and options would need their struct layout.
+        TypeTag::Signer | TypeTag::Struct(_) => return None,
+    })
+}
+
+fn pure_u64(arg: &SuiCallArg) -> Option<u64> {
+    match arg.pure()?.to_json_value().as_str() {
+        Some(value) => u64::from_str(value).ok(),
+        None => bcs::from_bytes(&pure_bytes(arg)?).ok(),
+    }
+}
+
 fn is_unstake_event(tag: &StructTag) -> bool {
     tag.address == SUI_SYSTEM_ADDRESS
         && tag.module.as_ident_str() == ident_str!("validator")
@@ -777,8 +1159,35 @@ impl PartialEq for Operation {
 #[derive(Deserialize, Serialize, Clone, Debug, Eq, PartialEq)]
 pub enum OperationMetadata {
     GenericTransaction(SuiTransactionBlockKind),
-    Stake { validator: SuiAddress },
-    WithdrawStake { stake_ids: Vec<ObjectID> },
+    Stake {
+        validator: SuiAddress,
+    },
+    WithdrawStake {
+        stake_ids: Vec<ObjectID>,
+    },
+    PartialWithdrawStake {
+        stake_id: ObjectID,
+        amount: u64,
+    },
+    MergeCoins {
+        primary_coin: ObjectID,
+        coins: Vec<ObjectID>,
+    },
+    SplitCoins {
+        coin: ObjectID,
+        amounts: Vec<u64>,
+    },
+    /// A single call to an arbitrary Move function, any values it returns are dropped. Type
+    /// arguments of parsed transactions use the short form of addresses, e.g. `0x2::sui::SUI`.
+    MoveCall {
+        package: ObjectID,
+        module: String,
+        function: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        type_arguments: Vec<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        arguments: Vec<MoveCallArgument>,
+    },
 }
 
 impl Operation {
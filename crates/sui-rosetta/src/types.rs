@@ -7,6 +7,7 @@ use std::str::FromStr;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use fastcrypto::encoding::Hex;
+use move_core_types::identifier::Identifier;
 use serde::de::Error as DeError;
 use serde::{Deserialize, Serializer};
 use serde::{Deserializer, Serialize};
@@ -18,12 +19,14 @@ use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockKind};
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest};
 use sui_types::crypto::PublicKey as SuiPublicKey;
 use sui_types::crypto::SignatureScheme;
-use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
+use sui_types::governance::{
+    ADD_STAKE_FUN_NAME, SPLIT_STAKE_FUN_NAME, STAKING_POOL_MODULE_NAME, WITHDRAW_STAKE_FUN_NAME,
+};
 use sui_types::messages_checkpoint::CheckpointDigest;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::{Argument, CallArg, Command, ObjectArg, TransactionData};
-use sui_types::SUI_SYSTEM_PACKAGE_ID;
+use sui_types::{parse_sui_type_tag, SUI_SYSTEM_PACKAGE_ID};
 
 use crate::errors::{Error, ErrorType};
 use crate::operations::Operations;
@@ -460,6 +463,10 @@ pub enum OperationType {
     PayCoin,
     Stake,
     WithdrawStake,
+    PartialWithdrawStake,
+    MergeCoins,
+    SplitCoins,
+    MoveCall,
     // All other Sui transaction types, readonly
     EpochChange,
     Genesis,
@@ -934,6 +941,44 @@ pub enum InternalOperation {
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         stake_ids: Vec<ObjectID>,
     },
+    PartialWithdrawStake {
+        sender: SuiAddress,
+        stake_id: ObjectID,
+        amount: u64,
+    },
+    MergeCoins {
+        sender: SuiAddress,
+        primary_coin: ObjectID,
+        coins: Vec<ObjectID>,
+    },
+    SplitCoins {
+        sender: SuiAddress,
+        coin: ObjectID,
+        amounts: Vec<u64>,
+    },
+    MoveCall {
+        sender: SuiAddress,
+        package: ObjectID,
+        module: String,
+        function: String,
+        type_arguments: Vec<String>,
+        arguments: Vec<MoveCallArgument>,
+    },
+}
+
+/// Argument of a custom Move call operation.
+#[derive(Deserialize, Serialize, Clone, Debug, Eq, PartialEq)]
+pub enum MoveCallArgument {
+    /// BCS bytes of a pure value.
+    Pure(Vec<u8>),
+    /// An owned or immutable object, resolved to its latest reference during
+    /// /construction/metadata.
+    Object(ObjectID),
+    SharedObject {
+        id: ObjectID,
+        initial_shared_version: SequenceNumber,
+        mutable: bool,
+    },
 }
 
 impl InternalOperation {
@@ -942,7 +987,38 @@ impl InternalOperation {
             InternalOperation::PaySui { sender, .. }
             | InternalOperation::PayCoin { sender, .. }
             | InternalOperation::Stake { sender, .. }
-            | InternalOperation::WithdrawStake { sender, .. } => *sender,
+            | InternalOperation::WithdrawStake { sender, .. }
+            | InternalOperation::PartialWithdrawStake { sender, .. }
+            | InternalOperation::MergeCoins { sender, .. }
+            | InternalOperation::SplitCoins { sender, .. }
+            | InternalOperation::MoveCall { sender, .. } => *sender,
+        }
+    }
+
+    /// Objects, other than gas coins, that need to be resolved to their latest reference
+    /// during /construction/metadata, in the order expected by `try_into_data`.
+    pub fn input_objects(&self) -> Vec<ObjectID> {
+        match self {
+            InternalOperation::PartialWithdrawStake { stake_id, .. } => vec![*stake_id],
+            InternalOperation::MergeCoins {
+                primary_coin,
+                coins,
+                ..
+            } => std::iter::once(*primary_coin)
+                .chain(coins.iter().copied())
+                .collect(),
+            InternalOperation::SplitCoins { coin, .. } => vec![*coin],
+            InternalOperation::MoveCall { arguments, .. } => arguments
+                .iter()
+                .filter_map(|arg| match arg {
+                    MoveCallArgument::Object(id) => Some(*id),
+                    MoveCallArgument::Pure(_) | MoveCallArgument::SharedObject { .. } => None,
+                })
+                .collect(),
+            InternalOperation::PaySui { .. }
+            | InternalOperation::PayCoin { .. }
+            | InternalOperation::Stake { .. }
+            | InternalOperation::WithdrawStake { .. } => vec![],
         }
     }
     /// Combine with ConstructionMetadata to form the TransactionData
@@ -1034,6 +1110,106 @@ impl InternalOperation {
                 }
                 builder.finish()
             }
+            InternalOperation::PartialWithdrawStake { amount, .. } => {
+                let mut builder = ProgrammableTransactionBuilder::new();
+                let stake = metadata
+                    .objects
+                    .first()
+                    .ok_or_else(|| Error::MissingInput("Stake object".to_string()))?;
+
+                // Split the requested principal off the stake and withdraw the split part,
+                // the remaining principal stays staked.
+                let stake = builder.obj(ObjectArg::ImmOrOwnedObject(*stake))?;
+                let amount = builder.pure(amount)?;
+                let split = builder.programmable_move_call(
+                    SUI_SYSTEM_PACKAGE_ID,
+                    STAKING_POOL_MODULE_NAME.to_owned(),
+                    SPLIT_STAKE_FUN_NAME.to_owned(),
+                    vec![],
+                    vec![stake, amount],
+                );
+                let system_state = builder.input(CallArg::SUI_SYSTEM_MUT)?;
+                builder.command(Command::move_call(
+                    SUI_SYSTEM_PACKAGE_ID,
+                    SUI_SYSTEM_MODULE_NAME.to_owned(),
+                    WITHDRAW_STAKE_FUN_NAME.to_owned(),
+                    vec![],
+                    vec![system_state, split],
+                ));
+                builder.finish()
+            }
+            InternalOperation::MergeCoins { .. } => {
+                let mut builder = ProgrammableTransactionBuilder::new();
+                let mut coins = metadata
+                    .objects
+                    .iter()
+                    .map(|coin| builder.obj(ObjectArg::ImmOrOwnedObject(*coin)))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter();
+                let primary_coin = coins
+                    .next()
+                    .ok_or_else(|| Error::MissingInput("Primary coin".to_string()))?;
+                builder.command(Command::MergeCoins(primary_coin, coins.collect()));
+                builder.finish()
+            }
+            InternalOperation::SplitCoins {
+                sender, amounts, ..
+            } => {
+                let mut builder = ProgrammableTransactionBuilder::new();
+                let coin = *metadata
+                    .objects
+                    .first()
+                    .ok_or_else(|| Error::MissingInput("Coin object".to_string()))?;
+                // The split coins are kept by the sender.
+                builder.pay(vec![coin], vec![sender; amounts.len()], amounts)?;
+                builder.finish()
+            }
+            InternalOperation::MoveCall {
+                package,
+                module,
+                function,
+                type_arguments,
+                arguments,
+                ..
+            } => {
+                let mut builder = ProgrammableTransactionBuilder::new();
+                let type_arguments = type_arguments
+                    .iter()
+                    .map(String::as_str)
+                    .map(parse_sui_type_tag)
+                    .collect::<Result<Vec<_>, _>>()?;
+                // Object arguments are resolved in the order of `input_objects`.
+                let mut objects = metadata.objects.iter();
+                let arguments = arguments
+                    .into_iter()
+                    .map(|arg| match arg {
+                        MoveCallArgument::Pure(bytes) => Ok(builder.pure_bytes(bytes, false)),
+                        MoveCallArgument::Object(id) => {
+                            let object = objects.next().ok_or_else(|| {
+                                Error::MissingInput(format!("Object {id} reference"))
+                            })?;
+                            Ok(builder.obj(ObjectArg::ImmOrOwnedObject(*object))?)
+                        }
+                        MoveCallArgument::SharedObject {
+                            id,
+                            initial_shared_version,
+                            mutable,
+                        } => Ok(builder.obj(ObjectArg::SharedObject {
+                            id,
+                            initial_shared_version,
+                            mutable,
+                        })?),
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                builder.programmable_move_call(
+                    package,
+                    Identifier::new(module)?,
+                    Identifier::new(function)?,
+                    type_arguments,
+                    arguments,
+                );
+                builder.finish()
+            }
         };
 
         Ok(TransactionData::new_programmable(
@@ -7,10 +7,48 @@ use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::transaction::{CallArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_TRANSFER};
 
-use crate::operations::Operations;
-use crate::types::{ConstructionMetadata, OperationType};
+use crate::operations::{pure_value, Operations};
+use crate::types::{ConstructionMetadata, InternalOperation, MoveCallArgument, OperationType};
 use crate::SUI;
 
+fn random_object_ref() -> (ObjectID, SequenceNumber, ObjectDigest) {
+    (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    )
+}
+
+/// Build the transaction for `operation`, then check that parsing it back gives operations of
+/// `type_` that build the same transaction.
+fn check_operation_round_trip(
+    operation: InternalOperation,
+    objects: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    type_: OperationType,
+) -> Result<(), anyhow::Error> {
+    let sender = operation.sender();
+    let gas = random_object_ref();
+    let gas_price = 10;
+    let metadata = || ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: objects.clone(),
+        total_coin_value: 0,
+        gas_price,
+        budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        currency: None,
+    };
+    let data = operation.try_into_data(metadata())?;
+
+    let ops: Operations = data.clone().try_into()?;
+    ops.clone()
+        .into_iter()
+        .for_each(|op| assert_eq!(op.type_, type_));
+    let parsed_data = ops.into_internal()?.try_into_data(metadata())?;
+    assert_eq!(data, parsed_data);
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_operation_data_parsing_pay_sui() -> Result<(), anyhow::Error> {
     let gas = (
@@ -111,6 +149,174 @@ async fn test_operation_data_parsing_pay_coin() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+#[tokio::test]
+async fn test_operation_data_parsing_pay_coin_multiple_coins() -> Result<(), anyhow::Error> {
+    let coins = vec![random_object_ref(), random_object_ref()];
+    let gas = random_object_ref();
+    let sender = SuiAddress::random_for_testing_only();
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .pay(
+                coins.clone(),
+                vec![SuiAddress::random_for_testing_only()],
+                vec![10000],
+            )
+            .unwrap();
+        builder.pure(serde_json::to_string(&SUI.clone())?)?;
+        builder.finish()
+    };
+    let gas_price = 10;
+    let data = TransactionData::new_programmable(
+        sender,
+        vec![gas],
+        pt,
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        gas_price,
+    );
+
+    // Merging the input coins is part of the payment.
+    let ops: Operations = data.clone().try_into()?;
+    ops.0
+        .iter()
+        .for_each(|op| assert_eq!(op.type_, OperationType::PayCoin));
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: coins,
+        total_coin_value: 0,
+        gas_price,
+        budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        currency: Some(SUI.clone()),
+    };
+    let parsed_data = ops.into_internal()?.try_into_data(metadata)?;
+    assert_eq!(data, parsed_data);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_operation_data_parsing_partial_withdraw_stake() -> Result<(), anyhow::Error> {
+    let stake = random_object_ref();
+    check_operation_round_trip(
+        InternalOperation::PartialWithdrawStake {
+            sender: SuiAddress::random_for_testing_only(),
+            stake_id: stake.0,
+            amount: 1_000_000_000,
+        },
+        vec![stake],
+        OperationType::PartialWithdrawStake,
+    )
+}
+
+#[tokio::test]
+async fn test_operation_data_parsing_merge_coins() -> Result<(), anyhow::Error> {
+    let coins = vec![
+        random_object_ref(),
+        random_object_ref(),
+        random_object_ref(),
+    ];
+    check_operation_round_trip(
+        InternalOperation::MergeCoins {
+            sender: SuiAddress::random_for_testing_only(),
+            primary_coin: coins[0].0,
+            coins: vec![coins[1].0, coins[2].0],
+        },
+        coins,
+        OperationType::MergeCoins,
+    )
+}
+
+#[tokio::test]
+async fn test_operation_data_parsing_split_coins() -> Result<(), anyhow::Error> {
+    let coin = random_object_ref();
+    check_operation_round_trip(
+        InternalOperation::SplitCoins {
+            sender: SuiAddress::random_for_testing_only(),
+            coin: coin.0,
+            amounts: vec![10000, 20000, 10000],
+        },
+        vec![coin],
+        OperationType::SplitCoins,
+    )
+}
+
+#[tokio::test]
+async fn test_operation_data_parsing_split_coins_with_payment() -> Result<(), anyhow::Error> {
+    let coin = random_object_ref();
+    let gas = random_object_ref();
+    let sender = SuiAddress::random_for_testing_only();
+
+    // Splitting a coin for the sender as part of a larger transaction is not an explicit split.
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.pay(vec![coin], vec![sender, sender], vec![10000, 20000])?;
+        builder.pay_sui(vec![sender], vec![30000])?;
+        builder.finish()
+    };
+    let gas_price = 10;
+    let data = TransactionData::new_programmable(
+        sender,
+        vec![gas],
+        pt,
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        gas_price,
+    );
+
+    let ops: Operations = data.try_into()?;
+    assert!(ops
+        .into_iter()
+        .all(|op| op.type_ != OperationType::SplitCoins));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_operation_data_parsing_move_call() -> Result<(), anyhow::Error> {
+    let coin = random_object_ref();
+    check_operation_round_trip(
+        InternalOperation::MoveCall {
+            sender: SuiAddress::random_for_testing_only(),
+            package: ObjectID::from_single_byte(2),
+            module: "pay".to_string(),
+            function: "split".to_string(),
+            type_arguments: vec!["0x2::sui::SUI".to_string()],
+            arguments: vec![
+                MoveCallArgument::Object(coin.0),
+                MoveCallArgument::Pure(bcs::to_bytes(&10000u64)?),
+            ],
+        },
+        vec![coin],
+        OperationType::MoveCall,
+    )?;
+    check_operation_round_trip(
+        InternalOperation::MoveCall {
+            sender: SuiAddress::random_for_testing_only(),
+            package: ObjectID::random(),
+            module: "counter".to_string(),
+            function: "increment".to_string(),
+            type_arguments: vec![],
+            arguments: vec![MoveCallArgument::SharedObject {
+                id: ObjectID::random(),
+                initial_shared_version: SequenceNumber::from_u64(1),
+                mutable: true,
+            }],
+        },
+        vec![],
+        OperationType::MoveCall,
+    )
+}
+
+#[test]
+fn test_move_call_pure_value() {
+    let bytes = bcs::to_bytes(&30215u64).unwrap();
+    let resolved =
+        SuiCallArg::try_from(CallArg::Pure(bytes.clone()), Some(&MoveTypeLayout::U64)).unwrap();
+    let unresolved = SuiCallArg::try_from(CallArg::Pure(bytes.clone()), None).unwrap();
+    assert_eq!(Some(bytes.clone()), pure_value(&resolved));
+    assert_eq!(Some(bytes), pure_value(&unresolved));
+}
+
 #[tokio::test]
 async fn test_sui_json() {
     let arg1 = CallArg::Pure(bcs::to_bytes(&1000000u64).unwrap());
@@ -23,7 +23,9 @@ use sui_rosetta::SUI;
 use test_cluster::TestClusterBuilder;
 use test_coin_utils::{init_package, mint};
 
-use crate::rosetta_client::{start_rosetta_test_server, RosettaEndpoint};
+use crate::rosetta_client::{
+    start_rosetta_test_server, start_rosetta_test_server_with_coin_types, RosettaEndpoint,
+};
 
 #[tokio::test]
 async fn test_custom_coin_balance() {
@@ -106,6 +108,91 @@ async fn test_custom_coin_balance() {
     );
 }
 
+#[tokio::test]
+async fn test_supported_coin_types() {
+    const COIN1_BALANCE: u64 = 100_000_000;
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let client = test_cluster.wallet.get_client().await.unwrap();
+    let keystore = &test_cluster.wallet.config.keystore;
+
+    let sender = test_cluster.get_address_0();
+    let init_ret = init_package(
+        &client,
+        keystore,
+        sender,
+        Path::new("tests/custom_coins/test_coin"),
+    )
+    .await
+    .unwrap();
+
+    let address1 = test_cluster.get_address_1();
+    let coin_tag = init_ret.coin_tag.clone();
+    let coin_type = coin_tag.to_canonical_string(true);
+    let _mint_res = mint(&client, keystore, init_ret, vec![(COIN1_BALANCE, address1)])
+        .await
+        .unwrap();
+
+    // Symbol and decimals are taken from the coin metadata, not from the request.
+    let request = AccountBalanceRequest {
+        network_identifier: NetworkIdentifier {
+            blockchain: "sui".to_string(),
+            network: SuiEnv::LocalNet,
+        },
+        account_identifier: AccountIdentifier {
+            address: address1,
+            sub_account: None,
+        },
+        block_identifier: Default::default(),
+        currencies: Currencies(vec![Currency {
+            symbol: "".to_string(),
+            decimals: 0,
+            metadata: CurrencyMetadata {
+                coin_type: coin_type.clone(),
+            },
+        }]),
+    };
+
+    let (rosetta_client, _handle) =
+        start_rosetta_test_server_with_coin_types(client.clone(), vec![coin_tag]).await;
+    let response: AccountBalanceResponse = rosetta_client
+        .call(RosettaEndpoint::Balance, &request)
+        .await;
+    assert_eq!(response.balances.len(), 1);
+    assert_eq!(response.balances[0].value, COIN1_BALANCE as i128);
+    assert_eq!(
+        response.balances[0].currency,
+        Currency {
+            symbol: "TEST_COIN".to_string(),
+            decimals: 6,
+            metadata: CurrencyMetadata { coin_type },
+        }
+    );
+
+    // Coin types that are not configured are rejected, even if another custom coin is.
+    let other_coin_type = init_package(
+        &client,
+        keystore,
+        sender,
+        Path::new("tests/custom_coins/test_coin_no_symbol"),
+    )
+    .await
+    .unwrap()
+    .coin_tag;
+    assert_ne!(other_coin_type.to_canonical_string(true), coin_type);
+    let (rosetta_client, _handle) =
+        start_rosetta_test_server_with_coin_types(client, vec![other_coin_type]).await;
+    let response: serde_json::Value = rosetta_client
+        .call(RosettaEndpoint::Balance, &request)
+        .await;
+    assert!(
+        response["details"]["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Unsupported currency"),
+        "{response:#?}"
+    );
+}
+
 #[tokio::test]
 async fn test_default_balance() {
     // mint coins to `test_culset.get_address_1()` and `test_culset.get_address_2()`
@@ -17,6 +17,7 @@ use sui_rosetta::types::{
 use sui_rosetta::CoinMetadataCache;
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
 use sui_swarm_config::genesis_config::{DEFAULT_GAS_AMOUNT, DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT};
+use sui_types::base_types::ObjectID;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::utils::to_sender_signed_transaction;
 use test_cluster::TestClusterBuilder;
@@ -383,6 +384,273 @@ async fn test_withdraw_stake() {
     assert_eq!(0, response.balances[0].value);
 }
 
+#[tokio::test]
+async fn test_partial_withdraw_stake() {
+    let test_cluster = TestClusterBuilder::new()
+        .with_epoch_duration_ms(60000)
+        .build()
+        .await;
+    let sender = test_cluster.get_address_0();
+    let client = test_cluster.wallet.get_client().await.unwrap();
+    let keystore = &test_cluster.wallet.config.keystore;
+
+    let (rosetta_client, _handle) = start_rosetta_test_server(client.clone()).await;
+
+    let validator = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await
+        .unwrap()
+        .active_validators[0]
+        .sui_address;
+
+    let ops = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"Stake",
+            "account": { "address" : sender.to_string() },
+            "amount" : { "value": "-2000000000" },
+            "metadata": { "Stake" : {"validator": validator.to_string()} }
+        }]
+    ))
+    .unwrap();
+    rosetta_client.rosetta_flow(&ops, keystore).await;
+
+    // Trigger epoch change.
+    test_cluster.trigger_reconfiguration().await;
+
+    let stake_id =
+        client.governance_api().get_stakes(sender).await.unwrap()[0].stakes[0].staked_sui_id;
+
+    // withdraw half of the stake
+    let ops = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"PartialWithdrawStake",
+            "account": { "address" : sender.to_string() },
+            "metadata": { "PartialWithdrawStake" : {"stake_id": stake_id, "amount": 1000000000} }
+        }]
+    ))
+    .unwrap();
+
+    let response = rosetta_client.rosetta_flow(&ops, keystore).await;
+
+    let tx = client
+        .read_api()
+        .get_transaction_with_options(
+            response.transaction_identifier.hash,
+            SuiTransactionBlockResponseOptions::new()
+                .with_input()
+                .with_effects()
+                .with_balance_changes()
+                .with_events(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        &SuiExecutionStatus::Success,
+        tx.effects.as_ref().unwrap().status()
+    );
+    let coin_cache = CoinMetadataCache::new(client.clone(), NonZeroUsize::new(2).unwrap());
+    let ops2 = Operations::try_from_response(tx, &coin_cache)
+        .await
+        .unwrap();
+    assert!(
+        ops2.contains(&ops),
+        "Operation mismatch. expecting:{}, got:{}",
+        serde_json::to_string(&ops).unwrap(),
+        serde_json::to_string(&ops2).unwrap()
+    );
+
+    // the rest of the stake is still active
+    let network_identifier = NetworkIdentifier {
+        blockchain: "sui".to_string(),
+        network: SuiEnv::LocalNet,
+    };
+    let response = rosetta_client
+        .get_balance(network_identifier, sender, Some(SubAccountType::Stake))
+        .await;
+    assert_eq!(1, response.balances.len());
+    assert_eq!(1000000000, response.balances[0].value);
+}
+
+#[tokio::test]
+async fn test_split_and_merge_coins() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let sender = test_cluster.get_address_0();
+    let client = test_cluster.wallet.get_client().await.unwrap();
+    let keystore = &test_cluster.wallet.config.keystore;
+
+    let (rosetta_client, _handle) = start_rosetta_test_server(client.clone()).await;
+    let coin_cache = CoinMetadataCache::new(client.clone(), NonZeroUsize::new(2).unwrap());
+
+    let coin = client
+        .coin_read_api()
+        .get_coins(sender, None, None, None)
+        .await
+        .unwrap()
+        .data[0]
+        .coin_object_id;
+
+    let ops = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"SplitCoins",
+            "account": { "address" : sender.to_string() },
+            "metadata": { "SplitCoins" : {"coin": coin, "amounts": [1000000000, 2000000000]} }
+        }]
+    ))
+    .unwrap();
+
+    let response = rosetta_client.rosetta_flow(&ops, keystore).await;
+
+    let tx = client
+        .read_api()
+        .get_transaction_with_options(
+            response.transaction_identifier.hash,
+            SuiTransactionBlockResponseOptions::new()
+                .with_input()
+                .with_effects()
+                .with_balance_changes()
+                .with_events(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        &SuiExecutionStatus::Success,
+        tx.effects.as_ref().unwrap().status()
+    );
+    let split_coins = tx
+        .effects
+        .as_ref()
+        .unwrap()
+        .created()
+        .iter()
+        .map(|o| o.object_id())
+        .collect::<Vec<_>>();
+    assert_eq!(2, split_coins.len());
+    let ops2 = Operations::try_from_response(tx, &coin_cache)
+        .await
+        .unwrap();
+    assert!(
+        ops2.contains(&ops),
+        "Operation mismatch. expecting:{}, got:{}",
+        serde_json::to_string(&ops).unwrap(),
+        serde_json::to_string(&ops2).unwrap()
+    );
+
+    // merge the split coins back
+    let ops = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"MergeCoins",
+            "account": { "address" : sender.to_string() },
+            "metadata": { "MergeCoins" : {"primary_coin": coin, "coins": split_coins} }
+        }]
+    ))
+    .unwrap();
+
+    let response = rosetta_client.rosetta_flow(&ops, keystore).await;
+
+    let tx = client
+        .read_api()
+        .get_transaction_with_options(
+            response.transaction_identifier.hash,
+            SuiTransactionBlockResponseOptions::new()
+                .with_input()
+                .with_effects()
+                .with_balance_changes()
+                .with_events(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        &SuiExecutionStatus::Success,
+        tx.effects.as_ref().unwrap().status()
+    );
+    let ops2 = Operations::try_from_response(tx, &coin_cache)
+        .await
+        .unwrap();
+    assert!(
+        ops2.contains(&ops),
+        "Operation mismatch. expecting:{}, got:{}",
+        serde_json::to_string(&ops).unwrap(),
+        serde_json::to_string(&ops2).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_move_call() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let sender = test_cluster.get_address_0();
+    let client = test_cluster.wallet.get_client().await.unwrap();
+    let keystore = &test_cluster.wallet.config.keystore;
+
+    let (rosetta_client, _handle) = start_rosetta_test_server(client.clone()).await;
+    let coin_cache = CoinMetadataCache::new(client.clone(), NonZeroUsize::new(2).unwrap());
+
+    let coin = client
+        .coin_read_api()
+        .get_coins(sender, None, None, None)
+        .await
+        .unwrap()
+        .data[0]
+        .coin_object_id;
+
+    // 0x2::pay::split<0x2::sui::SUI>(coin, 1000000000)
+    let ops = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"MoveCall",
+            "account": { "address" : sender.to_string() },
+            "metadata": { "MoveCall" : {
+                "package": ObjectID::from_single_byte(2),
+                "module": "pay",
+                "function": "split",
+                "type_arguments": ["0x2::sui::SUI"],
+                "arguments": [
+                    { "Object": coin },
+                    { "Pure": bcs::to_bytes(&1000000000u64).unwrap() }
+                ]
+            }}
+        }]
+    ))
+    .unwrap();
+
+    let response = rosetta_client.rosetta_flow(&ops, keystore).await;
+
+    let tx = client
+        .read_api()
+        .get_transaction_with_options(
+            response.transaction_identifier.hash,
+            SuiTransactionBlockResponseOptions::new()
+                .with_input()
+                .with_effects()
+                .with_balance_changes()
+                .with_events(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        &SuiExecutionStatus::Success,
+        tx.effects.as_ref().unwrap().status()
+    );
+    assert_eq!(1, tx.effects.as_ref().unwrap().created().len());
+    let ops2 = Operations::try_from_response(tx, &coin_cache)
+        .await
+        .unwrap();
+    assert!(
+        ops2.contains(&ops),
+        "Operation mismatch. expecting:{}, got:{}",
+        serde_json::to_string(&ops).unwrap(),
+        serde_json::to_string(&ops2).unwrap()
+    );
+}
+
 #[tokio::test]
 async fn test_pay_sui() {
     let test_cluster = TestClusterBuilder::new().build().await;
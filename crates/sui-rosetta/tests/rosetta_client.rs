@@ -6,6 +6,7 @@ use std::net::SocketAddr;
 use std::str::FromStr;
 
 use fastcrypto::encoding::{Encoding, Hex};
+use move_core_types::language_storage::TypeTag;
 use reqwest::Client;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -29,7 +30,15 @@ use sui_types::base_types::SuiAddress;
 use sui_types::crypto::SuiSignature;
 
 pub async fn start_rosetta_test_server(client: SuiClient) -> (RosettaClient, Vec<JoinHandle<()>>) {
-    let online_server = RosettaOnlineServer::new(SuiEnv::LocalNet, client);
+    start_rosetta_test_server_with_coin_types(client, vec![]).await
+}
+
+#[allow(dead_code)]
+pub async fn start_rosetta_test_server_with_coin_types(
+    client: SuiClient,
+    coin_types: Vec<TypeTag>,
+) -> (RosettaClient, Vec<JoinHandle<()>>) {
+    let online_server = RosettaOnlineServer::new(SuiEnv::LocalNet, client, coin_types);
     let offline_server = RosettaOfflineServer::new(SuiEnv::LocalNet);
     let local_ip = local_ip_utils::localhost_for_testing();
     let port = local_ip_utils::get_available_port(&local_ip);
@@ -332,15 +332,63 @@ impl StateSnapshotReaderV1 {
         })
     }
 
+    /// Download and verify all live objects in the snapshot, returning them in memory instead of
+    /// inserting them into a perpetual store. Meant for consumers that build their own state from
+    /// a snapshot, such as a `simulacrum` fork.
+    pub async fn read_live_objects(
+        &mut self,
+        abort_registration: AbortRegistration,
+    ) -> Result<Vec<LiveObject>> {
+        let (sha3_digests, _) = self.compute_checksum().await?;
+        let mut live_objects = vec![];
+        self.download_live_objects(abort_registration, sha3_digests, |obj_iter, sha3_digest| {
+            let mut hasher = Sha3_256::default();
+            let objects: Vec<LiveObject> = obj_iter
+                .inspect(|object| hasher.update(object.object_reference().2.inner()))
+                .collect();
+            if hasher.finalize().digest != *sha3_digest {
+                return Err(anyhow!("Sha does not match"));
+            }
+            live_objects.extend(objects);
+            Ok(())
+        })
+        .await?;
+        Ok(live_objects)
+    }
+
     async fn sync_live_objects(
         &self,
         perpetual_db: &AuthorityPerpetualTables,
         abort_registration: AbortRegistration,
         sha3_digests: Arc<Mutex<DigestByBucketAndPartition>>,
     ) -> Result<(), anyhow::Error> {
+        let threshold = self.indirect_objects_threshold;
+        self.download_live_objects(abort_registration, sha3_digests, |obj_iter, sha3_digest| {
+            AuthorityStore::bulk_insert_live_objects(
+                perpetual_db,
+                obj_iter,
+                threshold,
+                sha3_digest,
+            )
+            .expect("Failed to insert live objects");
+            Ok(())
+        })
+        .await
+    }
+
+    /// Download every object file of the snapshot, handing the objects of each file to
+    /// `on_object_file` along with the expected sha3 digest of their references.
+    async fn download_live_objects<F>(
+        &self,
+        abort_registration: AbortRegistration,
+        sha3_digests: Arc<Mutex<DigestByBucketAndPartition>>,
+        mut on_object_file: F,
+    ) -> Result<(), anyhow::Error>
+    where
+        F: FnMut(LiveObjectIter, &[u8; 32]) -> Result<()>,
+    {
         let epoch_dir = self.epoch_dir();
         let concurrency = self.concurrency;
-        let threshold = self.indirect_objects_threshold;
         let remote_object_store = self.remote_object_store.clone();
         let input_files: Vec<_> = self
             .object_files
@@ -396,15 +444,8 @@ impl StateSnapshotReaderV1 {
                     .try_for_each(|(bytes, file_metadata, sha3_digest)| {
                         let bytes_len = bytes.len();
                         let result: Result<(), anyhow::Error> =
-                            LiveObjectIter::new(&file_metadata, bytes).map(|obj_iter| {
-                                AuthorityStore::bulk_insert_live_objects(
-                                    perpetual_db,
-                                    obj_iter,
-                                    threshold,
-                                    &sha3_digest,
-                                )
-                                .expect("Failed to insert live objects");
-                            });
+                            LiveObjectIter::new(&file_metadata, bytes)
+                                .and_then(|obj_iter| on_object_file(obj_iter, &sha3_digest));
                         downloaded_bytes.fetch_add(bytes_len, Ordering::Relaxed);
                         obj_progress_bar_clone.inc(1);
                         obj_progress_bar_clone.set_message(format!(
@@ -7,17 +7,22 @@ use crate::FileCompression;
 use fastcrypto::hash::MultisetHash;
 use futures::future::AbortHandle;
 use indicatif::MultiProgress;
+use rand::rngs::OsRng;
+use simulacrum::Simulacrum;
 use std::collections::HashSet;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
-use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
 use sui_core::state_accumulator::StateAccumulator;
 use sui_protocol_config::ProtocolConfig;
 use sui_types::accumulator::Accumulator;
-use sui_types::base_types::ObjectID;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::gas_coin::GasCoin;
 use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
 use sui_types::object::Object;
+use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
 use tempfile::tempdir;
 
 fn temp_dir() -> std::path::PathBuf {
@@ -181,3 +186,101 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     )?;
     Ok(())
 }
+
+fn balance<R>(simulacrum: &Simulacrum<R>, owner: SuiAddress) -> u64 {
+    simulacrum
+        .store()
+        .owned_objects(owner)
+        .filter_map(|object| GasCoin::try_from(&object).ok())
+        .map(|coin| coin.value())
+        .sum()
+}
+
+#[tokio::test]
+async fn test_fork_simulacrum_from_snapshot() -> Result<(), anyhow::Error> {
+    let mut source = Simulacrum::new();
+    source.advance_epoch(/* create_random_state */ false);
+    let recipient = SuiAddress::random_for_testing_only();
+    let (tx, transfer_amount) = source.transfer_txn(recipient);
+    source.execute_transaction(tx)?;
+    source.create_checkpoint();
+    let epoch = source.epoch_start_state().epoch();
+
+    let db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let restored_local = temp_dir().join("local_dir_restore");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote),
+        ..Default::default()
+    };
+
+    let snapshot_writer = StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?;
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    for object in source.live_objects() {
+        perpetual_db.insert_object_test_only(object.clone())?;
+    }
+    let root_accumulator =
+        ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
+    snapshot_writer
+        .write_internal(epoch, true, perpetual_db.clone(), root_accumulator)
+        .await?;
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(restored_local),
+        ..Default::default()
+    };
+    let mut snapshot_reader = StateSnapshotReaderV1::new(
+        epoch,
+        &remote_store_config,
+        &local_store_restore_config,
+        usize::MAX,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+    )
+    .await?;
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    let objects = snapshot_reader
+        .read_live_objects(abort_registration)
+        .await?;
+
+    let mut fork = Simulacrum::new_from_live_objects(
+        OsRng,
+        objects.into_iter().filter_map(LiveObject::to_normal),
+    );
+    assert_eq!(fork.epoch_start_state().epoch(), epoch);
+    assert_eq!(balance(&fork, recipient), transfer_amount);
+
+    let (tx, fork_transfer_amount) = fork.transfer_txn(recipient);
+    let (effects, _) = fork.execute_transaction(tx)?;
+    assert!(effects.status().is_ok());
+    fork.create_checkpoint();
+    assert_eq!(
+        balance(&fork, recipient),
+        transfer_amount + fork_transfer_amount
+    );
+
+    // A DB checkpoint holds the same perpetual tables the snapshot was written from, and its live
+    // object set can be forked from directly.
+    let fork = Simulacrum::new_from_live_objects(
+        OsRng,
+        perpetual_db
+            .iter_live_object_set(false)
+            .filter_map(LiveObject::to_normal),
+    );
+    assert_eq!(fork.epoch_start_state().epoch(), epoch);
+    assert_eq!(balance(&fork, recipient), transfer_amount);
+    Ok(())
+}
@@ -14,6 +14,7 @@ use sui_types::base_types::{EpochId, ObjectID};
 use sui_types::digests::{CheckpointContentsDigest, TransactionDigest};
 use sui_types::effects::TransactionEffectsAPI;
 use sui_types::messages_checkpoint::{CheckpointDigest, CheckpointSequenceNumber};
+use typed_store::migration::read_progress;
 use typed_store::rocks::MetricConf;
 pub mod db_dump;
 mod index_search;
@@ -40,6 +41,7 @@ pub enum DbToolCommand {
     PruneObjects,
     PruneCheckpoints,
     SetCheckpointWatermark(SetCheckpointWatermarkOptions),
+    MigrationStatus,
 }
 
 #[derive(Parser)]
@@ -232,6 +234,7 @@ pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> an
             Ok(())
         }
         DbToolCommand::SetCheckpointWatermark(d) => set_checkpoint_watermark(&db_path, d),
+        DbToolCommand::MigrationStatus => print_migration_status(&db_path),
     }
 }
 
@@ -513,3 +516,36 @@ pub fn set_checkpoint_watermark(
     }
     Ok(())
 }
+
+/// Prints the schema version of the perpetual tables and the migrations still to be applied, which
+/// the node applies when it starts. Can be used while the node is running.
+pub fn print_migration_status(path: &Path) -> anyhow::Result<()> {
+    let perpetual_db = AuthorityPerpetualTables::open_readonly(&path.join("store"));
+    let progress = read_progress(&perpetual_db.objects.rocksdb)?;
+    println!(
+        "Schema version: {} (latest: {})",
+        progress.schema_version,
+        AuthorityPerpetualTables::SCHEMA_VERSION
+    );
+    if let Some(step_progress) = &progress.in_progress {
+        println!(
+            "Interrupted migration to version {}: step {}, {} entries migrated",
+            step_progress.target_version,
+            step_progress.step + 1,
+            step_progress.entries_migrated
+        );
+    }
+    for migration in AuthorityPerpetualTables::migrations()
+        .iter()
+        .filter(|m| m.version > progress.schema_version)
+    {
+        println!(
+            "Pending migration to version {}: {}",
+            migration.version, migration.description
+        );
+        for (i, step) in migration.steps.iter().enumerate() {
+            println!("  step {}: {}", i + 1, step.description());
+        }
+    }
+    Ok(())
+}
@@ -2,12 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use fastcrypto_zkp::bn254::zk_login::OIDCProvider;
-use sui_config::transaction_deny_config::TransactionDenyConfig;
+use move_core_types::language_storage::StructTag;
+use sui_config::transaction_deny_config::{
+    CommandKind, RuleAction, TransactionDenyConfig, TransactionRule,
+};
 use sui_types::{
     base_types::ObjectRef,
     error::{SuiError, SuiResult, UserInputError},
     signature::GenericSignature,
-    storage::BackingPackageStore,
+    storage::{BackingPackageStore, ObjectStore},
     transaction::{Command, InputObjectKind, TransactionData, TransactionDataAPI},
 };
 macro_rules! deny_if_true {
@@ -31,6 +34,7 @@ pub fn check_transaction_for_signing(
     receiving_objects: &[ObjectRef],
     filter_config: &TransactionDenyConfig,
     package_store: &dyn BackingPackageStore,
+    object_store: &dyn ObjectStore,
 ) -> SuiResult {
     check_disabled_features(filter_config, tx_data, tx_signatures)?;
 
@@ -42,6 +46,8 @@ pub fn check_transaction_for_signing(
 
     check_receiving_objects(filter_config, receiving_objects)?;
 
+    check_rules(filter_config, tx_data, input_object_kinds, object_store)?;
+
     Ok(())
 }
 
@@ -209,3 +215,121 @@ fn check_package_dependencies(
     }
     Ok(())
 }
+
+/// Apply the rules of the deny config in order, until one of them matches the transaction.
+fn check_rules(
+    filter_config: &TransactionDenyConfig,
+    tx_data: &TransactionData,
+    input_object_kinds: &[InputObjectKind],
+    object_store: &dyn ObjectStore,
+) -> SuiResult {
+    let rules = filter_config.rules();
+    // Input objects are only loaded if a rule needs their types.
+    let mut input_object_types = None;
+    for rule in rules.iter() {
+        if !rule_matches(
+            rule,
+            tx_data,
+            input_object_kinds,
+            object_store,
+            &mut input_object_types,
+        ) {
+            continue;
+        }
+        return match rule.action {
+            RuleAction::Allow => Ok(()),
+            RuleAction::Deny => Err(SuiError::UserInputError {
+                error: UserInputError::TransactionDeniedByRule {
+                    rule: rule.name.clone(),
+                },
+            }),
+        };
+    }
+    Ok(())
+}
+
+fn rule_matches(
+    rule: &TransactionRule,
+    tx_data: &TransactionData,
+    input_object_kinds: &[InputObjectKind],
+    object_store: &dyn ObjectStore,
+    input_object_types: &mut Option<Vec<StructTag>>,
+) -> bool {
+    if !rule.senders.is_empty() && !rule.senders.contains(&tx_data.sender()) {
+        return false;
+    }
+
+    let gas_budget = tx_data.gas_budget();
+    if rule.min_gas_budget.is_some_and(|min| gas_budget < min)
+        || rule.max_gas_budget.is_some_and(|max| gas_budget > max)
+    {
+        return false;
+    }
+
+    if !rule.command_kinds.is_empty()
+        && !tx_data
+            .kind()
+            .iter_commands()
+            .any(|command| rule.command_kinds.contains(&command_kind(command)))
+    {
+        return false;
+    }
+
+    if !rule.move_call_targets.is_empty()
+        && !tx_data
+            .move_calls()
+            .into_iter()
+            .any(|(package, module, function)| {
+                rule.move_call_targets
+                    .iter()
+                    .any(|target| target.matches(package, module, function))
+            })
+    {
+        return false;
+    }
+
+    if !rule.input_object_types.is_empty() {
+        let types = input_object_types
+            .get_or_insert_with(|| load_input_object_types(input_object_kinds, object_store));
+        if !types.iter().any(|type_| {
+            rule.input_object_types
+                .iter()
+                .any(|filter| filter.matches(type_))
+        }) {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn command_kind(command: &Command) -> CommandKind {
+    match command {
+        Command::MoveCall(_) => CommandKind::MoveCall,
+        Command::TransferObjects(..) => CommandKind::TransferObjects,
+        Command::SplitCoins(..) => CommandKind::SplitCoins,
+        Command::MergeCoins(..) => CommandKind::MergeCoins,
+        Command::Publish(..) => CommandKind::Publish,
+        Command::MakeMoveVec(..) => CommandKind::MakeMoveVec,
+        Command::Upgrade(..) => CommandKind::Upgrade,
+    }
+}
+
+/// Types of the input objects that exist. Missing objects are skipped, since the transaction is
+/// rejected when its inputs are loaded anyway.
+fn load_input_object_types(
+    input_object_kinds: &[InputObjectKind],
+    object_store: &dyn ObjectStore,
+) -> Vec<StructTag> {
+    input_object_kinds
+        .iter()
+        .filter_map(|kind| match kind {
+            InputObjectKind::MovePackage(_) => None,
+            InputObjectKind::ImmOrOwnedMoveObject((id, version, _)) => {
+                object_store.get_object_by_key(id, *version)
+            }
+            InputObjectKind::SharedMoveObject { id, .. } => object_store.get_object(id),
+        })
+        .filter_map(|object| object.struct_tag())
+        .collect()
+}
@@ -6,6 +6,7 @@ use std::{collections::BTreeMap, path::PathBuf, sync::Arc, time::Duration};
 use move_binary_format::CompiledModule;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
+use simulacrum::store::StoreContents;
 use simulacrum::Simulacrum;
 use std::num::NonZeroUsize;
 use sui_config::genesis;
@@ -409,6 +410,54 @@ impl SimulatorStore for PersistedStore {
     fn backing_store(&self) -> &dyn sui_types::storage::BackingStore {
         self
     }
+
+    fn contents(&self) -> StoreContents {
+        let store = &self.read_write;
+        StoreContents {
+            checkpoints: store
+                .checkpoints
+                .unbounded_iter()
+                .map(|(_, checkpoint)| checkpoint)
+                .collect(),
+            checkpoint_contents: store
+                .checkpoint_contents
+                .unbounded_iter()
+                .map(|(_, contents)| contents)
+                .collect(),
+            committees: store
+                .epoch_to_committee
+                .get(&())
+                .expect("Fatal: DB read failed")
+                .unwrap_or_default(),
+            transactions: store
+                .transactions
+                .unbounded_iter()
+                .map(|(_, transaction)| transaction)
+                .collect(),
+            effects: store
+                .effects
+                .unbounded_iter()
+                .map(|(_, effects)| effects)
+                .collect(),
+            events: store
+                .events_tx_digest_index
+                .unbounded_iter()
+                .filter_map(|(tx_digest, events_digest)| {
+                    let events = store
+                        .events
+                        .get(&events_digest)
+                        .expect("Fatal: DB read failed")?;
+                    Some((tx_digest, events))
+                })
+                .collect(),
+            objects: store
+                .objects
+                .unbounded_iter()
+                .flat_map(|(_, versions)| versions.into_values())
+                .collect(),
+            live_objects: store.live_objects.unbounded_iter().collect(),
+        }
+    }
 }
 
 impl BackingPackageStore for PersistedStore {
@@ -298,6 +298,9 @@ pub enum UserInputError {
 
     #[error("Object used as owned is not owned")]
     NotOwnedObjectError,
+
+    #[error("Transaction is denied by rule `{rule}`")]
+    TransactionDeniedByRule { rule: String },
 }
 
 #[derive(
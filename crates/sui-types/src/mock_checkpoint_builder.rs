@@ -32,9 +32,17 @@ pub struct MockCheckpointBuilder {
 
 impl MockCheckpointBuilder {
     pub fn new(previous_checkpoint: VerifiedCheckpoint) -> Self {
-        let epoch_rolling_gas_cost_summary =
-            previous_checkpoint.epoch_rolling_gas_cost_summary.clone();
-        let epoch = previous_checkpoint.epoch;
+        // If the previous checkpoint ended its epoch, the next one starts the following epoch
+        // with a fresh rolling gas cost summary.
+        let (epoch, epoch_rolling_gas_cost_summary) =
+            if previous_checkpoint.end_of_epoch_data.is_some() {
+                (previous_checkpoint.epoch + 1, GasCostSummary::default())
+            } else {
+                (
+                    previous_checkpoint.epoch,
+                    previous_checkpoint.epoch_rolling_gas_cost_summary.clone(),
+                )
+            };
 
         Self {
             previous_checkpoint: Some(previous_checkpoint),
@@ -179,7 +187,10 @@ impl MockCheckpointBuilder {
         (checkpoint, contents, full_contents)
     }
 
-    fn create_certified_checkpoint(
+    /// Certify `checkpoint` with the keys of every validator in the committee. This can be used to
+    /// create a checkpoint for the builder to start from, when the state being built on was not
+    /// produced by a previous builder (e.g. a fork of existing state).
+    pub fn create_certified_checkpoint(
         validator_keys: &impl ValidatorKeypairProvider,
         checkpoint: CheckpointSummary,
     ) -> VerifiedCheckpoint {
@@ -3,6 +3,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 // These values set to loosely attempt to limit
@@ -202,6 +203,82 @@ fn default_sketch_tolerance() -> f64 {
     DEFAULT_SKETCH_TOLERANCE
 }
 
+/// Relative cost of requests when evaluated by the `TokenBucket` and `SlidingWindow`
+/// policies, so that expensive requests count for more than cheap ones. Example:
+///
+/// ```ignore
+/// request-weights:
+///   default: 1
+///   weights:
+///     sui_dryRunTransactionBlock: 10
+///     sui_executeTransactionBlock: 10
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RequestWeights {
+    /// Weight of requests whose type has no entry in `weights`.
+    #[serde(default = "default_request_weight")]
+    pub default: u64,
+    /// Weight per request type, i.e. the JSON-RPC method name on rpc nodes
+    /// (e.g. `sui_getObject`), or the request type on validators (e.g. `transaction`).
+    #[serde(default)]
+    pub weights: BTreeMap<String, u64>,
+}
+
+impl RequestWeights {
+    pub fn weight(&self, request_type: Option<&str>) -> u64 {
+        request_type
+            .and_then(|request_type| self.weights.get(request_type))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+impl Default for RequestWeights {
+    fn default() -> Self {
+        Self {
+            default: default_request_weight(),
+            weights: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_request_weight() -> u64 {
+    1
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TokenBucketConfig {
+    /// Maximum number of tokens a client can accumulate, i.e. the largest
+    /// burst of request weight allowed.
+    pub capacity: u64,
+    /// Number of tokens added to a client's bucket per second, i.e. the
+    /// sustained request weight per second allowed.
+    pub refill_rate: f64,
+    #[serde(default)]
+    pub proxied_capacity: Option<u64>,
+    #[serde(default)]
+    pub proxied_refill_rate: Option<f64>,
+    #[serde(default)]
+    pub request_weights: RequestWeights,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SlidingWindowConfig {
+    /// Total request weight a client may reach within the window before being blocked.
+    pub client_threshold: u64,
+    #[serde(default)]
+    pub proxied_client_threshold: Option<u64>,
+    #[serde(default = "default_window_size_secs")]
+    pub window_size_secs: u64,
+    #[serde(default)]
+    pub request_weights: RequestWeights,
+}
+
 // Serializable representation of policy types, used in config
 // in order to easily change in tests or to killswitch
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
@@ -215,6 +292,20 @@ pub enum PolicyType {
     /// with granularity of `update_interval_secs`
     FreqThreshold(FreqThresholdConfig),
 
+    /// Blocks a client once it has used up a bucket of `capacity` tokens, refilled at
+    /// `refill_rate` tokens per second, where each request consumes tokens according
+    /// to `request_weights`. Proxied clients are only tracked if `proxied_capacity` and
+    /// `proxied_refill_rate` are set. As a spam policy, every tally is counted, regardless
+    /// of `spam_sample_rate`.
+    TokenBucket(TokenBucketConfig),
+
+    /// Blocks a client once the total weight of its requests, according to
+    /// `request_weights`, exceeds `client_threshold` over the last `window_size_secs`.
+    /// Every request is logged, so this is exact but uses memory proportional to the
+    /// request rate. Proxied clients are only tracked if `proxied_client_threshold` is set.
+    /// As a spam policy, every tally is counted, regardless of `spam_sample_rate`.
+    SlidingWindow(SlidingWindowConfig),
+
     /* Below this point are test policies, and thus should not be used in production */
     ///
     /// Simple policy that adds connection_ip to blocklist when the same connection_ip
@@ -247,7 +338,8 @@ pub struct PolicyConfig {
     /// endpoint-specific sample policy (not configurable) which
     /// weighs endpoints by the relative effort required to serve
     /// them. Therefore a sample rate of N will yield an actual
-    /// sample rate <= N.
+    /// sample rate <= N. Not applied to the TokenBucket and SlidingWindow
+    /// policies, whose limits are expressed in request weights.
     pub spam_sample_rate: Weight,
     #[serde(default = "default_dry_run")]
     pub dry_run: bool,
@@ -256,6 +348,14 @@ pub struct PolicyConfig {
     /// and any blocklist related configuration will be ignored.
     #[serde(default)]
     pub allow_list: Option<Vec<String>>,
+    /// If set, the blocklists are snapshotted to this file and restored
+    /// from it on startup, so that active blocks survive a restart.
+    #[serde(default)]
+    pub blocklist_persistence_path: Option<PathBuf>,
+    /// If set, every block and unblock, whether issued by a policy or
+    /// through the admin interface, is appended to this file as a JSON line.
+    #[serde(default)]
+    pub blocklist_audit_log_path: Option<PathBuf>,
 }
 
 impl Default for PolicyConfig {
@@ -270,6 +370,8 @@ impl Default for PolicyConfig {
             spam_sample_rate: default_spam_sample_rate(),
             dry_run: default_dry_run(),
             allow_list: None,
+            blocklist_persistence_path: None,
+            blocklist_audit_log_path: None,
         }
     }
 }
@@ -3,6 +3,7 @@
 
 use crate::{
     clever_error_rendering::render_clever_error_opt,
+    client_multisig::{MultiSigCommand, MultiSigProposalStatus},
     client_ptb::ptb::PTB,
     displays::Pretty,
     key_identity::{get_identity_address, KeyIdentity},
@@ -205,6 +206,11 @@ pub enum SuiClientCommands {
         signed_tx_bytes: String,
     },
 
+    /// Collect signatures for a transaction from a multisig address from its signers, one at a
+    /// time, and execute it once they meet the multisig's threshold.
+    #[clap(name = "multisig", subcommand)]
+    MultiSig(MultiSigCommand),
+
     /// Request gas coin from faucet. By default, it will use the active address and the active network.
     #[clap[name = "faucet"]]
     Faucet {
@@ -1656,6 +1662,7 @@ impl SuiClientCommands {
                 ptb.execute(context).await?;
                 SuiClientCommandResult::NoOutput
             }
+            SuiClientCommands::MultiSig(cmd) => cmd.execute(context).await?,
         };
         Ok(ret.prerender_clever_errors(context).await)
     }
@@ -2109,6 +2116,9 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::SyncClientState => {
                 writeln!(writer, "Client state sync complete.")?;
             }
+            SuiClientCommandResult::MultiSigProposal(status) => {
+                writeln!(writer, "{}", status)?;
+            }
             SuiClientCommandResult::ChainIdentifier(ci) => {
                 writeln!(writer, "{}", ci)?;
             }
@@ -2346,6 +2356,7 @@ impl SuiClientCommandResult {
             | SuiClientCommandResult::DevInspect(_)
             | SuiClientCommandResult::Envs(_, _)
             | SuiClientCommandResult::Gas(_)
+            | SuiClientCommandResult::MultiSigProposal(_)
             | SuiClientCommandResult::NewAddress(_)
             | SuiClientCommandResult::NewEnv(_)
             | SuiClientCommandResult::NoOutput
@@ -2496,6 +2507,7 @@ pub enum SuiClientCommandResult {
     DevInspect(DevInspectResults),
     Envs(Vec<SuiEnv>, Option<String>),
     Gas(Vec<GasCoin>),
+    MultiSigProposal(MultiSigProposalStatus),
     NewAddress(NewAddressOutput),
     NewEnv(SuiEnv),
     NoOutput,
@@ -0,0 +1,418 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    fmt::{Display, Formatter, Write},
+    fs,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use clap::*;
+use fastcrypto::encoding::{Base64, Encoding};
+use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
+use serde::{Deserialize, Serialize};
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_json_rpc_types::SuiTransactionBlockData;
+use sui_keys::keystore::AccountKeystore;
+use sui_sdk::wallet_context::WalletContext;
+use sui_types::{
+    base_types::SuiAddress,
+    crypto::{EncodeDecodeBase64, PublicKey, SuiSignature},
+    digests::TransactionDigest,
+    multisig::{MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit},
+    signature::GenericSignature,
+    transaction::{Transaction, TransactionData, TransactionDataAPI},
+};
+use tabled::{builder::Builder as TableBuilder, settings::Style as TableStyle};
+
+use crate::{
+    client_commands::SuiClientCommandResult,
+    key_identity::{get_identity_address, KeyIdentity},
+};
+
+/// Commands for collecting the signatures of a transaction sent from a multisig address, from its
+/// signers one at a time, and executing it once they meet the multisig's threshold. Signatures are
+/// tracked in a proposal file, which is passed between signers.
+#[derive(Subcommand)]
+#[clap(rename_all = "kebab-case")]
+pub enum MultiSigCommand {
+    /// Create a proposal for a transaction sent from the multisig address defined by `pks`,
+    /// `weights` and `threshold`, and write it to a new file at `proposal`.
+    Create {
+        /// BCS serialized transaction data bytes without its type tag, as base64 encoded string.
+        /// This is the output of sui client commands using --serialize-unsigned-transaction.
+        #[clap(long)]
+        tx_bytes: String,
+        /// The public keys `flag || pk` in Base64 of the multisig's members.
+        #[clap(long, num_args(1..))]
+        pks: Vec<PublicKey>,
+        /// The weights of the multisig's members, in the same order as `pks`.
+        #[clap(long, num_args(1..))]
+        weights: Vec<WeightUnit>,
+        /// The total weight of signatures required to execute the transaction.
+        #[clap(long)]
+        threshold: ThresholdUnit,
+        /// Path to write the proposal to.
+        proposal: PathBuf,
+    },
+
+    /// Decode the transaction in a proposal, and show which members have signed it so far.
+    Inspect {
+        /// Path to the proposal.
+        proposal: PathBuf,
+    },
+
+    /// Sign the transaction in a proposal with a key from the keystore, and add the signature to
+    /// the proposal. Defaults to the active address. The decoded transaction is shown first, and
+    /// signing has to be confirmed.
+    Sign {
+        /// Path to the proposal.
+        proposal: PathBuf,
+        /// The address (or its alias) of the member to sign as.
+        #[clap(long)]
+        #[arg(value_parser)]
+        address: Option<KeyIdentity>,
+        /// Sign without asking for confirmation.
+        #[clap(short = 'y', long)]
+        yes: bool,
+    },
+
+    /// Add a signature `flag || sig || pk` in Base64 that was produced elsewhere (e.g. by `sui
+    /// keytool sign`) to a proposal.
+    AddSignature {
+        /// Path to the proposal.
+        proposal: PathBuf,
+        #[clap(long)]
+        signature: GenericSignature,
+    },
+
+    /// Combine the signatures in a proposal into a multisig and execute its transaction. Fails if
+    /// the signatures do not meet the multisig's threshold.
+    Execute {
+        /// Path to the proposal.
+        proposal: PathBuf,
+    },
+}
+
+/// A transaction sent from a multisig address, and the signatures that have been collected for it
+/// from the multisig's members so far. This is the format of proposal files.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigProposal {
+    /// BCS serialized transaction data bytes, as base64 encoded string.
+    pub tx_bytes: String,
+    pub multisig_pk: MultiSigPublicKey,
+    /// Signatures from members of the multisig, in the order they were added.
+    pub signatures: Vec<GenericSignature>,
+}
+
+/// A proposal's transaction, decoded, and the members of its multisig, including whether they
+/// have signed it yet.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigProposalStatus {
+    pub multisig_address: SuiAddress,
+    pub digest: TransactionDigest,
+    pub transaction: SuiTransactionBlockData,
+    pub members: Vec<MultiSigMemberStatus>,
+    pub weight: ThresholdUnit,
+    pub threshold: ThresholdUnit,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigMemberStatus {
+    pub address: SuiAddress,
+    pub public_base64_key: String,
+    pub weight: WeightUnit,
+    pub signed: bool,
+}
+
+impl MultiSigCommand {
+    pub async fn execute(
+        self,
+        context: &mut WalletContext,
+    ) -> Result<SuiClientCommandResult, anyhow::Error> {
+        Ok(match self {
+            MultiSigCommand::Create {
+                tx_bytes,
+                pks,
+                weights,
+                threshold,
+                proposal: path,
+            } => {
+                ensure!(
+                    !path.exists(),
+                    "Proposal file {} already exists",
+                    path.display()
+                );
+
+                let multisig_pk = MultiSigPublicKey::new(pks, weights, threshold)?;
+                let proposal = MultiSigProposal {
+                    tx_bytes,
+                    multisig_pk,
+                    signatures: vec![],
+                };
+
+                let tx_data = proposal.tx_data()?;
+                let multisig_address = SuiAddress::from(&proposal.multisig_pk);
+                ensure!(
+                    tx_data.sender() == multisig_address,
+                    "Transaction is sent from {}, not from multisig address {multisig_address}",
+                    tx_data.sender(),
+                );
+
+                proposal.write(&path)?;
+                SuiClientCommandResult::MultiSigProposal(proposal.status()?)
+            }
+
+            MultiSigCommand::Inspect { proposal: path } => {
+                let proposal = MultiSigProposal::read(&path)?;
+                SuiClientCommandResult::MultiSigProposal(proposal.status()?)
+            }
+
+            MultiSigCommand::Sign {
+                proposal: path,
+                address,
+                yes,
+            } => {
+                let mut proposal = MultiSigProposal::read(&path)?;
+                let address = get_identity_address(address, context)?;
+
+                if !yes {
+                    println!("{}", proposal.status()?.transaction);
+                    print!("Sign this transaction as {address} [y/N]? ");
+                    if !read_line()?.trim().eq_ignore_ascii_case("y") {
+                        bail!("Signing aborted, the proposal was not changed");
+                    }
+                }
+
+                let tx_data = proposal.tx_data()?;
+                let signature = context.config.keystore.sign_secure(
+                    &address,
+                    &tx_data,
+                    Intent::sui_transaction(),
+                )?;
+
+                proposal.add_signature(signature.into())?;
+                proposal.write(&path)?;
+                SuiClientCommandResult::MultiSigProposal(proposal.status()?)
+            }
+
+            MultiSigCommand::AddSignature {
+                proposal: path,
+                signature,
+            } => {
+                let mut proposal = MultiSigProposal::read(&path)?;
+                proposal.add_signature(signature)?;
+                proposal.write(&path)?;
+                SuiClientCommandResult::MultiSigProposal(proposal.status()?)
+            }
+
+            MultiSigCommand::Execute { proposal: path } => {
+                let proposal = MultiSigProposal::read(&path)?;
+                let transaction = proposal.transaction()?;
+                let response = context.execute_transaction_may_fail(transaction).await?;
+                SuiClientCommandResult::TransactionBlock(response)
+            }
+        })
+    }
+}
+
+fn read_line() -> Result<String, anyhow::Error> {
+    let mut s = String::new();
+    let _ = io::stdout().flush();
+    io::stdin().read_line(&mut s)?;
+    Ok(s)
+}
+
+impl MultiSigProposal {
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Cannot read proposal file {}", path.display()))?;
+        let proposal: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("Cannot parse proposal file {}", path.display()))?;
+
+        proposal
+            .multisig_pk
+            .validate()
+            .map_err(|_| anyhow!("Invalid multisig public key in proposal"))?;
+        Ok(proposal)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(path, contents)
+            .with_context(|| format!("Cannot write proposal file {}", path.display()))
+    }
+
+    pub fn tx_data(&self) -> Result<TransactionData, anyhow::Error> {
+        let bytes =
+            Base64::decode(&self.tx_bytes).map_err(|_| anyhow!("Invalid Base64 encoding"))?;
+        bcs::from_bytes(&bytes).map_err(|_| {
+            anyhow!(
+                "Failed to parse tx bytes, check if it matches the output of sui client commands \
+                 with --serialize-unsigned-transaction"
+            )
+        })
+    }
+
+    /// Add `signature` to the proposal, after checking that it is a valid signature of the
+    /// proposal's transaction, from a member of the multisig that has not signed it yet.
+    pub fn add_signature(&mut self, signature: GenericSignature) -> Result<(), anyhow::Error> {
+        let pk = signature.to_public_key()?;
+        let Some(index) = self.multisig_pk.get_index(&pk) else {
+            bail!(
+                "Signature is from {}, which is not a member of multisig address {}",
+                SuiAddress::from(&pk),
+                SuiAddress::from(&self.multisig_pk),
+            );
+        };
+
+        if self.signed_indices()?.contains(&index) {
+            bail!(
+                "Proposal has already been signed by {}",
+                SuiAddress::from(&pk)
+            );
+        }
+
+        // Signatures from other kinds of authenticators (e.g. zkLogin) are checked when the
+        // transaction is executed.
+        if let GenericSignature::Signature(sig) = &signature {
+            let intent_msg = IntentMessage::new(Intent::sui_transaction(), self.tx_data()?);
+            sig.verify_secure(&intent_msg, SuiAddress::from(&pk), sig.scheme())
+                .map_err(|e| anyhow!("Invalid signature for proposal's transaction: {e}"))?;
+        }
+
+        self.signatures.push(signature);
+        Ok(())
+    }
+
+    /// Combine the proposal's signatures into a multisig, and pair it with its transaction.
+    pub fn transaction(&self) -> Result<Transaction, anyhow::Error> {
+        let status = self.status()?;
+        ensure!(
+            status.weight >= status.threshold,
+            "Proposal's signatures have weight {}, which does not meet the threshold {}",
+            status.weight,
+            status.threshold,
+        );
+
+        // Signatures must be combined in the same order as the multisig's public keys.
+        let mut signatures: Vec<_> = self
+            .signed_indices()?
+            .into_iter()
+            .zip(self.signatures.iter().cloned())
+            .collect();
+        signatures.sort_by_key(|(index, _)| *index);
+
+        let multisig = MultiSig::combine(
+            signatures.into_iter().map(|(_, s)| s).collect(),
+            self.multisig_pk.clone(),
+        )?;
+
+        Ok(Transaction::from_generic_sig_data(
+            self.tx_data()?,
+            vec![GenericSignature::MultiSig(multisig)],
+        ))
+    }
+
+    pub fn status(&self) -> Result<MultiSigProposalStatus, anyhow::Error> {
+        let tx_data = self.tx_data()?;
+        let digest = tx_data.digest();
+
+        // Decode the transaction without fetching the packages it calls, so that proposals can be
+        // inspected offline.
+        struct NoOpsModuleResolver;
+        impl ModuleResolver for NoOpsModuleResolver {
+            type Error = anyhow::Error;
+            fn get_module(&self, _id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+                Ok(None)
+            }
+        }
+
+        let transaction = SuiTransactionBlockData::try_from(tx_data, &&mut NoOpsModuleResolver)?;
+
+        let signed = self.signed_indices()?;
+        let mut weight: ThresholdUnit = 0;
+        let mut members = vec![];
+        for (index, (pk, w)) in self.multisig_pk.pubkeys().iter().enumerate() {
+            let has_signed = signed.contains(&(index as u8));
+            if has_signed {
+                weight += *w as ThresholdUnit;
+            }
+
+            members.push(MultiSigMemberStatus {
+                address: SuiAddress::from(pk),
+                public_base64_key: pk.encode_base64(),
+                weight: *w,
+                signed: has_signed,
+            });
+        }
+
+        Ok(MultiSigProposalStatus {
+            multisig_address: SuiAddress::from(&self.multisig_pk),
+            digest,
+            transaction,
+            members,
+            weight,
+            threshold: *self.multisig_pk.threshold(),
+        })
+    }
+
+    /// The indices of the multisig public keys that each signature is from, in the same order as
+    /// the signatures.
+    fn signed_indices(&self) -> Result<Vec<u8>, anyhow::Error> {
+        self.signatures
+            .iter()
+            .map(|s| {
+                let pk = s.to_public_key()?;
+                self.multisig_pk
+                    .get_index(&pk)
+                    .ok_or_else(|| anyhow!("Proposal contains a signature from a non-member"))
+            })
+            .collect()
+    }
+}
+
+impl Display for MultiSigProposalStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(writer, "Multisig Address: {}", self.multisig_address)?;
+        writeln!(writer, "Transaction Digest: {}", self.digest)?;
+        writeln!(writer, "{}", self.transaction)?;
+
+        let mut builder = TableBuilder::default();
+        builder.set_header(["address", "publicBase64Key", "weight", "signed"]);
+        for member in &self.members {
+            builder.push_record([
+                member.address.to_string(),
+                member.public_base64_key.clone(),
+                member.weight.to_string(),
+                if member.signed { "*" } else { "" }.to_string(),
+            ]);
+        }
+
+        let mut table = builder.build();
+        table.with(TableStyle::rounded());
+        writeln!(writer, "{table}")?;
+
+        if self.weight >= self.threshold {
+            writeln!(
+                writer,
+                "Signature weight {} meets the threshold {}, the transaction can be executed.",
+                self.weight, self.threshold,
+            )?;
+        } else {
+            writeln!(
+                writer,
+                "Signature weight {} does not meet the threshold {} yet.",
+                self.weight, self.threshold,
+            )?;
+        }
+
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
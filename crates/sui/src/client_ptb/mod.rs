@@ -7,4 +7,5 @@ pub mod error;
 pub mod lexer;
 pub mod parser;
 pub mod ptb;
+pub mod script;
 pub mod token;
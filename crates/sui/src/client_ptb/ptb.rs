@@ -7,6 +7,7 @@ use crate::{
         ast::{ParsedProgram, Program},
         builder::PTBBuilder,
         error::{build_error_reports, PTBError},
+        script::expand_args,
         token::{Lexeme, Token},
     },
     displays::Pretty,
@@ -53,10 +54,13 @@ impl PTB {
             ptb_description().print_help().unwrap();
             return Ok(());
         }
-        let source_string = to_source_string(self.args.clone());
+
+        // Expand any scripts into the commands they contain
+        let (args, source_map) = expand_args(self.args)?;
+        let source_string = to_source_string(args.clone());
 
         // Tokenize once to detect help flags
-        let tokens = self.args.iter().map(|s| s.as_str());
+        let tokens = args.iter().map(|s| s.as_str());
         for sp!(_, lexeme) in Lexer::new(tokens.clone()).into_iter().flatten() {
             match lexeme {
                 Lexeme(Token::Command, "help") => return Ok(ptb_description().print_long_help()?),
@@ -73,7 +77,7 @@ impl PTB {
         {
             Err(errors) => {
                 let suffix = if errors.len() > 1 { "s" } else { "" };
-                let rendered = build_error_reports(&source_string, errors);
+                let rendered = build_error_reports(&source_string, source_map.annotate(errors));
                 eprintln!("Encountered error{suffix} when parsing PTB:");
                 for e in rendered.iter() {
                     eprintln!("{:?}", e);
@@ -107,7 +111,7 @@ impl PTB {
         if !warnings.is_empty() {
             let suffix = if warnings.len() > 1 { "s" } else { "" };
             eprintln!("Warning{suffix} produced when building PTB:");
-            let rendered = build_error_reports(&source_string, warnings);
+            let rendered = build_error_reports(&source_string, source_map.annotate(warnings));
             for e in rendered.iter() {
                 eprintln!("{:?}", e);
             }
@@ -116,7 +120,7 @@ impl PTB {
             Err(errors) => {
                 let suffix = if errors.len() > 1 { "s" } else { "" };
                 eprintln!("Encountered error{suffix} when building PTB:");
-                let rendered = build_error_reports(&source_string, errors);
+                let rendered = build_error_reports(&source_string, source_map.annotate(errors));
                 for e in rendered.iter() {
                     eprintln!("{:?}", e);
                 }
@@ -292,6 +296,28 @@ pub fn ptb_description() -> clap::Command {
             \n --assign new_coins # bound new_coins to the result of previous transaction"
         )
         .value_names(["NAME", "VALUE"]))
+        .arg(arg!(
+            --"file" <PATH>
+            "Expand the PTB script at PATH into the commands it contains."
+        )
+        .long_help(
+            "Expand the PTB script at PATH into the commands it contains. Scripts contain commands \
+            written as they would be on the command line, and the following directives, at the \
+            start of a line:\
+            \n param NAME [DEFAULT]        declare a parameter, set with --param NAME=VALUE\
+            \n for NAME in [A, B, ...]     repeat the following lines for each element, up to 'end'\
+            \n macro NAME [PARAM ...]      define a macro from the following lines, up to 'end'\
+            \n expand NAME [ARG ...]       expand a macro\
+            \n include PATH                expand another script, relative to this one\
+            \nVariables are referred to as {NAME}.\
+            \n\nExamples:\
+            \n --file release.ptb --param package=@0x42 --gas-budget 100000000"
+        )
+        .value_hint(ValueHint::FilePath))
+        .arg(arg!(
+            --"param" <NAME_VALUE>
+            "Supply the value of a parameter declared by a PTB script, as NAME=VALUE."
+        ))
         .arg(arg!(
             --"dry-run"
             "Perform a dry run of the PTB instead of executing it."
@@ -0,0 +1,1032 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! PTB scripts are files of PTB commands, written as they would be on the command line (split into
+//! shell tokens using the shell's quoting rules), that are expanded into shell tokens before they
+//! are parsed. On top of plain commands, scripts support the following directives, each of which
+//! must appear at the start of a line:
+//!
+//! - `param NAME [DEFAULT]` declares a parameter, whose value is supplied on the command line with
+//!   `--param NAME=VALUE`, falling back to `DEFAULT`.
+//! - `for NAME in [A, B, ...]` ... `end` repeats its body once for each element of the list.
+//! - `macro NAME [PARAM ...]` ... `end` defines a macro, that is expanded with
+//!   `expand NAME [ARG ...]`.
+//! - `include PATH` expands the script at `PATH`, relative to the including script.
+//!
+//! Variables (parameters, loop variables and macro parameters) are referred to as `{NAME}` and are
+//! substituted wherever they appear in a shell token. Literal braces are written `{{` and `}}`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Error};
+
+use crate::client_ptb::error::PTBError;
+
+// Command line arguments
+pub const FILE: &str = "--file";
+pub const PARAM: &str = "--param";
+
+// Directives
+pub const PARAM_DIRECTIVE: &str = "param";
+pub const FOR_DIRECTIVE: &str = "for";
+pub const MACRO_DIRECTIVE: &str = "macro";
+pub const EXPAND_DIRECTIVE: &str = "expand";
+pub const INCLUDE_DIRECTIVE: &str = "include";
+pub const END_DIRECTIVE: &str = "end";
+
+/// Location of a logical line in a script, for error reporting.
+#[derive(Clone, Debug)]
+struct Loc {
+    path: PathBuf,
+    line: usize,
+}
+
+/// Location of a shell token in a script, for error reporting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenLoc {
+    path: PathBuf,
+    line: usize,
+    column: usize,
+}
+
+/// Maps positions in the source string built from expanded shell tokens (see
+/// `ptb::to_source_string`) back to the locations in scripts that those tokens came from.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    /// The offset of each token in the source string, and its location, if it came from a script.
+    tokens: Vec<(usize, Option<TokenLoc>)>,
+    /// The offset in the source string just past the last token.
+    end: usize,
+}
+
+/// A parsed script item.
+#[derive(Clone, Debug)]
+enum Item {
+    /// Shell tokens that form (part of) PTB commands, and their locations.
+    Tokens(Loc, Vec<(String, TokenLoc)>),
+    Param {
+        loc: Loc,
+        name: String,
+        default: Option<String>,
+    },
+    For {
+        loc: Loc,
+        var: String,
+        list: String,
+        body: Vec<Item>,
+    },
+    Macro {
+        loc: Loc,
+        name: String,
+        params: Vec<String>,
+        body: Vec<Item>,
+    },
+    Expand {
+        loc: Loc,
+        name: String,
+        args: Vec<String>,
+    },
+    Include(Loc, String),
+}
+
+/// A macro definition.
+struct Macro {
+    params: Vec<String>,
+    body: Vec<Item>,
+}
+
+/// Variables in scope, mapping names to the text they are substituted with.
+type Env = BTreeMap<String, String>;
+
+/// A logical line of a script: its location, its shell tokens, and the locations of those tokens.
+type Line = (Loc, Vec<String>, Vec<TokenLoc>);
+
+/// State that is shared by all the scripts expanded for one invocation of `sui client ptb`.
+struct Expander {
+    /// Parameter values supplied on the command line.
+    values: BTreeMap<String, String>,
+    /// Parameters declared by scripts so far, and the values they are bound to.
+    params: Env,
+    macros: BTreeMap<String, Macro>,
+    /// Scripts currently being expanded, to detect include cycles.
+    includes: Vec<PathBuf>,
+    /// Macros currently being expanded, to detect recursion.
+    expanding: Vec<String>,
+    /// The expanded shell tokens, and where they came from.
+    tokens: Vec<String>,
+    source_map: SourceMap,
+}
+
+/// Expand any scripts referred to by `--file PATH` in the command line arguments `args`, into the
+/// shell tokens they contain, binding their parameters to the values supplied by `--param
+/// NAME=VALUE`. Arguments outside of scripts are passed through as they are. Also returns a
+/// source map, to report errors in the expanded tokens against the scripts they came from.
+pub fn expand_args(args: Vec<String>) -> Result<(Vec<String>, SourceMap), Error> {
+    let mut values = BTreeMap::new();
+    let mut rest = vec![];
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg != PARAM {
+            rest.push(arg);
+            continue;
+        }
+
+        let param = args
+            .next()
+            .ok_or_else(|| anyhow!("Expected NAME=VALUE after {PARAM}"))?;
+        let Some((name, value)) = param.split_once('=') else {
+            bail!("Expected NAME=VALUE after {PARAM}, found {param:?}");
+        };
+
+        if values.insert(name.to_string(), value.to_string()).is_some() {
+            bail!("Parameter '{name}' supplied more than once");
+        }
+    }
+
+    let mut expander = Expander {
+        values,
+        params: Env::new(),
+        macros: BTreeMap::new(),
+        includes: vec![],
+        expanding: vec![],
+        tokens: vec![],
+        source_map: SourceMap::default(),
+    };
+
+    let mut rest = rest.into_iter();
+    while let Some(arg) = rest.next() {
+        if arg != FILE {
+            expander.push(arg, None);
+            continue;
+        }
+
+        let path = rest
+            .next()
+            .ok_or_else(|| anyhow!("Expected a path after {FILE}"))?;
+        expander.include(Path::new(&path))?;
+    }
+
+    let unused: Vec<_> = expander
+        .values
+        .keys()
+        .filter(|name| !expander.params.contains_key(*name))
+        .map(|name| format!("'{name}'"))
+        .collect();
+
+    if !unused.is_empty() {
+        bail!(
+            "Values supplied for undeclared parameter(s): {}",
+            unused.join(", ")
+        );
+    }
+
+    Ok((expander.tokens, expander.source_map))
+}
+
+impl SourceMap {
+    /// The location in a script of the token containing `offset` in the source string, if there
+    /// is one.
+    pub fn locate(&self, offset: usize) -> Option<&TokenLoc> {
+        if offset >= self.end {
+            return None;
+        }
+
+        let ix = self
+            .tokens
+            .partition_point(|(start, _)| *start <= offset)
+            .checked_sub(1)?;
+        self.tokens[ix].1.as_ref()
+    }
+
+    /// Prefix the messages of `errors` that point into tokens from scripts with the script
+    /// location of those tokens.
+    pub fn annotate(&self, errors: Vec<PTBError>) -> Vec<PTBError> {
+        errors
+            .into_iter()
+            .map(|mut error| {
+                if let Some(loc) = self.locate(error.span.start) {
+                    error.message = format!("{loc}: {}", error.message);
+                }
+                error
+            })
+            .collect()
+    }
+
+    /// Record the next token in the source string, of length `len`.
+    fn push(&mut self, len: usize, loc: Option<TokenLoc>) {
+        self.tokens.push((self.end, loc));
+        // Tokens are separated by one character in the source string.
+        self.end += len + 1;
+    }
+}
+
+impl Expander {
+    /// Add `token` to the expansion, coming from `loc` in a script, if it is set.
+    fn push(&mut self, token: String, loc: Option<TokenLoc>) {
+        self.source_map.push(token.len(), loc);
+        self.tokens.push(token);
+    }
+
+    /// Expand the script at `path`.
+    fn include(&mut self, path: &Path) -> Result<(), Error> {
+        let canonical = path
+            .canonicalize()
+            .with_context(|| format!("Cannot find PTB script {}", path.display()))?;
+
+        if self.includes.contains(&canonical) {
+            bail!("PTB script {} includes itself", path.display());
+        }
+
+        let contents = fs::read_to_string(&canonical)
+            .with_context(|| format!("Cannot read PTB script {}", path.display()))?;
+
+        let items = parse(path, &contents)?;
+        self.includes.push(canonical);
+        let env = self.params.clone();
+        self.expand(&items, &env)?;
+        self.includes.pop();
+        Ok(())
+    }
+
+    fn expand(&mut self, items: &[Item], env: &Env) -> Result<(), Error> {
+        // Parameters declared in this block are visible to the rest of the block.
+        let mut env = env.clone();
+
+        for item in items {
+            match item {
+                Item::Tokens(loc, ts) => {
+                    for (t, token_loc) in ts {
+                        let token = substitute(loc, t, &env)?;
+                        self.push(token, Some(token_loc.clone()));
+                    }
+                }
+
+                Item::Param { loc, name, default } => {
+                    if self.params.contains_key(name) {
+                        bail!("{loc}: Parameter '{name}' is declared more than once");
+                    }
+
+                    let value = match (self.values.get(name), default) {
+                        (Some(value), _) => value.clone(),
+                        (None, Some(default)) => substitute(loc, default, &env)?,
+                        (None, None) => bail!(
+                            "{loc}: Missing value for parameter '{name}', supply it with \
+                             {PARAM} {name}=VALUE"
+                        ),
+                    };
+
+                    self.params.insert(name.clone(), value.clone());
+                    env.insert(name.clone(), value);
+                }
+
+                Item::For {
+                    loc,
+                    var,
+                    list,
+                    body,
+                } => {
+                    let list = substitute(loc, list, &env)?;
+                    for element in split_list(loc, &list)? {
+                        let mut env = env.clone();
+                        env.insert(var.clone(), element);
+                        self.expand(body, &env)?;
+                    }
+                }
+
+                Item::Macro {
+                    loc,
+                    name,
+                    params,
+                    body,
+                } => {
+                    if self.macros.contains_key(name) {
+                        bail!("{loc}: Macro '{name}' is defined more than once");
+                    }
+
+                    self.macros.insert(
+                        name.clone(),
+                        Macro {
+                            params: params.clone(),
+                            body: body.clone(),
+                        },
+                    );
+                }
+
+                Item::Expand { loc, name, args } => {
+                    let Some(Macro { params, body }) = self.macros.get(name) else {
+                        bail!("{loc}: Unknown macro '{name}'");
+                    };
+
+                    if params.len() != args.len() {
+                        bail!(
+                            "{loc}: Macro '{name}' expects {} argument(s), but {} were supplied",
+                            params.len(),
+                            args.len(),
+                        );
+                    }
+
+                    if self.expanding.contains(name) {
+                        bail!("{loc}: Macro '{name}' expands itself");
+                    }
+
+                    // Macro bodies see the variables in scope where they are expanded, as well as
+                    // their arguments.
+                    let mut inner = env.clone();
+                    for (param, arg) in params.iter().zip(args) {
+                        inner.insert(param.clone(), substitute(loc, arg, &env)?);
+                    }
+
+                    let body = body.clone();
+                    self.expanding.push(name.clone());
+                    self.expand(&body, &inner)?;
+                    self.expanding.pop();
+                }
+
+                Item::Include(loc, path) => {
+                    let path = substitute(loc, path, &env)?;
+                    let dir = loc.path.parent().unwrap_or(Path::new(""));
+                    self.include(&dir.join(path))
+                        .with_context(|| format!("{loc}: Failed to include PTB script"))?;
+
+                    // Parameters declared by the included script become visible to its includer.
+                    env.extend(self.params.clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse the contents of the script at `path` into items.
+fn parse(path: &Path, contents: &str) -> Result<Vec<Item>, Error> {
+    let lines = logical_lines(path, contents)?;
+    let mut lines = lines.into_iter();
+    let (items, end) = parse_block(&mut lines)?;
+
+    if let Some(loc) = end {
+        bail!("{loc}: Unexpected '{END_DIRECTIVE}'");
+    }
+
+    Ok(items)
+}
+
+/// Parse items until the end of the script or an `end` directive, returning the items, and the
+/// location of the `end` directive, if there was one.
+fn parse_block(lines: &mut impl Iterator<Item = Line>) -> Result<(Vec<Item>, Option<Loc>), Error> {
+    let mut items = vec![];
+
+    while let Some((loc, tokens, locs)) = lines.next() {
+        let Some(first) = tokens.first() else {
+            continue;
+        };
+
+        let item = match first.as_str() {
+            END_DIRECTIVE => {
+                if tokens.len() > 1 {
+                    bail!("{loc}: Unexpected input after '{END_DIRECTIVE}'");
+                }
+                return Ok((items, Some(loc)));
+            }
+
+            PARAM_DIRECTIVE => match &tokens[1..] {
+                [name] => Item::Param {
+                    name: ident(&loc, name)?,
+                    default: None,
+                    loc,
+                },
+                [name, default @ ..] if !default.is_empty() => Item::Param {
+                    name: ident(&loc, name)?,
+                    default: Some(default.join(" ")),
+                    loc,
+                },
+                _ => bail!("{loc}: Expected '{PARAM_DIRECTIVE} NAME [DEFAULT]'"),
+            },
+
+            FOR_DIRECTIVE => {
+                let [_, var, in_, list @ ..] = &tokens[..] else {
+                    bail!("{loc}: Expected '{FOR_DIRECTIVE} NAME in [...]'");
+                };
+
+                if in_ != "in" || list.is_empty() {
+                    bail!("{loc}: Expected '{FOR_DIRECTIVE} NAME in [...]'");
+                }
+
+                let var = ident(&loc, var)?;
+                let list = list.join(" ");
+                let body = parse_body(lines, &loc, FOR_DIRECTIVE)?;
+                Item::For {
+                    loc,
+                    var,
+                    list,
+                    body,
+                }
+            }
+
+            MACRO_DIRECTIVE => {
+                let [_, name, params @ ..] = &tokens[..] else {
+                    bail!("{loc}: Expected '{MACRO_DIRECTIVE} NAME [PARAM ...]'");
+                };
+
+                let name = ident(&loc, name)?;
+                let params = params
+                    .iter()
+                    .map(|p| ident(&loc, p))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let unique: BTreeSet<_> = params.iter().collect();
+                if unique.len() != params.len() {
+                    bail!("{loc}: Duplicate parameter in macro '{name}'");
+                }
+
+                let body = parse_body(lines, &loc, MACRO_DIRECTIVE)?;
+                Item::Macro {
+                    loc,
+                    name,
+                    params,
+                    body,
+                }
+            }
+
+            EXPAND_DIRECTIVE => {
+                let [_, name, args @ ..] = &tokens[..] else {
+                    bail!("{loc}: Expected '{EXPAND_DIRECTIVE} NAME [ARG ...]'");
+                };
+
+                Item::Expand {
+                    name: ident(&loc, name)?,
+                    args: args.to_vec(),
+                    loc,
+                }
+            }
+
+            INCLUDE_DIRECTIVE => {
+                let [_, path] = &tokens[..] else {
+                    bail!("{loc}: Expected '{INCLUDE_DIRECTIVE} PATH'");
+                };
+
+                Item::Include(loc, path.clone())
+            }
+
+            _ => Item::Tokens(loc, tokens.into_iter().zip(locs).collect()),
+        };
+
+        items.push(item);
+    }
+
+    Ok((items, None))
+}
+
+/// Parse the body of a block directive (`directive`, found at `loc`), up to and including its
+/// `end`.
+fn parse_body(
+    lines: &mut impl Iterator<Item = Line>,
+    loc: &Loc,
+    directive: &str,
+) -> Result<Vec<Item>, Error> {
+    let (body, end) = parse_block(lines)?;
+    if end.is_none() {
+        bail!("{loc}: Missing '{END_DIRECTIVE}' for '{directive}'");
+    }
+
+    Ok(body)
+}
+
+/// Split the script into logical lines of shell tokens. A logical line usually corresponds to a
+/// line in the script, unless it contains a quoted string that spans multiple lines.
+fn logical_lines(path: &Path, contents: &str) -> Result<Vec<Line>, Error> {
+    let mut lines = vec![];
+    let mut pending: Option<(usize, String)> = None;
+
+    for (ix, line) in contents.lines().enumerate() {
+        let (start, text) = match pending.take() {
+            Some((start, mut text)) => {
+                text.push('\n');
+                text.push_str(line);
+                (start, text)
+            }
+            None => (ix + 1, line.to_string()),
+        };
+
+        match shlex::split(&text) {
+            Some(tokens) => {
+                let locs = token_locs(path, start, &text, tokens.len());
+                let loc = Loc {
+                    path: path.to_path_buf(),
+                    line: start,
+                };
+                lines.push((loc, tokens, locs));
+            }
+            None => pending = Some((start, text)),
+        }
+    }
+
+    if let Some((line, _)) = pending {
+        let loc = Loc {
+            path: path.to_path_buf(),
+            line,
+        };
+        bail!("{loc}: Unterminated quote");
+    }
+
+    Ok(lines)
+}
+
+/// The locations of the `count` shell tokens in `text`, a logical line starting at line `line` of
+/// the script at `path`.
+fn token_locs(path: &Path, line: usize, text: &str, count: usize) -> Vec<TokenLoc> {
+    let mut starts = word_starts(text);
+
+    // If the tokens could not be found in the text, fall back to the start of the logical line.
+    if starts.len() != count {
+        starts = vec![0; count];
+    }
+
+    starts
+        .into_iter()
+        .map(|start| {
+            let before = &text[..start];
+            let (line, column) = match before.rfind('\n') {
+                Some(nl) => (
+                    line + before.matches('\n').count(),
+                    before[nl + 1..].chars().count() + 1,
+                ),
+                None => (line, before.chars().count() + 1),
+            };
+
+            TokenLoc {
+                path: path.to_path_buf(),
+                line,
+                column,
+            }
+        })
+        .collect()
+}
+
+/// The byte offsets at which shell words start in `text`, following the same quoting and comment
+/// rules as `shlex::split`.
+fn word_starts(text: &str) -> Vec<usize> {
+    let mut starts = vec![];
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' => continue,
+
+            // Comments run to the end of the line.
+            '#' => {
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                continue;
+            }
+
+            _ => starts.push(ix),
+        }
+
+        // Consume the rest of the word.
+        let mut quote = None;
+        let mut c = c;
+        loop {
+            match (quote, c) {
+                (None, '\\') | (Some('"'), '\\') => {
+                    chars.next();
+                }
+                (None, '\'' | '"') => quote = Some(c),
+                (Some(q), c) if c == q => quote = None,
+                _ => {}
+            }
+
+            match chars.peek() {
+                Some((_, ' ' | '\t' | '\n')) if quote.is_none() => break,
+                Some(&(_, next)) => {
+                    c = next;
+                    chars.next();
+                }
+                None => break,
+            }
+        }
+    }
+
+    starts
+}
+
+/// Check that `name` is a valid variable or macro name.
+fn ident(loc: &Loc, name: &str) -> Result<String, Error> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    if !valid {
+        bail!("{loc}: Invalid name '{name}'");
+    }
+
+    Ok(name.to_string())
+}
+
+/// Replace references to variables (`{NAME}`) in `token` with their values from `env`.
+fn substitute(loc: &Loc, token: &str, env: &Env) -> Result<String, Error> {
+    let mut result = String::with_capacity(token.len());
+    let mut rest = token;
+
+    while let Some(ix) = rest.find(['{', '}']) {
+        result.push_str(&rest[..ix]);
+        let tail = &rest[ix..];
+
+        if let Some(tail) = tail.strip_prefix("{{") {
+            result.push('{');
+            rest = tail;
+        } else if let Some(tail) = tail.strip_prefix("}}") {
+            result.push('}');
+            rest = tail;
+        } else if let Some(tail) = tail.strip_prefix('{') {
+            let Some((name, tail)) = tail.split_once('}') else {
+                bail!("{loc}: Unterminated variable reference in {token:?}");
+            };
+
+            let Some(value) = env.get(name) else {
+                bail!("{loc}: Unbound variable '{name}'");
+            };
+
+            result.push_str(value);
+            rest = tail;
+        } else {
+            bail!("{loc}: Unmatched '}}' in {token:?}, write literal braces as '}}}}'");
+        }
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Split the list in a `for` directive into its elements. Elements are separated by commas that
+/// are not nested in brackets, parentheses, angle brackets or quotes.
+fn split_list(loc: &Loc, list: &str) -> Result<Vec<String>, Error> {
+    let Some(inner) = list
+        .trim()
+        .strip_prefix('[')
+        .and_then(|l| l.strip_suffix(']'))
+    else {
+        bail!("{loc}: Expected a list in '[...]', found {list:?}");
+    };
+
+    let mut elements = vec![];
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (ix, c) in inner.char_indices() {
+        match (quote, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '(' | '<') => depth += 1,
+            (None, ']' | ')' | '>') => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| anyhow!("{loc}: Unbalanced '{c}' in list {list:?}"))?;
+            }
+            (None, ',') if depth == 0 => {
+                elements.push(inner[start..ix].trim().to_string());
+                start = ix + 1;
+            }
+            (None, _) => {}
+        }
+    }
+
+    if quote.is_some() || depth != 0 {
+        bail!("{loc}: Unbalanced list {list:?}");
+    }
+
+    // Allow a trailing comma.
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        elements.push(last.to_string());
+    }
+
+    if let Some(ix) = elements.iter().position(|e| e.is_empty()) {
+        bail!("{loc}: Empty element at position {ix} of list {list:?}");
+    }
+
+    Ok(elements)
+}
+
+impl fmt::Display for Loc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.path.display(), self.line)
+    }
+}
+
+impl fmt::Display for TokenLoc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path.display(), self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `files` into a fresh directory, and expand `args`, with `--file` paths relative to that
+    /// directory.
+    fn expand(files: &[(&str, &str)], args: &[&str]) -> Result<Vec<String>, Error> {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, contents) in files {
+            fs::write(dir.path().join(name), contents).unwrap();
+        }
+
+        let mut after_file = false;
+        let args = args
+            .iter()
+            .map(|arg| {
+                let expanded = if after_file {
+                    dir.path().join(arg).to_string_lossy().to_string()
+                } else {
+                    arg.to_string()
+                };
+                after_file = *arg == FILE;
+                expanded
+            })
+            .collect();
+
+        expand_args(args).map(|(tokens, _)| tokens).map_err(|e| {
+            // Make errors independent of the temporary directory's location.
+            let root = format!("{}/", dir.path().display());
+            anyhow!(format!("{e:#}").replace(&root, ""))
+        })
+    }
+
+    fn tokens(ts: &[&str]) -> Vec<String> {
+        ts.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_plain_script() {
+        let script = r#"
+            # Split a coin and send it on
+            --split-coins gas [1000]
+            --assign coins
+            --transfer-objects [coins.0] @0x1
+        "#;
+
+        assert_eq!(
+            expand(&[("a.ptb", script)], &["--file", "a.ptb", "--preview"]).unwrap(),
+            tokens(&[
+                "--split-coins",
+                "gas",
+                "[1000]",
+                "--assign",
+                "coins",
+                "--transfer-objects",
+                "[coins.0]",
+                "@0x1",
+                "--preview",
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_multi_line_string() {
+        let script = "--assign x \"vector['a',\n  'b']\"\n--assign y 1";
+        assert_eq!(
+            expand(&[("a.ptb", script)], &["--file", "a.ptb"]).unwrap(),
+            tokens(&["--assign", "x", "vector['a',\n  'b']", "--assign", "y", "1"]),
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        let script = r#"
+            param recipient
+            param amount 1000
+            --split-coins gas [{amount}]
+            --assign coins
+            --transfer-objects [coins.0] {recipient}
+        "#;
+
+        assert_eq!(
+            expand(
+                &[("a.ptb", script)],
+                &["--file", "a.ptb", "--param", "recipient=@0x2"],
+            )
+            .unwrap(),
+            tokens(&[
+                "--split-coins",
+                "gas",
+                "[1000]",
+                "--assign",
+                "coins",
+                "--transfer-objects",
+                "[coins.0]",
+                "@0x2",
+            ]),
+        );
+
+        let err = expand(&[("a.ptb", script)], &["--file", "a.ptb"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "a.ptb:2: Missing value for parameter 'recipient', supply it with --param recipient=VALUE",
+        );
+
+        let err = expand(
+            &[("a.ptb", script)],
+            &[
+                "--file",
+                "a.ptb",
+                "--param",
+                "recipient=@0x2",
+                "--param",
+                "amonut=1",
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Values supplied for undeclared parameter(s): 'amonut'",
+        );
+    }
+
+    #[test]
+    fn test_for_loops() {
+        let script = r#"
+            param recipients "[@0x1, @0x2]"
+            for r in {recipients}
+                for amount in [1, 2,]
+                    --split-coins gas [{amount}]
+                    --assign c
+                    --transfer-objects [c.0] {r}
+                end
+            end
+        "#;
+
+        let expanded = expand(&[("a.ptb", script)], &["--file", "a.ptb"]).unwrap();
+        assert_eq!(
+            expanded[..8],
+            tokens(&[
+                "--split-coins",
+                "gas",
+                "[1]",
+                "--assign",
+                "c",
+                "--transfer-objects",
+                "[c.0]",
+                "@0x1",
+            ])[..],
+        );
+
+        let iterations: Vec<_> = expanded
+            .chunks(8)
+            .map(|c| format!("{} {}", c[2], c[7]))
+            .collect();
+        assert_eq!(iterations, ["[1] @0x1", "[2] @0x1", "[1] @0x2", "[2] @0x2"]);
+    }
+
+    #[test]
+    fn test_list_nesting() {
+        let loc = Loc {
+            path: PathBuf::from("a.ptb"),
+            line: 1,
+        };
+
+        assert_eq!(
+            split_list(&loc, r#"[vector[1, 2], some(3), "a, b", <u64>, '\',']"#).unwrap(),
+            tokens(&["vector[1, 2]", "some(3)", "\"a, b\"", "<u64>", r"'\','"]),
+        );
+
+        assert!(split_list(&loc, "1, 2").is_err());
+        assert!(split_list(&loc, "[1,, 2]").is_err());
+        assert!(split_list(&loc, "[vector[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_macros_and_includes() {
+        let common = r#"
+            param package
+            macro mint amount recipient
+                --move-call {package}::coin::mint {amount}
+                --assign minted
+                --transfer-objects [minted] {recipient}
+            end
+        "#;
+
+        let main = r#"
+            include common.ptb
+            expand mint 10 @0x1
+            for r in [@0x2, @0x3]
+                expand mint 20 {r}
+            end
+        "#;
+
+        let expanded = expand(
+            &[("common.ptb", common), ("main.ptb", main)],
+            &["--file", "main.ptb", "--param", "package=0x42"],
+        )
+        .unwrap();
+
+        assert_eq!(
+            expanded[..7],
+            tokens(&[
+                "--move-call",
+                "0x42::coin::mint",
+                "10",
+                "--assign",
+                "minted",
+                "--transfer-objects",
+                "[minted]",
+            ])[..],
+        );
+        assert_eq!(expanded.len(), 3 * 8);
+        assert_eq!(expanded[2 * 8 - 1], "@0x2");
+        assert_eq!(expanded[3 * 8 - 1], "@0x3");
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = |script: &str| {
+            expand(&[("a.ptb", script)], &["--file", "a.ptb"])
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            err("for x in [1]\n--assign y {x}"),
+            "a.ptb:1: Missing 'end' for 'for'"
+        );
+        assert_eq!(err("--assign y 1\nend"), "a.ptb:2: Unexpected 'end'");
+        assert_eq!(err("--assign y {x}"), "a.ptb:1: Unbound variable 'x'");
+        assert_eq!(err("--assign y \"1"), "a.ptb:1: Unterminated quote");
+        assert_eq!(err("expand m"), "a.ptb:1: Unknown macro 'm'");
+        assert_eq!(
+            err("macro m x\n--assign y {x}\nend\nexpand m"),
+            "a.ptb:4: Macro 'm' expects 1 argument(s), but 0 were supplied",
+        );
+        assert_eq!(
+            err("macro m\nexpand m\nend\nexpand m"),
+            "a.ptb:2: Macro 'm' expands itself",
+        );
+        assert_eq!(
+            err("include a.ptb"),
+            "a.ptb:1: Failed to include PTB script: PTB script a.ptb includes itself",
+        );
+        assert_eq!(
+            err("--assign y }"),
+            "a.ptb:1: Unmatched '}' in \"}\", write literal braces as '}}'",
+        );
+    }
+
+    #[test]
+    fn test_source_map() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.ptb");
+        let script = "# Comment\n--split-coins gas [1000]\n  --assign 'x\n  y' coins # trailing\n";
+        fs::write(&path, script).unwrap();
+
+        let (tokens, map) = expand_args(vec![
+            "--file".to_string(),
+            path.to_string_lossy().to_string(),
+            "--preview".to_string(),
+        ])
+        .unwrap();
+
+        let source = crate::client_ptb::ptb::to_source_string(tokens);
+        let locate = |token: &str| {
+            let offset = source.rfind(token).unwrap();
+            map.locate(offset).map(|loc| (loc.line, loc.column))
+        };
+
+        assert_eq!(locate("--split-coins"), Some((2, 1)));
+        assert_eq!(locate("[1000]"), Some((2, 19)));
+        assert_eq!(locate("--assign"), Some((3, 3)));
+        assert_eq!(locate("y"), Some((3, 12)));
+        assert_eq!(locate("coins"), Some((4, 6)));
+        assert_eq!(locate("--preview"), None);
+        assert_eq!(map.locate(usize::MAX), None);
+
+        let errors = map.annotate(vec![crate::err!(
+            crate::client_ptb::error::Span { start: 14, end: 17 },
+            "Oops"
+        )]);
+        assert_eq!(errors[0].message, format!("{}:2:15: Oops", path.display()));
+    }
+
+    #[test]
+    fn test_literal_braces() {
+        let script = r#"--assign y '"{{not a variable}}"'"#;
+        assert_eq!(
+            expand(&[("a.ptb", script)], &["--file", "a.ptb"]).unwrap(),
+            tokens(&["--assign", "y", "\"{not a variable}\""]),
+        );
+    }
+}
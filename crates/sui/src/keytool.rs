@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::key_identity::{get_identity_address_from_keystore, KeyIdentity};
 use crate::zklogin_commands_util::{perform_zk_login_test_tx, read_cli_line};
-use anyhow::anyhow;
+use anyhow::{anyhow, bail, ensure};
 use bip32::DerivationPath;
 use clap::*;
 use fastcrypto::ed25519::Ed25519KeyPair;
@@ -33,6 +33,9 @@ use std::fmt::{Debug, Display, Formatter};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use sui_keys::encrypted_keystore::{
+    EncryptedFileKeystore, KdfParams, Zeroizing, SUI_KEYSTORE_PASSPHRASE_ENV,
+};
 use sui_keys::key_derive::generate_new_key;
 use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
@@ -99,6 +102,11 @@ pub enum KeyToolCommand {
         #[clap(long, default_value = "0")]
         cur_epoch: u64,
     },
+    /// Encrypt the private keys in Sui CLI Keystore with a passphrase. The plaintext keystore
+    /// file is replaced by an encrypted one, and the passphrase is asked for the first time a key
+    /// is used, unless it is set in SUI_KEYSTORE_PASSPHRASE. Addresses and aliases can still be
+    /// listed without the passphrase.
+    Encrypt,
     /// Generate a new keypair with key scheme flag {ed25519 | secp256k1 | secp256r1}
     /// with optional derivation path, default to m/44'/784'/0'/0'/0' for ed25519 or
     /// m/54'/784'/0'/0/0 for secp256k1 or m/74'/784'/0'/0/0 for secp256r1. Word
@@ -301,6 +309,13 @@ pub struct AliasUpdate {
     new_alias: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedKeystore {
+    keystore_path: PathBuf,
+    addresses: Vec<SuiAddress>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DecodedMultiSig {
@@ -458,6 +473,7 @@ pub enum CommandOutput {
     Convert(ConvertOutput),
     DecodeMultiSig(DecodedMultiSigOutput),
     DecodeOrVerifyTx(DecodeOrVerifyTxOutput),
+    Encrypt(EncryptedKeystore),
     Error(String),
     Generate(Key),
     Import(Key),
@@ -556,6 +572,33 @@ impl KeyToolCommand {
                 CommandOutput::DecodeMultiSig(output)
             }
 
+            KeyToolCommand::Encrypt => {
+                let Keystore::File(file_keystore) = keystore else {
+                    bail!("Only a plaintext file keystore can be encrypted");
+                };
+                let keystore_path = file_keystore
+                    .path()
+                    .ok_or_else(|| anyhow!("The keystore is not backed by a file"))?
+                    .to_path_buf();
+                let passphrase = match std::env::var(SUI_KEYSTORE_PASSPHRASE_ENV) {
+                    Ok(passphrase) => passphrase,
+                    Err(_) => inquire::Password::new("New keystore passphrase:").prompt()?,
+                };
+                ensure!(!passphrase.is_empty(), "The passphrase cannot be empty");
+
+                let encrypted = EncryptedFileKeystore::encrypt_file_keystore(
+                    &keystore_path,
+                    Zeroizing::new(passphrase),
+                    KdfParams::default(),
+                )?;
+                let addresses = encrypted.addresses();
+                *keystore = Keystore::Encrypted(encrypted);
+                CommandOutput::Encrypt(EncryptedKeystore {
+                    keystore_path,
+                    addresses,
+                })
+            }
+
             KeyToolCommand::DecodeOrVerifyTx {
                 tx_bytes,
                 sig,
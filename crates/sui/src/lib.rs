@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod client_commands;
+pub mod client_multisig;
 #[macro_use]
 pub mod client_ptb;
 mod clever_error_rendering;
@@ -12,8 +12,11 @@ use std::env;
 use std::str::FromStr;
 
 use expect_test::expect;
+use fastcrypto::encoding::{Base64, Encoding};
 use move_package::{lock_file::schema::ManagedPackage, BuildConfig as MoveBuildConfig};
 use serde_json::json;
+use shared_crypto::intent::Intent;
+use sui::client_multisig::MultiSigCommand;
 use sui::client_ptb::ptb::PTB;
 use sui::key_identity::{get_identity_address, KeyIdentity};
 use sui::sui_commands::IndexerArgs;
@@ -21,7 +24,7 @@ use sui_sdk::SuiClient;
 use sui_test_transaction_builder::batch_make_transfer_transactions;
 use sui_types::object::Owner;
 use sui_types::transaction::{
-    TEST_ONLY_GAS_UNIT_FOR_GENERIC, TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
+    TransactionData, TEST_ONLY_GAS_UNIT_FOR_GENERIC, TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
     TEST_ONLY_GAS_UNIT_FOR_PUBLISH, TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
     TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
 };
@@ -53,9 +56,11 @@ use sui_swarm_config::genesis_config::{AccountConfig, GenesisConfig};
 use sui_swarm_config::network_config::NetworkConfig;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{
-    Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
+    Ed25519SuiSignature, PublicKey, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair,
+    SuiSignatureInner,
 };
 use sui_types::error::SuiObjectResponseError;
+use sui_types::multisig::MultiSigPublicKey;
 use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
 use test_cluster::{TestCluster, TestClusterBuilder};
 
@@ -2864,6 +2869,128 @@ async fn test_execute_signed_tx() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_multisig_proposal() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let members = [
+        test_cluster.get_address_0(),
+        test_cluster.get_address_1(),
+        test_cluster.get_address_2(),
+    ];
+    let context = &mut test_cluster.wallet;
+
+    let keys = context.config.keystore.keys();
+    let pks: Vec<PublicKey> = members
+        .iter()
+        .map(|address| {
+            keys.iter()
+                .find(|pk| SuiAddress::from(*pk) == *address)
+                .unwrap()
+                .clone()
+        })
+        .collect();
+    let multisig_address =
+        SuiAddress::from(&MultiSigPublicKey::new(pks.clone(), vec![1, 1, 1], 2)?);
+
+    // Fund the multisig address with a gas coin.
+    let coin = context
+        .get_one_gas_object_owned_by_address(members[0])
+        .await?
+        .unwrap();
+    SuiClientCommands::TransferSui {
+        to: KeyIdentity::Address(multisig_address),
+        sui_coin_object_id: coin.0,
+        amount: None,
+        opts: Opts {
+            gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
+            dry_run: false,
+            dev_inspect: false,
+            serialize_unsigned_transaction: false,
+            serialize_signed_transaction: false,
+        },
+    }
+    .execute(context)
+    .await?;
+
+    let gas = context.get_object_ref(coin.0).await?;
+    let tx_data = TransactionData::new_transfer_sui(
+        members[1],
+        multisig_address,
+        Some(1000),
+        gas,
+        rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        rgp,
+    );
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let proposal = tmp_dir.path().join("proposal.json");
+    SuiClientCommands::MultiSig(MultiSigCommand::Create {
+        tx_bytes: Base64::encode(bcs::to_bytes(&tx_data)?),
+        pks,
+        weights: vec![1, 1, 1],
+        threshold: 2,
+        proposal: proposal.clone(),
+    })
+    .execute(context)
+    .await?;
+
+    let sign = |address| {
+        SuiClientCommands::MultiSig(MultiSigCommand::Sign {
+            proposal: proposal.clone(),
+            address: Some(KeyIdentity::Address(address)),
+            yes: true,
+        })
+    };
+    let execute = || {
+        SuiClientCommands::MultiSig(MultiSigCommand::Execute {
+            proposal: proposal.clone(),
+        })
+    };
+
+    // One signature does not meet the threshold, and members can only sign once.
+    sign(members[0]).execute(context).await?;
+    assert!(execute().execute(context).await.is_err());
+    assert!(sign(members[0]).execute(context).await.is_err());
+
+    // Signatures produced elsewhere can be added to the proposal.
+    let signature =
+        context
+            .config
+            .keystore
+            .sign_secure(&members[2], &tx_data, Intent::sui_transaction())?;
+    SuiClientCommands::MultiSig(MultiSigCommand::AddSignature {
+        proposal: proposal.clone(),
+        signature: signature.into(),
+    })
+    .execute(context)
+    .await?;
+
+    let SuiClientCommandResult::MultiSigProposal(status) =
+        SuiClientCommands::MultiSig(MultiSigCommand::Inspect {
+            proposal: proposal.clone(),
+        })
+        .execute(context)
+        .await?
+    else {
+        panic!("Expected a multisig proposal status");
+    };
+
+    assert_eq!(status.multisig_address, multisig_address);
+    assert_eq!(status.digest, tx_data.digest());
+    assert_eq!((status.weight, status.threshold), (2, 2));
+    let signed: Vec<_> = status.members.iter().map(|m| m.signed).collect();
+    assert_eq!(signed, vec![true, false, true]);
+
+    let SuiClientCommandResult::TransactionBlock(response) = execute().execute(context).await?
+    else {
+        panic!("Expected a transaction block response");
+    };
+
+    assert!(response.effects.unwrap().status().is_ok());
+    Ok(())
+}
+
 #[sim_test]
 async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await;
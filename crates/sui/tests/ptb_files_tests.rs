@@ -13,6 +13,7 @@ const TEST_DIR: &str = "tests";
 #[tokio::main]
 async fn test_ptb_files(path: &Path) -> datatest_stable::Result<()> {
     use sui::client_ptb::ptb::{to_source_string, PTB};
+    use sui::client_ptb::script::{expand_args, SourceMap};
     use sui::client_ptb::{error::build_error_reports, ptb::PTBPreview};
     use test_cluster::TestClusterBuilder;
 
@@ -27,7 +28,21 @@ async fn test_ptb_files(path: &Path) -> datatest_stable::Result<()> {
 
     let fname = || path.file_name().unwrap().to_string_lossy().to_string();
     let file_contents = std::fs::read_to_string(path).unwrap();
-    let shlexed = shlex::split(&file_contents).unwrap();
+
+    // Files in a `scripts` directory are expanded as PTB scripts, the way `--file` is, so that
+    // errors are reported against the script lines and columns the commands came from.
+    let (shlexed, source_map) = if path.parent().is_some_and(|p| p.ends_with("scripts")) {
+        let script = path
+            .strip_prefix(env!("CARGO_MANIFEST_DIR"))
+            .unwrap_or(path);
+        expand_args(vec![
+            "--file".to_string(),
+            script.to_string_lossy().to_string(),
+        ])
+        .unwrap()
+    } else {
+        (shlex::split(&file_contents).unwrap(), SourceMap::default())
+    };
     let file_contents = to_source_string(shlexed.clone());
 
     // Parsing
@@ -35,7 +50,7 @@ async fn test_ptb_files(path: &Path) -> datatest_stable::Result<()> {
     let (program, program_meta) = match program {
         Ok(program) => program,
         Err(errors) => {
-            let rendered = build_error_reports(&file_contents, errors);
+            let rendered = build_error_reports(&file_contents, source_map.annotate(errors));
             let mut results = vec![];
             results.push(" === ERRORS AFTER PARSING INPUT COMMANDS === ".to_string());
             for e in rendered.iter() {
@@ -66,7 +81,7 @@ async fn test_ptb_files(path: &Path) -> datatest_stable::Result<()> {
     let (built_ptb, warnings) = PTB::build_ptb(program, context, client).await;
 
     if !warnings.is_empty() {
-        let rendered = build_error_reports(&file_contents, warnings);
+        let rendered = build_error_reports(&file_contents, source_map.annotate(warnings));
         results.push(" === WARNINGS === ".to_string());
         for warning in rendered.iter() {
             results.push(format!("{:?}", warning));
@@ -85,7 +100,7 @@ async fn test_ptb_files(path: &Path) -> datatest_stable::Result<()> {
 
     // === BUILDING PTB ERRORS ===
     if let Err(e) = built_ptb {
-        let rendered = build_error_reports(&file_contents, e);
+        let rendered = build_error_reports(&file_contents, source_map.annotate(e));
 
         results.push(" === BUILDING PTB ERRORS === ".to_string());
         for e in rendered.iter() {
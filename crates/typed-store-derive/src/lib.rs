@@ -21,6 +21,8 @@ const DB_OPTIONS_CUSTOM_FUNCTION: &str = "default_options_override_fn";
 const DB_OPTIONS_RENAME: &str = "rename";
 // Deprecate a column family
 const DB_OPTIONS_DEPRECATE: &str = "deprecated";
+// Schema version of the tables, used by `typed_store::migration`
+const DB_SCHEMA_VERSION: &str = "schema_version";
 
 /// Options can either be simplified form or
 enum GeneralTableOptions {
@@ -132,6 +134,29 @@ fn extract_struct_info(
     }
 }
 
+/// Extracts the schema version of the struct, 0 if not specified
+fn get_schema_version(attrs: &[Attribute]) -> syn::Result<u64> {
+    let Some(attr) = attrs.iter().find(|a| a.path.is_ident(DB_SCHEMA_VERSION)) else {
+        return Ok(0);
+    };
+    match attr.parse_meta()? {
+        Meta::NameValue(val) => {
+            if let Lit::Int(i) = val.lit {
+                i.base10_parse()
+            } else {
+                Err(syn::Error::new_spanned(
+                    val.lit,
+                    "Expected integer value for schema_version",
+                ))
+            }
+        }
+        meta => Err(syn::Error::new_spanned(
+            meta,
+            "Expected schema_version = <integer>",
+        )),
+    }
+}
+
 /// Extracts the table options override function
 /// The function must take no args and return Options
 fn get_options_override_function(attr: &Attribute) -> syn::Result<String> {
@@ -184,12 +209,19 @@ struct ExtractedStructInfo {
     deprecated_cfs: Vec<Ident>,
 }
 
-#[proc_macro_derive(DBMapUtils, attributes(default_options_override_fn, rename))]
+#[proc_macro_derive(
+    DBMapUtils,
+    attributes(default_options_override_fn, rename, schema_version)
+)]
 pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemStruct);
     let name = &input.ident;
     let generics = &input.generics;
     let generics_names = extract_generics_names(generics);
+    let schema_version = match get_schema_version(&input.attrs) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
     let allowed_types_with_post_process_fn: BTreeMap<_, _> =
         [("SallyColumn", ""), ("DBMap", "")].into_iter().collect();
@@ -278,6 +310,9 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                     #generics_names: #generics_bounds_token,
                 )*
             > #name #generics {
+                /// The schema version of the tables, set with the `schema_version` attribute.
+                /// `typed_store::migration::MigrationRunner` moves an existing DB to this version.
+                pub const SCHEMA_VERSION: u64 = #schema_version;
 
                 pub fn configurator() -> #config_struct_name {
                     #config_struct_name::init()
@@ -427,6 +462,23 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 }
             }
 
+            /// Opens a set of tables backed by an in-memory store instead of RocksDB
+            /// Tables keep the same ordering, iteration, batch and snapshot semantics, but nothing is persisted
+            /// RocksDB specific features such as transactions, merge operators and checkpoints are not supported
+            #[allow(unused_parens)]
+            pub fn open_tables_in_memory(
+                metric_conf: typed_store::rocks::MetricConf,
+            ) -> Self {
+                let db = typed_store::rocks::open_cf_in_memory(metric_conf, &[#(stringify!(#cf_names),)*]);
+                Self {
+                    #(
+                        #field_names: #post_process_fn(
+                            DBMap::#inner_types::reopen(&db, Some(stringify!(#cf_names)), &typed_store::rocks::ReadWriteOptions::default(), false).expect(&format!("Cannot open {} CF.", stringify!(#cf_names))[..])
+                        ),
+                    )*
+                }
+            }
+
             /// Returns a list of the tables name and type pairs
             pub fn describe_tables() -> std::collections::BTreeMap<String, (String, String)> {
                 vec![#(
@@ -0,0 +1,468 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A pure in-memory storage backend for `DBMap`. Column families are ordered maps from raw
+//! (serialized) keys to raw values, so tables keep the same key ordering as RocksDB. All column
+//! families of a database live behind a single lock, which makes batch writes atomic across
+//! column families and lets iterators and snapshots observe a consistent view of the data.
+//!
+//! This backend is meant for tests and embedded uses where opening RocksDB is too heavy. Nothing
+//! is persisted, and RocksDB specific features (transactions, merge operators, checkpoints,
+//! compactions and properties) are not supported.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound,
+    sync::RwLock,
+};
+
+use crate::TypedStoreError;
+
+type ColumnFamily = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// An in-memory database made of a set of named column families.
+#[derive(Debug, Default)]
+pub struct InMemoryDB {
+    cfs: RwLock<HashMap<String, ColumnFamily>>,
+}
+
+impl InMemoryDB {
+    /// Creates a database with the given (empty) column families.
+    pub fn new<I: AsRef<str>>(cfs: &[I]) -> Self {
+        Self {
+            cfs: RwLock::new(
+                cfs.iter()
+                    .map(|name| (name.as_ref().to_owned(), ColumnFamily::new()))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn cf_exists(&self, name: &str) -> bool {
+        self.cfs.read().unwrap().contains_key(name)
+    }
+
+    /// Creates an empty column family, if it does not exist yet.
+    pub fn create_cf(&self, name: &str) {
+        self.cfs
+            .write()
+            .unwrap()
+            .entry(name.to_owned())
+            .or_default();
+    }
+
+    /// Drops a column family along with all its entries.
+    pub fn drop_cf(&self, name: &str) {
+        self.cfs.write().unwrap().remove(name);
+    }
+
+    pub fn get(&self, cf: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        let cfs = self.cfs.read().unwrap();
+        Ok(column_family(&cfs, cf)?.get(key).cloned())
+    }
+
+    pub fn multi_get<K: AsRef<[u8]>>(
+        &self,
+        cf: &str,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<Vec<Option<Vec<u8>>>, TypedStoreError> {
+        let cfs = self.cfs.read().unwrap();
+        let cf = column_family(&cfs, cf)?;
+        Ok(keys
+            .into_iter()
+            .map(|key| cf.get(key.as_ref()).cloned())
+            .collect())
+    }
+
+    pub fn put(&self, cf: &str, key: Vec<u8>, value: Vec<u8>) -> Result<(), TypedStoreError> {
+        let mut cfs = self.cfs.write().unwrap();
+        column_family_mut(&mut cfs, cf)?.insert(key, value);
+        Ok(())
+    }
+
+    pub fn delete(&self, cf: &str, key: &[u8]) -> Result<(), TypedStoreError> {
+        let mut cfs = self.cfs.write().unwrap();
+        column_family_mut(&mut cfs, cf)?.remove(key);
+        Ok(())
+    }
+
+    /// Deletes all keys between `from` (inclusive) and `to` (non-inclusive).
+    pub fn delete_range(&self, cf: &str, from: &[u8], to: &[u8]) -> Result<(), TypedStoreError> {
+        let mut cfs = self.cfs.write().unwrap();
+        delete_range(column_family_mut(&mut cfs, cf)?, from, to);
+        Ok(())
+    }
+
+    /// Removes all entries of a column family, creating it if it does not exist.
+    pub fn clear_cf(&self, cf: &str) {
+        self.cfs
+            .write()
+            .unwrap()
+            .insert(cf.to_owned(), ColumnFamily::new());
+    }
+
+    /// Atomically applies all the operations in `batch`. Either all operations are applied or,
+    /// if one of them refers to an unknown column family, none is.
+    pub fn write(&self, batch: InMemoryBatch) -> Result<(), TypedStoreError> {
+        let mut cfs = self.cfs.write().unwrap();
+        if let Some(cf) = batch
+            .ops
+            .iter()
+            .map(|op| op.cf())
+            .find(|cf| !cfs.contains_key(*cf))
+        {
+            return Err(TypedStoreError::UnregisteredColumn(cf.to_owned()));
+        }
+
+        for op in batch.ops {
+            match op {
+                InMemoryBatchOp::Put { cf, key, value } => {
+                    column_family_mut(&mut cfs, &cf)?.insert(key, value);
+                }
+                InMemoryBatchOp::Delete { cf, key } => {
+                    column_family_mut(&mut cfs, &cf)?.remove(&key);
+                }
+                InMemoryBatchOp::DeleteRange { cf, from, to } => {
+                    delete_range(column_family_mut(&mut cfs, &cf)?, &from, &to);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a raw iterator over the entries of a column family whose keys are between
+    /// `lower_bound` (inclusive) and `upper_bound` (exclusive). Like RocksDB iterators, it is
+    /// unaffected by writes that happen after it is created.
+    pub fn raw_iterator(
+        &self,
+        cf: &str,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+    ) -> InMemoryRawIter {
+        let cfs = self.cfs.read().unwrap();
+        let is_empty_range =
+            matches!((&lower_bound, &upper_bound), (Some(lower), Some(upper)) if lower >= upper);
+        let entries = match cfs.get(cf) {
+            Some(cf) if !is_empty_range => {
+                let lower = lower_bound.map_or(Bound::Unbounded, Bound::Included);
+                let upper = upper_bound.map_or(Bound::Unbounded, Bound::Excluded);
+                cf.range::<Vec<u8>, _>((lower, upper))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect()
+            }
+            _ => vec![],
+        };
+        InMemoryRawIter::new(entries)
+    }
+
+    /// Returns an iterator over all entries of a column family, in key order.
+    pub fn iterator(&self, cf: &str) -> std::vec::IntoIter<(Vec<u8>, Vec<u8>)> {
+        self.raw_iterator(cf, None, None).entries.into_iter()
+    }
+
+    /// Takes a point-in-time snapshot of the whole database.
+    pub fn snapshot(&self) -> InMemorySnapshot {
+        InMemorySnapshot {
+            cfs: self.cfs.read().unwrap().clone(),
+        }
+    }
+}
+
+fn column_family<'a>(
+    cfs: &'a HashMap<String, ColumnFamily>,
+    cf: &str,
+) -> Result<&'a ColumnFamily, TypedStoreError> {
+    cfs.get(cf)
+        .ok_or_else(|| TypedStoreError::UnregisteredColumn(cf.to_owned()))
+}
+
+fn column_family_mut<'a>(
+    cfs: &'a mut HashMap<String, ColumnFamily>,
+    cf: &str,
+) -> Result<&'a mut ColumnFamily, TypedStoreError> {
+    cfs.get_mut(cf)
+        .ok_or_else(|| TypedStoreError::UnregisteredColumn(cf.to_owned()))
+}
+
+fn delete_range(cf: &mut ColumnFamily, from: &[u8], to: &[u8]) {
+    if from >= to {
+        return;
+    }
+    let keys: Vec<_> = cf
+        .range::<[u8], _>((Bound::Included(from), Bound::Excluded(to)))
+        .map(|(k, _)| k.clone())
+        .collect();
+    for key in keys {
+        cf.remove(&key);
+    }
+}
+
+/// A point-in-time, read-only view of an `InMemoryDB`.
+pub struct InMemorySnapshot {
+    cfs: HashMap<String, ColumnFamily>,
+}
+
+impl InMemorySnapshot {
+    pub fn get(&self, cf: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        Ok(column_family(&self.cfs, cf)?.get(key).cloned())
+    }
+
+    pub fn multi_get<K: AsRef<[u8]>>(
+        &self,
+        cf: &str,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<Vec<Option<Vec<u8>>>, TypedStoreError> {
+        let cf = column_family(&self.cfs, cf)?;
+        Ok(keys
+            .into_iter()
+            .map(|key| cf.get(key.as_ref()).cloned())
+            .collect())
+    }
+}
+
+enum InMemoryBatchOp {
+    Put {
+        cf: String,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        cf: String,
+        key: Vec<u8>,
+    },
+    DeleteRange {
+        cf: String,
+        from: Vec<u8>,
+        to: Vec<u8>,
+    },
+}
+
+impl InMemoryBatchOp {
+    fn cf(&self) -> &str {
+        match self {
+            Self::Put { cf, .. } | Self::Delete { cf, .. } | Self::DeleteRange { cf, .. } => cf,
+        }
+    }
+}
+
+/// A collection of write operations that are applied atomically by `InMemoryDB::write`, in the
+/// order they were added.
+#[derive(Default)]
+pub struct InMemoryBatch {
+    ops: Vec<InMemoryBatchOp>,
+    size_in_bytes: usize,
+}
+
+impl InMemoryBatch {
+    pub fn put_cf<K, V>(&mut self, cf: &str, key: K, value: V)
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let (key, value) = (key.as_ref().to_vec(), value.as_ref().to_vec());
+        self.size_in_bytes += key.len() + value.len();
+        self.ops.push(InMemoryBatchOp::Put {
+            cf: cf.to_owned(),
+            key,
+            value,
+        });
+    }
+
+    pub fn delete_cf<K: AsRef<[u8]>>(&mut self, cf: &str, key: K) {
+        let key = key.as_ref().to_vec();
+        self.size_in_bytes += key.len();
+        self.ops.push(InMemoryBatchOp::Delete {
+            cf: cf.to_owned(),
+            key,
+        });
+    }
+
+    /// Deletes all keys between `from` (inclusive) and `to` (non-inclusive).
+    pub fn delete_range_cf<K: AsRef<[u8]>>(&mut self, cf: &str, from: K, to: K) {
+        let (from, to) = (from.as_ref().to_vec(), to.as_ref().to_vec());
+        self.size_in_bytes += from.len() + to.len();
+        self.ops.push(InMemoryBatchOp::DeleteRange {
+            cf: cf.to_owned(),
+            from,
+            to,
+        });
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        self.size_in_bytes
+    }
+}
+
+/// A cursor over a snapshot of the entries of a column family, with the same interface and
+/// positioning rules as RocksDB's raw iterators: it is invalid until it is positioned with one of
+/// the `seek` methods, and becomes invalid when it moves past either end.
+pub struct InMemoryRawIter {
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+    position: Option<usize>,
+}
+
+impl InMemoryRawIter {
+    fn new(entries: Vec<(Vec<u8>, Vec<u8>)>) -> Self {
+        Self {
+            entries,
+            position: None,
+        }
+    }
+
+    pub fn valid(&self) -> bool {
+        self.position.is_some()
+    }
+
+    pub fn key(&self) -> Option<&[u8]> {
+        self.position.map(|i| self.entries[i].0.as_slice())
+    }
+
+    pub fn value(&self) -> Option<&[u8]> {
+        self.position.map(|i| self.entries[i].1.as_slice())
+    }
+
+    pub fn next(&mut self) {
+        self.position = self
+            .position
+            .map(|i| i + 1)
+            .filter(|i| *i < self.entries.len());
+    }
+
+    pub fn prev(&mut self) {
+        self.position = self.position.and_then(|i| i.checked_sub(1));
+    }
+
+    /// Positions the iterator on the first key that is greater than or equal to `key`.
+    pub fn seek<K: AsRef<[u8]>>(&mut self, key: K) {
+        let i = self
+            .entries
+            .partition_point(|(k, _)| k.as_slice() < key.as_ref());
+        self.position = Some(i).filter(|i| *i < self.entries.len());
+    }
+
+    /// Positions the iterator on the last key that is less than or equal to `key`.
+    pub fn seek_for_prev<K: AsRef<[u8]>>(&mut self, key: K) {
+        let i = self
+            .entries
+            .partition_point(|(k, _)| k.as_slice() <= key.as_ref());
+        self.position = i.checked_sub(1);
+    }
+
+    pub fn seek_to_first(&mut self) {
+        self.position = Some(0).filter(|i| *i < self.entries.len());
+    }
+
+    pub fn seek_to_last(&mut self) {
+        self.position = self.entries.len().checked_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db_with_keys(keys: &[u8]) -> InMemoryDB {
+        let db = InMemoryDB::new(&["cf"]);
+        for k in keys {
+            db.put("cf", vec![*k], vec![*k]).unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn test_raw_iterator_positioning() {
+        let db = db_with_keys(&[1, 3, 5]);
+        let mut iter = db.raw_iterator("cf", None, None);
+        assert!(!iter.valid());
+
+        iter.seek_to_first();
+        assert_eq!(iter.key(), Some(&[1][..]));
+        iter.next();
+        assert_eq!(iter.key(), Some(&[3][..]));
+        iter.prev();
+        iter.prev();
+        assert!(!iter.valid());
+
+        iter.seek([2u8]);
+        assert_eq!(iter.key(), Some(&[3][..]));
+        iter.seek([6u8]);
+        assert!(!iter.valid());
+
+        iter.seek_for_prev([4u8]);
+        assert_eq!(iter.key(), Some(&[3][..]));
+        iter.seek_for_prev([0u8]);
+        assert!(!iter.valid());
+
+        iter.seek_to_last();
+        assert_eq!(iter.value(), Some(&[5][..]));
+        iter.next();
+        assert!(!iter.valid());
+    }
+
+    #[test]
+    fn test_raw_iterator_bounds_and_isolation() {
+        let db = db_with_keys(&[1, 2, 3, 4, 5]);
+        let mut iter = db.raw_iterator("cf", Some(vec![2]), Some(vec![4]));
+
+        // Writes after the iterator is created are not visible to it.
+        db.put("cf", vec![3], vec![30]).unwrap();
+        db.delete("cf", &[2]).unwrap();
+
+        let mut entries = vec![];
+        iter.seek_to_first();
+        while iter.valid() {
+            entries.push((iter.key().unwrap().to_vec(), iter.value().unwrap().to_vec()));
+            iter.next();
+        }
+        assert_eq!(entries, vec![(vec![2], vec![2]), (vec![3], vec![3])]);
+
+        assert_eq!(db.iterator("cf").count(), 4);
+        assert_eq!(
+            db.raw_iterator("cf", Some(vec![4]), Some(vec![2]))
+                .entries
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_batch_is_atomic() {
+        let db = db_with_keys(&[1, 2, 3, 4]);
+
+        let mut batch = InMemoryBatch::default();
+        batch.put_cf("cf", [5u8], [5u8]);
+        batch.delete_range_cf("cf", [1u8], [3u8]);
+        db.write(batch).unwrap();
+        assert_eq!(
+            db.iterator("cf").map(|(k, _)| k[0]).collect::<Vec<_>>(),
+            vec![3, 4, 5]
+        );
+
+        // A batch touching an unknown column family is rejected as a whole.
+        let mut batch = InMemoryBatch::default();
+        batch.delete_cf("cf", [3u8]);
+        batch.put_cf("unknown", [1u8], [1u8]);
+        assert_eq!(
+            db.write(batch),
+            Err(TypedStoreError::UnregisteredColumn("unknown".to_owned()))
+        );
+        assert_eq!(db.get("cf", &[3]).unwrap(), Some(vec![3]));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let db = db_with_keys(&[1, 2]);
+        let snapshot = db.snapshot();
+        db.delete("cf", &[1]).unwrap();
+        db.put("cf", vec![3], vec![3]).unwrap();
+
+        assert_eq!(
+            snapshot.multi_get("cf", [[1u8], [2], [3]]).unwrap(),
+            vec![Some(vec![1]), Some(vec![2]), None]
+        );
+        assert_eq!(
+            db.multi_get("cf", [[1u8], [2], [3]]).unwrap(),
+            vec![None, Some(vec![2]), Some(vec![3])]
+        );
+    }
+}
@@ -0,0 +1,705 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Versioned schema migrations for the column families of a database.
+//!
+//! A [`Migration`] moves a database to a new schema version through a list of [`MigrationStep`]s,
+//! for example rewriting every entry of a column family with a new key or value type, or renaming
+//! a column family. The [`MigrationRunner`] applies pending migrations in batches while the
+//! database stays open. Each batch of migrated entries is written together with the progress of
+//! the migration, which is kept in the [`MIGRATIONS_CF`] column family, so an interrupted
+//! migration resumes from the last written batch.
+
+use std::marker::PhantomData;
+use std::ops::Bound;
+use std::sync::Arc;
+
+use bincode::Options;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::info;
+
+use crate::rocks::errors::{typed_store_err_from_bincode_err, typed_store_err_from_rocks_err};
+use crate::rocks::{be_fix_int_ser, default_db_options, DBBatch, DBMap, ReadWriteOptions, RocksDB};
+use crate::traits::Map;
+use crate::TypedStoreError;
+
+/// The column family holding the migration progress of a database.
+pub const MIGRATIONS_CF: &str = "typed_store_migrations";
+const PROGRESS_KEY: &str = "progress";
+const DEFAULT_BATCH_SIZE: usize = 10_000;
+
+/// The migration state of a database, as persisted in [`MIGRATIONS_CF`].
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MigrationProgress {
+    /// The version of the last migration fully applied to the database.
+    pub schema_version: u64,
+    /// The position within the migration currently being applied, if any.
+    pub in_progress: Option<StepProgress>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StepProgress {
+    /// The version of the migration being applied.
+    pub target_version: u64,
+    /// The index of the step being applied within the migration.
+    pub step: usize,
+    /// The serialized key of the last entry migrated by the step.
+    pub cursor: Option<Vec<u8>>,
+    /// The number of entries migrated by the step so far.
+    pub entries_migrated: u64,
+}
+
+/// The outcome of migrating a single batch of entries.
+pub struct StepBatch {
+    /// The number of entries read from the source column family.
+    pub entries: u64,
+    /// The serialized key of the last entry read, `None` if no entry was read.
+    pub cursor: Option<Vec<u8>>,
+}
+
+/// A unit of work within a migration.
+pub trait MigrationStep: Send + Sync {
+    fn description(&self) -> String;
+
+    /// Creates the column families the step writes to. This runs every time the step is
+    /// (re)started, so it must be idempotent.
+    fn prepare(&self, _db: &Arc<RocksDB>) -> Result<(), TypedStoreError> {
+        Ok(())
+    }
+
+    /// Migrates up to `limit` entries following `cursor` (or from the first entry if `cursor` is
+    /// `None`), adding the resulting writes to `batch`. The step is complete once it reads fewer
+    /// than `limit` entries.
+    fn migrate_batch(
+        &self,
+        db: &Arc<RocksDB>,
+        cursor: Option<&[u8]>,
+        limit: usize,
+        batch: &mut DBBatch,
+    ) -> Result<StepBatch, TypedStoreError>;
+
+    /// Runs once every entry has been migrated. The process can stop before the completion of
+    /// the step is recorded, in which case this runs again, so it must be idempotent.
+    fn finish(&self, _db: &Arc<RocksDB>) -> Result<(), TypedStoreError> {
+        Ok(())
+    }
+}
+
+/// Rewrites every entry of a column family of type `DBMap<K, V>` into a column family of type
+/// `DBMap<NK, NV>`.
+pub struct RewriteTable<K, V, NK, NV, F> {
+    source: String,
+    target: String,
+    transform: F,
+    _phantom: PhantomData<fn(K, V) -> (NK, NV)>,
+}
+
+impl<K, V, NK, NV, F> RewriteTable<K, V, NK, NV, F>
+where
+    F: Fn(K, V) -> Option<(NK, NV)> + Send + Sync,
+{
+    /// Entries for which `transform` returns `None` are not written to `target`.
+    /// If `target` is a different column family, it is created if missing and `source` is dropped
+    /// once every entry has been rewritten. If `target` is `source`, the entries are rewritten in
+    /// place and `transform` must preserve their keys.
+    pub fn new(source: &str, target: &str, transform: F) -> Self {
+        Self {
+            source: source.to_owned(),
+            target: target.to_owned(),
+            transform,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn in_place(&self) -> bool {
+        self.source == self.target
+    }
+}
+
+impl<K, V> RewriteTable<K, V, K, V, fn(K, V) -> Option<(K, V)>> {
+    /// Moves every entry of `from` into `to`, then drops `from`.
+    pub fn rename(from: &str, to: &str) -> Self {
+        Self::new(from, to, |key, value| Some((key, value)))
+    }
+}
+
+impl<K, V, NK, NV, F> MigrationStep for RewriteTable<K, V, NK, NV, F>
+where
+    K: Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+    NK: Serialize,
+    NV: Serialize,
+    F: Fn(K, V) -> Option<(NK, NV)> + Send + Sync,
+{
+    fn description(&self) -> String {
+        if self.in_place() {
+            format!("rewrite {} in place", self.source)
+        } else {
+            format!("rewrite {} into {}", self.source, self.target)
+        }
+    }
+
+    fn prepare(&self, db: &Arc<RocksDB>) -> Result<(), TypedStoreError> {
+        if self.in_place() {
+            return Ok(());
+        }
+        create_cf_if_missing(db, &self.target)
+    }
+
+    fn migrate_batch(
+        &self,
+        db: &Arc<RocksDB>,
+        cursor: Option<&[u8]>,
+        limit: usize,
+        batch: &mut DBBatch,
+    ) -> Result<StepBatch, TypedStoreError> {
+        if !self.in_place() && !db.cf_exists(&self.source) {
+            // The step already finished, but stopped before its completion was recorded.
+            return Ok(StepBatch {
+                entries: 0,
+                cursor: None,
+            });
+        }
+        let source = open_table::<K, V>(db, &self.source)?;
+        let target = open_table::<NK, NV>(db, &self.target)?;
+        let lower_bound = match cursor {
+            Some(cursor) => Bound::Excluded(deserialize_key::<K>(cursor)?),
+            None => Bound::Unbounded,
+        };
+
+        let mut entries = 0;
+        let mut last_key = None;
+        let mut inserts = vec![];
+        let mut deletes = vec![];
+        for item in source
+            .safe_range_iter((lower_bound, Bound::Unbounded))
+            .take(limit)
+        {
+            let (key, value) = item?;
+            let raw_key = be_fix_int_ser(&key)?;
+            match (self.transform)(key, value) {
+                Some((new_key, new_value)) => {
+                    if self.in_place() && be_fix_int_ser(&new_key)? != raw_key {
+                        return Err(TypedStoreError::MigrationError(format!(
+                            "rewriting {} in place must preserve keys",
+                            self.source
+                        )));
+                    }
+                    inserts.push((new_key, new_value));
+                }
+                None if self.in_place() => deletes.push(deserialize_key::<K>(&raw_key)?),
+                None => {}
+            }
+            entries += 1;
+            last_key = Some(raw_key);
+        }
+
+        batch.insert_batch(&target, inserts)?;
+        batch.delete_batch(&source, deletes)?;
+        Ok(StepBatch {
+            entries,
+            cursor: last_key,
+        })
+    }
+
+    fn finish(&self, db: &Arc<RocksDB>) -> Result<(), TypedStoreError> {
+        if self.in_place() || !db.cf_exists(&self.source) {
+            return Ok(());
+        }
+        db.drop_cf(&self.source)
+            .map_err(typed_store_err_from_rocks_err)
+    }
+}
+
+/// Drops a column family that is no longer part of the schema.
+pub struct DropTable(pub String);
+
+impl MigrationStep for DropTable {
+    fn description(&self) -> String {
+        format!("drop {}", self.0)
+    }
+
+    fn migrate_batch(
+        &self,
+        _db: &Arc<RocksDB>,
+        _cursor: Option<&[u8]>,
+        _limit: usize,
+        _batch: &mut DBBatch,
+    ) -> Result<StepBatch, TypedStoreError> {
+        Ok(StepBatch {
+            entries: 0,
+            cursor: None,
+        })
+    }
+
+    fn finish(&self, db: &Arc<RocksDB>) -> Result<(), TypedStoreError> {
+        if !db.cf_exists(&self.0) {
+            return Ok(());
+        }
+        db.drop_cf(&self.0).map_err(typed_store_err_from_rocks_err)
+    }
+}
+
+/// Moves a database from the previous schema version to `version`.
+pub struct Migration {
+    pub version: u64,
+    pub description: String,
+    pub steps: Vec<Box<dyn MigrationStep>>,
+}
+
+impl Migration {
+    pub fn new(version: u64, description: &str) -> Self {
+        Self {
+            version,
+            description: description.to_owned(),
+            steps: vec![],
+        }
+    }
+
+    pub fn with_step(mut self, step: impl MigrationStep + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+}
+
+/// Applies pending migrations to an open database and records their progress.
+pub struct MigrationRunner {
+    db: Arc<RocksDB>,
+    progress: DBMap<String, MigrationProgress>,
+    migrations: Vec<Migration>,
+    batch_size: usize,
+}
+
+impl MigrationRunner {
+    /// `migrations` must be sorted by strictly increasing version, starting above 0.
+    pub fn new(db: &Arc<RocksDB>, migrations: Vec<Migration>) -> Result<Self, TypedStoreError> {
+        let mut previous_version = 0;
+        for migration in &migrations {
+            if migration.version <= previous_version {
+                return Err(TypedStoreError::MigrationError(format!(
+                    "migration versions must be strictly increasing from 1, found {} after {}",
+                    migration.version, previous_version
+                )));
+            }
+            previous_version = migration.version;
+        }
+        create_cf_if_missing(db, MIGRATIONS_CF)?;
+        Ok(Self {
+            db: db.clone(),
+            progress: open_table(db, MIGRATIONS_CF)?,
+            migrations,
+            batch_size: DEFAULT_BATCH_SIZE,
+        })
+    }
+
+    /// Sets the maximum number of entries migrated per write batch.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn progress(&self) -> Result<MigrationProgress, TypedStoreError> {
+        Ok(self
+            .progress
+            .get(&PROGRESS_KEY.to_owned())?
+            .unwrap_or_default())
+    }
+
+    /// Returns the migrations that have not been fully applied yet.
+    pub fn pending(&self) -> Result<Vec<&Migration>, TypedStoreError> {
+        let schema_version = self.progress()?.schema_version;
+        Ok(self
+            .migrations
+            .iter()
+            .filter(|m| m.version > schema_version)
+            .collect())
+    }
+
+    /// Brings the database to `schema_version`, the latest version of its schema, applying pending
+    /// migrations and resuming an interrupted one. A newly created database (`is_new`) has nothing
+    /// to migrate, so it is recorded at `schema_version` directly. Databases written at a newer
+    /// version are refused.
+    pub fn run_to_latest(&self, schema_version: u64, is_new: bool) -> Result<(), TypedStoreError> {
+        if is_new {
+            return initialize_progress(&self.db, schema_version);
+        }
+        let progress = self.progress()?;
+        if progress.schema_version > schema_version {
+            return Err(TypedStoreError::MigrationError(format!(
+                "database is at schema version {}, which is newer than the latest version {}",
+                progress.schema_version, schema_version
+            )));
+        }
+        let reached = self.run_to(schema_version)?;
+        if reached != schema_version {
+            return Err(TypedStoreError::MigrationError(format!(
+                "migrations end at schema version {reached}, expected {schema_version}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Applies every pending migration and returns the resulting schema version.
+    pub fn run(&self) -> Result<u64, TypedStoreError> {
+        self.run_to(u64::MAX)
+    }
+
+    /// Applies pending migrations up to and including `target_version`, resuming an interrupted
+    /// migration if there is one, and returns the resulting schema version.
+    pub fn run_to(&self, target_version: u64) -> Result<u64, TypedStoreError> {
+        let mut progress = self.progress()?;
+        for migration in &self.migrations {
+            if migration.version <= progress.schema_version || migration.version > target_version {
+                continue;
+            }
+            let mut step_progress = match progress.in_progress.take() {
+                Some(p) if p.target_version == migration.version => p,
+                Some(p) => {
+                    return Err(TypedStoreError::MigrationError(format!(
+                        "interrupted migration to version {} is not the next pending migration {}",
+                        p.target_version, migration.version
+                    )))
+                }
+                None => StepProgress {
+                    target_version: migration.version,
+                    step: 0,
+                    cursor: None,
+                    entries_migrated: 0,
+                },
+            };
+            info!(
+                "Applying migration to schema version {}: {}",
+                migration.version, migration.description
+            );
+
+            while let Some(step) = migration.steps.get(step_progress.step) {
+                info!(
+                    "Running migration step {}/{}: {}",
+                    step_progress.step + 1,
+                    migration.steps.len(),
+                    step.description()
+                );
+                step.prepare(&self.db)?;
+                loop {
+                    let mut batch = self.progress.batch();
+                    let outcome = step.migrate_batch(
+                        &self.db,
+                        step_progress.cursor.as_deref(),
+                        self.batch_size,
+                        &mut batch,
+                    )?;
+                    step_progress.entries_migrated += outcome.entries;
+                    if outcome.cursor.is_some() {
+                        step_progress.cursor = outcome.cursor;
+                    }
+                    progress.in_progress = Some(step_progress.clone());
+                    batch.insert_batch(&self.progress, [(PROGRESS_KEY.to_owned(), &progress)])?;
+                    batch.write()?;
+                    if outcome.entries < self.batch_size as u64 {
+                        break;
+                    }
+                }
+                step.finish(&self.db)?;
+                info!(
+                    "Migration step {} done, {} entries migrated",
+                    step_progress.step + 1,
+                    step_progress.entries_migrated
+                );
+
+                step_progress = StepProgress {
+                    target_version: migration.version,
+                    step: step_progress.step + 1,
+                    cursor: None,
+                    entries_migrated: 0,
+                };
+                progress.in_progress = Some(step_progress.clone());
+                self.progress.insert(&PROGRESS_KEY.to_owned(), &progress)?;
+            }
+
+            progress = MigrationProgress {
+                schema_version: migration.version,
+                in_progress: None,
+            };
+            self.progress.insert(&PROGRESS_KEY.to_owned(), &progress)?;
+        }
+        Ok(progress.schema_version)
+    }
+}
+
+/// Reads the migration progress of a database without modifying it, e.g. from a read only
+/// handle. Databases that never ran a migration are at schema version 0.
+pub fn read_progress(db: &Arc<RocksDB>) -> Result<MigrationProgress, TypedStoreError> {
+    if !db.cf_exists(MIGRATIONS_CF) {
+        return Ok(MigrationProgress::default());
+    }
+    Ok(open_table::<String, MigrationProgress>(db, MIGRATIONS_CF)?
+        .get(&PROGRESS_KEY.to_owned())?
+        .unwrap_or_default())
+}
+
+/// Records that a newly created database is at `schema_version`, since it has nothing to migrate.
+/// Does nothing if the database already recorded its migration progress.
+pub fn initialize_progress(db: &Arc<RocksDB>, schema_version: u64) -> Result<(), TypedStoreError> {
+    create_cf_if_missing(db, MIGRATIONS_CF)?;
+    let table = open_table::<String, MigrationProgress>(db, MIGRATIONS_CF)?;
+    if table.contains_key(&PROGRESS_KEY.to_owned())? {
+        return Ok(());
+    }
+    table.insert(
+        &PROGRESS_KEY.to_owned(),
+        &MigrationProgress {
+            schema_version,
+            in_progress: None,
+        },
+    )
+}
+
+fn create_cf_if_missing(db: &Arc<RocksDB>, name: &str) -> Result<(), TypedStoreError> {
+    if db.cf_exists(name) {
+        return Ok(());
+    }
+    db.create_cf(name, &default_db_options().options)
+        .map_err(typed_store_err_from_rocks_err)
+}
+
+// Tables are only opened for the duration of a migration, so skip the per table metrics task.
+fn open_table<K, V>(db: &Arc<RocksDB>, cf: &str) -> Result<DBMap<K, V>, TypedStoreError> {
+    DBMap::reopen(db, Some(cf), &ReadWriteOptions::default(), true)
+}
+
+fn deserialize_key<K: DeserializeOwned>(raw_key: &[u8]) -> Result<K, TypedStoreError> {
+    bincode::DefaultOptions::new()
+        .with_big_endian()
+        .with_fixint_encoding()
+        .deserialize(raw_key)
+        .map_err(typed_store_err_from_bincode_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rocks::{open_cf_in_memory, MetricConf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn db_with_entries(cf: &str, count: u64) -> Arc<RocksDB> {
+        let db = open_cf_in_memory(MetricConf::default(), &[cf]);
+        let table = open_table::<u64, u64>(&db, cf).unwrap();
+        table.multi_insert((0..count).map(|i| (i, i))).unwrap();
+        db
+    }
+
+    /// Fails every batch after the first `batches` ones, to simulate a crash mid migration.
+    struct FailAfter<S> {
+        inner: S,
+        batches: AtomicUsize,
+    }
+
+    impl<S: MigrationStep> MigrationStep for FailAfter<S> {
+        fn description(&self) -> String {
+            self.inner.description()
+        }
+
+        fn prepare(&self, db: &Arc<RocksDB>) -> Result<(), TypedStoreError> {
+            self.inner.prepare(db)
+        }
+
+        fn migrate_batch(
+            &self,
+            db: &Arc<RocksDB>,
+            cursor: Option<&[u8]>,
+            limit: usize,
+            batch: &mut DBBatch,
+        ) -> Result<StepBatch, TypedStoreError> {
+            if self
+                .batches
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |b| b.checked_sub(1))
+                .is_err()
+            {
+                return Err(TypedStoreError::RocksDBError("crash".to_owned()));
+            }
+            self.inner.migrate_batch(db, cursor, limit, batch)
+        }
+
+        fn finish(&self, db: &Arc<RocksDB>) -> Result<(), TypedStoreError> {
+            self.inner.finish(db)
+        }
+    }
+
+    #[test]
+    fn test_rewrite_into_new_table() {
+        let db = db_with_entries("numbers", 25);
+        let migrations =
+            vec![
+                Migration::new(1, "store numbers as strings").with_step(RewriteTable::new(
+                    "numbers",
+                    "numbers_v2",
+                    |key: u64, value: u64| (key % 5 != 0).then(|| (key, value.to_string())),
+                )),
+            ];
+        let runner = MigrationRunner::new(&db, migrations)
+            .unwrap()
+            .with_batch_size(10);
+        assert_eq!(runner.pending().unwrap().len(), 1);
+
+        assert_eq!(runner.run().unwrap(), 1);
+        assert!(!db.cf_exists("numbers"));
+        let table = open_table::<u64, String>(&db, "numbers_v2").unwrap();
+        let entries: Vec<_> = table.safe_iter().map(Result::unwrap).collect();
+        let expected: Vec<_> = (0..25)
+            .filter(|i| i % 5 != 0)
+            .map(|i| (i, i.to_string()))
+            .collect();
+        assert_eq!(entries, expected);
+        assert_eq!(
+            runner.progress().unwrap(),
+            MigrationProgress {
+                schema_version: 1,
+                in_progress: None,
+            }
+        );
+        assert!(runner.pending().unwrap().is_empty());
+        assert_eq!(read_progress(&db).unwrap().schema_version, 1);
+    }
+
+    #[test]
+    fn test_resume_after_interruption() {
+        let db = db_with_entries("numbers", 25);
+        let migration = |batches| {
+            Migration::new(1, "add 1000").with_step(FailAfter {
+                inner: RewriteTable::new("numbers", "numbers", |key: u64, value: u64| {
+                    Some((key, value + 1000))
+                }),
+                batches: AtomicUsize::new(batches),
+            })
+        };
+
+        let runner = MigrationRunner::new(&db, vec![migration(2)])
+            .unwrap()
+            .with_batch_size(10);
+        assert!(runner.run().is_err());
+        let progress = runner.progress().unwrap();
+        assert_eq!(progress.schema_version, 0);
+        let step_progress = progress.in_progress.unwrap();
+        assert_eq!(step_progress.step, 0);
+        assert_eq!(step_progress.entries_migrated, 20);
+        assert_eq!(step_progress.cursor, Some(be_fix_int_ser(&19u64).unwrap()));
+
+        let runner = MigrationRunner::new(&db, vec![migration(usize::MAX)])
+            .unwrap()
+            .with_batch_size(10);
+        assert_eq!(runner.run().unwrap(), 1);
+        let table = open_table::<u64, u64>(&db, "numbers").unwrap();
+        let entries: Vec<_> = table.safe_iter().map(Result::unwrap).collect();
+        let expected: Vec<_> = (0..25).map(|i| (i, i + 1000)).collect();
+        assert_eq!(entries, expected);
+    }
+
+    #[test]
+    fn test_run_to_and_rename() {
+        let db = db_with_entries("numbers", 5);
+        let migrations = vec![
+            Migration::new(1, "rename numbers").with_step(
+                RewriteTable::<u64, u64, _, _, _>::rename("numbers", "values"),
+            ),
+            Migration::new(2, "drop values").with_step(DropTable("values".to_owned())),
+        ];
+        let runner = MigrationRunner::new(&db, migrations).unwrap();
+
+        assert_eq!(runner.run_to(1).unwrap(), 1);
+        assert!(!db.cf_exists("numbers"));
+        let table = open_table::<u64, u64>(&db, "values").unwrap();
+        assert_eq!(table.safe_iter().count(), 5);
+        assert_eq!(runner.pending().unwrap().len(), 1);
+
+        assert_eq!(runner.run().unwrap(), 2);
+        assert!(!db.cf_exists("values"));
+        // Running again is a no-op.
+        assert_eq!(runner.run().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_invalid_migrations() {
+        let db = db_with_entries("numbers", 5);
+        assert!(matches!(
+            MigrationRunner::new(
+                &db,
+                vec![Migration::new(2, "two"), Migration::new(1, "one")]
+            ),
+            Err(TypedStoreError::MigrationError(_))
+        ));
+        assert!(MigrationRunner::new(&db, vec![Migration::new(0, "zero")]).is_err());
+
+        let migrations =
+            vec![
+                Migration::new(1, "change keys in place").with_step(RewriteTable::new(
+                    "numbers",
+                    "numbers",
+                    |key: u64, value: u64| Some((key + 1, value)),
+                )),
+            ];
+        let runner = MigrationRunner::new(&db, migrations).unwrap();
+        assert!(matches!(
+            runner.run(),
+            Err(TypedStoreError::MigrationError(_))
+        ));
+        assert_eq!(runner.progress().unwrap().schema_version, 0);
+    }
+
+    #[test]
+    fn test_run_to_latest() {
+        let migrations = || {
+            vec![Migration::new(1, "add 1000").with_step(RewriteTable::new(
+                "numbers",
+                "numbers",
+                |key: u64, value: u64| Some((key, value + 1000)),
+            ))]
+        };
+
+        // Existing databases are migrated.
+        let db = db_with_entries("numbers", 5);
+        let runner = MigrationRunner::new(&db, migrations()).unwrap();
+        runner.run_to_latest(1, false).unwrap();
+        let table = open_table::<u64, u64>(&db, "numbers").unwrap();
+        assert_eq!(table.get(&4).unwrap(), Some(1004));
+        assert_eq!(read_progress(&db).unwrap().schema_version, 1);
+        // Running again is a no-op.
+        runner.run_to_latest(1, false).unwrap();
+        assert_eq!(table.get(&4).unwrap(), Some(1004));
+
+        // New databases are recorded at the latest version without migrating.
+        let db = db_with_entries("numbers", 5);
+        let runner = MigrationRunner::new(&db, migrations()).unwrap();
+        runner.run_to_latest(1, true).unwrap();
+        let table = open_table::<u64, u64>(&db, "numbers").unwrap();
+        assert_eq!(table.get(&4).unwrap(), Some(4));
+        assert_eq!(read_progress(&db).unwrap().schema_version, 1);
+
+        // Databases from newer binaries, or missing migrations, are refused.
+        assert!(matches!(
+            MigrationRunner::new(&db, vec![])
+                .unwrap()
+                .run_to_latest(0, false),
+            Err(TypedStoreError::MigrationError(_))
+        ));
+        let db = db_with_entries("numbers", 5);
+        assert!(matches!(
+            MigrationRunner::new(&db, migrations())
+                .unwrap()
+                .run_to_latest(2, false),
+            Err(TypedStoreError::MigrationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_initialize_progress() {
+        let db = db_with_entries("numbers", 0);
+        assert_eq!(read_progress(&db).unwrap().schema_version, 0);
+
+        initialize_progress(&db, 3).unwrap();
+        assert_eq!(read_progress(&db).unwrap().schema_version, 3);
+
+        // Progress that was already recorded is kept.
+        initialize_progress(&db, 5).unwrap();
+        assert_eq!(read_progress(&db).unwrap().schema_version, 3);
+    }
+}
@@ -8,6 +8,7 @@ pub mod util;
 pub(crate) mod values;
 
 use self::{iter::Iter, keys::Keys, values::Values};
+use crate::memstore::{InMemoryBatch, InMemoryDB, InMemoryRawIter, InMemorySnapshot};
 use crate::rocks::errors::typed_store_err_from_bcs_err;
 use crate::rocks::errors::typed_store_err_from_bincode_err;
 use crate::rocks::errors::typed_store_err_from_rocks_err;
@@ -252,44 +253,105 @@ impl Drop for OptimisticTransactionDBWrapper {
     }
 }
 
+#[derive(Debug)]
+pub struct InMemoryDBWrapper {
+    pub underlying: InMemoryDB,
+    pub metric_conf: MetricConf,
+}
+
+impl InMemoryDBWrapper {
+    fn new(underlying: InMemoryDB, metric_conf: MetricConf) -> Self {
+        DBMetrics::get().increment_num_active_dbs(&metric_conf.db_name);
+        Self {
+            underlying,
+            metric_conf,
+        }
+    }
+}
+
+impl Drop for InMemoryDBWrapper {
+    fn drop(&mut self) {
+        DBMetrics::get().decrement_num_active_dbs(&self.metric_conf.db_name);
+    }
+}
+
 /// Thin wrapper to unify interface across different db types
 #[derive(Debug)]
 pub enum RocksDB {
     DBWithThreadMode(DBWithThreadModeWrapper),
     OptimisticTransactionDB(OptimisticTransactionDBWrapper),
+    InMemory(InMemoryDBWrapper),
 }
 
+// The in-memory store has no column family handles, so `DBMap` serves it directly instead of
+// going through the methods below that take one. Those methods spell out what the in-memory
+// store does instead, which is usually returning an error.
 macro_rules! delegate_call {
-    ($self:ident.$method:ident($($args:ident),*)) => {
+    ($self:ident.$method:ident($($args:ident),*), in_memory: $in_memory:expr) => {
         match $self {
             Self::DBWithThreadMode(d) => d.underlying.$method($($args),*),
             Self::OptimisticTransactionDB(d) => d.underlying.$method($($args),*),
+            Self::InMemory(_) => $in_memory,
         }
     }
 }
 
+fn unsupported_in_memory(operation: &str) -> TypedStoreError {
+    TypedStoreError::RocksDBError(format!(
+        "{operation} is not supported by the in-memory store"
+    ))
+}
+
 impl Drop for RocksDB {
     fn drop(&mut self) {
-        delegate_call!(self.cancel_all_background_work(/* wait */ true))
+        match self {
+            Self::DBWithThreadMode(d) => {
+                d.underlying.cancel_all_background_work(/* wait */ true)
+            }
+            Self::OptimisticTransactionDB(d) => {
+                d.underlying.cancel_all_background_work(/* wait */ true)
+            }
+            Self::InMemory(_) => {}
+        }
     }
 }
 
 impl RocksDB {
-    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<Vec<u8>>, rocksdb::Error> {
-        delegate_call!(self.get(key))
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        match self {
+            Self::DBWithThreadMode(d) => d
+                .underlying
+                .get(key)
+                .map_err(typed_store_err_from_rocks_err),
+            Self::OptimisticTransactionDB(d) => d
+                .underlying
+                .get(key)
+                .map_err(typed_store_err_from_rocks_err),
+            Self::InMemory(d) => d
+                .underlying
+                .get(rocksdb::DEFAULT_COLUMN_FAMILY_NAME, key.as_ref()),
+        }
     }
 
     pub fn multi_get_cf<'a, 'b: 'a, K, I, W>(
         &'a self,
         keys: I,
         readopts: &ReadOptions,
-    ) -> Vec<Result<Option<Vec<u8>>, rocksdb::Error>>
+    ) -> Vec<Result<Option<Vec<u8>>, TypedStoreError>>
     where
         K: AsRef<[u8]>,
         I: IntoIterator<Item = (&'b W, K)>,
         W: 'b + AsColumnFamilyRef,
     {
-        delegate_call!(self.multi_get_cf_opt(keys, readopts))
+        delegate_call!(self.multi_get_cf_opt(keys, readopts), in_memory: {
+            return keys
+                .into_iter()
+                .map(|_| Err(unsupported_in_memory("multi_get_cf")))
+                .collect();
+        })
+        .into_iter()
+        .map(|r| r.map_err(typed_store_err_from_rocks_err))
+        .collect()
     }
 
     pub fn batched_multi_get_cf_opt<I, K>(
@@ -298,20 +360,32 @@ impl RocksDB {
         keys: I,
         sorted_input: bool,
         readopts: &ReadOptions,
-    ) -> Vec<Result<Option<DBPinnableSlice<'_>>, Error>>
+    ) -> Vec<Result<Option<DBPinnableSlice<'_>>, TypedStoreError>>
     where
         I: IntoIterator<Item = K>,
         K: AsRef<[u8]>,
     {
-        delegate_call!(self.batched_multi_get_cf_opt(cf, keys, sorted_input, readopts))
+        delegate_call!(
+            self.batched_multi_get_cf_opt(cf, keys, sorted_input, readopts),
+            in_memory: {
+                return keys
+                    .into_iter()
+                    .map(|_| Err(unsupported_in_memory("batched_multi_get_cf_opt")))
+                    .collect();
+            }
+        )
+        .into_iter()
+        .map(|r| r.map_err(typed_store_err_from_rocks_err))
+        .collect()
     }
 
+    /// The in-memory store has no properties, so it never reports a value.
     pub fn property_int_value_cf(
         &self,
         cf: &impl AsColumnFamilyRef,
         name: impl CStrLike,
     ) -> Result<Option<u64>, rocksdb::Error> {
-        delegate_call!(self.property_int_value_cf(cf, name))
+        delegate_call!(self.property_int_value_cf(cf, name), in_memory: Ok(None))
     }
 
     pub fn get_pinned_cf_opt<K: AsRef<[u8]>>(
@@ -319,12 +393,22 @@ impl RocksDB {
         cf: &impl AsColumnFamilyRef,
         key: K,
         readopts: &ReadOptions,
-    ) -> Result<Option<DBPinnableSlice<'_>>, rocksdb::Error> {
-        delegate_call!(self.get_pinned_cf_opt(cf, key, readopts))
+    ) -> Result<Option<DBPinnableSlice<'_>>, TypedStoreError> {
+        delegate_call!(self.get_pinned_cf_opt(cf, key, readopts), in_memory: {
+            return Err(unsupported_in_memory("get_pinned_cf_opt"));
+        })
+        .map_err(typed_store_err_from_rocks_err)
     }
 
     pub fn cf_handle(&self, name: &str) -> Option<Arc<rocksdb::BoundColumnFamily<'_>>> {
-        delegate_call!(self.cf_handle(name))
+        delegate_call!(self.cf_handle(name), in_memory: None)
+    }
+
+    pub fn cf_exists(&self, name: &str) -> bool {
+        match self {
+            Self::InMemory(d) => d.underlying.cf_exists(name),
+            _ => self.cf_handle(name).is_some(),
+        }
     }
 
     pub fn create_cf<N: AsRef<str>>(
@@ -332,11 +416,25 @@ impl RocksDB {
         name: N,
         opts: &rocksdb::Options,
     ) -> Result<(), rocksdb::Error> {
-        delegate_call!(self.create_cf(name, opts))
+        match self {
+            Self::DBWithThreadMode(d) => d.underlying.create_cf(name, opts),
+            Self::OptimisticTransactionDB(d) => d.underlying.create_cf(name, opts),
+            Self::InMemory(d) => {
+                d.underlying.create_cf(name.as_ref());
+                Ok(())
+            }
+        }
     }
 
     pub fn drop_cf(&self, name: &str) -> Result<(), rocksdb::Error> {
-        delegate_call!(self.drop_cf(name))
+        match self {
+            Self::DBWithThreadMode(d) => d.underlying.drop_cf(name),
+            Self::OptimisticTransactionDB(d) => d.underlying.drop_cf(name),
+            Self::InMemory(d) => {
+                d.underlying.drop_cf(name);
+                Ok(())
+            }
+        }
     }
 
     pub fn delete_file_in_range<K: AsRef<[u8]>>(
@@ -344,8 +442,11 @@ impl RocksDB {
         cf: &impl AsColumnFamilyRef,
         from: K,
         to: K,
-    ) -> Result<(), rocksdb::Error> {
-        delegate_call!(self.delete_file_in_range_cf(cf, from, to))
+    ) -> Result<(), TypedStoreError> {
+        delegate_call!(self.delete_file_in_range_cf(cf, from, to), in_memory: {
+            return Err(unsupported_in_memory("delete_file_in_range"));
+        })
+        .map_err(typed_store_err_from_rocks_err)
     }
 
     pub fn delete_cf<K: AsRef<[u8]>>(
@@ -353,16 +454,26 @@ impl RocksDB {
         cf: &impl AsColumnFamilyRef,
         key: K,
         writeopts: &WriteOptions,
-    ) -> Result<(), rocksdb::Error> {
+    ) -> Result<(), TypedStoreError> {
         fail_point!("delete-cf-before");
-        let ret = delegate_call!(self.delete_cf_opt(cf, key, writeopts));
+        let ret = match self {
+            Self::DBWithThreadMode(d) => d
+                .underlying
+                .delete_cf_opt(cf, key, writeopts)
+                .map_err(typed_store_err_from_rocks_err),
+            Self::OptimisticTransactionDB(d) => d
+                .underlying
+                .delete_cf_opt(cf, key, writeopts)
+                .map_err(typed_store_err_from_rocks_err),
+            Self::InMemory(_) => Err(unsupported_in_memory("delete_cf")),
+        };
         fail_point!("delete-cf-after");
         #[allow(clippy::let_and_return)]
         ret
     }
 
     pub fn path(&self) -> &Path {
-        delegate_call!(self.path())
+        delegate_call!(self.path(), in_memory: Path::new(""))
     }
 
     pub fn put_cf<K, V>(
@@ -371,13 +482,23 @@ impl RocksDB {
         key: K,
         value: V,
         writeopts: &WriteOptions,
-    ) -> Result<(), rocksdb::Error>
+    ) -> Result<(), TypedStoreError>
     where
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
     {
         fail_point!("put-cf-before");
-        let ret = delegate_call!(self.put_cf_opt(cf, key, value, writeopts));
+        let ret = match self {
+            Self::DBWithThreadMode(d) => d
+                .underlying
+                .put_cf_opt(cf, key, value, writeopts)
+                .map_err(typed_store_err_from_rocks_err),
+            Self::OptimisticTransactionDB(d) => d
+                .underlying
+                .put_cf_opt(cf, key, value, writeopts)
+                .map_err(typed_store_err_from_rocks_err),
+            Self::InMemory(_) => Err(unsupported_in_memory("put_cf")),
+        };
         fail_point!("put-cf-after");
         #[allow(clippy::let_and_return)]
         ret
@@ -389,11 +510,12 @@ impl RocksDB {
         key: K,
         readopts: &ReadOptions,
     ) -> bool {
-        delegate_call!(self.key_may_exist_cf_opt(cf, key, readopts))
+        // A false positive only costs the caller a lookup, unlike a false negative.
+        delegate_call!(self.key_may_exist_cf_opt(cf, key, readopts), in_memory: true)
     }
 
     pub fn try_catch_up_with_primary(&self) -> Result<(), rocksdb::Error> {
-        delegate_call!(self.try_catch_up_with_primary())
+        delegate_call!(self.try_catch_up_with_primary(), in_memory: Ok(()))
     }
 
     pub fn write(
@@ -415,6 +537,7 @@ impl RocksDB {
                     .map_err(typed_store_err_from_rocks_err)?;
                 Ok(())
             }
+            (RocksDB::InMemory(db), RocksDBBatch::InMemory(batch)) => db.underlying.write(batch),
             _ => Err(TypedStoreError::RocksDBError(
                 "using invalid batch type for the database".to_string(),
             )),
@@ -430,6 +553,9 @@ impl RocksDB {
         match self {
             Self::OptimisticTransactionDB(db) => Ok(db.underlying.transaction()),
             Self::DBWithThreadMode(_) => panic!(),
+            Self::InMemory(_) => Err(TypedStoreError::RocksDBError(
+                "transactions are not supported by the in-memory store".to_string(),
+            )),
         }
     }
 
@@ -446,6 +572,9 @@ impl RocksDB {
                     .transaction_opt(&WriteOptions::default(), &tx_opts))
             }
             Self::DBWithThreadMode(_) => panic!(),
+            Self::InMemory(_) => Err(TypedStoreError::RocksDBError(
+                "transactions are not supported by the in-memory store".to_string(),
+            )),
         }
     }
 
@@ -453,14 +582,15 @@ impl RocksDB {
         &'a self,
         cf_handle: &impl AsColumnFamilyRef,
         readopts: ReadOptions,
-    ) -> RocksDBRawIter<'b> {
+    ) -> Result<RocksDBRawIter<'b>, TypedStoreError> {
         match self {
-            Self::DBWithThreadMode(db) => {
-                RocksDBRawIter::DB(db.underlying.raw_iterator_cf_opt(cf_handle, readopts))
-            }
-            Self::OptimisticTransactionDB(db) => RocksDBRawIter::OptimisticTransactionDB(
+            Self::DBWithThreadMode(db) => Ok(RocksDBRawIter::DB(
                 db.underlying.raw_iterator_cf_opt(cf_handle, readopts),
-            ),
+            )),
+            Self::OptimisticTransactionDB(db) => Ok(RocksDBRawIter::OptimisticTransactionDB(
+                db.underlying.raw_iterator_cf_opt(cf_handle, readopts),
+            )),
+            Self::InMemory(_) => Err(unsupported_in_memory("raw_iterator_cf")),
         }
     }
 
@@ -469,14 +599,15 @@ impl RocksDB {
         cf_handle: &impl AsColumnFamilyRef,
         readopts: ReadOptions,
         mode: IteratorMode<'_>,
-    ) -> RocksDBIter<'b> {
+    ) -> Result<RocksDBIter<'b>, TypedStoreError> {
         match self {
-            Self::DBWithThreadMode(db) => {
-                RocksDBIter::DB(db.underlying.iterator_cf_opt(cf_handle, readopts, mode))
-            }
-            Self::OptimisticTransactionDB(db) => RocksDBIter::OptimisticTransactionDB(
+            Self::DBWithThreadMode(db) => Ok(RocksDBIter::DB(
+                db.underlying.iterator_cf_opt(cf_handle, readopts, mode),
+            )),
+            Self::OptimisticTransactionDB(db) => Ok(RocksDBIter::OptimisticTransactionDB(
                 db.underlying.iterator_cf_opt(cf_handle, readopts, mode),
-            ),
+            )),
+            Self::InMemory(_) => Err(unsupported_in_memory("iterator_cf")),
         }
     }
 
@@ -486,7 +617,7 @@ impl RocksDB {
         start: Option<K>,
         end: Option<K>,
     ) {
-        delegate_call!(self.compact_range_cf(cf, start, end))
+        delegate_call!(self.compact_range_cf(cf, start, end), in_memory: ())
     }
 
     pub fn compact_range_to_bottom<K: AsRef<[u8]>>(
@@ -497,11 +628,12 @@ impl RocksDB {
     ) {
         let opt = &mut CompactOptions::default();
         opt.set_bottommost_level_compaction(BottommostLevelCompaction::ForceOptimized);
-        delegate_call!(self.compact_range_cf_opt(cf, start, end, opt))
+        delegate_call!(self.compact_range_cf_opt(cf, start, end, opt), in_memory: ())
     }
 
     pub fn flush(&self) -> Result<(), TypedStoreError> {
-        delegate_call!(self.flush()).map_err(|e| TypedStoreError::RocksDBError(e.into_string()))
+        delegate_call!(self.flush(), in_memory: Ok(()))
+            .map_err(|e| TypedStoreError::RocksDBError(e.into_string()))
     }
 
     pub fn snapshot(&self) -> RocksDBSnapshot<'_> {
@@ -510,6 +642,7 @@ impl RocksDB {
             Self::OptimisticTransactionDB(d) => {
                 RocksDBSnapshot::OptimisticTransactionDB(d.underlying.snapshot())
             }
+            Self::InMemory(d) => RocksDBSnapshot::InMemory(d.underlying.snapshot()),
         }
     }
 
@@ -521,6 +654,11 @@ impl RocksDB {
             Self::OptimisticTransactionDB(d) => {
                 Checkpoint::new(&d.underlying).map_err(typed_store_err_from_rocks_err)?
             }
+            Self::InMemory(_) => {
+                return Err(TypedStoreError::RocksDBError(
+                    "checkpoints are not supported by the in-memory store".to_string(),
+                ))
+            }
         };
         checkpoint
             .create_checkpoint(path)
@@ -529,7 +667,7 @@ impl RocksDB {
     }
 
     pub fn flush_cf(&self, cf: &impl AsColumnFamilyRef) -> Result<(), rocksdb::Error> {
-        delegate_call!(self.flush_cf(cf))
+        delegate_call!(self.flush_cf(cf), in_memory: Ok(()))
     }
 
     pub fn set_options_cf(
@@ -537,13 +675,14 @@ impl RocksDB {
         cf: &impl AsColumnFamilyRef,
         opts: &[(&str, &str)],
     ) -> Result<(), rocksdb::Error> {
-        delegate_call!(self.set_options_cf(cf, opts))
+        delegate_call!(self.set_options_cf(cf, opts), in_memory: Ok(()))
     }
 
     pub fn get_sampling_interval(&self) -> SamplingInterval {
         match self {
             Self::DBWithThreadMode(d) => d.metric_conf.read_sample_interval.new_from_self(),
             Self::OptimisticTransactionDB(d) => d.metric_conf.read_sample_interval.new_from_self(),
+            Self::InMemory(d) => d.metric_conf.read_sample_interval.new_from_self(),
         }
     }
 
@@ -551,6 +690,7 @@ impl RocksDB {
         match self {
             Self::DBWithThreadMode(d) => d.metric_conf.read_sample_interval.new_from_self(),
             Self::OptimisticTransactionDB(d) => d.metric_conf.read_sample_interval.new_from_self(),
+            Self::InMemory(d) => d.metric_conf.read_sample_interval.new_from_self(),
         }
     }
 
@@ -558,6 +698,7 @@ impl RocksDB {
         match self {
             Self::DBWithThreadMode(d) => d.metric_conf.write_sample_interval.new_from_self(),
             Self::OptimisticTransactionDB(d) => d.metric_conf.write_sample_interval.new_from_self(),
+            Self::InMemory(d) => d.metric_conf.write_sample_interval.new_from_self(),
         }
     }
 
@@ -565,6 +706,7 @@ impl RocksDB {
         match self {
             Self::DBWithThreadMode(d) => d.metric_conf.iter_sample_interval.new_from_self(),
             Self::OptimisticTransactionDB(d) => d.metric_conf.iter_sample_interval.new_from_self(),
+            Self::InMemory(d) => d.metric_conf.iter_sample_interval.new_from_self(),
         }
     }
 
@@ -572,6 +714,7 @@ impl RocksDB {
         let name = match self {
             Self::DBWithThreadMode(d) => &d.metric_conf.db_name,
             Self::OptimisticTransactionDB(d) => &d.metric_conf.db_name,
+            Self::InMemory(d) => &d.metric_conf.db_name,
         };
         if name.is_empty() {
             self.default_db_name()
@@ -589,13 +732,21 @@ impl RocksDB {
     }
 
     pub fn live_files(&self) -> Result<Vec<LiveFile>, Error> {
-        delegate_call!(self.live_files())
+        delegate_call!(self.live_files(), in_memory: Ok(vec![]))
+    }
+
+    fn in_memory(&self) -> Option<&InMemoryDB> {
+        match self {
+            Self::InMemory(d) => Some(&d.underlying),
+            _ => None,
+        }
     }
 }
 
 pub enum RocksDBSnapshot<'a> {
     DBWithThreadMode(rocksdb::Snapshot<'a>),
     OptimisticTransactionDB(SnapshotWithThreadMode<'a, OptimisticTransactionDB>),
+    InMemory(InMemorySnapshot),
 }
 
 impl<'a> RocksDBSnapshot<'a> {
@@ -603,57 +754,88 @@ impl<'a> RocksDBSnapshot<'a> {
         &'a self,
         keys: I,
         readopts: ReadOptions,
-    ) -> Vec<Result<Option<Vec<u8>>, rocksdb::Error>>
+    ) -> Vec<Result<Option<Vec<u8>>, TypedStoreError>>
     where
         K: AsRef<[u8]>,
         I: IntoIterator<Item = (&'b W, K)>,
         W: 'b + AsColumnFamilyRef,
     {
-        match self {
+        let results = match self {
             Self::DBWithThreadMode(s) => s.multi_get_cf_opt(keys, readopts),
             Self::OptimisticTransactionDB(s) => s.multi_get_cf_opt(keys, readopts),
-        }
+            Self::InMemory(_) => {
+                return keys
+                    .into_iter()
+                    .map(|_| Err(unsupported_in_memory("multi_get_cf_opt")))
+                    .collect()
+            }
+        };
+        results
+            .into_iter()
+            .map(|r| r.map_err(typed_store_err_from_rocks_err))
+            .collect()
     }
+
     pub fn multi_get_cf<'b: 'a, K, I, W>(
         &'a self,
         keys: I,
-    ) -> Vec<Result<Option<Vec<u8>>, rocksdb::Error>>
+    ) -> Vec<Result<Option<Vec<u8>>, TypedStoreError>>
     where
         K: AsRef<[u8]>,
         I: IntoIterator<Item = (&'b W, K)>,
         W: 'b + AsColumnFamilyRef,
     {
-        match self {
-            Self::DBWithThreadMode(s) => s.multi_get_cf(keys),
-            Self::OptimisticTransactionDB(s) => s.multi_get_cf(keys),
-        }
+        self.multi_get_cf_opt(keys, ReadOptions::default())
     }
 }
 
 pub enum RocksDBBatch {
     Regular(rocksdb::WriteBatch),
     Transactional(rocksdb::WriteBatchWithTransaction<true>),
+    InMemory(InMemoryBatch),
 }
 
+// In-memory batches are keyed by column family name rather than handle, so `DBBatch` writes to
+// them directly and the methods below reject them.
 macro_rules! delegate_batch_call {
     ($self:ident.$method:ident($($args:ident),*)) => {
         match $self {
-            Self::Regular(b) => b.$method($($args),*),
-            Self::Transactional(b) => b.$method($($args),*),
+            Self::Regular(b) => {
+                b.$method($($args),*);
+                Ok(())
+            }
+            Self::Transactional(b) => {
+                b.$method($($args),*);
+                Ok(())
+            }
+            Self::InMemory(_) => Err(unsupported_in_memory(stringify!($method))),
         }
     }
 }
 
 impl RocksDBBatch {
     fn size_in_bytes(&self) -> usize {
-        delegate_batch_call!(self.size_in_bytes())
+        match self {
+            Self::Regular(b) => b.size_in_bytes(),
+            Self::Transactional(b) => b.size_in_bytes(),
+            Self::InMemory(b) => b.size_in_bytes(),
+        }
     }
 
-    pub fn delete_cf<K: AsRef<[u8]>>(&mut self, cf: &impl AsColumnFamilyRef, key: K) {
+    pub fn delete_cf<K: AsRef<[u8]>>(
+        &mut self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<(), TypedStoreError> {
         delegate_batch_call!(self.delete_cf(cf, key))
     }
 
-    pub fn put_cf<K, V>(&mut self, cf: &impl AsColumnFamilyRef, key: K, value: V)
+    pub fn put_cf<K, V>(
+        &mut self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        value: V,
+    ) -> Result<(), TypedStoreError>
     where
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
@@ -661,7 +843,12 @@ impl RocksDBBatch {
         delegate_batch_call!(self.put_cf(cf, key, value))
     }
 
-    pub fn merge_cf<K, V>(&mut self, cf: &impl AsColumnFamilyRef, key: K, value: V)
+    pub fn merge_cf<K, V>(
+        &mut self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        value: V,
+    ) -> Result<(), TypedStoreError>
     where
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
@@ -681,6 +868,7 @@ impl RocksDBBatch {
                 Ok(())
             }
             Self::Transactional(_) => panic!(),
+            Self::InMemory(_) => Err(unsupported_in_memory("delete_range_cf")),
         }
     }
 }
@@ -748,7 +936,8 @@ impl<K, V> DBMap<K, V> {
         let db_metrics_cloned = db_metrics.clone();
         let cf = opt_cf.to_string();
         let (sender, mut recv) = tokio::sync::oneshot::channel();
-        if !is_deprecated {
+        // The in-memory store has no RocksDB properties to report.
+        if !is_deprecated && db.in_memory().is_none() {
             tokio::task::spawn(async move {
                 let mut interval =
                     tokio::time::interval(Duration::from_secs(CF_METRICS_REPORT_PERIOD_SECS));
@@ -833,8 +1022,9 @@ impl<K, V> DBMap<K, V> {
             .unwrap_or(rocksdb::DEFAULT_COLUMN_FAMILY_NAME)
             .to_owned();
 
-        db.cf_handle(&cf_key)
-            .ok_or_else(|| TypedStoreError::UnregisteredColumn(cf_key.clone()))?;
+        if !db.cf_exists(&cf_key) {
+            return Err(TypedStoreError::UnregisteredColumn(cf_key));
+        }
 
         Ok(DBMap::new(db.clone(), rw_options, &cf_key, is_deprecated))
     }
@@ -849,6 +1039,7 @@ impl<K, V> DBMap<K, V> {
             RocksDB::OptimisticTransactionDB(_) => {
                 RocksDBBatch::Transactional(WriteBatchWithTransaction::<true>::default())
             }
+            RocksDB::InMemory(_) => RocksDBBatch::InMemory(InMemoryBatch::default()),
         };
         DBBatch::new(
             &self.rocksdb,
@@ -860,6 +1051,9 @@ impl<K, V> DBMap<K, V> {
     }
 
     pub fn compact_range<J: Serialize>(&self, start: &J, end: &J) -> Result<(), TypedStoreError> {
+        if self.rocksdb.in_memory().is_some() {
+            return Ok(());
+        }
         let from_buf = be_fix_int_ser(start)?;
         let to_buf = be_fix_int_ser(end)?;
         self.rocksdb
@@ -873,6 +1067,9 @@ impl<K, V> DBMap<K, V> {
         start: Vec<u8>,
         end: Vec<u8>,
     ) -> Result<(), TypedStoreError> {
+        if self.rocksdb.in_memory().is_some() {
+            return Ok(());
+        }
         let cf = self
             .rocksdb
             .cf_handle(cf_name)
@@ -886,6 +1083,9 @@ impl<K, V> DBMap<K, V> {
         start: &J,
         end: &J,
     ) -> Result<(), TypedStoreError> {
+        if self.rocksdb.in_memory().is_some() {
+            return Ok(());
+        }
         let from_buf = be_fix_int_ser(start)?;
         let to_buf = be_fix_int_ser(end)?;
         self.rocksdb
@@ -900,17 +1100,36 @@ impl<K, V> DBMap<K, V> {
     }
 
     pub fn iterator_cf(&self) -> RocksDBIter<'_> {
-        self.rocksdb
-            .iterator_cf(&self.cf(), self.opts.readopts(), IteratorMode::Start)
+        match &*self.rocksdb {
+            RocksDB::DBWithThreadMode(db) => RocksDBIter::DB(db.underlying.iterator_cf_opt(
+                &self.cf(),
+                self.opts.readopts(),
+                IteratorMode::Start,
+            )),
+            RocksDB::OptimisticTransactionDB(db) => {
+                RocksDBIter::OptimisticTransactionDB(db.underlying.iterator_cf_opt(
+                    &self.cf(),
+                    self.opts.readopts(),
+                    IteratorMode::Start,
+                ))
+            }
+            RocksDB::InMemory(db) => RocksDBIter::InMemory(db.underlying.iterator(&self.cf)),
+        }
     }
 
     pub fn flush(&self) -> Result<(), TypedStoreError> {
+        if self.rocksdb.in_memory().is_some() {
+            return Ok(());
+        }
         self.rocksdb
             .flush_cf(&self.cf())
             .map_err(|e| TypedStoreError::RocksDBError(e.into_string()))
     }
 
     pub fn set_options(&self, opts: &[(&str, &str)]) -> Result<(), rocksdb::Error> {
+        if self.rocksdb.in_memory().is_some() {
+            return Ok(());
+        }
         self.rocksdb.set_options_cf(&self.cf(), opts)
     }
 
@@ -959,7 +1178,6 @@ impl<K, V> DBMap<K, V> {
                 &self.opts.readopts(),
             )
             .into_iter()
-            .map(|r| r.map_err(|e| TypedStoreError::RocksDBError(e.into_string())))
             .collect();
         let entries = results?;
         let entry_size = entries
@@ -980,6 +1198,24 @@ impl<K, V> DBMap<K, V> {
         Ok(entries)
     }
 
+    /// Returns a vector of raw values corresponding to the keys provided, read from the
+    /// in-memory store backing this map.
+    fn multi_get_in_memory<J>(
+        &self,
+        db: &InMemoryDB,
+        keys: impl IntoIterator<Item = J>,
+    ) -> Result<Vec<Option<Vec<u8>>>, TypedStoreError>
+    where
+        J: Borrow<K>,
+        K: Serialize,
+    {
+        let keys_bytes: Result<Vec<_>, TypedStoreError> = keys
+            .into_iter()
+            .map(|k| be_fix_int_ser(k.borrow()))
+            .collect();
+        db.multi_get(&self.cf, keys_bytes?)
+    }
+
     fn report_metrics(rocksdb: &Arc<RocksDB>, cf_name: &str, db_metrics: &Arc<DBMetrics>) {
         let Some(cf) = rocksdb.cf_handle(cf_name) else {
             tracing::warn!(
@@ -1265,43 +1501,71 @@ impl<K, V> DBMap<K, V> {
         )
     }
 
-    // Creates a RocksDB read option with specified lower and upper bounds.
-    /// Lower bound is inclusive, and upper bound is exclusive.
-    fn create_read_options_with_bounds(
+    // Creates a raw iterator over the entries between the serialized `lower_bound` (inclusive)
+    // and `upper_bound` (exclusive), if any.
+    fn raw_iterator_with_bounds(
         &self,
-        lower_bound: Option<K>,
-        upper_bound: Option<K>,
-    ) -> ReadOptions
-    where
-        K: Serialize,
-    {
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+    ) -> RocksDBRawIter<'_> {
+        match &*self.rocksdb {
+            RocksDB::DBWithThreadMode(db) => RocksDBRawIter::DB(db.underlying.raw_iterator_cf_opt(
+                &self.cf(),
+                self.readopts_with_bounds(lower_bound, upper_bound),
+            )),
+            RocksDB::OptimisticTransactionDB(db) => {
+                RocksDBRawIter::OptimisticTransactionDB(db.underlying.raw_iterator_cf_opt(
+                    &self.cf(),
+                    self.readopts_with_bounds(lower_bound, upper_bound),
+                ))
+            }
+            RocksDB::InMemory(db) => RocksDBRawIter::InMemory(db.underlying.raw_iterator(
+                &self.cf,
+                lower_bound,
+                upper_bound,
+            )),
+        }
+    }
+
+    fn readopts_with_bounds(
+        &self,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+    ) -> ReadOptions {
         let mut readopts = self.opts.readopts();
         if let Some(lower_bound) = lower_bound {
-            let key_buf = be_fix_int_ser(&lower_bound).unwrap();
-            readopts.set_iterate_lower_bound(key_buf);
+            readopts.set_iterate_lower_bound(lower_bound);
         }
         if let Some(upper_bound) = upper_bound {
-            let key_buf = be_fix_int_ser(&upper_bound).unwrap();
-            readopts.set_iterate_upper_bound(key_buf);
+            readopts.set_iterate_upper_bound(upper_bound);
         }
         readopts
     }
 
-    // Creates a RocksDB read option with lower and upper bounds set corresponding to `range`.
-    fn create_read_options_with_range(&self, range: impl RangeBounds<K>) -> ReadOptions
+    // Serializes the specified lower and upper bounds.
+    /// Lower bound is inclusive, and upper bound is exclusive.
+    fn serialize_bounds(
+        lower_bound: Option<K>,
+        upper_bound: Option<K>,
+    ) -> (Option<Vec<u8>>, Option<Vec<u8>>)
     where
         K: Serialize,
     {
-        let mut readopts = self.opts.readopts();
-
-        let lower_bound = range.start_bound();
-        let upper_bound = range.end_bound();
+        (
+            lower_bound.map(|lower_bound| be_fix_int_ser(&lower_bound).unwrap()),
+            upper_bound.map(|upper_bound| be_fix_int_ser(&upper_bound).unwrap()),
+        )
+    }
 
-        match lower_bound {
+    // Serializes lower (inclusive) and upper (exclusive) bounds corresponding to `range`.
+    fn serialize_range(range: impl RangeBounds<K>) -> (Option<Vec<u8>>, Option<Vec<u8>>)
+    where
+        K: Serialize,
+    {
+        let lower_bound = match range.start_bound() {
             Bound::Included(lower_bound) => {
                 // Rocksdb lower bound is inclusive by default so nothing to do
-                let key_buf = be_fix_int_ser(&lower_bound).expect("Serialization must not fail");
-                readopts.set_iterate_lower_bound(key_buf);
+                Some(be_fix_int_ser(&lower_bound).expect("Serialization must not fail"))
             }
             Bound::Excluded(lower_bound) => {
                 let mut key_buf =
@@ -1309,12 +1573,12 @@ impl<K, V> DBMap<K, V> {
 
                 // Since we want exclusive, we need to increment the key to exclude the previous
                 big_endian_saturating_add_one(&mut key_buf);
-                readopts.set_iterate_lower_bound(key_buf);
+                Some(key_buf)
             }
-            Bound::Unbounded => (),
+            Bound::Unbounded => None,
         };
 
-        match upper_bound {
+        let upper_bound = match range.end_bound() {
             Bound::Included(upper_bound) => {
                 let mut key_buf =
                     be_fix_int_ser(&upper_bound).expect("Serialization must not fail");
@@ -1323,18 +1587,19 @@ impl<K, V> DBMap<K, V> {
                 if !is_max(&key_buf) {
                     // Since we want exclusive, we need to increment the key to get the upper bound
                     big_endian_saturating_add_one(&mut key_buf);
-                    readopts.set_iterate_upper_bound(key_buf);
+                    Some(key_buf)
+                } else {
+                    None
                 }
             }
             Bound::Excluded(upper_bound) => {
                 // Rocksdb upper bound is inclusive by default so nothing to do
-                let key_buf = be_fix_int_ser(&upper_bound).expect("Serialization must not fail");
-                readopts.set_iterate_upper_bound(key_buf);
+                Some(be_fix_int_ser(&upper_bound).expect("Serialization must not fail"))
             }
-            Bound::Unbounded => (),
+            Bound::Unbounded => None,
         };
 
-        readopts
+        (lower_bound, upper_bound)
     }
 }
 
@@ -1483,7 +1748,10 @@ impl DBBatch {
             .into_iter()
             .try_for_each::<_, Result<_, TypedStoreError>>(|k| {
                 let k_buf = be_fix_int_ser(k.borrow())?;
-                self.batch.delete_cf(&db.cf(), k_buf);
+                match &mut self.batch {
+                    RocksDBBatch::InMemory(batch) => batch.delete_cf(&db.cf, k_buf),
+                    batch => batch.delete_cf(&db.cf(), k_buf)?,
+                }
 
                 Ok(())
             })?;
@@ -1512,7 +1780,10 @@ impl DBBatch {
         let from_buf = be_fix_int_ser(from)?;
         let to_buf = be_fix_int_ser(to)?;
 
-        self.batch.delete_range_cf(&db.cf(), from_buf, to_buf)?;
+        match &mut self.batch {
+            RocksDBBatch::InMemory(batch) => batch.delete_range_cf(&db.cf, from_buf, to_buf),
+            batch => batch.delete_range_cf(&db.cf(), from_buf, to_buf)?,
+        }
         Ok(())
     }
 
@@ -1532,7 +1803,10 @@ impl DBBatch {
                 let k_buf = be_fix_int_ser(k.borrow())?;
                 let v_buf = bcs::to_bytes(v.borrow()).map_err(typed_store_err_from_bcs_err)?;
                 total += k_buf.len() + v_buf.len();
-                self.batch.put_cf(&db.cf(), k_buf, v_buf);
+                match &mut self.batch {
+                    RocksDBBatch::InMemory(batch) => batch.put_cf(&db.cf, k_buf, v_buf),
+                    batch => batch.put_cf(&db.cf(), k_buf, v_buf)?,
+                }
                 Ok(())
             })?;
         self.db_metrics
@@ -1552,13 +1826,14 @@ impl DBBatch {
         if !Arc::ptr_eq(&db.rocksdb, &self.rocksdb) {
             return Err(TypedStoreError::CrossDBBatch);
         }
+        self.check_merge_supported()?;
 
         new_vals
             .into_iter()
             .try_for_each::<_, Result<_, TypedStoreError>>(|(k, v)| {
                 let k_buf = be_fix_int_ser(k.borrow())?;
                 let v_buf = bcs::to_bytes(v.borrow()).map_err(typed_store_err_from_bcs_err)?;
-                self.batch.merge_cf(&db.cf(), k_buf, v_buf);
+                self.batch.merge_cf(&db.cf(), k_buf, v_buf)?;
                 Ok(())
             })?;
         Ok(self)
@@ -1573,15 +1848,27 @@ impl DBBatch {
         if !Arc::ptr_eq(&db.rocksdb, &self.rocksdb) {
             return Err(TypedStoreError::CrossDBBatch);
         }
+        self.check_merge_supported()?;
         new_vals
             .into_iter()
             .try_for_each::<_, Result<_, TypedStoreError>>(|(k, v)| {
                 let k_buf = be_fix_int_ser(k.borrow())?;
-                self.batch.merge_cf(&db.cf(), k_buf, v);
+                self.batch.merge_cf(&db.cf(), k_buf, v)?;
                 Ok(())
             })?;
         Ok(self)
     }
+
+    // Merge operators are configured through RocksDB options, so there is nothing to merge with
+    // in the in-memory store.
+    fn check_merge_supported(&self) -> Result<(), TypedStoreError> {
+        match self.batch {
+            RocksDBBatch::InMemory(_) => Err(TypedStoreError::RocksDBError(
+                "merge operations are not supported by the in-memory store".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
 }
 
 pub struct DBTransaction<'a> {
@@ -1779,6 +2066,7 @@ macro_rules! delegate_iter_call {
             Self::DB(db) => db.$method($($args),*),
             Self::OptimisticTransactionDB(db) => db.$method($($args),*),
             Self::OptimisticTransaction(db) => db.$method($($args),*),
+            Self::InMemory(db) => db.$method($($args),*),
         }
     }
 }
@@ -1794,6 +2082,7 @@ pub enum RocksDBRawIter<'a> {
             Transaction<'a, rocksdb::OptimisticTransactionDB<MultiThreaded>>,
         >,
     ),
+    InMemory(InMemoryRawIter),
 }
 
 impl<'a> RocksDBRawIter<'a> {
@@ -1825,7 +2114,12 @@ impl<'a> RocksDBRawIter<'a> {
         delegate_iter_call!(self.seek_for_prev(key))
     }
     pub fn status(&self) -> Result<(), rocksdb::Error> {
-        delegate_iter_call!(self.status())
+        match self {
+            Self::DB(db) => db.status(),
+            Self::OptimisticTransactionDB(db) => db.status(),
+            Self::OptimisticTransaction(db) => db.status(),
+            Self::InMemory(_) => Ok(()),
+        }
     }
 }
 
@@ -1834,6 +2128,7 @@ pub enum RocksDBIter<'a> {
     OptimisticTransactionDB(
         rocksdb::DBIteratorWithThreadMode<'a, rocksdb::OptimisticTransactionDB<MultiThreaded>>,
     ),
+    InMemory(std::vec::IntoIter<(Vec<u8>, Vec<u8>)>),
 }
 
 impl<'a> Iterator for RocksDBIter<'a> {
@@ -1842,6 +2137,9 @@ impl<'a> Iterator for RocksDBIter<'a> {
         match self {
             Self::DB(db) => db.next(),
             Self::OptimisticTransactionDB(db) => db.next(),
+            Self::InMemory(db) => db
+                .next()
+                .map(|(k, v)| Ok((k.into_boxed_slice(), v.into_boxed_slice()))),
         }
     }
 }
@@ -1860,6 +2158,9 @@ where
     #[instrument(level = "trace", skip_all, err)]
     fn contains_key(&self, key: &K) -> Result<bool, TypedStoreError> {
         let key_buf = be_fix_int_ser(key)?;
+        if let Some(db) = self.rocksdb.in_memory() {
+            return Ok(db.get(&self.cf, &key_buf)?.is_some());
+        }
         // [`rocksdb::DBWithThreadMode::key_may_exist_cf`] can have false positives,
         // but no false negatives. We use it to short-circuit the absent case
         let readopts = self.opts.readopts();
@@ -1868,8 +2169,7 @@ where
             .key_may_exist_cf(&self.cf(), &key_buf, &readopts)
             && self
                 .rocksdb
-                .get_pinned_cf_opt(&self.cf(), &key_buf, &readopts)
-                .map_err(typed_store_err_from_rocks_err)?
+                .get_pinned_cf_opt(&self.cf(), &key_buf, &readopts)?
                 .is_some())
     }
 
@@ -1881,12 +2181,22 @@ where
     where
         J: Borrow<K>,
     {
+        if let Some(db) = self.rocksdb.in_memory() {
+            let values = self.multi_get_in_memory(db, keys)?;
+            return Ok(values.into_iter().map(|v| v.is_some()).collect());
+        }
         let values = self.multi_get_pinned(keys)?;
         Ok(values.into_iter().map(|v| v.is_some()).collect())
     }
 
     #[instrument(level = "trace", skip_all, err)]
     fn get(&self, key: &K) -> Result<Option<V>, TypedStoreError> {
+        if let Some(db) = self.rocksdb.in_memory() {
+            return db
+                .get(&self.cf, &be_fix_int_ser(key)?)?
+                .map(|data| bcs::from_bytes(&data).map_err(typed_store_err_from_bcs_err))
+                .transpose();
+        }
         let _timer = self
             .db_metrics
             .op_metrics
@@ -1901,8 +2211,7 @@ where
         let key_buf = be_fix_int_ser(key)?;
         let res = self
             .rocksdb
-            .get_pinned_cf_opt(&self.cf(), &key_buf, &self.opts.readopts())
-            .map_err(typed_store_err_from_rocks_err)?;
+            .get_pinned_cf_opt(&self.cf(), &key_buf, &self.opts.readopts())?;
         self.db_metrics
             .op_metrics
             .rocksdb_get_bytes
@@ -1923,6 +2232,9 @@ where
 
     #[instrument(level = "trace", skip_all, err)]
     fn get_raw_bytes(&self, key: &K) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        if let Some(db) = self.rocksdb.in_memory() {
+            return db.get(&self.cf, &be_fix_int_ser(key)?);
+        }
         let _timer = self
             .db_metrics
             .op_metrics
@@ -1937,8 +2249,7 @@ where
         let key_buf = be_fix_int_ser(key)?;
         let res = self
             .rocksdb
-            .get_pinned_cf_opt(&self.cf(), &key_buf, &self.opts.readopts())
-            .map_err(typed_store_err_from_rocks_err)?;
+            .get_pinned_cf_opt(&self.cf(), &key_buf, &self.opts.readopts())?;
         self.db_metrics
             .op_metrics
             .rocksdb_get_bytes
@@ -1957,6 +2268,10 @@ where
 
     #[instrument(level = "trace", skip_all, err)]
     fn insert(&self, key: &K, value: &V) -> Result<(), TypedStoreError> {
+        if let Some(db) = self.rocksdb.in_memory() {
+            let value_buf = bcs::to_bytes(value).map_err(typed_store_err_from_bcs_err)?;
+            return db.put(&self.cf, be_fix_int_ser(key)?, value_buf);
+        }
         let timer = self
             .db_metrics
             .op_metrics
@@ -1981,8 +2296,7 @@ where
                 .report_metrics(&self.cf);
         }
         self.rocksdb
-            .put_cf(&self.cf(), &key_buf, &value_buf, &self.opts.writeopts())
-            .map_err(typed_store_err_from_rocks_err)?;
+            .put_cf(&self.cf(), &key_buf, &value_buf, &self.opts.writeopts())?;
 
         let elapsed = timer.stop_and_record();
         if elapsed > 1.0 {
@@ -2004,6 +2318,9 @@ where
 
     #[instrument(level = "trace", skip_all, err)]
     fn remove(&self, key: &K) -> Result<(), TypedStoreError> {
+        if let Some(db) = self.rocksdb.in_memory() {
+            return db.delete(&self.cf, &be_fix_int_ser(key)?);
+        }
         let _timer = self
             .db_metrics
             .op_metrics
@@ -2017,8 +2334,7 @@ where
         };
         let key_buf = be_fix_int_ser(key)?;
         self.rocksdb
-            .delete_cf(&self.cf(), key_buf, &self.opts.writeopts())
-            .map_err(typed_store_err_from_rocks_err)?;
+            .delete_cf(&self.cf(), key_buf, &self.opts.writeopts())?;
         self.db_metrics
             .op_metrics
             .rocksdb_deletes
@@ -2043,9 +2359,11 @@ where
     fn delete_file_in_range(&self, from: &K, to: &K) -> Result<(), TypedStoreError> {
         let from_buf = be_fix_int_ser(from.borrow())?;
         let to_buf = be_fix_int_ser(to.borrow())?;
+        if let Some(db) = self.rocksdb.in_memory() {
+            return db.delete_range(&self.cf, &from_buf, &to_buf);
+        }
         self.rocksdb
-            .delete_file_in_range(&self.cf(), from_buf, to_buf)
-            .map_err(typed_store_err_from_rocks_err)?;
+            .delete_file_in_range(&self.cf(), from_buf, to_buf)?;
         Ok(())
     }
 
@@ -2055,6 +2373,10 @@ where
     /// one is not created yet
     #[instrument(level = "trace", skip_all, err)]
     fn unsafe_clear(&self) -> Result<(), TypedStoreError> {
+        if let Some(db) = self.rocksdb.in_memory() {
+            db.clear_cf(&self.cf);
+            return Ok(());
+        }
         let _ = self.rocksdb.drop_cf(&self.cf);
         self.rocksdb
             .create_cf(self.cf.clone(), &default_db_options().options)
@@ -2090,9 +2412,7 @@ where
     /// Returns an unbounded iterator visiting each key-value pair in the map.
     /// This is potentially unsafe as it can perform a full table scan
     fn unbounded_iter(&'a self) -> Self::Iterator {
-        let db_iter = self
-            .rocksdb
-            .raw_iterator_cf(&self.cf(), self.opts.readopts());
+        let db_iter = self.raw_iterator_with_bounds(None, None);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         Iter::new(
             self.cf.clone(),
@@ -2113,8 +2433,8 @@ where
         lower_bound: Option<K>,
         upper_bound: Option<K>,
     ) -> Self::Iterator {
-        let readopts = self.create_read_options_with_bounds(lower_bound, upper_bound);
-        let db_iter = self.rocksdb.raw_iterator_cf(&self.cf(), readopts);
+        let (lower_bound, upper_bound) = Self::serialize_bounds(lower_bound, upper_bound);
+        let db_iter = self.raw_iterator_with_bounds(lower_bound, upper_bound);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         Iter::new(
             self.cf.clone(),
@@ -2130,8 +2450,8 @@ where
     /// Similar to `iter_with_bounds` but allows specifying inclusivity/exclusivity of ranges explicitly.
     /// TODO: find better name
     fn range_iter(&'a self, range: impl RangeBounds<K>) -> Self::Iterator {
-        let readopts = self.create_read_options_with_range(range);
-        let db_iter = self.rocksdb.raw_iterator_cf(&self.cf(), readopts);
+        let (lower_bound, upper_bound) = Self::serialize_range(range);
+        let db_iter = self.raw_iterator_with_bounds(lower_bound, upper_bound);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         Iter::new(
             self.cf.clone(),
@@ -2145,9 +2465,7 @@ where
     }
 
     fn safe_iter(&'a self) -> Self::SafeIterator {
-        let db_iter = self
-            .rocksdb
-            .raw_iterator_cf(&self.cf(), self.opts.readopts());
+        let db_iter = self.raw_iterator_with_bounds(None, None);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         SafeIter::new(
             self.cf.clone(),
@@ -2165,8 +2483,8 @@ where
         lower_bound: Option<K>,
         upper_bound: Option<K>,
     ) -> Self::SafeIterator {
-        let readopts = self.create_read_options_with_bounds(lower_bound, upper_bound);
-        let db_iter = self.rocksdb.raw_iterator_cf(&self.cf(), readopts);
+        let (lower_bound, upper_bound) = Self::serialize_bounds(lower_bound, upper_bound);
+        let db_iter = self.raw_iterator_with_bounds(lower_bound, upper_bound);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         SafeIter::new(
             self.cf.clone(),
@@ -2180,8 +2498,8 @@ where
     }
 
     fn safe_range_iter(&'a self, range: impl RangeBounds<K>) -> Self::SafeIterator {
-        let readopts = self.create_read_options_with_range(range);
-        let db_iter = self.rocksdb.raw_iterator_cf(&self.cf(), readopts);
+        let (lower_bound, upper_bound) = Self::serialize_range(range);
+        let db_iter = self.raw_iterator_with_bounds(lower_bound, upper_bound);
         let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = self.create_iter_context();
         SafeIter::new(
             self.cf.clone(),
@@ -2195,18 +2513,14 @@ where
     }
 
     fn keys(&'a self) -> Self::Keys {
-        let mut db_iter = self
-            .rocksdb
-            .raw_iterator_cf(&self.cf(), self.opts.readopts());
+        let mut db_iter = self.raw_iterator_with_bounds(None, None);
         db_iter.seek_to_first();
 
         Keys::new(db_iter)
     }
 
     fn values(&'a self) -> Self::Values {
-        let mut db_iter = self
-            .rocksdb
-            .raw_iterator_cf(&self.cf(), self.opts.readopts());
+        let mut db_iter = self.raw_iterator_with_bounds(None, None);
         db_iter.seek_to_first();
 
         Values::new(db_iter)
@@ -2221,6 +2535,9 @@ where
     where
         J: Borrow<K>,
     {
+        if let Some(db) = self.rocksdb.in_memory() {
+            return self.multi_get_in_memory(db, keys);
+        }
         let results = self
             .multi_get_pinned(keys)?
             .into_iter()
@@ -2238,6 +2555,17 @@ where
     where
         J: Borrow<K>,
     {
+        if let Some(db) = self.rocksdb.in_memory() {
+            return self
+                .multi_get_in_memory(db, keys)?
+                .into_iter()
+                .map(|value| {
+                    value
+                        .map(|data| bcs::from_bytes(&data).map_err(typed_store_err_from_bcs_err))
+                        .transpose()
+                })
+                .collect();
+        }
         let results = self.multi_get_pinned(keys)?;
         let values_parsed: Result<Vec<_>, TypedStoreError> = results
             .into_iter()
@@ -2262,25 +2590,39 @@ where
     where
         J: Borrow<K>,
     {
+        let snapshot = self.snapshot()?;
+        if let RocksDBSnapshot::InMemory(snapshot) = &snapshot {
+            // The in-memory snapshot is keyed by column family name, and reads from it are cheap
+            // enough that there is no need to chunk them.
+            let keys_bytes: Result<Vec<_>, TypedStoreError> = keys
+                .into_iter()
+                .map(|k| be_fix_int_ser(k.borrow()))
+                .collect();
+            return snapshot
+                .multi_get(&self.cf, keys_bytes?)?
+                .into_iter()
+                .map(|value| {
+                    value
+                        .map(|data| bcs::from_bytes(&data).map_err(typed_store_err_from_bcs_err))
+                        .transpose()
+                })
+                .collect();
+        }
         let cf = self.cf();
         let keys_bytes = keys
             .into_iter()
             .map(|k| (&cf, be_fix_int_ser(k.borrow()).unwrap()));
         let chunked_keys = keys_bytes.into_iter().chunks(chunk_size);
-        let snapshot = self.snapshot()?;
         let mut results = vec![];
         for chunk in chunked_keys.into_iter() {
             let chunk_result = snapshot.multi_get_cf(chunk);
             let values_parsed: Result<Vec<_>, TypedStoreError> = chunk_result
                 .into_iter()
-                .map(|value_byte| {
-                    let value_byte = value_byte.map_err(typed_store_err_from_rocks_err)?;
-                    match value_byte {
-                        Some(data) => Ok(Some(
-                            bcs::from_bytes(&data).map_err(typed_store_err_from_bcs_err)?,
-                        )),
-                        None => Ok(None),
-                    }
+                .map(|value_byte| match value_byte? {
+                    Some(data) => Ok(Some(
+                        bcs::from_bytes(&data).map_err(typed_store_err_from_bcs_err)?,
+                    )),
+                    None => Ok(None),
                 })
                 .collect();
             results.extend(values_parsed?);
@@ -2751,6 +3093,15 @@ pub fn open_cf_opts_transactional<P: AsRef<Path>>(
     })
 }
 
+/// Opens a database backed by an in-memory store instead of RocksDB, with a number of (empty)
+/// column families. Nothing written to it is persisted.
+pub fn open_cf_in_memory(metric_conf: MetricConf, opt_cfs: &[&str]) -> Arc<RocksDB> {
+    Arc::new(RocksDB::InMemory(InMemoryDBWrapper::new(
+        InMemoryDB::new(opt_cfs),
+        metric_conf,
+    )))
+}
+
 /// Opens a database with options, and a number of column families with individual options that are created if they do not exist.
 pub fn open_cf_opts_secondary<P: AsRef<Path>>(
     primary_path: P,
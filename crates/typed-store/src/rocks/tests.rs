@@ -1488,6 +1488,236 @@ async fn refcount_with_compaction_test() {
     assert!(is_ref_count_value(&value));
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_in_memory_iter(#[values(true, false)] use_safe_iter: bool) {
+    let db = open_in_memory_map(None);
+    for i in (1..100).filter(|i| *i != 50) {
+        db.insert(&i, &i.to_string()).expect("Failed to insert");
+    }
+
+    assert_eq!(
+        get_iter(&db, use_safe_iter).collect::<Vec<_>>(),
+        (1..100)
+            .filter(|i| *i != 50)
+            .map(|i| (i, i.to_string()))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        get_iter(&db, use_safe_iter)
+            .skip_to(&50)
+            .expect("Seek failed")
+            .next(),
+        Some((51, "51".to_string()))
+    );
+    assert_eq!(
+        get_iter(&db, use_safe_iter)
+            .skip_prior_to(&50)
+            .expect("Seek failed")
+            .next(),
+        Some((49, "49".to_string()))
+    );
+    assert_eq!(
+        get_iter(&db, use_safe_iter)
+            .skip_to_last()
+            .reverse()
+            .take(2)
+            .collect::<Vec<_>>(),
+        vec![(99, "99".to_string()), (98, "98".to_string())]
+    );
+    assert_eq!(
+        get_iter_with_bounds(&db, Some(45), Some(55), use_safe_iter)
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>(),
+        vec![45, 46, 47, 48, 49, 51, 52, 53, 54]
+    );
+    assert_eq!(
+        get_range_iter(&db, 48..=51, use_safe_iter)
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>(),
+        vec![48, 49, 51]
+    );
+    assert_eq!(
+        get_range_iter(&db, (Bound::Excluded(97), Bound::Unbounded), use_safe_iter)
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>(),
+        vec![98, 99]
+    );
+    assert_eq!(db.keys().skip_to(&98).unwrap().count(), 2);
+    assert_eq!(db.values().next(), Some(Ok("1".to_string())));
+}
+
+#[tokio::test]
+async fn test_in_memory_iter_is_isolated_from_writes() {
+    let db = open_in_memory_map(None);
+    db.multi_insert((1..4).map(|i| (i, i.to_string())))
+        .expect("Failed to multi-insert");
+
+    let iter = db.safe_iter();
+    db.insert(&4, &"4".to_string()).expect("Failed to insert");
+    db.remove(&1).expect("Failed to remove");
+
+    assert_eq!(
+        iter.map(Result::unwrap).map(|(k, _)| k).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(
+        db.safe_iter()
+            .map(Result::unwrap)
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>(),
+        vec![2, 3, 4]
+    );
+}
+
+#[tokio::test]
+async fn test_in_memory_reads() {
+    let db = open_in_memory_map(None);
+    db.insert(&123, &"123".to_string())
+        .expect("Failed to insert");
+    db.insert(&456, &"456".to_string())
+        .expect("Failed to insert");
+
+    assert!(db.contains_key(&123).expect("Failed to call contains key"));
+    assert!(!db.contains_key(&789).expect("Failed to call contains key"));
+    assert_eq!(
+        db.multi_contains_keys([123, 789])
+            .expect("Failed to multi contain"),
+        vec![true, false]
+    );
+    assert_eq!(
+        db.get_raw_bytes(&123).expect("Failed to get_raw_bytes"),
+        Some(bcs::to_bytes(&"123".to_string()).unwrap())
+    );
+    let expected = vec![Some("123".to_string()), Some("456".to_string()), None];
+    assert_eq!(
+        db.multi_get([123, 456, 789]).expect("Failed to multi get"),
+        expected
+    );
+    assert_eq!(
+        db.chunked_multi_get([123, 456, 789], 1)
+            .expect("Failed to chunk multi get"),
+        expected
+    );
+}
+
+#[tokio::test]
+async fn test_in_memory_batch() {
+    let rocks = open_cf_in_memory(MetricConf::default(), &["First_CF", "Second_CF"]);
+    let db_cf_1: DBMap<i32, String> = DBMap::reopen(
+        &rocks,
+        Some("First_CF"),
+        &ReadWriteOptions::default(),
+        false,
+    )
+    .expect("Failed to open storage");
+    let db_cf_2: DBMap<i32, String> = DBMap::reopen(
+        &rocks,
+        Some("Second_CF"),
+        &ReadWriteOptions::default(),
+        false,
+    )
+    .expect("Failed to open storage");
+    assert!(DBMap::<i32, String>::reopen(
+        &rocks,
+        Some("quux"),
+        &ReadWriteOptions::default(),
+        false
+    )
+    .is_err());
+
+    let mut batch = db_cf_1.batch();
+    batch
+        .insert_batch(&db_cf_1, (0..101).map(|i| (i, i.to_string())))
+        .expect("Failed to batch insert")
+        .insert_batch(&db_cf_2, (1000..1100).map(|i| (i, i.to_string())))
+        .expect("Failed to batch insert");
+    batch
+        .delete_batch(&db_cf_2, (1000..1100).step_by(2))
+        .expect("Failed to batch delete");
+    batch
+        .schedule_delete_range(&db_cf_1, &50, &100)
+        .expect("Failed to delete range");
+
+    // Nothing is visible until the batch is written.
+    assert!(db_cf_1.is_empty());
+    batch.write().expect("Failed to execute batch");
+
+    assert_eq!(
+        db_cf_1.keys().map(Result::unwrap).collect::<Vec<_>>(),
+        (0..50).chain(100..101).collect::<Vec<_>>()
+    );
+    assert!(db_cf_2.keys().all(|k| k.unwrap() % 2 == 1));
+
+    // Batches can't span different databases.
+    let other = open_cf_in_memory(MetricConf::default(), &["First_CF"]);
+    let other_cf: DBMap<i32, String> = DBMap::reopen(
+        &other,
+        Some("First_CF"),
+        &ReadWriteOptions::default(),
+        false,
+    )
+    .expect("Failed to open storage");
+    assert!(db_cf_1
+        .batch()
+        .insert_batch(&other_cf, [(1, "1".to_string())])
+        .is_err());
+
+    db_cf_1.unsafe_clear().expect("Failed to clear");
+    assert!(db_cf_1.is_empty());
+    assert!(!db_cf_2.is_empty());
+}
+
+#[tokio::test]
+async fn test_in_memory_snapshot() {
+    let db = open_in_memory_map(None);
+    db.insert(&123, &"123".to_string())
+        .expect("Failed to insert");
+    let snapshot = db.snapshot().expect("Failed to take snapshot");
+    db.insert(&456, &"456".to_string())
+        .expect("Failed to insert");
+
+    let RocksDBSnapshot::InMemory(snapshot) = snapshot else {
+        panic!("Expected an in-memory snapshot");
+    };
+    let keys = [123, 456].map(|k| be_fix_int_ser(&k).unwrap());
+    assert_eq!(
+        snapshot
+            .multi_get(db.cf_name(), keys)
+            .expect("Failed to multi get"),
+        vec![Some(bcs::to_bytes(&"123".to_string()).unwrap()), None]
+    );
+    assert_eq!(
+        db.chunked_multi_get([123, 456], 1)
+            .expect("Failed to chunk multi get"),
+        vec![Some("123".to_string()), Some("456".to_string())]
+    );
+    assert_eq!(db.table_summary().expect("Failed to summarize").num_keys, 2);
+}
+
+#[tokio::test]
+async fn test_in_memory_unsupported_operations() {
+    let db: DBMap<i32, String> = open_in_memory_map(None);
+    assert!(db.transaction().is_err());
+    assert!(db.batch().merge_batch(&db, [(1, "1".to_string())]).is_err());
+    assert!(db.checkpoint_db(&temp_dir()).is_err());
+    // Reads of the default column family don't need a handle.
+    db.insert(&1, &"1".to_string()).expect("Failed to insert");
+    assert_eq!(
+        db.rocksdb
+            .get(be_fix_int_ser(&1).unwrap())
+            .expect("Failed to get"),
+        Some(bcs::to_bytes(&"1".to_string()).unwrap())
+    );
+}
+
+fn open_in_memory_map<K, V>(opt_cf: Option<&str>) -> DBMap<K, V> {
+    let cf = opt_cf.unwrap_or(rocksdb::DEFAULT_COLUMN_FAMILY_NAME);
+    let db = open_cf_in_memory(MetricConf::default(), &[cf]);
+    DBMap::reopen(&db, Some(cf), &ReadWriteOptions::default(), false)
+        .expect("failed to open in-memory store")
+}
+
 fn open_map<P: AsRef<Path>, K, V>(
     path: P,
     opt_cf: Option<&str>,
@@ -11,6 +11,7 @@ use std::fmt::Debug;
 use std::sync::Mutex;
 use std::time::Duration;
 use typed_store::metrics::SamplingInterval;
+use typed_store::migration::{read_progress, Migration, MigrationRunner, RewriteTable};
 use typed_store::rocks::list_tables;
 use typed_store::rocks::DBMap;
 use typed_store::rocks::RocksDBAccessType;
@@ -333,6 +334,82 @@ async fn macro_transactional_test() {
     assert_eq!(tables.table1.get(&key), Ok(Some("1".to_string())));
 }
 
+#[test]
+fn macro_in_memory_test() {
+    let tables = Tables::open_tables_in_memory(MetricConf::default());
+    let keys_vals = (1..10).map(|i| (i, i.to_string()));
+    let mut batch = tables.table1.batch();
+    batch
+        .insert_batch(&tables.table1, vec![("key".to_string(), "1".to_string())])
+        .unwrap()
+        .insert_batch(&tables.table2, keys_vals.clone())
+        .unwrap();
+    batch.write().unwrap();
+
+    assert_eq!(
+        tables.table1.get(&"key".to_string()),
+        Ok(Some("1".to_string()))
+    );
+    assert_eq!(
+        tables
+            .table2
+            .safe_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>(),
+        keys_vals.collect::<Vec<_>>()
+    );
+
+    // Tables opened separately do not share any state.
+    let other = Tables::open_tables_in_memory(MetricConf::default());
+    assert!(other.table1.is_empty());
+    assert!(other.table2.is_empty());
+}
+
+#[derive(DBMapUtils)]
+#[schema_version = 1]
+struct TablesV1 {
+    table1: DBMap<String, String>,
+    #[rename = "table2_v1"]
+    table2: DBMap<i32, usize>,
+}
+
+#[tokio::test]
+async fn migration_test() {
+    let dbdir = temp_dir();
+    assert_eq!(Tables::SCHEMA_VERSION, 0);
+    assert_eq!(TablesV1::SCHEMA_VERSION, 1);
+    {
+        let db = Tables::open_tables_read_write(dbdir.clone(), MetricConf::default(), None, None);
+        db.table2
+            .multi_insert((0..10).map(|i| (i, "x".repeat(i as usize))))
+            .unwrap();
+
+        let migrations = vec![
+            Migration::new(1, "store the length of table2 values").with_step(RewriteTable::new(
+                "table2",
+                "table2_v1",
+                |key: i32, value: String| Some((key, value.len())),
+            )),
+        ];
+        let runner = MigrationRunner::new(&db.table1.rocksdb, migrations)
+            .unwrap()
+            .with_batch_size(3);
+        assert_eq!(runner.run_to(TablesV1::SCHEMA_VERSION), Ok(1));
+        assert_eq!(read_progress(&db.table1.rocksdb).unwrap().schema_version, 1);
+    }
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    let db = TablesV1::open_tables_read_write(dbdir, MetricConf::default(), None, None);
+    assert_eq!(
+        db.table2
+            .safe_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>(),
+        (0..10).map(|i| (i, i as usize)).collect::<Vec<_>>()
+    );
+    assert_eq!(read_progress(&db.table1.rocksdb).unwrap().schema_version, 1);
+}
+
 /// We show that custom functions can be applied
 #[derive(DBMapUtils)]
 struct TablesCustomOptions {